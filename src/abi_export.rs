@@ -0,0 +1,217 @@
+//! 导出合约的 Solidity 接口（`export-abi` 特性）
+//!
+//! SDK 只根据公开方法生成函数和错误声明：同一个错误会在继承的接口中重复出现，事件也不会列出。
+//! 这里在 SDK 的输出上补充各合约的事件（直接取自对应模块 `sol!` 块中的声明，避免与代码不一致），
+//! 去掉已在父接口中声明过的错误，并把部署后调用一次的初始化函数单独标注出来：
+//! Stylus 合约没有部署时执行的构造函数，名为 `constructor` 的初始化方法不能在 Solidity 接口中声明，
+//! 以注释形式给出函数签名和选择器。
+
+use core::{fmt, marker::PhantomData};
+use stylus_sdk::{
+    abi::export::GenerateAbi,
+    alloy_primitives::{hex, keccak256},
+};
+
+/// 打印当前特性所选合约的完整 Solidity 接口
+pub fn print_abi(license: &str, pragma: &str) {
+    #[cfg(feature = "dsc")]
+    print::<crate::decentralized_stable_coin::DecentralizedStableCoin>(
+        license,
+        pragma,
+        &[
+            ("IErc20", include_str!("erc20.rs")),
+            (
+                "IDecentralizedStableCoin",
+                include_str!("decentralized_stable_coin.rs"),
+            ),
+            ("IVotes", include_str!("erc20_votes.rs")),
+        ],
+    );
+    #[cfg(feature = "dsc-staking")]
+    print::<crate::dsc_staking::DscStaking>(
+        license,
+        pragma,
+        &[("IDscStaking", include_str!("dsc_staking.rs"))],
+    );
+    #[cfg(feature = "mock-erc20")]
+    print::<crate::mock_erc20::MockErc20>(
+        license,
+        pragma,
+        &[("IMockErc20", include_str!("erc20.rs"))],
+    );
+    #[cfg(feature = "mock-v3-aggregator")]
+    print::<crate::mock_v3_aggregator::MockV3Aggregator>(license, pragma, &[]);
+    #[cfg(feature = "oft-adapter")]
+    print::<crate::oft_adapter::OftAdapter>(
+        license,
+        pragma,
+        &[("IOftAdapter", include_str!("oft_adapter.rs"))],
+    );
+    #[cfg(feature = "savings-vault")]
+    print::<crate::savings_vault::SavingsVault>(
+        license,
+        pragma,
+        &[
+            ("IErc20", include_str!("erc20.rs")),
+            ("ISavingsVault", include_str!("savings_vault.rs")),
+        ],
+    );
+    #[cfg(feature = "stability-pool")]
+    print::<crate::stability_pool::StabilityPool>(
+        license,
+        pragma,
+        &[("IStabilityPool", include_str!("stability_pool.rs"))],
+    );
+    #[cfg(not(any(
+        feature = "dsc",
+        feature = "dsc-staking",
+        feature = "mock-erc20",
+        feature = "mock-v3-aggregator",
+        feature = "oft-adapter",
+        feature = "savings-vault",
+        feature = "stability-pool"
+    )))]
+    print::<crate::DSCEngine>(
+        license,
+        pragma,
+        &[
+            ("IErc721", include_str!("erc721.rs")),
+            ("IDSCEngine", include_str!("lib.rs")),
+        ],
+    );
+}
+
+// 使 SDK 生成的接口可以格式化为字符串
+struct Abi<T: GenerateAbi>(PhantomData<T>);
+
+impl<T: GenerateAbi> fmt::Display for Abi<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        T::fmt_abi(f)
+    }
+}
+
+// 打印文件头和补充后的接口，events 为接口名到其事件所在源文件的映射
+fn print<T: GenerateAbi>(license: &str, pragma: &str, events: &[(&str, &str)]) {
+    println!("/**");
+    println!(" * This file was automatically generated by Stylus and represents a Rust program.");
+    println!(" * For more information, please see [The Stylus SDK](https://github.com/OffchainLabs/stylus-sdk-rs).");
+    println!(" */");
+    println!();
+    println!("// SPDX-License-Identifier: {license}");
+    println!("{pragma}");
+    println!();
+    print!("{}", complete(&Abi::<T>(PhantomData).to_string(), events));
+}
+
+// 逐个接口补充事件、去掉重复的错误并标注初始化函数
+fn complete(abi: &str, events: &[(&str, &str)]) -> String {
+    let mut out = String::new();
+    let mut declared_errors: Vec<String> = Vec::new();
+    let mut lines = abi.lines();
+    while let Some(line) = lines.next() {
+        let Some(header) = line.strip_prefix("interface ") else {
+            out.push_str(line);
+            out.push('\n');
+            continue;
+        };
+        let name = header.split_whitespace().next().unwrap_or_default();
+        // 接口中的各项以空行分隔，每项占一行
+        let mut items: Vec<String> = Vec::new();
+        for item in lines.by_ref() {
+            if item == "}" {
+                break;
+            }
+            if !item.is_empty() {
+                items.push(item.to_string());
+            }
+        }
+
+        let mut initializers = Vec::new();
+        let mut functions = Vec::new();
+        let mut errors = Vec::new();
+        for item in items {
+            let declaration = item.trim();
+            if declaration.starts_with("function constructor(")
+                || declaration.starts_with("function initialize(")
+                || declaration.starts_with("function initializeWithPeg(")
+            {
+                initializers.push(declaration.to_string());
+            } else if declaration.starts_with("error ") {
+                // 继承的接口中已声明的错误不能重复声明
+                if !declared_errors.iter().any(|e| e == declaration) {
+                    declared_errors.push(declaration.to_string());
+                    errors.push(item);
+                }
+            } else {
+                functions.push(item);
+            }
+        }
+        let events = events
+            .iter()
+            .filter(|(interface, _)| *interface == name)
+            .flat_map(|(_, source)| event_declarations(source))
+            .map(|event| format!("    {event}"));
+
+        out.push_str(line);
+        out.push('\n');
+        for initializer in &initializers {
+            let signature = initializer
+                .trim_start_matches("function ")
+                .split(" external")
+                .next()
+                .unwrap_or_default();
+            out.push_str(&format!(
+                "    // 初始化函数：部署后调用一次，选择器 0x{}\n",
+                selector(signature)
+            ));
+            if initializer.starts_with("function constructor(") {
+                out.push_str("    // ");
+            } else {
+                out.push_str("    ");
+            }
+            out.push_str(initializer);
+            out.push_str("\n\n");
+        }
+        let body: Vec<String> = functions.into_iter().chain(events).chain(errors).collect();
+        out.push_str(&body.join("\n\n"));
+        out.push_str("\n}\n");
+    }
+    out
+}
+
+// 提取源文件 `sol!` 块中的事件声明，多行声明合并为一行
+fn event_declarations(source: &str) -> Vec<String> {
+    let mut events = Vec::new();
+    let mut current: Option<String> = None;
+    for line in source.lines() {
+        let line = line.trim();
+        if line.starts_with("//") {
+            continue;
+        }
+        if current.is_none() && line.starts_with("event ") {
+            current = Some(String::new());
+        }
+        if let Some(event) = current.as_mut() {
+            if !event.is_empty() && !event.ends_with('(') && !line.starts_with(')') {
+                event.push(' ');
+            }
+            event.push_str(line);
+            if line.ends_with(';') {
+                events.extend(current.take());
+            }
+        }
+    }
+    events
+}
+
+// 按函数声明计算选择器：去掉参数名和数据位置，只保留类型
+fn selector(declaration: &str) -> String {
+    let (name, params) = declaration.split_once('(').unwrap_or((declaration, ")"));
+    let params = params.trim_end_matches(')');
+    let types: Vec<&str> = params
+        .split(',')
+        .filter_map(|param| param.split_whitespace().next())
+        .collect();
+    let signature = format!("{name}({})", types.join(","));
+    hex::encode(&keccak256(signature.as_bytes())[..4])
+}
@@ -0,0 +1,237 @@
+//! 荷兰式拍卖清算：按递减价格出售被清算仓位的抵押品
+//!
+//! `kick` 从不健康的仓位中扣押一批抵押品（lot）并记录待偿还的债务（tab）和清算罚金，
+//! 起拍价为预言机价格乘以起拍溢价，此后在拍卖时长内线性降至零。
+//! 任何人都可以用 `take` 按当前价格支付 DSC 购买抵押品；价格降到零后可以 `redo` 重新起拍。
+//! 拍卖所得先偿还债务，再支付计入盈余缓冲的清算罚金；
+//! 债务和罚金付清或抵押品售罄时拍卖结束，剩余抵押品退回仓位。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    block,
+    call::Call,
+    contract, evm,
+    prelude::*,
+};
+
+use crate::{
+    meta_tx::msg_sender, surplus::SURPLUS_LIQUIDATION_PENALTY, AuctionActive, AuctionKicked,
+    AuctionNeedsReset, AuctionNotExpired, AuctionNotFound, AuctionPriceExceeded, AuctionReset,
+    AuctionTaken, DSCEngine, DSCEngineError, HealthFactorOk, IDecentralizedStableCoin,
+    TransferFailed, IERC20, LIQUIDATION_PRECISION,
+};
+
+sol_storage! {
+    // 单个拍卖的状态
+    pub struct Auction {
+        address user;                        // 被清算的用户
+        address token;                       // 拍卖的抵押品
+        uint256 tab;                         // 待偿还的债务
+        uint256 penalty;                     // 待支付的清算罚金
+        uint256 lot;                         // 待出售的抵押品（记账单位）
+        uint256 start_price;                 // 起拍价：每个完整代币的美元价格（18 位精度）
+        uint256 start_time;                  // 起拍时间
+    }
+}
+
+impl DSCEngine {
+    // 拍卖当前价格：起拍价在拍卖时长内线性降至零
+    pub(crate) fn _auction_price(&self, id: U256) -> U256 {
+        let auction = self.auctions.getter(id);
+        let duration = self.auction_duration.get();
+        let elapsed = U256::from(block::timestamp()).saturating_sub(auction.start_time.get());
+        if elapsed >= duration {
+            return U256::ZERO;
+        }
+        (auction.start_price.get() * (duration - elapsed)) / duration
+    }
+
+    // 起拍价：预言机价格乘以起拍溢价，价格不可用时回滚
+    fn _auction_start_price(&self, token: Address) -> Result<U256, DSCEngineError> {
+        Ok(
+            (self._usd_value(token, self._token_unit(token))? * self.auction_start_premium.get())
+                / LIQUIDATION_PRECISION,
+        )
+    }
+
+    // 发起拍卖，返回拍卖编号（调用前需先计提）
+    pub(crate) fn _kick(&mut self, token: Address, user: Address) -> Result<U256, DSCEngineError> {
+        if self.active_auction.get(user) != U256::ZERO {
+            return Err(DSCEngineError::AuctionActive(AuctionActive {}));
+        }
+        self._require_local_collateral(token)?;
+        self._check_liquidation_grace(token)?;
+        if self._liquidation_health_factor(user, token)? >= self._min_health_factor() {
+            return Err(DSCEngineError::HealthFactorOk(HealthFactorOk {}));
+        }
+        let tab = self._max_liquidatable_debt(user, token)?;
+        self.more_than_zero(tab)?;
+        // 按抵押品（风险等级）的最高清算奖励比例收取罚金，并扣押可覆盖债务和罚金的抵押品
+        let penalty = (tab * self._collateral_max_bonus(token)) / LIQUIDATION_PRECISION;
+        let collateral = self._token_amount_from_usd(token, tab + penalty)?;
+        let user_shares = self.collateral_deposited.getter(user).get(token);
+        let lot = self
+            ._amount_to_shares(token, collateral, self.total_deposited.get(token), true)
+            .min(user_shares);
+        self.collateral_deposited
+            .setter(user)
+            .setter(token)
+            .set(user_shares - lot);
+
+        let id = self.auction_count.get() + U256::from(1);
+        self.auction_count.set(id);
+        self.active_auction.setter(user).set(id);
+        let start_price = self._auction_start_price(token)?;
+        let action_nonce = self._next_action_nonce(user);
+        let mut auction = self.auctions.setter(id);
+        auction.user.set(user);
+        auction.token.set(token);
+        auction.tab.set(tab);
+        auction.penalty.set(penalty);
+        auction.lot.set(lot);
+        auction.start_price.set(start_price);
+        auction.start_time.set(U256::from(block::timestamp()));
+        evm::log(AuctionKicked {
+            id,
+            user,
+            token,
+            tab,
+            lot,
+            startPrice: start_price,
+            actionNonce: action_nonce,
+        });
+        Ok(id)
+    }
+
+    // 按当前价格购买拍卖中的抵押品，返回购得的抵押品数量（调用前需先计提）
+    pub(crate) fn _take(
+        &mut self,
+        id: U256,
+        max_collateral: U256,
+        max_price: U256,
+    ) -> Result<U256, DSCEngineError> {
+        let auction = self.auctions.getter(id);
+        let user = auction.user.get();
+        let token = auction.token.get();
+        let lot = auction.lot.get();
+        if user.is_zero() {
+            return Err(DSCEngineError::AuctionNotFound(AuctionNotFound {}));
+        }
+        let price = self._auction_price(id);
+        if price == U256::ZERO {
+            return Err(DSCEngineError::AuctionNeedsReset(AuctionNeedsReset {}));
+        }
+        if price > max_price {
+            return Err(DSCEngineError::AuctionPriceExceeded(AuctionPriceExceeded {
+                _0: price,
+            }));
+        }
+        // 用户在拍卖期间偿还的债务不再需要拍卖
+        let tab = auction.tab.get().min(self._liquidatable_debt(user, token));
+        let penalty = auction.penalty.get();
+        if tab + penalty == U256::ZERO {
+            self._close_auction(id);
+            return Ok(U256::ZERO);
+        }
+        let unit = self._token_unit(token);
+        let mut collateral = max_collateral.min(self._shares_to_amount(token, lot));
+        let mut owe = (collateral * price) / unit;
+        if owe > tab + penalty {
+            owe = tab + penalty;
+            collateral = (owe * unit) / price;
+        }
+        self.more_than_zero(collateral)?;
+        // 先偿还被清算用户的债务，剩余部分作为清算罚金计入盈余
+        let taker = msg_sender();
+        let debt_paid = owe.min(tab);
+        let penalty_paid = owe - debt_paid;
+        if debt_paid > U256::ZERO {
+            self._burn_dsc(debt_paid, user, taker, self._debt_bucket(user, token))?;
+        }
+        if penalty_paid > U256::ZERO {
+            if IDecentralizedStableCoin::new(self.dsc.get())
+                .transfer_from(Call::new(), taker, contract::address(), penalty_paid)
+                .is_err()
+            {
+                return Err(DSCEngineError::TransferFailed(TransferFailed {}));
+            }
+            self._add_surplus(SURPLUS_LIQUIDATION_PENALTY, penalty_paid);
+        }
+        let total_assets = self.total_deposited.get(token);
+        let debited = self
+            ._amount_to_shares(token, collateral, total_assets, true)
+            .min(lot);
+        let total_collateral = self.total_collateral_deposited.get(token);
+        self.total_collateral_deposited
+            .setter(token)
+            .set(total_collateral - debited);
+        self.total_deposited
+            .setter(token)
+            .set(total_assets.saturating_sub(collateral));
+        let tab = tab - debt_paid;
+        let penalty = penalty - penalty_paid;
+        let lot = lot - debited;
+        let mut auction = self.auctions.setter(id);
+        auction.tab.set(tab);
+        auction.penalty.set(penalty);
+        auction.lot.set(lot);
+        if tab + penalty == U256::ZERO || lot == U256::ZERO {
+            self._close_auction(id);
+            self._record_bad_debt(user)?;
+        }
+        if IERC20::new(token)
+            .transfer(Call::new(), taker, collateral)
+            .is_err()
+        {
+            return Err(DSCEngineError::TransferFailed(TransferFailed {}));
+        }
+        evm::log(AuctionTaken {
+            id,
+            taker,
+            collateral,
+            owe,
+        });
+        Ok(collateral)
+    }
+
+    // 价格降到零后按最新预言机价格重新起拍
+    pub(crate) fn _redo(&mut self, id: U256) -> Result<(), DSCEngineError> {
+        let token = self.auctions.getter(id).token.get();
+        if self.auctions.getter(id).user.get().is_zero() {
+            return Err(DSCEngineError::AuctionNotFound(AuctionNotFound {}));
+        }
+        if self._auction_price(id) > U256::ZERO {
+            return Err(DSCEngineError::AuctionNotExpired(AuctionNotExpired {}));
+        }
+        let start_price = self._auction_start_price(token)?;
+        let mut auction = self.auctions.setter(id);
+        auction.start_price.set(start_price);
+        auction.start_time.set(U256::from(block::timestamp()));
+        evm::log(AuctionReset {
+            id,
+            startPrice: start_price,
+        });
+        Ok(())
+    }
+
+    // 结束拍卖，剩余抵押品退回用户仓位
+    fn _close_auction(&mut self, id: U256) {
+        let auction = self.auctions.getter(id);
+        let user = auction.user.get();
+        let token = auction.token.get();
+        let lot = auction.lot.get();
+        if lot > U256::ZERO {
+            let user_shares = self.collateral_deposited.getter(user).get(token);
+            self.collateral_deposited
+                .setter(user)
+                .setter(token)
+                .set(user_shares + lot);
+        }
+        self.active_auction.setter(user).set(U256::ZERO);
+        let mut auction = self.auctions.setter(id);
+        auction.user.set(Address::ZERO);
+        auction.tab.set(U256::ZERO);
+        auction.penalty.set(U256::ZERO);
+        auction.lot.set(U256::ZERO);
+    }
+}
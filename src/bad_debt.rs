@@ -0,0 +1,74 @@
+//! 坏账与保险基金
+//!
+//! 清算后仓位已无抵押品价值但仍有债务时，核销该仓位的债务并把未偿本金计入系统坏账。
+//! 保险基金是引擎持有并专门记账的 DSC，可由任何人存入或由治理从盈余缓冲中划拨，
+//! 用于销毁 DSC 冲抵坏账。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    call::Call,
+    evm,
+};
+
+use crate::{
+    BadDebtCovered, BadDebtRecorded, DSCEngine, DSCEngineError, IDecentralizedStableCoin,
+    InsuranceFunded, TransferFailed,
+};
+
+impl DSCEngine {
+    // 仓位已无抵押品价值但仍有债务时，核销债务并计入坏账（调用前需先计提）
+    pub(crate) fn _record_bad_debt(&mut self, user: Address) -> Result<(), DSCEngineError> {
+        // 拍卖中的抵押品尚未售出，不视为坏账
+        if self.active_auction.get(user) != U256::ZERO {
+            return Ok(());
+        }
+        // 价格不可用时回滚，不能把仍有价值的抵押品当作零
+        let (debt, collateral_value) = self._get_account_info(user)?;
+        if debt == U256::ZERO || collateral_value > U256::ZERO {
+            return Ok(());
+        }
+        self._remove_debt(user, debt, None);
+        // 未偿本金对应已流通的 DSC，即系统的缺口
+        let principal = self.dsc_minted.get(user);
+        self.dsc_minted.setter(user).set(U256::ZERO);
+        self.total_dsc_minted
+            .set(self.total_dsc_minted.get() - principal);
+        let total_bad_debt = self.system_bad_debt.get() + principal;
+        self.system_bad_debt.set(total_bad_debt);
+        self._sync_active_position(user);
+        evm::log(BadDebtRecorded {
+            user,
+            amount: principal,
+            totalBadDebt: total_bad_debt,
+        });
+        Ok(())
+    }
+
+    // 增加保险基金余额
+    pub(crate) fn _add_to_insurance(&mut self, from: Address, amount: U256) {
+        self.insurance_fund.set(self.insurance_fund.get() + amount);
+        evm::log(InsuranceFunded { from, amount });
+    }
+
+    // 用保险基金销毁 DSC 冲抵坏账，返回实际冲抵的数量
+    pub(crate) fn _cover_bad_debt(&mut self, amount: U256) -> Result<U256, DSCEngineError> {
+        // 已进入债务拍卖的坏账由拍卖募集的 DSC 冲抵
+        let covered = amount
+            .min(self.insurance_fund.get())
+            .min(self._unreserved_bad_debt());
+        if covered == U256::ZERO {
+            return Ok(U256::ZERO);
+        }
+        self.insurance_fund.set(self.insurance_fund.get() - covered);
+        let remaining = self.system_bad_debt.get() - covered;
+        self.system_bad_debt.set(remaining);
+        IDecentralizedStableCoin::new(self.dsc.get())
+            .burn(Call::new(), covered)
+            .map_err(|_| DSCEngineError::TransferFailed(TransferFailed {}))?;
+        evm::log(BadDebtCovered {
+            amount: covered,
+            remainingBadDebt: remaining,
+        });
+        Ok(covered)
+    }
+}
@@ -0,0 +1,57 @@
+//! 借款阈值：比清算阈值更严格的铸造和提取限制
+//!
+//! 借款阈值与默认清算阈值使用同一精度（清算精度），且不高于默认清算阈值。铸造、提取抵押品、
+//! 切换 E-mode 和接收仓位转让后，仓位的健康因子须不低于借款健康因子
+//! `最小健康因子 × 默认清算阈值 / 借款阈值`，清算仍以最小健康因子为界。
+//! 例如清算阈值为 50、借款阈值为 40 时，新仓位最多铸造到健康因子 1.25，价格下跌 20% 后才可能被清算。
+//! 风险等级、E-mode 和动态阈值下按同一比例收紧。借款阈值为零时不启用，借款健康因子等于最小健康因子。
+//! 只偿还债务的操作不受借款阈值限制。
+
+use stylus_sdk::alloy_primitives::{Address, U256};
+
+use crate::{
+    math::{mul_div, Rounding},
+    BreaksHealthFactor, DSCEngine, DSCEngineError,
+};
+
+impl DSCEngine {
+    // 借款阈值（清算精度），为零时不启用
+    pub(crate) fn _borrow_threshold(&self) -> U256 {
+        U256::from(self._config().borrow_threshold)
+    }
+
+    // 设置借款阈值
+    pub(crate) fn _set_borrow_threshold(&mut self, threshold: u32) {
+        let mut config = self._config();
+        config.borrow_threshold = threshold;
+        self._set_config(config);
+    }
+
+    // 借款健康因子：铸造和提取后仓位须达到的健康因子
+    pub(crate) fn _borrow_health_factor(&self) -> U256 {
+        let borrow_threshold = self._borrow_threshold();
+        if borrow_threshold == U256::ZERO {
+            return self._min_health_factor();
+        }
+        mul_div(
+            self._min_health_factor(),
+            self._liquidation_threshold(),
+            borrow_threshold,
+            Rounding::Up,
+        )
+    }
+
+    // 检查健康因子是否达到借款健康因子
+    pub(crate) fn _revert_if_borrow_health_factor_is_broken(
+        &self,
+        user: Address,
+    ) -> Result<(), DSCEngineError> {
+        let user_health_factor = self._health_factor(user)?;
+        if user_health_factor < self._borrow_health_factor() {
+            return Err(DSCEngineError::BreaksHealthFactor(BreaksHealthFactor {
+                _0: user_health_factor,
+            }));
+        }
+        Ok(())
+    }
+}
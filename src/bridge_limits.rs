@@ -0,0 +1,136 @@
+//! xERC20（ERC-7281）跨链桥铸造和销毁额度
+//!
+//! 治理为每个跨链桥分别设置铸造和销毁的额度上限，额度使用后在一天内按线性速度恢复到上限，
+//! 官方桥和第三方桥都可以在有限风险下远程铸造 DSC，无需包装代币即可扩展到多条链。
+//! CCIP 代币池同样登记为跨链桥，通过 `mint`、`burn(amount)`、`burn(from, amount)` 和 `burnFrom`
+//! 铸造和销毁 DSC。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    block,
+    prelude::*,
+};
+
+use crate::{
+    decentralized_stable_coin::{
+        DecentralizedStableCoin, DecentralizedStableCoinError, NotHighEnoughLimits,
+    },
+    erc20::InsufficientAllowance,
+};
+
+// 额度从零恢复到上限所需的时间：一天
+const LIMIT_DURATION: u64 = 86_400;
+
+sol_storage! {
+    // 跨链桥的一项额度
+    pub struct BridgeLimit {
+        uint256 max_limit;       // 额度上限
+        uint256 current_limit;   // 上次使用后剩余的额度
+        uint256 rate_per_second; // 每秒恢复的额度
+        uint256 timestamp;       // 上次使用或修改额度的时间
+    }
+}
+
+impl BridgeLimit {
+    // 当前可用的额度（包含上次使用后已恢复的部分）
+    pub(crate) fn current(&self) -> U256 {
+        let max_limit = self.max_limit.get();
+        let current_limit = self.current_limit.get();
+        if current_limit == max_limit {
+            return current_limit;
+        }
+        let elapsed = U256::from(block::timestamp()).saturating_sub(self.timestamp.get());
+        if elapsed >= U256::from(LIMIT_DURATION) {
+            return max_limit;
+        }
+        (current_limit + elapsed * self.rate_per_second.get()).min(max_limit)
+    }
+
+    // 修改额度上限，剩余额度按上限的变化同步增减
+    fn set_max(&mut self, limit: U256) {
+        let old_limit = self.max_limit.get();
+        let current_limit = self.current();
+        let new_current = if old_limit > limit {
+            current_limit.saturating_sub(old_limit - limit)
+        } else {
+            current_limit + (limit - old_limit)
+        };
+        self.max_limit.set(limit);
+        self.current_limit.set(new_current);
+        self.rate_per_second.set(limit / U256::from(LIMIT_DURATION));
+        self.timestamp.set(U256::from(block::timestamp()));
+    }
+
+    // 使用额度，额度不足时回滚
+    fn spend(&mut self, amount: U256) -> Result<(), DecentralizedStableCoinError> {
+        let current_limit = self.current();
+        if amount > current_limit {
+            return Err(DecentralizedStableCoinError::NotHighEnoughLimits(
+                NotHighEnoughLimits {},
+            ));
+        }
+        self.current_limit.set(current_limit - amount);
+        self.timestamp.set(U256::from(block::timestamp()));
+        Ok(())
+    }
+}
+
+impl DecentralizedStableCoin {
+    // 设置跨链桥的铸造和销毁额度上限
+    pub(crate) fn _set_bridge_limits(
+        &mut self,
+        bridge: Address,
+        minting_limit: U256,
+        burning_limit: U256,
+    ) {
+        self.minting_limits.setter(bridge).set_max(minting_limit);
+        self.burning_limits.setter(bridge).set_max(burning_limit);
+    }
+
+    // 跨链桥铸造时使用铸造额度
+    pub(crate) fn _use_minting_limit(
+        &mut self,
+        bridge: Address,
+        amount: U256,
+    ) -> Result<(), DecentralizedStableCoinError> {
+        self.minting_limits.setter(bridge).spend(amount)
+    }
+
+    // 跨链桥销毁时使用销毁额度
+    pub(crate) fn _use_burning_limit(
+        &mut self,
+        bridge: Address,
+        amount: U256,
+    ) -> Result<(), DecentralizedStableCoinError> {
+        self.burning_limits.setter(bridge).spend(amount)
+    }
+
+    // 跨链桥销毁 from 的 DSC，代他人销毁时消耗授权
+    pub(crate) fn _bridge_burn(
+        &mut self,
+        bridge: Address,
+        from: Address,
+        amount: U256,
+    ) -> Result<(), DecentralizedStableCoinError> {
+        self._use_burning_limit(bridge, amount)?;
+        self.not_blacklisted(from)?;
+        if bridge != from {
+            let mut allowances = self.erc20.allowances.setter(from);
+            let mut allowance = allowances.setter(bridge);
+            let old_allowance = allowance.get();
+            if old_allowance < amount {
+                return Err(DecentralizedStableCoinError::InsufficientAllowance(
+                    InsufficientAllowance {
+                        owner: from,
+                        spender: bridge,
+                        have: old_allowance,
+                        want: amount,
+                    },
+                ));
+            }
+            allowance.set(old_allowance - amount);
+        }
+        self.erc20.burn(from, amount)?;
+        self._after_token_transfer(from, Address::ZERO, amount)
+    }
+}
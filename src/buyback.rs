@@ -0,0 +1,113 @@
+//! 回购销毁：DSC 低于锚定价格时，用协议持有的抵押品或稳定币在 DEX 上买入 DSC 并销毁
+//!
+//! 回购储备按代币内部记账，由国库或任何人转入支持的抵押品或 PSM 稳定币。
+//! 回购经 DEX 路由卖出储备，买入的 DSC 不少于卖出代币按锚定价格折算的价值（抵押品按预言机价格，
+//! PSM 稳定币按 1:1），因此只有 DSC 的成交价低于锚定价格时回购才能成交；调用者可以给出更高的最少买入量。
+//! 每个周期内回购卖出的价值不能超过治理设定的上限，上限为零时不能回购。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    block,
+    call::Call,
+    contract, evm,
+};
+
+use crate::{
+    meta_tx::msg_sender, BurnFailed, BuybackCapExceeded, BuybackExecuted, BuybackFunded, DSCEngine,
+    DSCEngineError, IDecentralizedStableCoin, InsufficientBuybackReserve, NotAllowedToken,
+    TransferFailed, IERC20,
+};
+
+impl DSCEngine {
+    // 回购储备代币按锚定价格折算的 DSC 数量，只接受支持的抵押品和 PSM 稳定币
+    fn _buyback_par_value(&self, token: Address, amount: U256) -> Result<U256, DSCEngineError> {
+        if !token.is_zero() && token == self.psm_token.get() {
+            return Ok(self._psm_to_dsc_amount(amount));
+        }
+        if self.price_feeds.get(token).is_zero() {
+            return Err(DSCEngineError::NotAllowedToken(NotAllowedToken {}));
+        }
+        // 价格不可用时回滚，不能以零价值为最少买入量
+        self._usd_value(token, amount)
+    }
+
+    // 当前所处的回购周期
+    fn _buyback_epoch(&self) -> U256 {
+        let window = self.buyback_window.get();
+        if window == U256::ZERO {
+            return U256::ZERO;
+        }
+        U256::from(block::timestamp()) / window
+    }
+
+    // 本周期内已回购的价值
+    pub(crate) fn _epoch_buyback_spent(&self) -> U256 {
+        if self.buyback_epoch.get() == self._buyback_epoch() {
+            self.buyback_epoch_spent.get()
+        } else {
+            U256::ZERO
+        }
+    }
+
+    // 转入代币计入回购储备
+    pub(crate) fn _fund_buyback(
+        &mut self,
+        token: Address,
+        amount: U256,
+    ) -> Result<(), DSCEngineError> {
+        self._buyback_par_value(token, U256::ZERO)?;
+        let from = msg_sender();
+        if !matches!(
+            IERC20::new(token).transfer_from(Call::new(), from, contract::address(), amount),
+            Ok(true)
+        ) {
+            return Err(DSCEngineError::TransferFailed(TransferFailed {}));
+        }
+        let reserve = self.buyback_reserves.get(token) + amount;
+        self.buyback_reserves.setter(token).set(reserve);
+        evm::log(BuybackFunded {
+            token,
+            from,
+            amount,
+        });
+        Ok(())
+    }
+
+    // 卖出回购储备买入 DSC 并销毁，返回销毁的 DSC 数量
+    pub(crate) fn _buyback_and_burn(
+        &mut self,
+        token: Address,
+        amount_in: U256,
+        min_dsc_out: U256,
+    ) -> Result<U256, DSCEngineError> {
+        let reserve = self.buyback_reserves.get(token);
+        if amount_in > reserve {
+            return Err(DSCEngineError::InsufficientBuybackReserve(
+                InsufficientBuybackReserve {},
+            ));
+        }
+        let par_value = self._buyback_par_value(token, amount_in)?;
+        let spent = self._epoch_buyback_spent();
+        let remaining = self.buyback_epoch_cap.get().saturating_sub(spent);
+        if par_value > remaining {
+            return Err(DSCEngineError::BuybackCapExceeded(BuybackCapExceeded {
+                _0: remaining,
+            }));
+        }
+        let dsc = self.dsc.get();
+        let dsc_bought =
+            self._swap_via_router(token, amount_in, dsc, par_value.max(min_dsc_out))?;
+        self.buyback_reserves.setter(token).set(reserve - amount_in);
+        self.buyback_epoch.set(self._buyback_epoch());
+        self.buyback_epoch_spent.set(spent + par_value);
+        IDecentralizedStableCoin::new(dsc)
+            .burn(Call::new(), dsc_bought)
+            .map_err(|_| DSCEngineError::BurnFailed(BurnFailed {}))?;
+        evm::log(BuybackExecuted {
+            token,
+            amountIn: amount_in,
+            dscBurned: dsc_bought,
+        });
+        Ok(dsc_bought)
+    }
+}
@@ -0,0 +1,26 @@
+//! 有符号数到无符号数的转换
+//!
+//! 预言机以 `int256` 报价。负数报价和放大到 18 位精度后会溢出的报价都转换为明确的错误，
+//! 不再在转换失败时静默地把价格当作零。
+
+use stylus_sdk::alloy_primitives::{I256, U256};
+
+use crate::{DSCEngineError, NegativeValue, ValueOverflow, ADDITIONAL_FEED_PRECISION};
+
+// 将有符号数转换为无符号数，负数返回 NegativeValue
+pub(crate) fn to_u256(value: I256) -> Result<U256, DSCEngineError> {
+    if value.is_negative() {
+        return Err(DSCEngineError::NegativeValue(NegativeValue { _0: value }));
+    }
+    Ok(value.into_raw())
+}
+
+// 将预言机报价转换为价格（预言机精度），负数返回 NegativeValue，
+// 乘以 ADDITIONAL_FEED_PRECISION 后超出 256 位返回 ValueOverflow
+pub(crate) fn answer_to_price(answer: I256) -> Result<U256, DSCEngineError> {
+    let price = to_u256(answer)?;
+    if price.checked_mul(ADDITIONAL_FEED_PRECISION).is_none() {
+        return Err(DSCEngineError::ValueOverflow(ValueOverflow { _0: answer }));
+    }
+    Ok(price)
+}
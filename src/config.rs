@@ -0,0 +1,112 @@
+//! 打包存储的风险参数
+//!
+//! 最小健康因子、清算阈值、清算奖励、平仓系数、手续费开关、借款阈值和临界健康因子打包在同一个存储槽中，
+//! 估值、铸造和清算路径只需一次冷读取即可取得全部参数。
+//!
+//! 槽位布局（从低位开始）：最小健康因子 u64 | 清算阈值 u32 | 清算奖励 u32 | 平仓系数 u32 | 标志位 u8 |
+//! 借款阈值 u24 | 临界健康因子 u64
+
+use stylus_sdk::alloy_primitives::U256;
+
+use crate::DSCEngine;
+
+// 标志位：手续费开关
+const FLAG_FEE_SWITCH: u8 = 1;
+
+// 解包后的风险参数
+#[derive(Clone, Copy)]
+pub(crate) struct RiskConfig {
+    pub(crate) min_health_factor: u64,
+    pub(crate) liquidation_threshold: u32,
+    pub(crate) liquidation_bonus: u32,
+    pub(crate) close_factor: u32,
+    pub(crate) flags: u8,
+    pub(crate) borrow_threshold: u32,
+    pub(crate) critical_health_factor: u64,
+}
+
+impl RiskConfig {
+    fn unpack(word: U256) -> Self {
+        let limb = word.as_limbs();
+        Self {
+            min_health_factor: limb[0],
+            liquidation_threshold: limb[1] as u32,
+            liquidation_bonus: (limb[1] >> 32) as u32,
+            close_factor: limb[2] as u32,
+            flags: (limb[2] >> 32) as u8,
+            borrow_threshold: (limb[2] >> 40) as u32,
+            critical_health_factor: limb[3],
+        }
+    }
+
+    fn pack(self) -> U256 {
+        U256::from_limbs([
+            self.min_health_factor,
+            u64::from(self.liquidation_threshold) | (u64::from(self.liquidation_bonus) << 32),
+            u64::from(self.close_factor)
+                | (u64::from(self.flags) << 32)
+                | (u64::from(self.borrow_threshold) << 40),
+            self.critical_health_factor,
+        ])
+    }
+}
+
+impl DSCEngine {
+    // 读取打包的风险参数
+    pub(crate) fn _config(&self) -> RiskConfig {
+        RiskConfig::unpack(self.risk_config.get())
+    }
+
+    // 写入打包的风险参数
+    pub(crate) fn _set_config(&mut self, config: RiskConfig) {
+        self.risk_config.set(config.pack());
+    }
+
+    // 最小健康因子
+    pub(crate) fn _min_health_factor(&self) -> U256 {
+        U256::from(self._config().min_health_factor)
+    }
+
+    // 默认清算阈值（清算精度）
+    pub(crate) fn _liquidation_threshold(&self) -> U256 {
+        U256::from(self._config().liquidation_threshold)
+    }
+
+    // 清算奖励（清算精度）
+    pub(crate) fn _liquidation_bonus(&self) -> U256 {
+        U256::from(self._config().liquidation_bonus)
+    }
+
+    // 平仓系数（清算精度）
+    pub(crate) fn _close_factor(&self) -> U256 {
+        U256::from(self._config().close_factor)
+    }
+
+    // 临界健康因子：健康因子低于该值时不受平仓系数限制，可一次清算全部债务；为零时不启用
+    pub(crate) fn _critical_health_factor(&self) -> U256 {
+        U256::from(self._config().critical_health_factor)
+    }
+
+    // 设置临界健康因子
+    pub(crate) fn _set_critical_health_factor(&mut self, health_factor: u64) {
+        let mut config = self._config();
+        config.critical_health_factor = health_factor;
+        self._set_config(config);
+    }
+
+    // 手续费开关是否开启
+    pub(crate) fn _fee_switch(&self) -> bool {
+        self._config().flags & FLAG_FEE_SWITCH != 0
+    }
+
+    // 设置手续费开关
+    pub(crate) fn _set_fee_switch(&mut self, enabled: bool) {
+        let mut config = self._config();
+        if enabled {
+            config.flags |= FLAG_FEE_SWITCH;
+        } else {
+            config.flags &= !FLAG_FEE_SWITCH;
+        }
+        self._set_config(config);
+    }
+}
@@ -0,0 +1,186 @@
+//! 债务拍卖：保险基金不足以冲抵坏账时，增发协议代币换取 DSC 销毁
+//!
+//! 坏账先由保险基金冲抵，剩余部分达到每场募集数量时，任何人都可以发起一场反向拍卖：
+//! 募集的 DSC 数量固定，竞拍者报出愿意接受的协议代币数量，每次出价须比当前数量至少减少治理设定的比例。
+//! 首次出价的 DSC 立即销毁并冲抵坏账，之后的出价者把同等数量的 DSC 退给被超越的出价者。
+//! 拍卖结束后为最后的出价者增发其报出的协议代币；无人出价的拍卖结束后释放其占用的坏账，可以重新发起。
+//! 引擎须是协议代币的铸造者。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    block,
+    call::Call,
+    contract, evm,
+    prelude::*,
+};
+
+use crate::{
+    meta_tx::msg_sender, psm::BPS, BurnFailed, DSCEngine, DSCEngineError, DebtAuctionBid,
+    DebtAuctionClosed, DebtAuctionKicked, DebtAuctionNotFound, DebtAuctionSettled,
+    IDecentralizedStableCoin, InsufficientLotDecrease, InvalidDebtAuctionParams, MintFailed,
+    NoDebtToAuction, TransferFailed,
+};
+
+sol_interface! {
+    // 协议代币接口：引擎作为铸造者为拍卖获胜者增发
+    interface IProtocolToken {
+        function mint(address to, uint256 amount) external returns (bool);
+    }
+}
+
+sol_storage! {
+    // 一场债务拍卖，募集数量为零表示不存在或已结算
+    pub struct DebtAuction {
+        address bidder;                      // 当前出价者：为零地址表示尚无出价
+        uint256 lot;                         // 当前出价者接受的协议代币数量
+        uint256 bid;                         // 募集的 DSC 数量
+        uint256 ends_at;                     // 结束时间
+    }
+}
+
+impl DSCEngine {
+    // 尚未被保险基金冲抵、也未进入拍卖的坏账
+    pub(crate) fn _unreserved_bad_debt(&self) -> U256 {
+        self.system_bad_debt
+            .get()
+            .saturating_sub(self.debt_on_auction.get())
+    }
+
+    // 设置债务拍卖参数
+    pub(crate) fn _set_debt_auction_params(
+        &mut self,
+        token: Address,
+        lot: U256,
+        bid: U256,
+        duration: U256,
+        min_decrease: U256,
+    ) -> Result<(), DSCEngineError> {
+        if token.is_zero()
+            || lot == U256::ZERO
+            || bid == U256::ZERO
+            || duration == U256::ZERO
+            || min_decrease >= U256::from(BPS)
+        {
+            return Err(DSCEngineError::InvalidDebtAuctionParams(
+                InvalidDebtAuctionParams {},
+            ));
+        }
+        self.debt_auction_token.set(token);
+        self.debt_auction_lot.set(lot);
+        self.debt_auction_bid.set(bid);
+        self.debt_auction_duration.set(duration);
+        self.debt_auction_min_decrease.set(min_decrease);
+        Ok(())
+    }
+
+    // 先用保险基金冲抵坏账，剩余坏账足够时发起一场债务拍卖，返回拍卖编号
+    pub(crate) fn _kick_debt_auction(&mut self) -> Result<U256, DSCEngineError> {
+        let bid = self.debt_auction_bid.get();
+        if bid == U256::ZERO {
+            return Err(DSCEngineError::InvalidDebtAuctionParams(
+                InvalidDebtAuctionParams {},
+            ));
+        }
+        self._cover_bad_debt(U256::MAX)?;
+        if self._unreserved_bad_debt() < bid {
+            return Err(DSCEngineError::NoDebtToAuction(NoDebtToAuction {}));
+        }
+        self.debt_on_auction.set(self.debt_on_auction.get() + bid);
+        let id = self.debt_auction_count.get() + U256::from(1);
+        self.debt_auction_count.set(id);
+        let lot = self.debt_auction_lot.get();
+        let ends_at = U256::from(block::timestamp()) + self.debt_auction_duration.get();
+        let mut auction = self.debt_auctions.setter(id);
+        auction.lot.set(lot);
+        auction.bid.set(bid);
+        auction.ends_at.set(ends_at);
+        evm::log(DebtAuctionKicked {
+            id,
+            lot,
+            bid,
+            endsAt: ends_at,
+        });
+        Ok(id)
+    }
+
+    // 以更少的协议代币数量出价
+    pub(crate) fn _bid_debt_auction(&mut self, id: U256, lot: U256) -> Result<(), DSCEngineError> {
+        let auction = self.debt_auctions.getter(id);
+        let bid = auction.bid.get();
+        if bid == U256::ZERO {
+            return Err(DSCEngineError::DebtAuctionNotFound(DebtAuctionNotFound {}));
+        }
+        if U256::from(block::timestamp()) >= auction.ends_at.get() {
+            return Err(DSCEngineError::DebtAuctionClosed(DebtAuctionClosed {}));
+        }
+        let max_lot = (auction.lot.get()
+            * (U256::from(BPS) - self.debt_auction_min_decrease.get()))
+            / U256::from(BPS);
+        if lot > max_lot {
+            return Err(DSCEngineError::InsufficientLotDecrease(
+                InsufficientLotDecrease { _0: max_lot },
+            ));
+        }
+        let previous_bidder = auction.bidder.get();
+        let bidder = msg_sender();
+        let dsc = IDecentralizedStableCoin::new(self.dsc.get());
+        if previous_bidder.is_zero() {
+            // 首次出价：募集的 DSC 立即销毁并冲抵坏账
+            let transferred = dsc.transfer_from(Call::new(), bidder, contract::address(), bid);
+            if !matches!(transferred, Ok(true)) {
+                return Err(DSCEngineError::TransferFailed(TransferFailed {}));
+            }
+            IDecentralizedStableCoin::new(self.dsc.get())
+                .burn(Call::new(), bid)
+                .map_err(|_| DSCEngineError::BurnFailed(BurnFailed {}))?;
+            self.system_bad_debt
+                .set(self.system_bad_debt.get().saturating_sub(bid));
+            self.debt_on_auction
+                .set(self.debt_on_auction.get().saturating_sub(bid));
+        } else {
+            // 之后的出价者把同等数量的 DSC 退给被超越的出价者
+            let transferred = dsc.transfer_from(Call::new(), bidder, previous_bidder, bid);
+            if !matches!(transferred, Ok(true)) {
+                return Err(DSCEngineError::TransferFailed(TransferFailed {}));
+            }
+        }
+        let mut auction = self.debt_auctions.setter(id);
+        auction.bidder.set(bidder);
+        auction.lot.set(lot);
+        evm::log(DebtAuctionBid { id, bidder, lot });
+        Ok(())
+    }
+
+    // 结算已结束的拍卖：为获胜者增发协议代币，无人出价时释放占用的坏账
+    pub(crate) fn _settle_debt_auction(&mut self, id: U256) -> Result<(), DSCEngineError> {
+        let auction = self.debt_auctions.getter(id);
+        let bid = auction.bid.get();
+        if bid == U256::ZERO {
+            return Err(DSCEngineError::DebtAuctionNotFound(DebtAuctionNotFound {}));
+        }
+        if U256::from(block::timestamp()) < auction.ends_at.get() {
+            return Err(DSCEngineError::DebtAuctionClosed(DebtAuctionClosed {}));
+        }
+        let winner = auction.bidder.get();
+        let lot = auction.lot.get();
+        {
+            let mut auction = self.debt_auctions.setter(id);
+            auction.bidder.set(Address::ZERO);
+            auction.lot.set(U256::ZERO);
+            auction.bid.set(U256::ZERO);
+            auction.ends_at.set(U256::ZERO);
+        }
+        if winner.is_zero() {
+            self.debt_on_auction
+                .set(self.debt_on_auction.get().saturating_sub(bid));
+        } else {
+            let minted =
+                IProtocolToken::new(self.debt_auction_token.get()).mint(Call::new(), winner, lot);
+            if !matches!(minted, Ok(true)) {
+                return Err(DSCEngineError::MintFailed(MintFailed {}));
+            }
+        }
+        evm::log(DebtAuctionSettled { id, winner, lot });
+        Ok(())
+    }
+}
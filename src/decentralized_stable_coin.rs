@@ -1,25 +1,74 @@
-use alloy_primitives::{Address, U256};
+use alloc::string::String;
+use alloy_primitives::{Address, FixedBytes, U256};
 use alloy_sol_types::sol;
-use stylus_sdk::{call::MethodError, msg, prelude::*, storage::StorageAddress};
+use stylus_sdk::{
+    call::MethodError,
+    evm, msg,
+    prelude::*,
+    storage::{StorageAddress, StorageArray, StorageMap, StorageType, StorageU256},
+};
 
-use crate::erc20::{Erc20, Erc20Error, Erc20Params};
+use crate::bridge_limits::BridgeLimit;
+use crate::erc165::dsc_supports_interface;
+use crate::erc20::{
+    Erc20, Erc20Error, Erc20Params, InsufficientAllowance, InsufficientBalance, MintFailed,
+};
+use crate::erc20_votes::{FutureLookup, Votes};
 
 sol! {
+    // 合规角色事件：记录新的合规管理地址
+    event ComplianceUpdated(address indexed compliance);
+    // 黑名单事件：记录地址被加入或移出黑名单
+    event BlacklistUpdated(address indexed account, bool blacklisted);
+    // 跨链桥额度事件：记录跨链桥的铸造和销毁额度上限
+    event BridgeLimitsSet(uint256 mintingLimit, uint256 burningLimit, address indexed bridge);
+    // 供应上限事件：记录新的总供应上限
+    event MaxSupplyUpdated(uint256 maxSupply);
+    // 转账钩子事件：记录新登记的转账钩子，为零地址表示移除
+    event TransferHookUpdated(address indexed hook);
+
     error MustBeMoreThanZero();
     error BurnAmountExceedsBalance();
     error NotZeroAddress();
     error UnknownError();
     error NotOwner();
+    error NotCompliance();
+    error Blacklisted(address account);
+    error NotHighEnoughLimits();
+    error MaxSupplyExceeded(uint256 maxSupply);
+    error TransferHookFailed();
 }
 
 sol_storage! {
+    #[cfg_attr(feature = "dsc", entrypoint)]
     pub struct DecentralizedStableCoin {
         #[borrow]
         Erc20<StylusTokenParams> erc20;
         address owner;
+        // 存储布局版本，存储布局变化时递增
+        uint256 storage_version;
+        // 合规角色：管理黑名单的地址，为零时不能修改黑名单
+        address compliance;
+        // 黑名单：名单中的地址不能转账、接收、铸造或销毁 DSC
+        mapping(address => bool) blacklisted;
+        // 跨链桥铸造额度：跨链桥地址 => 额度
+        mapping(address => BridgeLimit) minting_limits;
+        // 跨链桥销毁额度：跨链桥地址 => 额度
+        mapping(address => BridgeLimit) burning_limits;
+        // 总供应上限：为零时不限制
+        uint256 max_supply;
+        // 投票权委托和历史检查点
+        Votes votes;
+        // 转账钩子：余额变化后通知的合约，为零时不通知
+        address transfer_hook;
+        // 预留槽位：供后续版本追加状态，追加字段时相应缩小
+        uint256[39] storage_gap;
     }
 }
 
+// 当前存储布局版本
+pub const DSC_STORAGE_VERSION: u64 = 1;
+
 /// Immutable definitions
 pub struct StylusTokenParams;
 impl Erc20Params for StylusTokenParams {
@@ -35,7 +84,26 @@ pub enum DecentralizedStableCoinError {
     NotZeroAddress(NotZeroAddress),
     UnknownError(UnknownError),
     NotOwner(NotOwner),
-    Erc20Error(Erc20Error),
+    NotCompliance(NotCompliance),
+    Blacklisted(Blacklisted),
+    NotHighEnoughLimits(NotHighEnoughLimits),
+    MaxSupplyExceeded(MaxSupplyExceeded),
+    FutureLookup(FutureLookup),
+    TransferHookFailed(TransferHookFailed),
+    InsufficientBalance(InsufficientBalance),
+    InsufficientAllowance(InsufficientAllowance),
+    MintFailed(MintFailed),
+}
+
+// ERC-20 错误逐个展开为稳定币错误，导出的 ABI 中才能列出具体的错误定义
+impl From<Erc20Error> for DecentralizedStableCoinError {
+    fn from(e: Erc20Error) -> Self {
+        match e {
+            Erc20Error::InsufficientBalance(e) => Self::InsufficientBalance(e),
+            Erc20Error::InsufficientAllowance(e) => Self::InsufficientAllowance(e),
+            Erc20Error::MintFailed(e) => Self::MintFailed(e),
+        }
+    }
 }
 
 impl MethodError for DecentralizedStableCoinError {
@@ -45,9 +113,25 @@ impl MethodError for DecentralizedStableCoinError {
 }
 
 #[public]
+#[inherit(Erc20<StylusTokenParams>)]
 impl DecentralizedStableCoin {
-    pub fn constructor(&mut self) {
+    pub fn constructor(&mut self) -> Result<(), DecentralizedStableCoinError> {
+        // owner 只能设置一次，由引擎在初始化时调用
+        if !self.owner.get().is_zero() {
+            return Err(DecentralizedStableCoinError::NotOwner(NotOwner {}));
+        }
         self.owner.set(msg::sender());
+        self.storage_version.set(U256::from(DSC_STORAGE_VERSION));
+        Ok(())
+    }
+
+    pub fn storage_version(&self) -> U256 {
+        self.storage_version.get()
+    }
+
+    /// ERC-165 接口检测：声明支持 ERC-165 和 ERC-20
+    pub fn supports_interface(&self, interface_id: FixedBytes<4>) -> bool {
+        dsc_supports_interface(interface_id)
     }
 
     pub fn new(owner: Address) -> Result<(), DecentralizedStableCoinError> {
@@ -56,8 +140,50 @@ impl DecentralizedStableCoin {
         Ok(())
     }
 
-    pub fn burn(&mut self, amount: U256) -> Result<(), DecentralizedStableCoinError> {
+    /// 设置合规角色，只能由 owner（引擎）调用
+    pub fn set_compliance(
+        &mut self,
+        compliance: Address,
+    ) -> Result<(), DecentralizedStableCoinError> {
         self.only_owner()?;
+        self.compliance.set(compliance);
+        evm::log(ComplianceUpdated { compliance });
+        Ok(())
+    }
+
+    /// 把地址加入或移出黑名单，只能由合规角色调用
+    pub fn set_blacklisted(
+        &mut self,
+        account: Address,
+        blacklisted: bool,
+    ) -> Result<(), DecentralizedStableCoinError> {
+        if self.compliance.get().is_zero() || msg::sender() != self.compliance.get() {
+            return Err(DecentralizedStableCoinError::NotCompliance(
+                NotCompliance {},
+            ));
+        }
+        self.blacklisted.setter(account).set(blacklisted);
+        evm::log(BlacklistUpdated {
+            account,
+            blacklisted,
+        });
+        Ok(())
+    }
+
+    pub fn compliance(&self) -> Address {
+        self.compliance.get()
+    }
+
+    pub fn is_blacklisted(&self, account: Address) -> bool {
+        self.blacklisted.get(account)
+    }
+
+    /// 销毁调用者持有的 DSC：owner（引擎）不受限制，跨链桥（如 CCIP 代币池）使用其销毁额度
+    pub fn burn(&mut self, amount: U256) -> Result<(), DecentralizedStableCoinError> {
+        if msg::sender() != self.owner.get() {
+            self._use_burning_limit(msg::sender(), amount)?;
+        }
+        self.not_blacklisted(msg::sender())?;
 
         if amount == U256::ZERO {
             return Err(DecentralizedStableCoinError::MustBeMoreThanZero(
@@ -82,15 +208,18 @@ impl DecentralizedStableCoin {
                 }
                 _ => DecentralizedStableCoinError::UnknownError(UnknownError {}),
             })?;
-        Ok(())
+        self._after_token_transfer(msg::sender(), Address::ZERO, amount)
     }
 
+    /// 铸造 DSC：owner（引擎）不受限制，跨链桥使用其铸造额度
     pub fn mint(
         &mut self,
         to: Address,
         amount: U256,
     ) -> Result<bool, DecentralizedStableCoinError> {
-        self.only_owner()?;
+        if msg::sender() != self.owner.get() {
+            self._use_minting_limit(msg::sender(), amount)?;
+        }
 
         if amount == U256::ZERO {
             return Err(DecentralizedStableCoinError::MustBeMoreThanZero(
@@ -102,13 +231,101 @@ impl DecentralizedStableCoin {
                 NotZeroAddress {},
             ));
         }
+        self.not_blacklisted(to)?;
+        let max_supply = self.max_supply.get();
+        if max_supply != U256::ZERO && self.erc20.total_supply.get() + amount > max_supply {
+            return Err(DecentralizedStableCoinError::MaxSupplyExceeded(
+                MaxSupplyExceeded {
+                    maxSupply: max_supply,
+                },
+            ));
+        }
 
         self.erc20
             .mint(to, amount)
             .map_err(|_| DecentralizedStableCoinError::UnknownError(UnknownError {}))?;
+        self._after_token_transfer(Address::ZERO, to, amount)?;
         Ok(true)
     }
 
+    /// 跨链桥销毁 `from` 的 DSC，使用其销毁额度；代他人销毁时消耗授权
+    #[selector(name = "burn")]
+    pub fn burn_account(
+        &mut self,
+        from: Address,
+        amount: U256,
+    ) -> Result<(), DecentralizedStableCoinError> {
+        self._bridge_burn(msg::sender(), from, amount)
+    }
+
+    /// 同 `burn(address,uint256)`，兼容 CCIP 代币池的销毁接口
+    pub fn burn_from(
+        &mut self,
+        from: Address,
+        amount: U256,
+    ) -> Result<(), DecentralizedStableCoinError> {
+        self._bridge_burn(msg::sender(), from, amount)
+    }
+
+    /// 设置跨链桥的铸造和销毁额度上限，只能由 owner（引擎）调用
+    pub fn set_limits(
+        &mut self,
+        bridge: Address,
+        minting_limit: U256,
+        burning_limit: U256,
+    ) -> Result<(), DecentralizedStableCoinError> {
+        self.only_owner()?;
+        self._set_bridge_limits(bridge, minting_limit, burning_limit);
+        evm::log(BridgeLimitsSet {
+            mintingLimit: minting_limit,
+            burningLimit: burning_limit,
+            bridge,
+        });
+        Ok(())
+    }
+
+    /// 设置总供应上限，设为零时不限制，只能由 owner（引擎）调用
+    pub fn set_max_supply(&mut self, max_supply: U256) -> Result<(), DecentralizedStableCoinError> {
+        self.only_owner()?;
+        self.max_supply.set(max_supply);
+        evm::log(MaxSupplyUpdated {
+            maxSupply: max_supply,
+        });
+        Ok(())
+    }
+
+    /// 登记转账钩子，设为零地址时移除，只能由 owner（引擎）调用
+    pub fn set_transfer_hook(&mut self, hook: Address) -> Result<(), DecentralizedStableCoinError> {
+        self.only_owner()?;
+        self.transfer_hook.set(hook);
+        evm::log(TransferHookUpdated { hook });
+        Ok(())
+    }
+
+    pub fn transfer_hook(&self) -> Address {
+        self.transfer_hook.get()
+    }
+
+    pub fn max_supply(&self) -> U256 {
+        self.max_supply.get()
+    }
+
+    pub fn minting_max_limit_of(&self, bridge: Address) -> U256 {
+        self.minting_limits.getter(bridge).max_limit.get()
+    }
+
+    pub fn burning_max_limit_of(&self, bridge: Address) -> U256 {
+        self.burning_limits.getter(bridge).max_limit.get()
+    }
+
+    pub fn minting_current_limit_of(&self, bridge: Address) -> U256 {
+        self.minting_limits.getter(bridge).current()
+    }
+
+    pub fn burning_current_limit_of(&self, bridge: Address) -> U256 {
+        self.burning_limits.getter(bridge).current()
+    }
+
     fn only_owner(&self) -> Result<(), DecentralizedStableCoinError> {
         if msg::sender() != self.owner.get() {
             return Err(DecentralizedStableCoinError::NotOwner(NotOwner {}));
@@ -116,15 +333,92 @@ impl DecentralizedStableCoin {
         Ok(())
     }
 
+    pub(crate) fn not_blacklisted(
+        &self,
+        account: Address,
+    ) -> Result<(), DecentralizedStableCoinError> {
+        if self.blacklisted.get(account) {
+            return Err(DecentralizedStableCoinError::Blacklisted(Blacklisted {
+                account,
+            }));
+        }
+        Ok(())
+    }
+
+    pub fn transfer(
+        &mut self,
+        to: Address,
+        value: U256,
+    ) -> Result<bool, DecentralizedStableCoinError> {
+        self.not_blacklisted(msg::sender())?;
+        self.not_blacklisted(to)?;
+        self.erc20.transfer(to, value)?;
+        self._after_token_transfer(msg::sender(), to, value)?;
+        Ok(true)
+    }
+
     pub fn transfer_from(
         &mut self,
         from: Address,
         to: Address,
         value: U256,
     ) -> Result<bool, DecentralizedStableCoinError> {
-        self.erc20
-            .transfer_from(from, to, value)
-            .map_err(DecentralizedStableCoinError::Erc20Error)
+        self.not_blacklisted(msg::sender())?;
+        self.not_blacklisted(from)?;
+        self.not_blacklisted(to)?;
+        self.erc20.transfer_from(from, to, value)?;
+        self._after_token_transfer(from, to, value)?;
+        Ok(true)
+    }
+
+    /// 把调用者的投票权委托给 `delegatee`（委托给自己才能用自己的余额投票）
+    pub fn delegate(&mut self, delegatee: Address) {
+        let balance = self.erc20.balance_of(msg::sender());
+        self.votes.delegate(msg::sender(), delegatee, balance);
+    }
+
+    pub fn delegates(&self, account: Address) -> Address {
+        self.votes.delegates(account)
+    }
+
+    /// 委托对象当前的票数
+    pub fn get_votes(&self, account: Address) -> U256 {
+        self.votes.get_votes(account)
+    }
+
+    /// 委托对象在历史区块结束时的票数，只能查询已经结束的区块
+    pub fn get_past_votes(
+        &self,
+        account: Address,
+        timepoint: U256,
+    ) -> Result<U256, DecentralizedStableCoinError> {
+        self.votes
+            .get_past_votes(account, timepoint)
+            .map_err(DecentralizedStableCoinError::FutureLookup)
+    }
+
+    /// 历史区块结束时的总供应量，只能查询已经结束的区块
+    pub fn get_past_total_supply(
+        &self,
+        timepoint: U256,
+    ) -> Result<U256, DecentralizedStableCoinError> {
+        self.votes
+            .get_past_total_supply(timepoint)
+            .map_err(DecentralizedStableCoinError::FutureLookup)
+    }
+
+    pub fn num_checkpoints(&self, account: Address) -> U256 {
+        self.votes.num_checkpoints(account)
+    }
+
+    /// ERC-6372 时钟：检查点按区块号记录
+    pub fn clock(&self) -> U256 {
+        Votes::clock()
+    }
+
+    #[selector(name = "CLOCK_MODE")]
+    pub fn clock_mode(&self) -> String {
+        "mode=blocknumber&from=default".into()
     }
 }
 
@@ -134,6 +428,15 @@ impl Default for DecentralizedStableCoin {
             Self {
                 erc20: Erc20::default(),
                 owner: StorageAddress::new(U256::from(0), u8::from(0)),
+                storage_version: StorageU256::new(U256::from(0), 0),
+                compliance: StorageAddress::new(U256::from(0), 0),
+                blacklisted: StorageMap::new(U256::from(0), 0),
+                minting_limits: StorageMap::new(U256::from(0), 0),
+                burning_limits: StorageMap::new(U256::from(0), 0),
+                max_supply: StorageU256::new(U256::from(0), 0),
+                votes: Votes::new(U256::from(0), 0),
+                transfer_hook: StorageAddress::new(U256::from(0), 0),
+                storage_gap: StorageArray::new(U256::from(0), 0),
             }
         }
     }
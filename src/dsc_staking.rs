@@ -0,0 +1,340 @@
+//! DSC 质押：质押 DSC（或 LP 代币）分享协议收入
+//!
+//! 协议收入以奖励代币（通常为 DSC）转入本合约，例如把引擎的国库地址设为本合约，
+//! 由 `collectTreasuryFees` 推送手续费。收入按周期分配：每个周期结束后任何人可调用 `distribute()`，
+//! 把上个周期收到、尚未分配的收入在下一个周期内按秒匀速释放，质押者按质押数量和质押时长分得。
+//! 取回质押需先申请，进入冷却期的数量不再分得收益，冷却期结束后才能取回。
+//! 质押代币与奖励代币相同时，质押本金和已承诺释放的收入都不计入新收入。
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::sol;
+use stylus_sdk::{
+    block,
+    call::{Call, MethodError},
+    contract, evm, msg,
+    prelude::*,
+};
+
+sol! {
+    event Staked(address indexed user, uint256 amount);
+    event UnstakeRequested(address indexed user, uint256 amount, uint256 unlockAt);
+    event Unstaked(address indexed user, uint256 amount);
+    event RewardPaid(address indexed user, uint256 amount);
+    event EpochStarted(uint256 indexed epoch, uint256 amount, uint256 rewardRate, uint256 periodFinish);
+
+    error NotOwner();
+    error ZeroAmount();
+    error InsufficientStake();
+    error CooldownActive(uint256);
+    error EpochNotEnded(uint256);
+    error TransferFailed();
+}
+
+sol_interface! {
+    interface IERC20 {
+        function transfer(address to, uint256 value) external returns (bool);
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+        function balanceOf(address account) external view returns (uint256);
+    }
+}
+
+sol_storage! {
+    #[cfg_attr(feature = "dsc-staking", entrypoint)]
+    pub struct DscStaking {
+        address stake_token;
+        address reward_token;
+        address owner;
+        uint256 epoch_duration;
+        uint256 cooldown;
+        uint256 total_staked;
+        mapping(address => uint256) staked;
+        uint256 total_cooling;
+        mapping(address => uint256) cooling;
+        mapping(address => uint256) unlock_at;
+        uint256 epoch;
+        uint256 reward_rate;
+        uint256 period_finish;
+        uint256 last_update;
+        uint256 reward_per_share;
+        uint256 reserved_rewards;
+        mapping(address => uint256) reward_per_share_paid;
+        mapping(address => uint256) rewards;
+    }
+}
+
+#[derive(SolidityError)]
+pub enum DscStakingError {
+    NotOwner(NotOwner),
+    ZeroAmount(ZeroAmount),
+    InsufficientStake(InsufficientStake),
+    CooldownActive(CooldownActive),
+    EpochNotEnded(EpochNotEnded),
+    TransferFailed(TransferFailed),
+}
+
+impl MethodError for DscStakingError {
+    fn encode(self) -> Vec<u8> {
+        From::from(self)
+    }
+}
+
+#[public]
+impl DscStaking {
+    pub fn constructor(
+        &mut self,
+        stake_token: Address,
+        reward_token: Address,
+        epoch_duration: U256,
+        cooldown: U256,
+    ) -> Result<(), DscStakingError> {
+        if !self.owner.get().is_zero() {
+            return Err(DscStakingError::NotOwner(NotOwner {}));
+        }
+        if epoch_duration == U256::ZERO {
+            return Err(DscStakingError::ZeroAmount(ZeroAmount {}));
+        }
+        self.owner.set(msg::sender());
+        self.stake_token.set(stake_token);
+        self.reward_token.set(reward_token);
+        self.epoch_duration.set(epoch_duration);
+        self.cooldown.set(cooldown);
+        self.last_update.set(U256::from(block::timestamp()));
+        Ok(())
+    }
+
+    /// 设置分配周期（秒）和取回质押的冷却期（秒），从下一个周期开始生效
+    pub fn set_params(
+        &mut self,
+        epoch_duration: U256,
+        cooldown: U256,
+    ) -> Result<(), DscStakingError> {
+        if msg::sender() != self.owner.get() {
+            return Err(DscStakingError::NotOwner(NotOwner {}));
+        }
+        if epoch_duration == U256::ZERO {
+            return Err(DscStakingError::ZeroAmount(ZeroAmount {}));
+        }
+        self.epoch_duration.set(epoch_duration);
+        self.cooldown.set(cooldown);
+        Ok(())
+    }
+
+    /// 质押 `amount` 数量的质押代币
+    pub fn stake(&mut self, amount: U256) -> Result<(), DscStakingError> {
+        if amount == U256::ZERO {
+            return Err(DscStakingError::ZeroAmount(ZeroAmount {}));
+        }
+        let sender = msg::sender();
+        self._update_reward(sender);
+        if IERC20::new(self.stake_token.get())
+            .transfer_from(Call::new(), sender, contract::address(), amount)
+            .is_err()
+        {
+            return Err(DscStakingError::TransferFailed(TransferFailed {}));
+        }
+        let staked = self.staked.get(sender);
+        self.staked.setter(sender).set(staked + amount);
+        self.total_staked.set(self.total_staked.get() + amount);
+        evm::log(Staked {
+            user: sender,
+            amount,
+        });
+        Ok(())
+    }
+
+    /// 申请取回 `amount` 数量的质押，进入冷却期后不再分得收益，返回可以取回的时间
+    pub fn request_unstake(&mut self, amount: U256) -> Result<U256, DscStakingError> {
+        let sender = msg::sender();
+        let staked = self.staked.get(sender);
+        if amount == U256::ZERO || amount > staked {
+            return Err(DscStakingError::InsufficientStake(InsufficientStake {}));
+        }
+        self._update_reward(sender);
+        self.staked.setter(sender).set(staked - amount);
+        self.total_staked.set(self.total_staked.get() - amount);
+        let cooling = self.cooling.get(sender);
+        self.cooling.setter(sender).set(cooling + amount);
+        self.total_cooling.set(self.total_cooling.get() + amount);
+        // 追加申请时重新计算整笔冷却中数量的解锁时间
+        let unlock_at = U256::from(block::timestamp()) + self.cooldown.get();
+        self.unlock_at.setter(sender).set(unlock_at);
+        evm::log(UnstakeRequested {
+            user: sender,
+            amount,
+            unlockAt: unlock_at,
+        });
+        Ok(unlock_at)
+    }
+
+    /// 冷却期结束后取回全部冷却中的质押，返回取回的数量
+    pub fn unstake(&mut self) -> Result<U256, DscStakingError> {
+        let sender = msg::sender();
+        let amount = self.cooling.get(sender);
+        if amount == U256::ZERO {
+            return Err(DscStakingError::InsufficientStake(InsufficientStake {}));
+        }
+        let unlock_at = self.unlock_at.get(sender);
+        if U256::from(block::timestamp()) < unlock_at {
+            return Err(DscStakingError::CooldownActive(CooldownActive {
+                _0: unlock_at,
+            }));
+        }
+        self.cooling.setter(sender).set(U256::ZERO);
+        self.total_cooling.set(self.total_cooling.get() - amount);
+        self._transfer(self.stake_token.get(), sender, amount)?;
+        evm::log(Unstaked {
+            user: sender,
+            amount,
+        });
+        Ok(amount)
+    }
+
+    /// 领取已分得的收益，返回领取的数量
+    pub fn claim(&mut self) -> Result<U256, DscStakingError> {
+        let sender = msg::sender();
+        self._update_reward(sender);
+        let amount = self.rewards.get(sender);
+        if amount == U256::ZERO {
+            return Ok(U256::ZERO);
+        }
+        self.rewards.setter(sender).set(U256::ZERO);
+        self.reserved_rewards
+            .set(self.reserved_rewards.get() - amount);
+        self._transfer(self.reward_token.get(), sender, amount)?;
+        evm::log(RewardPaid {
+            user: sender,
+            amount,
+        });
+        Ok(amount)
+    }
+
+    /// 当前周期结束后开始新周期：把尚未分配的收入在新周期内匀速释放，返回本周期分配的数量
+    pub fn distribute(&mut self) -> Result<U256, DscStakingError> {
+        let now = U256::from(block::timestamp());
+        let period_finish = self.period_finish.get();
+        if now < period_finish {
+            return Err(DscStakingError::EpochNotEnded(EpochNotEnded {
+                _0: period_finish,
+            }));
+        }
+        self._update_reward(Address::ZERO);
+        let duration = self.epoch_duration.get();
+        let reward_rate = self._available_revenue()? / duration;
+        let amount = reward_rate * duration;
+        self.reserved_rewards
+            .set(self.reserved_rewards.get() + amount);
+        self.reward_rate.set(reward_rate);
+        self.last_update.set(now);
+        self.period_finish.set(now + duration);
+        let epoch = self.epoch.get() + U256::from(1);
+        self.epoch.set(epoch);
+        evm::log(EpochStarted {
+            epoch,
+            amount,
+            rewardRate: reward_rate,
+            periodFinish: now + duration,
+        });
+        Ok(amount)
+    }
+
+    /// 质押者已分得、尚未领取的收益
+    pub fn earned(&self, user: Address) -> U256 {
+        let paid = self.reward_per_share_paid.get(user);
+        self.rewards.get(user)
+            + (self.staked.get(user) * (self._reward_per_share() - paid)) / Self::precision()
+    }
+
+    /// 尚未分配的收入：奖励代币余额扣除质押本金和已承诺释放的收入
+    pub fn available_revenue(&self) -> Result<U256, DscStakingError> {
+        self._available_revenue()
+    }
+
+    pub fn get_stake(&self, user: Address) -> (U256, U256, U256) {
+        (
+            self.staked.get(user),
+            self.cooling.get(user),
+            self.unlock_at.get(user),
+        )
+    }
+
+    pub fn get_epoch(&self) -> (U256, U256, U256) {
+        (
+            self.epoch.get(),
+            self.reward_rate.get(),
+            self.period_finish.get(),
+        )
+    }
+
+    pub fn get_params(&self) -> (U256, U256) {
+        (self.epoch_duration.get(), self.cooldown.get())
+    }
+
+    pub fn total_staked(&self) -> U256 {
+        self.total_staked.get()
+    }
+}
+
+// 内部辅助函数：不对外暴露
+impl DscStaking {
+    fn precision() -> U256 {
+        U256::from(10).pow(U256::from(18))
+    }
+
+    // 当前每单位质押累计的收益：只累计到本周期结束，没有质押期间释放的收入不计入
+    fn _reward_per_share(&self) -> U256 {
+        let stored = self.reward_per_share.get();
+        let total_staked = self.total_staked.get();
+        if total_staked == U256::ZERO {
+            return stored;
+        }
+        let end = U256::from(block::timestamp()).min(self.period_finish.get());
+        let elapsed = end.saturating_sub(self.last_update.get());
+        stored + (elapsed * self.reward_rate.get() * Self::precision()) / total_staked
+    }
+
+    // 结算全局累计收益，并结算 user 已分得的收益（user 为零地址时只结算全局）
+    fn _update_reward(&mut self, user: Address) {
+        let total_staked = self.total_staked.get();
+        let now = U256::from(block::timestamp());
+        let end = now.min(self.period_finish.get());
+        // 没有质押期间释放的收入退回未分配部分，留到下个周期
+        if total_staked == U256::ZERO && end > self.last_update.get() {
+            let unreleased = (end - self.last_update.get()) * self.reward_rate.get();
+            self.reserved_rewards
+                .set(self.reserved_rewards.get().saturating_sub(unreleased));
+        }
+        let reward_per_share = self._reward_per_share();
+        self.reward_per_share.set(reward_per_share);
+        self.last_update.set(end.max(self.last_update.get()));
+        if !user.is_zero() {
+            let earned = self.earned(user);
+            self.rewards.setter(user).set(earned);
+            self.reward_per_share_paid
+                .setter(user)
+                .set(reward_per_share);
+        }
+    }
+
+    fn _available_revenue(&self) -> Result<U256, DscStakingError> {
+        let reward_token = self.reward_token.get();
+        let balance = IERC20::new(reward_token)
+            .balance_of(Call::new(), contract::address())
+            .map_err(|_| DscStakingError::TransferFailed(TransferFailed {}))?;
+        let mut committed = self.reserved_rewards.get();
+        if reward_token == self.stake_token.get() {
+            committed += self.total_staked.get() + self.total_cooling.get();
+        }
+        Ok(balance.saturating_sub(committed))
+    }
+
+    fn _transfer(&self, token: Address, to: Address, amount: U256) -> Result<(), DscStakingError> {
+        if IERC20::new(token)
+            .transfer(Call::new(), to, amount)
+            .is_err()
+        {
+            return Err(DscStakingError::TransferFailed(TransferFailed {}));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+//! 按波动率动态调整的清算阈值
+//!
+//! 治理为抵押品设置阈值的上下限、触发收紧的价格偏离、每次调整的步长和两次调整的最短间隔。
+//! 任何 keeper 都可以调用 `poke_dynamic_threshold`：读取预言机最新一轮相对上一轮的价格偏离，
+//! 偏离达到触发值时把阈值下调一个步长，否则上调一个步长，始终保持在上下限之间。
+//! 市场剧烈波动时仓位可借额度随之自动收紧，行情平稳后逐步恢复。
+//! 启用动态阈值的抵押品以调整后的阈值替代风险等级或默认阈值。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    block,
+    call::Call,
+    evm,
+    prelude::*,
+};
+
+use crate::{
+    casting::to_u256, psm::BPS, DSCEngine, DSCEngineError, DynamicLtvCooldown,
+    DynamicLtvNotConfigured, DynamicThresholdUpdated, IAggregatorV3, InvalidDynamicLtv,
+    PriceFeedError, LIQUIDATION_PRECISION,
+};
+
+sol_storage! {
+    // 单个抵押品的动态阈值参数，阈值上限为零表示未启用
+    pub struct LtvAdjuster {
+        uint256 min_threshold;               // 阈值下限（清算精度）
+        uint256 max_threshold;               // 阈值上限（清算精度），也是启用时的初始阈值
+        uint256 volatility_trigger;          // 触发收紧的价格偏离（基点）
+        uint256 step;                        // 每次调整的步长（清算精度）
+        uint256 cooldown;                    // 两次调整之间的最短秒数
+        uint256 threshold;                   // 当前生效的阈值（清算精度）
+        uint256 last_poke;                   // 上次调整的时间戳
+    }
+}
+
+impl DSCEngine {
+    // 抵押品当前生效的动态阈值，未启用时为 None
+    pub(crate) fn _dynamic_threshold(&self, token: Address) -> Option<U256> {
+        let adjuster = self.ltv_adjusters.getter(token);
+        if adjuster.max_threshold.get() == U256::ZERO {
+            None
+        } else {
+            Some(adjuster.threshold.get())
+        }
+    }
+
+    // 预言机最新一轮相对上一轮的价格偏离（基点）
+    pub(crate) fn _price_deviation_bps(&self, token: Address) -> Result<U256, DSCEngineError> {
+        let feed = self.price_feeds.get(token);
+        let round = self
+            ._feed_round(feed)
+            .ok_or(DSCEngineError::PriceFeedError(PriceFeedError {}))?;
+        // 没有上一轮时视为没有偏离
+        if round.round_id == 0 {
+            return Ok(U256::ZERO);
+        }
+        let Ok((_, previous_answer, _, _, _)) =
+            IAggregatorV3::new(feed).get_round_data(Call::new(), round.round_id - 1)
+        else {
+            return Ok(U256::ZERO);
+        };
+        let price = to_u256(round.answer)?;
+        let previous_price = to_u256(previous_answer)?;
+        if previous_price == U256::ZERO {
+            return Ok(U256::ZERO);
+        }
+        Ok((price.abs_diff(previous_price) * U256::from(BPS)) / previous_price)
+    }
+
+    // 设置抵押品的动态阈值参数，阈值上限为零时停用
+    pub(crate) fn _set_dynamic_ltv(
+        &mut self,
+        token: Address,
+        min_threshold: U256,
+        max_threshold: U256,
+        volatility_trigger: U256,
+        step: U256,
+        cooldown: U256,
+    ) -> Result<(), DSCEngineError> {
+        // 上限不能达到 100%，且按最高清算奖励清算后健康因子必须能够改善
+        if max_threshold != U256::ZERO
+            && (min_threshold == U256::ZERO
+                || min_threshold > max_threshold
+                || step == U256::ZERO
+                || volatility_trigger == U256::ZERO
+                || max_threshold * (LIQUIDATION_PRECISION + self._collateral_max_bonus(token))
+                    >= LIQUIDATION_PRECISION * LIQUIDATION_PRECISION)
+        {
+            return Err(DSCEngineError::InvalidDynamicLtv(InvalidDynamicLtv {}));
+        }
+        let mut adjuster = self.ltv_adjusters.setter(token);
+        adjuster.min_threshold.set(min_threshold);
+        adjuster.max_threshold.set(max_threshold);
+        adjuster.volatility_trigger.set(volatility_trigger);
+        adjuster.step.set(step);
+        adjuster.cooldown.set(cooldown);
+        adjuster.threshold.set(max_threshold);
+        adjuster.last_poke.set(U256::ZERO);
+        Ok(())
+    }
+
+    // 按当前价格偏离调整一次动态阈值，返回调整后的阈值
+    pub(crate) fn _poke_dynamic_threshold(
+        &mut self,
+        token: Address,
+    ) -> Result<U256, DSCEngineError> {
+        let Some(threshold) = self._dynamic_threshold(token) else {
+            return Err(DSCEngineError::DynamicLtvNotConfigured(
+                DynamicLtvNotConfigured {},
+            ));
+        };
+        let now = U256::from(block::timestamp());
+        let adjuster = self.ltv_adjusters.getter(token);
+        let next_poke = adjuster.last_poke.get() + adjuster.cooldown.get();
+        if adjuster.last_poke.get() != U256::ZERO && now < next_poke {
+            return Err(DSCEngineError::DynamicLtvCooldown(DynamicLtvCooldown {
+                _0: next_poke,
+            }));
+        }
+        let min_threshold = adjuster.min_threshold.get();
+        let max_threshold = adjuster.max_threshold.get();
+        let trigger = adjuster.volatility_trigger.get();
+        let step = adjuster.step.get();
+        let deviation = self._price_deviation_bps(token)?;
+        let new_threshold = if deviation >= trigger {
+            threshold.saturating_sub(step).max(min_threshold)
+        } else {
+            (threshold + step).min(max_threshold)
+        };
+        let mut adjuster = self.ltv_adjusters.setter(token);
+        adjuster.threshold.set(new_threshold);
+        adjuster.last_poke.set(now);
+        evm::log(DynamicThresholdUpdated {
+            token,
+            deviationBps: deviation,
+            threshold: new_threshold,
+        });
+        Ok(new_threshold)
+    }
+}
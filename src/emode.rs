@@ -0,0 +1,123 @@
+//! E-mode：相关性抵押品类别的高效模式
+//!
+//! 治理可以设置抵押品类别（如 ETH 相关资产、美元稳定币），每个类别有高于默认值的清算阈值。
+//! 用户选择某个类别后只能存入该类别的抵押品，健康因子按类别阈值计算。
+//! 类别 0 表示未启用 E-mode；类别阈值为零或用户持有类别外的抵押品时，按抵押品自身的清算阈值计算。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256, U8},
+    evm,
+};
+
+use crate::{
+    CollateralEModeCategorySet, DSCEngine, DSCEngineError, EModeCategorySet,
+    EModeCollateralMismatch, InvalidEModeCategory, UserEModeSet, LIQUIDATION_PRECISION,
+};
+
+impl DSCEngine {
+    // 用户适用的清算阈值：E-mode 生效时为类别阈值，否则按抵押品价值加权
+    pub(crate) fn _liquidation_threshold_of(&self, user: Address) -> U256 {
+        self._emode_threshold(user)
+            .unwrap_or_else(|| self._weighted_threshold(user))
+    }
+
+    // 用户生效中的 E-mode 类别阈值
+    pub(crate) fn _emode_threshold(&self, user: Address) -> Option<U256> {
+        let category = self.user_emode_category.get(user);
+        if category == U8::ZERO {
+            return None;
+        }
+        let threshold = self.emode_thresholds.get(category);
+        if threshold == U256::ZERO || !self._collateral_in_emode(user, category) {
+            return None;
+        }
+        Some(threshold)
+    }
+
+    // 用户持有的抵押品是否全部属于指定类别
+    fn _collateral_in_emode(&self, user: Address, category: U8) -> bool {
+        for i in 0..self.collateral_tokens.len() {
+            if let Some(token) = self.collateral_tokens.get(i) {
+                if self.collateral_emode_category.get(token) != category
+                    && self.collateral_deposited.getter(user).get(token) > U256::ZERO
+                {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    // 启用 E-mode 的用户只能存入所选类别的抵押品
+    pub(crate) fn _check_emode_collateral(
+        &self,
+        user: Address,
+        token: Address,
+    ) -> Result<(), DSCEngineError> {
+        let category = self.user_emode_category.get(user);
+        if category != U8::ZERO && self.collateral_emode_category.get(token) != category {
+            return Err(DSCEngineError::EModeCollateralMismatch(
+                EModeCollateralMismatch {},
+            ));
+        }
+        Ok(())
+    }
+
+    // 设置 E-mode 类别的清算阈值，阈值为零表示停用该类别
+    pub(crate) fn _set_emode_category(
+        &mut self,
+        category: u8,
+        threshold: U256,
+    ) -> Result<(), DSCEngineError> {
+        // 类别阈值不能低于默认阈值，也不能达到 100%
+        if category == 0
+            || (threshold != U256::ZERO
+                && (threshold < self._liquidation_threshold()
+                    || threshold >= LIQUIDATION_PRECISION))
+        {
+            return Err(DSCEngineError::InvalidEModeCategory(
+                InvalidEModeCategory {},
+            ));
+        }
+        self.emode_thresholds
+            .setter(U8::from(category))
+            .set(threshold);
+        evm::log(EModeCategorySet {
+            category,
+            threshold,
+        });
+        Ok(())
+    }
+
+    // 设置抵押品所属的 E-mode 类别
+    pub(crate) fn _set_collateral_emode_category(&mut self, token: Address, category: u8) {
+        self.collateral_emode_category
+            .setter(token)
+            .set(U8::from(category));
+        evm::log(CollateralEModeCategorySet { token, category });
+    }
+
+    // 为仓位选择 E-mode 类别，切换后仓位必须保持健康
+    pub(crate) fn _set_user_emode(
+        &mut self,
+        user: Address,
+        category: u8,
+    ) -> Result<(), DSCEngineError> {
+        let key = U8::from(category);
+        if category != 0 {
+            if self.emode_thresholds.get(key) == U256::ZERO {
+                return Err(DSCEngineError::InvalidEModeCategory(
+                    InvalidEModeCategory {},
+                ));
+            }
+            if !self._collateral_in_emode(user, key) {
+                return Err(DSCEngineError::EModeCollateralMismatch(
+                    EModeCollateralMismatch {},
+                ));
+            }
+        }
+        self.user_emode_category.setter(user).set(key);
+        evm::log(UserEModeSet { user, category });
+        self._revert_if_borrow_health_factor_is_broken(user)
+    }
+}
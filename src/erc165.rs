@@ -0,0 +1,63 @@
+//! ERC-165 接口检测：引擎和稳定币合约通过 `supportsInterface` 声明支持的接口
+//!
+//! 接口 ID 为接口中全部函数选择器的异或。`IDSCEngine` 的接口 ID 只覆盖存入、铸造、偿还、赎回、
+//! 清算和仓位查询这组核心函数，治理参数和扩展功能的函数不计入，后续增加函数不会改变已发布的 ID。
+//! 稳定币目前没有实现 permit（ERC-2612）和闪电铸造（ERC-3156），不声明这两个接口。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, FixedBytes, U256},
+    function_selector,
+};
+
+// ERC-165 自身的接口 ID
+pub const ERC165_INTERFACE_ID: u32 = 0x01ffc9a7;
+// ERC-20 的接口 ID
+pub const ERC20_INTERFACE_ID: u32 = 0x36372b07;
+// ERC-721 的接口 ID：引擎同时是金库 NFT 合约
+pub const ERC721_INTERFACE_ID: u32 = 0x80ac58cd;
+// IDSCEngine 核心函数的接口 ID：0xc575ccf6
+pub const DSC_ENGINE_INTERFACE_ID: u32 = xor_selectors(&[
+    function_selector!("depositCollateralAndMintDsc", Address, U256, U256),
+    function_selector!("depositCollateral", Address, U256),
+    function_selector!("redeemCollateralForDsc", Address, U256, U256),
+    function_selector!("redeemCollateral", Address, U256),
+    function_selector!("mintDsc", U256),
+    function_selector!("burnDsc", U256),
+    function_selector!("liquidate", Address, Address, U256),
+    function_selector!("getHealthFactor", Address),
+    function_selector!("getAccountInformation", Address),
+    function_selector!("getAccountCollateralValueInUsd", Address),
+    function_selector!("getUsdValue", Address, U256),
+    function_selector!("getTokenAmountFromUsd", Address, U256),
+    function_selector!("getCollateralTokens"),
+    function_selector!("getCollateralBalanceOfUser", Address, Address),
+    function_selector!("getCollateralTokenPriceFeed", Address),
+    function_selector!("getDsc"),
+]);
+
+// 按选择器计算接口 ID
+pub(crate) const fn xor_selectors(selectors: &[[u8; 4]]) -> u32 {
+    let mut id = 0;
+    let mut i = 0;
+    while i < selectors.len() {
+        id ^= u32::from_be_bytes(selectors[i]);
+        i += 1;
+    }
+    id
+}
+
+// 引擎支持的接口
+pub(crate) fn engine_supports_interface(interface_id: FixedBytes<4>) -> bool {
+    matches!(
+        u32::from_be_bytes(interface_id.0),
+        ERC165_INTERFACE_ID | ERC721_INTERFACE_ID | DSC_ENGINE_INTERFACE_ID
+    )
+}
+
+// 稳定币合约支持的接口
+pub(crate) fn dsc_supports_interface(interface_id: FixedBytes<4>) -> bool {
+    matches!(
+        u32::from_be_bytes(interface_id.0),
+        ERC165_INTERFACE_ID | ERC20_INTERFACE_ID
+    )
+}
@@ -0,0 +1,207 @@
+//! ERC20Votes 风格的投票权委托与历史检查点
+//!
+//! 持币人把投票权委托给自己或他人后，委托对象的票数随持币人余额变化；未委托的余额没有投票权。
+//! 每次票数或总供应量变化时按区块号记录检查点，可以查询任意历史区块结束时的票数和总供应量，
+//! 供链上投票和快照使用。[`Votes`] 只记录投票权，由持有 [`Erc20`](crate::erc20::Erc20) 的代币合约
+//! 在铸造、销毁和转账后调用 `transfer_voting_units` 同步，同一区块内的多次变化合并为一个检查点。
+
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::sol;
+use stylus_sdk::{block, evm, prelude::*, storage::StorageMap};
+
+sol! {
+    // 委托事件：记录委托人更换的委托对象
+    event DelegateChanged(address indexed delegator, address indexed fromDelegate, address indexed toDelegate);
+    // 票数变化事件：记录委托对象变化前后的票数
+    event DelegateVotesChanged(address indexed delegate, uint256 previousVotes, uint256 newVotes);
+
+    error FutureLookup(uint256 timepoint, uint256 clock);
+}
+
+sol_storage! {
+    // 票数检查点：自 from_block 区块起的票数
+    pub struct Checkpoint {
+        uint256 from_block;
+        uint256 votes;
+    }
+
+    // 投票权记录
+    pub struct Votes {
+        // 委托关系：持币人 => 委托对象
+        mapping(address => address) delegates;
+        // 每个委托对象的检查点数量
+        mapping(address => uint256) checkpoint_counts;
+        // 委托对象的检查点：委托对象 => 序号 => 检查点
+        mapping(address => mapping(uint256 => Checkpoint)) checkpoints;
+        // 总供应量的检查点数量
+        uint256 total_checkpoint_count;
+        // 总供应量的检查点：序号 => 检查点
+        mapping(uint256 => Checkpoint) total_checkpoints;
+    }
+}
+
+impl Votes {
+    // 当前时钟：区块号
+    pub(crate) fn clock() -> U256 {
+        U256::from(block::number())
+    }
+
+    pub(crate) fn delegates(&self, account: Address) -> Address {
+        self.delegates.get(account)
+    }
+
+    pub(crate) fn num_checkpoints(&self, account: Address) -> U256 {
+        self.checkpoint_counts.get(account)
+    }
+
+    // 委托对象当前的票数
+    pub(crate) fn get_votes(&self, account: Address) -> U256 {
+        let count = self.checkpoint_counts.get(account);
+        if count == U256::ZERO {
+            return U256::ZERO;
+        }
+        self.checkpoints
+            .getter(account)
+            .getter(count - U256::from(1))
+            .votes
+            .get()
+    }
+
+    // 委托对象在 timepoint 区块结束时的票数，只能查询已经结束的区块
+    pub(crate) fn get_past_votes(
+        &self,
+        account: Address,
+        timepoint: U256,
+    ) -> Result<U256, FutureLookup> {
+        Self::check_past(timepoint)?;
+        let checkpoints = self.checkpoints.getter(account);
+        let count = self.checkpoint_counts.get(account);
+        Ok(Self::lookup(
+            |i| {
+                let checkpoint = checkpoints.getter(i);
+                (checkpoint.from_block.get(), checkpoint.votes.get())
+            },
+            count,
+            timepoint,
+        ))
+    }
+
+    // timepoint 区块结束时的总供应量，只能查询已经结束的区块
+    pub(crate) fn get_past_total_supply(&self, timepoint: U256) -> Result<U256, FutureLookup> {
+        Self::check_past(timepoint)?;
+        Ok(Self::lookup(
+            |i| {
+                let checkpoint = self.total_checkpoints.getter(i);
+                (checkpoint.from_block.get(), checkpoint.votes.get())
+            },
+            self.total_checkpoint_count.get(),
+            timepoint,
+        ))
+    }
+
+    // 持币人更换委托对象，余额对应的票数从原委托对象转到新委托对象
+    pub(crate) fn delegate(&mut self, account: Address, delegatee: Address, balance: U256) {
+        let old_delegate = self.delegates.get(account);
+        self.delegates.insert(account, delegatee);
+        evm::log(DelegateChanged {
+            delegator: account,
+            fromDelegate: old_delegate,
+            toDelegate: delegatee,
+        });
+        self.move_delegate_votes(old_delegate, delegatee, balance);
+    }
+
+    // 余额变化后同步投票权：from 为零地址表示铸造，to 为零地址表示销毁
+    pub(crate) fn transfer_voting_units(&mut self, from: Address, to: Address, amount: U256) {
+        if amount == U256::ZERO {
+            return;
+        }
+        if from.is_zero() || to.is_zero() {
+            let count = self.total_checkpoint_count.get();
+            let latest = if count == U256::ZERO {
+                U256::ZERO
+            } else {
+                self.total_checkpoints
+                    .getter(count - U256::from(1))
+                    .votes
+                    .get()
+            };
+            let supply = if from.is_zero() {
+                latest + amount
+            } else {
+                latest - amount
+            };
+            let count = Self::push(&mut self.total_checkpoints, count, supply);
+            self.total_checkpoint_count.set(count);
+        }
+        let (src, dst) = (self.delegates.get(from), self.delegates.get(to));
+        self.move_delegate_votes(src, dst, amount);
+    }
+
+    fn move_delegate_votes(&mut self, src: Address, dst: Address, amount: U256) {
+        if src == dst || amount == U256::ZERO {
+            return;
+        }
+        if !src.is_zero() {
+            let previous = self.get_votes(src);
+            self.write_votes(src, previous, previous - amount);
+        }
+        if !dst.is_zero() {
+            let previous = self.get_votes(dst);
+            self.write_votes(dst, previous, previous + amount);
+        }
+    }
+
+    fn write_votes(&mut self, account: Address, previous: U256, votes: U256) {
+        let count = self.checkpoint_counts.get(account);
+        let count = Self::push(&mut self.checkpoints.setter(account), count, votes);
+        self.checkpoint_counts.setter(account).set(count);
+        evm::log(DelegateVotesChanged {
+            delegate: account,
+            previousVotes: previous,
+            newVotes: votes,
+        });
+    }
+
+    // 写入当前区块的检查点，同一区块内覆盖最后一个检查点，返回新的检查点数量
+    fn push(checkpoints: &mut StorageMap<U256, Checkpoint>, count: U256, votes: U256) -> U256 {
+        let now = Self::clock();
+        if count > U256::ZERO {
+            let mut last = checkpoints.setter(count - U256::from(1));
+            if last.from_block.get() == now {
+                last.votes.set(votes);
+                return count;
+            }
+        }
+        let mut checkpoint = checkpoints.setter(count);
+        checkpoint.from_block.set(now);
+        checkpoint.votes.set(votes);
+        count + U256::from(1)
+    }
+
+    fn check_past(timepoint: U256) -> Result<(), FutureLookup> {
+        let clock = Self::clock();
+        if timepoint >= clock {
+            return Err(FutureLookup { timepoint, clock });
+        }
+        Ok(())
+    }
+
+    // 二分查找 timepoint 时生效的检查点：最后一个 from_block 不晚于 timepoint 的检查点
+    fn lookup(checkpoint: impl Fn(U256) -> (U256, U256), count: U256, timepoint: U256) -> U256 {
+        let (mut low, mut high) = (U256::ZERO, count);
+        while low < high {
+            let mid = (low + high) / U256::from(2);
+            if checkpoint(mid).0 > timepoint {
+                high = mid;
+            } else {
+                low = mid + U256::from(1);
+            }
+        }
+        if high == U256::ZERO {
+            U256::ZERO
+        } else {
+            checkpoint(high - U256::from(1)).1
+        }
+    }
+}
@@ -0,0 +1,352 @@
+//! Implementation of the ERC-721 standard
+//!
+//! The eponymous [`Erc721`] type provides all the standard methods,
+//! and is intended to be inherited by other contract types.
+//!
+//! You can configure the behavior of [`Erc721`] via the [`Erc721Params`] trait,
+//! which allows specifying the name and symbol of the collection.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Imported packages
+use alloc::{string::String, vec, vec::Vec};
+use alloy_primitives::{Address, FixedBytes, U256};
+use alloy_sol_types::sol;
+use core::{borrow::BorrowMut, marker::PhantomData};
+use stylus_sdk::{abi::Bytes, call::MethodError, evm, msg, prelude::*};
+
+pub trait Erc721Params {
+    /// Immutable NFT name
+    const NAME: &'static str;
+
+    /// Immutable NFT symbol
+    const SYMBOL: &'static str;
+}
+
+sol_storage! {
+    /// Erc721 implements all ERC-721 methods
+    pub struct Erc721<T: Erc721Params> {
+        /// Token id to owner map
+        mapping(uint256 => address) owners;
+        /// User to balance map
+        mapping(address => uint256) balances;
+        /// Token id to approved user map
+        mapping(uint256 => address) token_approvals;
+        /// User to operator map (the operator can manage all NFTs of the owner)
+        mapping(address => mapping(address => bool)) operator_approvals;
+        /// Used to allow [`Erc721Params`]
+        PhantomData<T> phantom;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event Transfer(address indexed from, address indexed to, uint256 indexed token_id);
+    event Approval(address indexed owner, address indexed approved, uint256 indexed token_id);
+    event ApprovalForAll(address indexed owner, address indexed operator, bool approved);
+
+    error InvalidTokenId(uint256 token_id);
+    error IncorrectOwner(address from, uint256 token_id, address real_owner);
+    error NotApproved(address owner, address spender, uint256 token_id);
+    error TransferToZero(uint256 token_id);
+    error ReceiverRefused(address receiver, uint256 token_id, bytes4 returned);
+}
+
+sol_interface! {
+    interface IERC721TokenReceiver {
+        function onERC721Received(address operator, address from, uint256 token_id, bytes data) external returns(bytes4);
+    }
+}
+
+/// Selector returned by `onERC721Received` when a contract accepts the NFT
+const ERC721_TOKEN_RECEIVER_ID: u32 = 0x150b7a02;
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum Erc721Error {
+    InvalidTokenId(InvalidTokenId),
+    IncorrectOwner(IncorrectOwner),
+    NotApproved(NotApproved),
+    TransferToZero(TransferToZero),
+    ReceiverRefused(ReceiverRefused),
+}
+
+impl MethodError for Erc721Error {
+    fn encode(self) -> Vec<u8> {
+        From::from(self)
+    }
+}
+
+// These methods aren't exposed to other contracts
+// Methods marked as "pub" here are usable outside of the erc721 module (i.e. they're callable from lib.rs)
+impl<T: Erc721Params> Erc721<T> {
+    /// Requires that msg::sender() is authorized to spend a given token
+    pub fn require_authorized_to_spend(
+        &self,
+        from: Address,
+        token_id: U256,
+    ) -> Result<(), Erc721Error> {
+        let owner = self.owner_of(token_id)?;
+        if from != owner {
+            return Err(Erc721Error::IncorrectOwner(IncorrectOwner {
+                from,
+                token_id,
+                real_owner: owner,
+            }));
+        }
+        if !self.is_approved_or_owner(msg::sender(), token_id) {
+            return Err(Erc721Error::NotApproved(NotApproved {
+                owner,
+                spender: msg::sender(),
+                token_id,
+            }));
+        }
+        Ok(())
+    }
+
+    /// Whether `spender` is the owner, the approved address or an operator of the token
+    pub fn is_approved_or_owner(&self, spender: Address, token_id: U256) -> bool {
+        let owner = self.owners.get(token_id);
+        if owner.is_zero() {
+            return false;
+        }
+        spender == owner
+            || self.token_approvals.get(token_id) == spender
+            || self.operator_approvals.getter(owner).get(spender)
+    }
+
+    /// Transfers `token_id` from `from` to `to`
+    /// This function does check that `from` is the owner of the token, but it does not check
+    /// that `to` is not the zero address, as this function is usable for burning
+    pub fn transfer(
+        &mut self,
+        token_id: U256,
+        from: Address,
+        to: Address,
+    ) -> Result<(), Erc721Error> {
+        let mut owner = self.owners.setter(token_id);
+        let previous_owner = owner.get();
+        if previous_owner != from {
+            return Err(Erc721Error::IncorrectOwner(IncorrectOwner {
+                from,
+                token_id,
+                real_owner: previous_owner,
+            }));
+        }
+        owner.set(to);
+
+        // Decrements the number of tokens owned by from
+        let mut from_balance = self.balances.setter(from);
+        let balance = from_balance.get() - U256::from(1);
+        from_balance.set(balance);
+
+        // Increments the number of tokens owned by to
+        let mut to_balance = self.balances.setter(to);
+        let balance = to_balance.get() + U256::from(1);
+        to_balance.set(balance);
+
+        // Clears the approval on each transfer
+        self.token_approvals.delete(token_id);
+
+        evm::log(Transfer { from, to, token_id });
+        Ok(())
+    }
+
+    /// Calls `onERC721Received` on the `to` address if it is a contract
+    /// Otherwise, does nothing
+    fn call_receiver<S: TopLevelStorage>(
+        storage: &mut S,
+        token_id: U256,
+        from: Address,
+        to: Address,
+        data: Vec<u8>,
+    ) -> Result<(), Erc721Error> {
+        if to.has_code() {
+            let receiver = IERC721TokenReceiver::new(to);
+            let received = receiver
+                .on_erc_721_received(&mut *storage, msg::sender(), from, token_id, data.into())
+                .map_err(|_e| {
+                    Erc721Error::ReceiverRefused(ReceiverRefused {
+                        receiver: receiver.address,
+                        token_id,
+                        returned: FixedBytes(0_u32.to_be_bytes()),
+                    })
+                })?
+                .0;
+
+            if u32::from_be_bytes(received) != ERC721_TOKEN_RECEIVER_ID {
+                return Err(Erc721Error::ReceiverRefused(ReceiverRefused {
+                    receiver: receiver.address,
+                    token_id,
+                    returned: FixedBytes(received),
+                }));
+            }
+        }
+        Ok(())
+    }
+
+    /// Transfers and calls `onERC721Received`
+    pub fn safe_transfer<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        token_id: U256,
+        from: Address,
+        to: Address,
+        data: Vec<u8>,
+    ) -> Result<(), Erc721Error> {
+        storage.borrow_mut().transfer(token_id, from, to)?;
+        Self::call_receiver(storage, token_id, from, to, data)
+    }
+
+    /// Mints `token_id` to `to`
+    pub fn mint(&mut self, to: Address, token_id: U256) -> Result<(), Erc721Error> {
+        if to.is_zero() {
+            return Err(Erc721Error::TransferToZero(TransferToZero { token_id }));
+        }
+        let mut owner = self.owners.setter(token_id);
+        if !owner.get().is_zero() {
+            return Err(Erc721Error::InvalidTokenId(InvalidTokenId { token_id }));
+        }
+        owner.set(to);
+        let mut to_balance = self.balances.setter(to);
+        let balance = to_balance.get() + U256::from(1);
+        to_balance.set(balance);
+        evm::log(Transfer {
+            from: Address::ZERO,
+            to,
+            token_id,
+        });
+        Ok(())
+    }
+}
+
+// these methods are public to other contracts
+#[public]
+impl<T: Erc721Params> Erc721<T> {
+    /// Immutable NFT name.
+    pub fn name() -> Result<String, Erc721Error> {
+        Ok(T::NAME.into())
+    }
+
+    /// Immutable NFT symbol.
+    pub fn symbol() -> Result<String, Erc721Error> {
+        Ok(T::SYMBOL.into())
+    }
+
+    /// Gets the number of NFTs owned by an account.
+    pub fn balance_of(&self, owner: Address) -> Result<U256, Erc721Error> {
+        Ok(self.balances.get(owner))
+    }
+
+    /// Gets the owner of the NFT, if it exists.
+    pub fn owner_of(&self, token_id: U256) -> Result<Address, Erc721Error> {
+        let owner = self.owners.get(token_id);
+        if owner.is_zero() {
+            return Err(Erc721Error::InvalidTokenId(InvalidTokenId { token_id }));
+        }
+        Ok(owner)
+    }
+
+    /// Transfers an NFT, but only after checking the `to` address can receive the NFT.
+    /// It includes additional data for the receiver.
+    #[selector(name = "safeTransferFrom")]
+    pub fn safe_transfer_from_with_data<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        from: Address,
+        to: Address,
+        token_id: U256,
+        data: Bytes,
+    ) -> Result<(), Erc721Error> {
+        if to.is_zero() {
+            return Err(Erc721Error::TransferToZero(TransferToZero { token_id }));
+        }
+        storage
+            .borrow_mut()
+            .require_authorized_to_spend(from, token_id)?;
+
+        Self::safe_transfer(storage, token_id, from, to, data.0)
+    }
+
+    /// Equivalent to [`safe_transfer_from_with_data`], but without the additional data.
+    ///
+    /// Note: because Rust doesn't allow multiple methods with the same name,
+    /// we use the `#[selector]` macro attribute to simulate solidity overloading.
+    #[selector(name = "safeTransferFrom")]
+    pub fn safe_transfer_from<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        from: Address,
+        to: Address,
+        token_id: U256,
+    ) -> Result<(), Erc721Error> {
+        Self::safe_transfer_from_with_data(storage, from, to, token_id, vec![].into())
+    }
+
+    /// Transfers the NFT.
+    pub fn transfer_from(
+        &mut self,
+        from: Address,
+        to: Address,
+        token_id: U256,
+    ) -> Result<(), Erc721Error> {
+        if to.is_zero() {
+            return Err(Erc721Error::TransferToZero(TransferToZero { token_id }));
+        }
+        self.require_authorized_to_spend(from, token_id)?;
+        self.transfer(token_id, from, to)?;
+        Ok(())
+    }
+
+    /// Grants an account the ability to manage the sender's NFT.
+    pub fn approve(&mut self, approved: Address, token_id: U256) -> Result<(), Erc721Error> {
+        let owner = self.owner_of(token_id)?;
+
+        // require authorization
+        if msg::sender() != owner && !self.operator_approvals.getter(owner).get(msg::sender()) {
+            return Err(Erc721Error::NotApproved(NotApproved {
+                owner,
+                spender: msg::sender(),
+                token_id,
+            }));
+        }
+        self.token_approvals.insert(token_id, approved);
+
+        evm::log(Approval {
+            approved,
+            owner,
+            token_id,
+        });
+        Ok(())
+    }
+
+    /// Grants an account the ability to manage all of the sender's NFTs.
+    pub fn set_approval_for_all(
+        &mut self,
+        operator: Address,
+        approved: bool,
+    ) -> Result<(), Erc721Error> {
+        let owner = msg::sender();
+        self.operator_approvals
+            .setter(owner)
+            .insert(operator, approved);
+
+        evm::log(ApprovalForAll {
+            owner,
+            operator,
+            approved,
+        });
+        Ok(())
+    }
+
+    /// Gets the account managing an NFT.
+    pub fn get_approved(&self, token_id: U256) -> Result<Address, Erc721Error> {
+        Ok(self.token_approvals.get(token_id))
+    }
+
+    /// Determines if an account has been authorized to managing all of a user's NFTs.
+    pub fn is_approved_for_all(
+        &self,
+        owner: Address,
+        operator: Address,
+    ) -> Result<bool, Erc721Error> {
+        Ok(self.operator_approvals.getter(owner).get(operator))
+    }
+}
@@ -0,0 +1,55 @@
+//! 一笔交易开仓：存入 ETH 作为抵押品并铸造 DSC
+//!
+//! 调用者发送的 ETH 先包装为 WETH 记入仓位，再铸造 DSC；
+//! 设置 `min_out` 时，铸造的 DSC 会通过 DEX 路由兑换回 WETH 并追加为抵押品一次，实现简单的杠杆开仓。
+
+use stylus_sdk::{alloy_primitives::U256, call::Call, contract, msg, prelude::*};
+
+use crate::{meta_tx::msg_sender, DSCEngine, DSCEngineError, TransferFailed, WethNotSet};
+
+sol_interface! {
+    // WETH 接口：包装原生 ETH
+    interface IWETH {
+        function deposit() external payable;
+    }
+}
+
+impl DSCEngine {
+    // 开仓的内部实现，返回记入仓位的 WETH 总量（调用前需先计提）
+    pub(crate) fn _open_position_eth(
+        &mut self,
+        dsc_to_mint: U256,
+        min_out: U256,
+    ) -> Result<U256, DSCEngineError> {
+        let weth = self.weth.get();
+        if weth.is_zero() {
+            return Err(DSCEngineError::WethNotSet(WethNotSet {}));
+        }
+        self.is_allowed_token(weth)?;
+        let value = msg::value();
+        self.more_than_zero(value)?;
+        let sender = msg_sender();
+
+        // 包装 ETH 并记入仓位
+        let balance_before = self._engine_balance(weth);
+        IWETH::new(weth)
+            .deposit(Call::new().value(value))
+            .map_err(|_| DSCEngineError::TransferFailed(TransferFailed {}))?;
+        let received = self._engine_balance(weth).saturating_sub(balance_before);
+        self.more_than_zero(received)?;
+        self._credit_collateral(sender, weth, received)?;
+        if dsc_to_mint == U256::ZERO {
+            return Ok(received);
+        }
+        if min_out == U256::ZERO {
+            self._mint_dsc(sender, dsc_to_mint, sender, None)?;
+            return Ok(received);
+        }
+
+        // 杠杆：铸造的 DSC 兑换回 WETH 并追加为抵押品
+        self._mint_dsc(sender, dsc_to_mint, contract::address(), None)?;
+        let amount_out = self._swap_via_router(self.dsc.get(), dsc_to_mint, weth, min_out)?;
+        self._credit_collateral(sender, weth, amount_out)?;
+        Ok(received + amount_out)
+    }
+}
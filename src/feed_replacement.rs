@@ -0,0 +1,53 @@
+//! 更换抵押品的价格预言机
+//!
+//! 治理更换预言机时，新预言机当前的报价相对旧预言机最近一次报价的偏离不能超过治理设定的上限，
+//! 防止误填地址或治理权限被盗用时抵押品被瞬间错误定价。旧预言机已无法读取时以熔断模块最近接受的价格为准。
+//! 最大偏离为零时不检查。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    evm,
+};
+
+use crate::{
+    casting::answer_to_price, psm::BPS, DSCEngine, DSCEngineError, FeedDeviationTooHigh,
+    PriceFeedError, PriceFeedUpdated,
+};
+
+impl DSCEngine {
+    // 更换抵押品的价格预言机，新报价偏离旧报价超过上限时回滚
+    pub(crate) fn _set_price_feed(
+        &mut self,
+        token: Address,
+        new_feed: Address,
+    ) -> Result<(), DSCEngineError> {
+        if new_feed.is_zero() {
+            return Err(DSCEngineError::PriceFeedError(PriceFeedError {}));
+        }
+        let old_feed = self.price_feeds.get(token);
+        let old_price = self._last_known_price(token)?;
+        let (answer, _) = self
+            ._feed_answer(new_feed)
+            .ok_or(DSCEngineError::PriceFeedError(PriceFeedError {}))?;
+        let new_price = answer_to_price(answer)?;
+        if new_price == U256::ZERO || old_price == U256::ZERO {
+            return Err(DSCEngineError::PriceFeedError(PriceFeedError {}));
+        }
+        let max_deviation = self.max_feed_replacement_deviation.get();
+        let deviation = (new_price.abs_diff(old_price) * U256::from(BPS)) / old_price;
+        if max_deviation != U256::ZERO && deviation > max_deviation {
+            return Err(DSCEngineError::FeedDeviationTooHigh(FeedDeviationTooHigh {
+                _0: deviation,
+            }));
+        }
+        self.price_feeds.insert(token, new_feed);
+        evm::log(PriceFeedUpdated {
+            token,
+            oldFeed: old_feed,
+            newFeed: new_feed,
+            oldPrice: old_price,
+            newPrice: new_price,
+        });
+        Ok(())
+    }
+}
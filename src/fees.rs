@@ -0,0 +1,56 @@
+//! 铸造手续费与抵押品赎回手续费
+//!
+//! 铸造手续费以 DSC 计，计入借款人债务并直接铸造给国库；
+//! 抵押品赎回手续费以抵押品计，从赎回数量中扣除后转给国库。
+//! 豁免名单中的集成方不收取手续费，未设置国库地址时也不收取。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    evm,
+};
+
+use crate::{psm::BPS, DSCEngine, DSCEngineError, FeeCharged};
+
+impl DSCEngine {
+    // 按基点费率计算用户应付的手续费
+    pub(crate) fn _fee_for(&self, user: Address, amount: U256, rate: U256) -> U256 {
+        if self.fee_exempt.get(user) || self.treasury.get().is_zero() {
+            return U256::ZERO;
+        }
+        (amount * rate) / U256::from(BPS)
+    }
+
+    // 从 user 仓位直接赎回抵押品给 to，超过大额提取比例时必须改走延时提取
+    pub(crate) fn _redeem_collateral_with_fee(
+        &mut self,
+        token: Address,
+        amount: U256,
+        user: Address,
+        to: Address,
+    ) -> Result<(), DSCEngineError> {
+        self._check_withdrawal_delay(token, amount)?;
+        self._withdraw_collateral_with_fee(token, amount, user, to)
+    }
+
+    // 从 user 仓位赎回抵押品给 to，并把抵押品赎回手续费转给国库（价格偏离熔断时不允许提取，计入流出限制）
+    pub(crate) fn _withdraw_collateral_with_fee(
+        &mut self,
+        token: Address,
+        amount: U256,
+        user: Address,
+        to: Address,
+    ) -> Result<(), DSCEngineError> {
+        self._check_price_breaker(token)?;
+        self._record_outflow(token, amount)?;
+        let fee = self._fee_for(user, amount, self.collateral_redemption_fee.get());
+        if fee > U256::ZERO {
+            self._redeem_collateral(token, fee, user, self.treasury.get())?;
+            evm::log(FeeCharged {
+                user,
+                token,
+                amount: fee,
+            });
+        }
+        self._redeem_collateral(token, amount - fee, user, to)
+    }
+}
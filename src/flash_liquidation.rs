@@ -0,0 +1,114 @@
+//! 闪电清算：无需预先持有 DSC 的清算流程
+//!
+//! 引擎本身是 DSC 的铸造者，因此闪电铸造以记账方式实现：先减少被清算仓位的债务并扣押抵押品，
+//! 再通过治理配置的 DEX 路由把抵押品兑换成 DSC，用兑换所得偿还闪电铸造的 DSC 和协议分成，
+//! 剩余的 DSC 和未用完的抵押品归调用者。兑换所得不足时整笔交易回滚。
+//!
+//! 调用者只能给出卖出的抵押品数量和最少买入的 DSC 数量，兑换调用由引擎自行构造，
+//! 不转发调用者提供的原始调用数据，避免借引擎的身份调用路由动用引擎持有的资产。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    call::Call,
+    contract,
+};
+
+use crate::{
+    meta_tx::msg_sender, surplus::SURPLUS_LIQUIDATION_PENALTY, AuctionActive, DSCEngine,
+    DSCEngineError, DexRouterNotSet, ExceedsMaxLiquidatableDebt, FlashLiquidationUnprofitable,
+    HealthFactorNotImproved, HealthFactorOk, IDecentralizedStableCoin, StabilityPoolCanAbsorb,
+    TransferFailed, IERC20, LIQUIDATION_PRECISION,
+};
+
+impl DSCEngine {
+    // 闪电清算的内部实现，返回调用者获得的 DSC 利润（调用前需先计提）
+    pub(crate) fn _flash_liquidate(
+        &mut self,
+        collateral: Address,
+        user: Address,
+        debt_to_cover: U256,
+        collateral_to_swap: U256,
+        min_dsc_out: U256,
+    ) -> Result<U256, DSCEngineError> {
+        self._require_local_collateral(collateral)?;
+        if self.dex_router.get().is_zero() {
+            return Err(DSCEngineError::DexRouterNotSet(DexRouterNotSet {}));
+        }
+        let starting_user_health_factor = self._liquidation_health_factor(user, collateral)?;
+        if starting_user_health_factor >= self._min_health_factor() {
+            return Err(DSCEngineError::HealthFactorOk(HealthFactorOk {}));
+        }
+        let max_debt_to_cover = self._max_liquidatable_debt(user, collateral)?;
+        if debt_to_cover > max_debt_to_cover {
+            return Err(DSCEngineError::ExceedsMaxLiquidatableDebt(
+                ExceedsMaxLiquidatableDebt {
+                    _0: max_debt_to_cover,
+                },
+            ));
+        }
+        if self.active_auction.get(user) != U256::ZERO {
+            return Err(DSCEngineError::AuctionActive(AuctionActive {}));
+        }
+        self._check_liquidation_grace(collateral)?;
+        if self._stability_pool_balance()? >= debt_to_cover {
+            return Err(DSCEngineError::StabilityPoolCanAbsorb(
+                StabilityPoolCanAbsorb {},
+            ));
+        }
+        let engine = contract::address();
+        let dsc = IDecentralizedStableCoin::new(self.dsc.get());
+
+        // 扣押抵押品和清算奖励，留在引擎中用于兑换
+        let token_amount_from_debt_covered =
+            self._token_amount_from_usd(collateral, debt_to_cover)?;
+        let bonus = self._liquidation_bonus_for(user, collateral)?;
+        let bonus_collateral = (token_amount_from_debt_covered * bonus) / LIQUIDATION_PRECISION;
+        let seized = token_amount_from_debt_covered + bonus_collateral;
+        self._redeem_collateral(collateral, seized, user, engine)?;
+        // 闪电铸造：先在记账上偿还被清算用户的债务
+        let principal_paid =
+            self._reduce_debt(debt_to_cover, user, self._debt_bucket(user, collateral))?;
+
+        // 通过 DEX 路由把扣押的抵押品兑换成 DSC，卖出数量不超过扣押的数量
+        let collateral_sold = collateral_to_swap.min(seized);
+        let dsc_received =
+            self._swap_via_router(collateral, collateral_sold, self.dsc.get(), min_dsc_out)?;
+
+        // 偿还闪电铸造的 DSC 和清算奖励中的协议分成
+        let protocol_fee = self._liquidation_protocol_fee(debt_to_cover, bonus);
+        let owed = debt_to_cover + protocol_fee;
+        if dsc_received < owed {
+            return Err(DSCEngineError::FlashLiquidationUnprofitable(
+                FlashLiquidationUnprofitable { _0: dsc_received },
+            ));
+        }
+        self._burn_repaid_dsc(debt_to_cover, principal_paid, user, engine)?;
+        self._add_surplus(SURPLUS_LIQUIDATION_PENALTY, protocol_fee);
+
+        // 利润和未用完的抵押品归调用者
+        let sender = msg_sender();
+        let profit = dsc_received - owed;
+        if profit > U256::ZERO && dsc.transfer(Call::new(), sender, profit).is_err() {
+            return Err(DSCEngineError::TransferFailed(TransferFailed {}));
+        }
+        // 路由按精确输入数量卖出，未卖出的部分按记账数量计算，不读取引擎的代币余额
+        let leftover = seized - collateral_sold;
+        if leftover > U256::ZERO
+            && IERC20::new(collateral)
+                .transfer(Call::new(), sender, leftover)
+                .is_err()
+        {
+            return Err(DSCEngineError::TransferFailed(TransferFailed {}));
+        }
+
+        let ending_user_health_factor = self._liquidation_health_factor(user, collateral)?;
+        if ending_user_health_factor <= starting_user_health_factor {
+            return Err(DSCEngineError::HealthFactorNotImproved(
+                HealthFactorNotImproved {},
+            ));
+        }
+        self._record_bad_debt(user)?;
+        self._pay_keeper_reward(sender)?;
+        Ok(profit)
+    }
+}
@@ -0,0 +1,75 @@
+//! 预言机恢复后的清算宽限期
+//!
+//! L2 排序器或价格预言机中断期间，借款人无法及时补充抵押品。排序器停机或预言机超过心跳间隔
+//! 未更新时暂停清算；恢复后的宽限期内仍不允许清算，让借款人有机会按恢复后的价格调整仓位。
+//! 排序器状态读取 Chainlink 排序器在线状态预言机（answer 为 0 表示在线，startedAt 为状态变化时间）；
+//! 价格预言机的恢复时间按最新一轮与上一轮的更新间隔是否超过心跳间隔判断。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, I256, U256},
+    block,
+    call::Call,
+};
+
+use crate::{
+    DSCEngine, DSCEngineError, IAggregatorV3, LiquidationGracePeriod, PriceFeedDown, SequencerDown,
+};
+
+impl DSCEngine {
+    // 检查当前是否允许清算指定抵押品
+    pub(crate) fn _check_liquidation_grace(
+        &self,
+        collateral: Address,
+    ) -> Result<(), DSCEngineError> {
+        let now = U256::from(block::timestamp());
+        let grace_period = self.liquidation_grace_period.get();
+
+        // 排序器停机或刚恢复时不允许清算
+        let sequencer_feed = self.sequencer_uptime_feed.get();
+        if !sequencer_feed.is_zero() {
+            let round = self
+                ._feed_round(sequencer_feed)
+                .ok_or(DSCEngineError::SequencerDown(SequencerDown {}))?;
+            if round.answer != I256::ZERO {
+                return Err(DSCEngineError::SequencerDown(SequencerDown {}));
+            }
+            let grace_end = round.started_at + grace_period;
+            if now < grace_end {
+                return Err(DSCEngineError::LiquidationGracePeriod(
+                    LiquidationGracePeriod { _0: grace_end },
+                ));
+            }
+        }
+
+        // 价格预言机超过心跳间隔未更新，或中断后刚恢复时不允许清算（应急价格生效期间按应急价格清算）
+        let heartbeat = self._oracle_timeout(collateral);
+        if heartbeat == U256::ZERO || self._emergency_price(collateral).is_some() {
+            return Ok(());
+        }
+        let feed = self.price_feeds.get(collateral);
+        let round = self
+            ._feed_round(feed)
+            .ok_or(DSCEngineError::PriceFeedDown(PriceFeedDown {}))?;
+        if now.saturating_sub(round.updated_at) > heartbeat {
+            return Err(DSCEngineError::PriceFeedDown(PriceFeedDown {}));
+        }
+        if round.round_id == 0 {
+            return Ok(());
+        }
+        // 上一轮不存在（如预言机刚切换阶段）时不视为中断
+        let Ok((_, _, _, previous_updated_at, _)) =
+            IAggregatorV3::new(feed).get_round_data(Call::new(), round.round_id - 1)
+        else {
+            return Ok(());
+        };
+        if round.updated_at.saturating_sub(previous_updated_at) > heartbeat {
+            let grace_end = round.updated_at + grace_period;
+            if now < grace_end {
+                return Err(DSCEngineError::LiquidationGracePeriod(
+                    LiquidationGracePeriod { _0: grace_end },
+                ));
+            }
+        }
+        Ok(())
+    }
+}
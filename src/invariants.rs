@@ -0,0 +1,173 @@
+//! 基于 proptest 的不变量测试：随机执行一串用户操作，每一步之后检查协议记账
+//!
+//! - 所有仓位的抵押品总价值不低于 DSC 总供应量
+//! - 各用户的 `dsc_minted` 之和等于 DSC 的 totalSupply
+//! - 引擎实际持有的抵押品不少于记入各仓位的抵押品之和，代币余额之和等于总供应量
+//!
+//! 价格在 1500 ~ 2500 美元之间波动：按 50% 清算阈值铸造的仓位在这个区间内始终足额抵押，
+//! 第一条不变量才成立。失败的操作按链上语义回滚，不影响后续步骤。
+
+use proptest::prelude::*;
+use stylus_sdk::alloy_primitives::{Address, U256};
+
+use crate::test_utils::{
+    call_as, mint_token, set_price, setup, token_balance, token_supply, transact, DSC, ENGINE,
+    LIQUIDATOR, OWNER, USER, WETH, WETH_FEED,
+};
+use crate::DSCEngine;
+
+// 参与操作的账户
+const ACTORS: [Address; 3] = [USER, LIQUIDATOR, OWNER];
+// 每个账户初始持有的 WETH
+const INITIAL_WETH: u128 = 100_000_000_000_000_000_000;
+// 价格预言机的 8 位精度
+const FEED_UNIT: i64 = 100_000_000;
+// 基点精度
+const BPS: u64 = 10_000;
+
+// 铸造、偿还、赎回和清算的数量按当前可操作上限的基点比例生成，使多数操作能够成功
+#[derive(Clone, Debug)]
+enum Action {
+    Deposit {
+        actor: usize,
+        amount: u128,
+    },
+    Mint {
+        actor: usize,
+        bps: u64,
+    },
+    Burn {
+        actor: usize,
+        bps: u64,
+    },
+    Redeem {
+        actor: usize,
+        bps: u64,
+    },
+    Liquidate {
+        liquidator: usize,
+        user: usize,
+        bps: u64,
+    },
+    SetPrice {
+        dollars: i64,
+    },
+}
+
+fn action() -> impl Strategy<Value = Action> {
+    let actor = 0..ACTORS.len();
+    // 存入数量以 18 位精度计，最多 50 WETH
+    let collateral = 1u128..50_000_000_000_000_000_000;
+    let bps = 1u64..=BPS;
+    prop_oneof![
+        (actor.clone(), collateral).prop_map(|(actor, amount)| Action::Deposit { actor, amount }),
+        // 铸造偏向接近上限，使价格下跌后出现可清算的仓位
+        (actor.clone(), 8_000..=BPS).prop_map(|(actor, bps)| Action::Mint { actor, bps }),
+        (actor.clone(), bps.clone()).prop_map(|(actor, bps)| Action::Burn { actor, bps }),
+        (actor.clone(), bps.clone()).prop_map(|(actor, bps)| Action::Redeem { actor, bps }),
+        // 清算人总是其他账户
+        (actor, 1..ACTORS.len(), bps).prop_map(|(user, offset, bps)| Action::Liquidate {
+            liquidator: (user + offset) % ACTORS.len(),
+            user,
+            bps
+        }),
+        (1_500i64..=2_500).prop_map(|dollars| Action::SetPrice { dollars }),
+    ]
+}
+
+// 按基点比例取 value 的一部分
+fn portion(value: U256, bps: u64) -> U256 {
+    (value * U256::from(bps)) / U256::from(BPS)
+}
+
+fn apply(engine: &mut DSCEngine, action: &Action) {
+    // 失败的操作已由 transact 回滚，这里只关心成功后的状态
+    call_as(OWNER);
+    let _ = match *action {
+        Action::Deposit { actor, amount } => transact(ACTORS[actor], || {
+            engine.deposit_collateral(WETH, U256::from(amount))
+        }),
+        Action::Mint { actor, bps } => {
+            // 按 50% 清算阈值计算的剩余可铸造额度
+            let (minted, collateral_value, _) = engine.get_account_information(ACTORS[actor]);
+            let headroom = (collateral_value / U256::from(2)).saturating_sub(minted);
+            transact(ACTORS[actor], || engine.mint_dsc(portion(headroom, bps)))
+        }
+        Action::Burn { actor, bps } => {
+            let (minted, _, _) = engine.get_account_information(ACTORS[actor]);
+            transact(ACTORS[actor], || engine.burn_dsc(portion(minted, bps)))
+        }
+        Action::Redeem { actor, bps } => {
+            let deposited = engine.get_collateral_balance_of_user(ACTORS[actor], WETH);
+            transact(ACTORS[actor], || {
+                engine.redeem_collateral(WETH, portion(deposited, bps))
+            })
+        }
+        Action::Liquidate {
+            liquidator,
+            user,
+            bps,
+        } => {
+            let max_debt = engine.get_max_liquidatable_debt(ACTORS[user], WETH);
+            transact(ACTORS[liquidator], || {
+                engine.liquidate(WETH, ACTORS[user], portion(max_debt, bps))
+            })
+        }
+        Action::SetPrice { dollars } => {
+            set_price(WETH_FEED, dollars * FEED_UNIT);
+            Ok(())
+        }
+    };
+}
+
+fn check_invariants(engine: &DSCEngine) -> Result<(), TestCaseError> {
+    call_as(OWNER);
+    let dsc_supply = token_supply(DSC);
+    let mut total_minted = U256::ZERO;
+    let mut total_collateral = U256::ZERO;
+    let mut total_collateral_value = U256::ZERO;
+    for actor in ACTORS {
+        let (minted, collateral_value, _) = engine.get_account_information(actor);
+        total_minted += minted;
+        total_collateral_value += collateral_value;
+        total_collateral += engine.get_collateral_balance_of_user(actor, WETH);
+    }
+    prop_assert!(
+        total_collateral_value >= dsc_supply,
+        "collateral value {} below DSC supply {}",
+        total_collateral_value,
+        dsc_supply
+    );
+    prop_assert_eq!(total_minted, dsc_supply);
+    prop_assert!(token_balance(WETH, ENGINE) >= total_collateral);
+
+    let weth_held = ACTORS
+        .iter()
+        .fold(token_balance(WETH, ENGINE), |sum, actor| {
+            sum + token_balance(WETH, *actor)
+        });
+    prop_assert_eq!(weth_held, token_supply(WETH));
+    let dsc_held = ACTORS
+        .iter()
+        .fold(token_balance(DSC, ENGINE), |sum, actor| {
+            sum + token_balance(DSC, *actor)
+        });
+    prop_assert_eq!(dsc_held, dsc_supply);
+    Ok(())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(256))]
+
+    #[test]
+    fn accounting_invariants_hold(actions in prop::collection::vec(action(), 1..40)) {
+        let mut engine = setup();
+        for actor in ACTORS {
+            mint_token(WETH, actor, U256::from(INITIAL_WETH));
+        }
+        for action in &actions {
+            apply(&mut engine, action);
+            check_invariants(&engine)?;
+        }
+    }
+}
@@ -1,26 +1,289 @@
-#![cfg_attr(not(feature = "export-abi"), no_main)]
+#![cfg_attr(not(any(feature = "export-abi", test)), no_main)]
+#![cfg_attr(feature = "export-abi", recursion_limit = "512")]
 extern crate alloc;
 
+#[cfg(feature = "export-abi")]
+mod abi_export;
+mod auction;
+mod bad_debt;
+mod borrow_threshold;
+mod bridge_limits;
+mod buyback;
+mod casting;
+mod config;
+mod debt_auction;
 mod decentralized_stable_coin;
+mod dsc_staking;
+mod dynamic_ltv;
+mod emode;
+mod erc165;
 mod erc20;
+mod erc20_votes;
+mod erc721;
+mod eth_entry;
+mod feed_replacement;
+mod fees;
+mod flash_liquidation;
+mod grace_period;
+#[cfg(test)]
+mod invariants;
+mod liquidation_bonus;
+mod margin;
+mod math;
+mod meta_tx;
+mod migration;
+mod mint_limit;
+#[cfg(feature = "mocks")]
+mod mock_erc20;
+#[cfg(feature = "mocks")]
+mod mock_v3_aggregator;
+mod oft_adapter;
+mod oracle_cache;
+mod outflow_limit;
+mod param_registry;
+mod peg;
+mod permissioned;
+mod position_transfer;
+mod price_breaker;
+mod price_fallback;
+mod price_override;
+mod price_source;
+mod protocol_liquidity;
+mod psm;
+mod rate_index;
+mod rate_strategy;
+mod redemption;
+mod referral;
+mod remote_collateral;
+mod rewards;
+mod risk_metrics;
+mod risk_tier;
+mod savings_vault;
+mod signature;
+mod signed_liquidation;
+mod stability_fee;
+mod stability_pool;
+mod surplus;
+mod synths;
+#[cfg(test)]
+mod test_utils;
+#[cfg(test)]
+mod tests;
+mod transfer_hook;
+mod vault;
+mod withdrawal_delay;
+mod zap;
 
 use alloy_sol_types::sol;
-use decentralized_stable_coin::{DecentralizedStableCoin, DecentralizedStableCoinError};
+use auction::Auction;
+use config::RiskConfig;
+use debt_auction::DebtAuction;
+use dynamic_ltv::LtvAdjuster;
+use erc721::{Erc721, Erc721Error, Erc721Params};
+use math::{mul_div, wad_div, wad_mul, Rounding};
+use meta_tx::msg_sender;
+use outflow_limit::OutflowEpoch;
+use param_registry::{address_value, id_subject, ParamRecord};
+use price_fallback::GoodPrice;
+use price_override::PriceOverride;
+use price_source::{PriceSource, PriceSourceConfig};
+use rate_strategy::RateStrategy;
+use rewards::RewardPool;
+use risk_tier::RiskTier;
+use signed_liquidation::LiquidationOrder;
 use stylus_sdk::{
-    alloy_primitives::{Address, U256},
+    abi::Bytes,
+    alloy_primitives::{Address, FixedBytes, B256, I256, U256, U8},
     call::Call,
-    call::MethodError,
-    contract, evm, msg,
+    call::{self, MethodError},
+    contract, evm, function_selector, msg,
     prelude::*,
 };
+use synths::Synth;
+use withdrawal_delay::PendingWithdrawal;
 
 sol! {
-    // 抵押品存入事件：记录用户存入抵押品的信息
-    event CollateralDeposited(address indexed user, address indexed token, uint256 amount);
-    // 抵押品赎回事件：记录抵押品赎回的信息
+    // 仓位事件（存入、赎回、铸造、偿还和各类清算）都带有仓位所属用户的操作序号 actionNonce：
+    // 每个用户从 1 开始逐个递增，索引器据此发现遗漏的日志并确定同一用户操作的先后顺序
+
+    // 抵押品存入事件：记录用户存入抵押品的信息，以及存入后该抵押品的余额和用户总债务
+    event CollateralDeposited(
+        address indexed user, address indexed token, uint256 amount, uint256 collateralBalance, uint256 totalDebt, uint256 actionNonce
+    );
+    // 抵押品赎回事件：记录抵押品赎回的信息，以及赎回后该抵押品的余额和用户总债务
     event CollateralRedeemed(
-        address indexed redeemedFrom, address indexed redeemedTo, uint256 indexed amount, address token
+        address indexed redeemedFrom,
+        address indexed redeemedTo,
+        uint256 indexed amount,
+        address token,
+        uint256 collateralBalance,
+        uint256 totalDebt,
+        uint256 actionNonce
+    );
+    // 稳定币铸造事件：记录债务所属仓位、接收者、铸造数量和铸造后的总债务
+    event DscMinted(address indexed user, address indexed to, uint256 amount, uint256 totalDebt, uint256 actionNonce);
+    // 稳定币偿还事件：记录被偿还的仓位、支付者、偿还数量（含利息）和偿还后的总债务
+    event DscBurned(
+        address indexed onBehalfOf, address indexed from, uint256 amount, uint256 totalDebt, uint256 actionNonce
+    );
+    // 稳定费计提事件：记录抵押品新增的利息和最新债务指数
+    event StabilityFeeAccrued(address indexed token, uint256 fee, uint256 index);
+    // 拍卖发起事件：记录被清算用户、抵押品、待偿还债务、扣押的抵押品和起拍价
+    event AuctionKicked(
+        uint256 indexed id, address indexed user, address indexed token, uint256 tab, uint256 lot, uint256 startPrice, uint256 actionNonce
+    );
+    // 拍卖成交事件：记录购买者、购得的抵押品和支付的 DSC
+    event AuctionTaken(uint256 indexed id, address indexed taker, uint256 collateral, uint256 owe);
+    // 拍卖重启事件：记录新的起拍价
+    event AuctionReset(uint256 indexed id, uint256 startPrice);
+    // 坏账记录事件：记录被核销的仓位、坏账数量和系统坏账总额
+    event BadDebtRecorded(address indexed user, uint256 amount, uint256 totalBadDebt);
+    // 保险基金注资事件：记录注资来源和数量
+    event InsuranceFunded(address indexed from, uint256 amount);
+    // 坏账冲抵事件：记录保险基金冲抵的坏账和剩余坏账
+    event BadDebtCovered(uint256 amount, uint256 remainingBadDebt);
+    // 盈余计入事件：记录收入来源和数量
+    event SurplusAccrued(uint8 indexed source, uint256 amount);
+    // 盈余提取事件：记录接收地址和数量
+    event SurplusWithdrawn(address indexed to, uint256 amount);
+    // 手续费事件：记录用户支付给国库的铸造或抵押品赎回手续费（token 为支付的代币）
+    event FeeCharged(address indexed user, address indexed token, uint256 amount);
+    // keeper 奖励事件：记录发起清算的 keeper 获得的固定 DSC 奖励
+    event KeeperRewardPaid(address indexed keeper, uint256 amount);
+    // 金库开设事件：记录金库编号、所有者和派生的仓位地址
+    event VaultOpened(uint256 indexed id, address indexed owner, address position);
+    // E-mode 类别事件：记录类别的清算阈值
+    event EModeCategorySet(uint8 indexed category, uint256 threshold);
+    // 抵押品类别事件：记录抵押品所属的 E-mode 类别
+    event CollateralEModeCategorySet(address indexed token, uint8 indexed category);
+    // 用户 E-mode 事件：记录仓位选择的 E-mode 类别
+    event UserEModeSet(address indexed user, uint8 indexed category);
+    // 保证金模式事件：记录仓位选择的全仓或逐仓模式
+    event MarginModeSet(address indexed user, bool isolated);
+    // 仓位转让同意事件：记录接收方是否同意接收来自转出方的仓位
+    event PositionTransferAccepted(address indexed from, address indexed to, bool accepted);
+    // 仓位转让事件：记录转出方、接收方和接收方合并后的债务
+    event PositionTransferred(address indexed from, address indexed to, uint256 debt);
+    // 仓位迁出事件：记录迁移到的新引擎、迁移的债务和迁出后的总债务
+    event PositionMigrated(
+        address indexed user, address indexed newEngine, uint256 debt, uint256 totalDebt, uint256 actionNonce
+    );
+    // 仓位迁入事件：记录迁出的旧引擎、记入的债务和迁入后的总债务
+    event PositionReceived(
+        address indexed user, address indexed oldEngine, uint256 debt, uint256 totalDebt, uint256 actionNonce
+    );
+    // 国库手续费事件：记录开启手续费开关后划给国库的收入
+    event TreasuryFeeCollected(uint8 indexed source, uint256 amount);
+    // 国库领取事件：记录转到国库的手续费
+    event TreasuryFeesClaimed(address indexed treasury, uint256 amount);
+    // 稳定池清算事件：记录稳定池偿还的债务和获得的抵押品
+    event StabilityPoolOffset(
+        address indexed user, address indexed token, uint256 debtOffset, uint256 collateralAmount
+    );
+    // 赎回事件：记录赎回者销毁的 DSC 数量、获得的抵押品数量和抵押品手续费
+    event Redemption(
+        address indexed redeemer, address indexed token, uint256 dscAmount, uint256 collateralAmount, uint256 fee
+    );
+    // PSM 兑换事件：记录兑换方向、PSM 代币数量、DSC 数量和手续费
+    event PsmSwap(address indexed user, bool toDsc, uint256 gemAmount, uint256 dscAmount, uint256 fee);
+    // 价格偏离告警事件：记录触发熔断的抵押品、上次接受的价格和偏离的新价格
+    event PriceDeviationAlert(address indexed token, uint256 lastPrice, uint256 price);
+    // 熔断解除事件：记录治理解除熔断的抵押品和新的基准价格
+    event PriceBreakerCleared(address indexed token, uint256 price);
+    // 许可模式事件：记录是否只允许白名单地址存入和铸造
+    event PermissionedModeSet(bool enabled);
+    // 白名单事件：记录地址被加入或移出白名单
+    event AllowlistUpdated(address indexed account, bool allowed);
+    // 手续费豁免事件：记录集成方是否豁免铸造和赎回手续费
+    event FeeExemptUpdated(address indexed account, bool exempt);
+    // 签名清算订单事件：记录签名者、被清算用户、订单 nonce、偿还的债务和获得的抵押品
+    event LiquidationOrderFilled(
+        address indexed liquidator, address indexed user, uint256 nonce, uint256 debtCovered, uint256 collateralOut, uint256 actionNonce
+    );
+    // 远程抵押品记入事件：记录存入证明的消息编号、来源链、用户、抵押品别名和数量
+    event RemoteCollateralAttested(
+        bytes32 indexed messageId, uint256 chainId, address indexed user, address indexed token, uint256 amount
+    );
+    // 远程抵押品释放事件：记录通知远程链释放给接收者的抵押品
+    event RemoteCollateralReleased(uint256 chainId, address indexed to, address indexed token, uint256 amount);
+    // 风险等级事件：记录等级的清算阈值、最高清算奖励、存入上限和预言机超时
+    event RiskTierSet(
+        uint8 indexed tier, uint256 liquidationThreshold, uint256 liquidationBonus, uint256 supplyCap, uint256 oracleTimeout
+    );
+    // 抵押品风险等级事件：记录抵押品被分配到的风险等级
+    event CollateralRiskTierSet(address indexed token, uint8 indexed tier);
+    // 动态阈值事件：记录调整时的价格偏离（基点）和调整后的清算阈值
+    event DynamicThresholdUpdated(address indexed token, uint256 deviationBps, uint256 threshold);
+    // 预言机更换事件：记录新旧预言机地址和更换时各自的报价
+    event PriceFeedUpdated(
+        address indexed token, address oldFeed, address newFeed, uint256 oldPrice, uint256 newPrice
+    );
+    // 抵押品偿还事件：记录卖出的抵押品数量和偿还的债务数量
+    event RepaidWithCollateral(
+        address indexed user, address indexed token, uint256 collateralSold, uint256 debtRepaid
+    );
+    // 应急价格事件：记录守护者设置的应急价格、校验时的参考价格和失效时间
+    event EmergencyPriceSet(
+        address indexed token, uint256 price, uint256 referencePrice, uint256 expiresAt, address indexed guardian
+    );
+    // 应急价格撤销事件：记录撤销应急价格的守护者或治理地址
+    event EmergencyPriceCleared(address indexed token, address indexed by);
+    // 提取请求事件：记录大额提取请求的仓位、抵押品、接收者、数量和可执行时间
+    event WithdrawalRequested(
+        uint256 indexed id, address indexed position, address indexed token, address to, uint256 amount, uint256 executableAt
+    );
+    // 提取执行事件
+    event WithdrawalExecuted(uint256 indexed id);
+    // 提取取消事件：记录取消请求的守护者或治理地址
+    event WithdrawalCancelled(uint256 indexed id, address indexed by);
+    // 清算奖励曲线事件：记录最低清算奖励和达到最高奖励的健康因子
+    event LiquidationBonusCurveSet(uint256 minBonus, uint256 fullBonusHealthFactor);
+    // 利率策略事件：记录抵押品的基础费率、两段斜率和最优使用率，全部为零表示改用固定稳定费率
+    event RateStrategySet(
+        address indexed token, uint256 baseRate, uint256 slope1, uint256 slope2, uint256 optimalUtilization
     );
+    // 推荐关系事件：记录用户首次铸造时附带的推荐人
+    event ReferrerSet(address indexed user, address indexed referrer);
+    // 返佣记入事件：记录推荐人从被推荐用户的手续费中获得的返佣
+    event ReferralRewardAccrued(address indexed referrer, address indexed user, uint256 amount);
+    // 返佣领取事件：记录推荐人领取的返佣
+    event ReferralRewardsClaimed(address indexed referrer, uint256 amount);
+    // 挖矿奖励领取事件：记录用户领取的奖励代币数量
+    event RewardsClaimed(address indexed user, uint256 amount);
+    // 参数修改事件：记录登记表中参数的键、参数名、对象以及修改前后的值
+    event ParamUpdated(bytes32 indexed key, bytes32 name, bytes32 subject, uint256 previous, uint256 value);
+    // 合成资产登记事件：记录合成资产的价格预言机和债务上限
+    event SynthAdded(address indexed synth, address priceFeed, uint256 debtCeiling);
+    // 合成资产债务上限事件
+    event SynthDebtCeilingSet(address indexed synth, uint256 debtCeiling);
+    // 合成资产铸造事件：记录铸造数量和用户铸造后的该合成资产债务
+    event SynthMinted(
+        address indexed user, address indexed synth, uint256 amount, uint256 totalDebt, uint256 actionNonce
+    );
+    // 合成资产销毁事件：记录偿还数量和用户偿还后的该合成资产债务
+    event SynthBurned(
+        address indexed onBehalfOf, address indexed from, address indexed synth, uint256 amount, uint256 totalDebt, uint256 actionNonce
+    );
+    // 合成资产清算事件：记录偿还的合成资产债务和扣押的抵押品
+    event SynthLiquidated(
+        address indexed user, address indexed liquidator, address indexed synth, address collateral, uint256 debtCovered, uint256 collateralSeized, uint256 actionNonce
+    );
+    // 债务拍卖发起事件：记录初始协议代币数量、募集的 DSC 数量和结束时间
+    event DebtAuctionKicked(uint256 indexed id, uint256 lot, uint256 bid, uint256 endsAt);
+    // 债务拍卖出价事件：记录出价者接受的协议代币数量
+    event DebtAuctionBid(uint256 indexed id, address indexed bidder, uint256 lot);
+    // 债务拍卖结算事件：记录获胜者和增发的协议代币数量，无人出价时获胜者为零地址
+    event DebtAuctionSettled(uint256 indexed id, address indexed winner, uint256 lot);
+    // 回购储备转入事件
+    event BuybackFunded(address indexed token, address indexed from, uint256 amount);
+    // 回购销毁事件：记录卖出的储备数量和销毁的 DSC 数量
+    event BuybackExecuted(address indexed token, uint256 amountIn, uint256 dscBurned);
+    // 协议自有流动性注入事件：记录注入的 DSC、USDC 数量和得到的 LP 代币数量
+    event ProtocolLiquidityAdded(uint256 dscAmount, uint256 usdcAmount, uint256 liquidity);
+    // 协议自有流动性撤出事件：记录撤出的 LP 代币数量和取回的 DSC、USDC 数量
+    event ProtocolLiquidityRemoved(uint256 liquidity, uint256 dscAmount, uint256 usdcAmount);
+    // 价格来源事件：记录抵押品的来源类型、来源合约、报价预言机和更换时的来源价格
+    event PriceSourceSet(address indexed token, uint8 kind, address oracle, address quoteFeed, uint256 price);
 
     // 错误定义
     error TokenAddressesAndPriceFeedAddressesMustBeSameLength(); // 代币地址和价格预言机地址长度不匹配错误
@@ -33,6 +296,99 @@ sol! {
     error HealthFactorNotImproved();                           // 健康因子未改善错误
     error PriceFeedError();                                    // 价格预言机错误
     error ConversionError();                                   // 数据转换错误
+    error ExceedsMaxLiquidatableDebt(uint256);                 // 超出单次可清算债务上限错误
+    error TokenDecimalsUnavailable();                          // 无法读取代币精度错误
+    error NotOwner();                                          // 非治理地址错误
+    error CollateralModeLocked();                              // 抵押品已有存款，不能切换记账模式错误
+    error DscInitializationFailed();                           // 稳定币合约初始化失败错误
+    error NotSavingsVault();                                   // 非储蓄金库调用错误
+    error PsmNotConfigured();                                  // PSM 未配置错误
+    error PsmDebtCeilingExceeded();                            // 超出 PSM 债务上限错误
+    error PsmTokenLocked();                                    // PSM 仍有储备，不能更换代币错误
+    error InsufficientPsmReserves();                           // 换出数量超过 PSM 储备或 PSM 债务错误
+    error InvalidFee();                                        // 手续费超出上限错误
+    error RedemptionFeeExceeded(uint256);                      // 赎回手续费超出调用者上限错误
+    error NoRedeemablePositions();                             // 没有可赎回仓位错误
+    error UnsortedRedemptionList();                            // 赎回列表中的仓位未按当前健康因子从低到高排列错误
+    error StabilityPoolNotSet();                               // 未设置稳定池错误
+    error StabilityPoolCanAbsorb();                            // 稳定池可承接清算，需先通过稳定池清算错误
+    error AuctionActive();                                     // 用户已有进行中的拍卖错误
+    error AuctionNotFound();                                   // 拍卖不存在或已结束错误
+    error AuctionNeedsReset();                                 // 拍卖价格已降到零，需要重启错误
+    error AuctionNotExpired();                                 // 拍卖尚未到期，不能重启错误
+    error AuctionPriceExceeded(uint256);                       // 拍卖价格高于购买者上限错误
+    error InsufficientSurplus();                               // 盈余缓冲不足错误
+    error TreasuryNotSet();                                    // 未设置国库地址错误
+    error DexRouterNotSet();                                   // 未设置 DEX 路由错误
+    error SwapFailed();                                        // DEX 兑换失败错误
+    error FlashLiquidationUnprofitable(uint256);               // 闪电清算兑换所得不足以偿还错误
+    error SlippageExceeded(uint256);                           // 兑换所得低于最小值错误
+    error WethNotSet();                                        // 未设置 WETH 地址错误
+    error NotVaultOwner();                                     // 非金库所有者错误
+    error InvalidEModeCategory();                              // 无效的 E-mode 类别错误
+    error EModeCollateralMismatch();                           // 抵押品不属于所选 E-mode 类别错误
+    error PositionNotEmpty();                                  // 仓位非空时不能切换保证金模式错误
+    error PositionTransferNotAccepted();                       // 接收方未同意接收仓位错误
+    error MigrationNotAllowed();                               // 未登记的迁移目标或来源错误
+    error MigrationFailed();                                   // 新引擎拒绝接收迁移仓位错误
+    error AlreadyInitialized();                                // 引擎已初始化错误
+    error InsufficientCollateral(uint256);                     // 赎回数量超过已存入抵押品错误
+    error RepayExceedsDebt(uint256);                           // 偿还数量超过债务错误
+    error BurnFailed();                                        // 销毁失败错误
+    error SequencerDown();                                     // 排序器停机错误
+    error PriceFeedDown();                                     // 价格预言机中断错误
+    error LiquidationGracePeriod(uint256);                     // 清算宽限期内错误（宽限期结束时间）
+    error MintRateLimited(uint256);                            // 超过本周期铸造上限错误（剩余额度）
+    error MintCooldownActive(uint256);                         // 大额铸造冷却期内错误（冷却期结束时间）
+    error PriceCircuitBreakerTripped(address token);           // 抵押品价格偏离熔断中错误
+    error DscComplianceUpdateFailed();                         // 设置稳定币合规角色失败错误
+    error DscBridgeLimitsUpdateFailed();                       // 设置稳定币跨链桥额度失败错误
+    error DscMaxSupplyUpdateFailed();                          // 设置稳定币总供应上限失败错误
+    error DscTransferHookUpdateFailed();                       // 设置稳定币转账钩子失败错误
+    error NotAllowlisted(address account);                     // 许可模式下地址不在白名单中错误
+    error InvalidSignature();                                  // 签名无效或 nonce 不匹配错误
+    error OrderExpired();                                      // 签名订单已过截止时间错误
+    error NotRemoteMessenger();                                // 非跨链消息适配器调用错误
+    error InvalidRemoteCollateral();                           // 远程抵押品或存入证明无效错误
+    error RemoteChainCapExceeded(uint256);                     // 超出远程链抵押品价值上限错误
+    error RemoteReleaseFailed();                               // 通知远程链释放抵押品失败错误
+    error RemoteCollateralUnsupported();                       // 该操作不支持远程抵押品错误
+    error InvalidBonusCurve();                                 // 清算奖励曲线参数无效错误
+    error InvalidRiskTier();                                   // 风险等级无效或未配置错误
+    error SupplyCapExceeded(uint256);                          // 超出抵押品存入上限错误
+    error InvalidDynamicLtv();                                 // 动态阈值参数无效错误
+    error DynamicLtvNotConfigured();                           // 抵押品未启用动态阈值错误
+    error DynamicLtvCooldown(uint256);                         // 动态阈值调整间隔未到错误（可再次调整的时间）
+    error NegativeValue(int256);                               // 有符号数值为负，不能转换为无符号数错误
+    error ValueOverflow(int256);                               // 数值超出可安全运算的范围错误
+    error InvalidRateStrategy();                               // 利率策略的最优使用率无效错误
+    error WithdrawalDelayRequired();                           // 赎回数量过大，必须走延时提取错误
+    error WithdrawalNotFound();                                // 提取请求不存在、已执行或已取消错误
+    error WithdrawalNotReady(uint256);                         // 提取请求延时未结束错误（可执行时间）
+    error NotGuardian();                                       // 非守护者或治理地址错误
+    error InvalidWithdrawalDelay();                            // 延时提取参数无效错误
+    error OutflowRateLimited(uint256);                         // 超过本周期抵押品流出上限错误（剩余额度）
+    error InvalidOutflowLimit();                               // 流出限制参数无效错误
+    error InvalidEmergencyPrice();                             // 应急价格为零、偏离过大或有效期过长错误
+    error FeedDeviationTooHigh(uint256);                       // 新预言机报价偏离旧报价过大错误（偏离基点）
+    error InvalidCriticalHealthFactor();                       // 临界健康因子高于最小健康因子错误
+    error RewardTokenNotSet();                                 // 未设置挖矿奖励代币错误
+    error InvalidBorrowThreshold();                            // 借款阈值高于清算阈值错误
+    error InvalidPriceSource();                                // 价格来源类型未知、参数缺失或报价为零错误
+    error NotAllowedSynth();                                   // 合成资产未登记或不能登记错误
+    error SynthDebtCeilingExceeded(uint256);                   // 超出合成资产债务上限错误
+    error SynthDebtOutstanding();                              // 仓位仍有合成资产债务错误
+    error InvalidDebtAuctionParams();                          // 债务拍卖参数无效或未配置错误
+    error NoDebtToAuction();                                   // 保险基金冲抵后剩余坏账不足一场拍卖错误
+    error DebtAuctionNotFound();                               // 债务拍卖不存在或已结算错误
+    error DebtAuctionClosed();                                 // 债务拍卖已结束（出价）或尚未结束（结算）错误
+    error InsufficientLotDecrease(uint256);                    // 出价的协议代币数量降幅不足错误（允许的最大数量）
+    error InsufficientBuybackReserve();                        // 回购储备不足错误
+    error BuybackCapExceeded(uint256);                         // 超过本周期回购上限错误（剩余额度）
+    error PolNotConfigured();                                  // 协议自有流动性池未配置错误
+    error PolDebtCeilingExceeded();                            // 超过协议自有流动性债务上限错误
+    error InsufficientProtocolLiquidity();                     // 协议自有流动性不足错误
+    error PolPositionOpen();                                   // 仍持有 LP 代币时不能更换流动性池错误
 }
 
 // Assuming we have these imports available
@@ -51,7 +407,104 @@ pub enum DSCEngineError {
     HealthFactorNotImproved(HealthFactorNotImproved), // 健康因子未改善错误
     PriceFeedError(PriceFeedError),         // 价格预言机错误
     ConversionError(ConversionError),       // 数据转换错误
-    DecentralizedStableCoinError(DecentralizedStableCoinError), // 稳定币合约错误
+    ExceedsMaxLiquidatableDebt(ExceedsMaxLiquidatableDebt), // 超出可清算债务上限错误
+    TokenDecimalsUnavailable(TokenDecimalsUnavailable), // 无法读取代币精度错误
+    NotOwner(NotOwner),                     // 非治理地址错误
+    CollateralModeLocked(CollateralModeLocked), // 记账模式锁定错误
+    DscInitializationFailed(DscInitializationFailed), // 稳定币合约初始化失败错误
+    NotSavingsVault(NotSavingsVault),       // 非储蓄金库调用错误
+    PsmNotConfigured(PsmNotConfigured),     // PSM 未配置错误
+    PsmDebtCeilingExceeded(PsmDebtCeilingExceeded), // 超出 PSM 债务上限错误
+    PsmTokenLocked(PsmTokenLocked),         // PSM 代币锁定错误
+    InsufficientPsmReserves(InsufficientPsmReserves), // PSM 储备不足错误
+    InvalidFee(InvalidFee),                 // 手续费超出上限错误
+    RedemptionFeeExceeded(RedemptionFeeExceeded), // 赎回手续费超出上限错误
+    NoRedeemablePositions(NoRedeemablePositions), // 没有可赎回仓位错误
+    UnsortedRedemptionList(UnsortedRedemptionList), // 赎回列表未排序错误
+    StabilityPoolNotSet(StabilityPoolNotSet), // 未设置稳定池错误
+    StabilityPoolCanAbsorb(StabilityPoolCanAbsorb), // 稳定池可承接清算错误
+    AuctionActive(AuctionActive),           // 已有进行中的拍卖错误
+    AuctionNotFound(AuctionNotFound),       // 拍卖不存在错误
+    AuctionNeedsReset(AuctionNeedsReset),   // 拍卖需要重启错误
+    AuctionNotExpired(AuctionNotExpired),   // 拍卖尚未到期错误
+    AuctionPriceExceeded(AuctionPriceExceeded), // 拍卖价格超出上限错误
+    InsufficientSurplus(InsufficientSurplus), // 盈余缓冲不足错误
+    TreasuryNotSet(TreasuryNotSet),         // 未设置国库地址错误
+    DexRouterNotSet(DexRouterNotSet),       // 未设置 DEX 路由错误
+    SwapFailed(SwapFailed),                 // DEX 兑换失败错误
+    FlashLiquidationUnprofitable(FlashLiquidationUnprofitable), // 闪电清算兑换所得不足错误
+    SlippageExceeded(SlippageExceeded),     // 兑换滑点超出错误
+    WethNotSet(WethNotSet),                 // 未设置 WETH 地址错误
+    NotVaultOwner(NotVaultOwner),           // 非金库所有者错误
+    InvalidTokenId(erc721::InvalidTokenId), // 金库 NFT 编号无效错误
+    IncorrectOwner(erc721::IncorrectOwner), // 金库 NFT 持有者不符错误
+    NotApproved(erc721::NotApproved),       // 金库 NFT 未授权错误
+    TransferToZero(erc721::TransferToZero), // 金库 NFT 转给零地址错误
+    ReceiverRefused(erc721::ReceiverRefused), // 金库 NFT 接收方拒绝错误
+    InvalidEModeCategory(InvalidEModeCategory), // 无效的 E-mode 类别错误
+    EModeCollateralMismatch(EModeCollateralMismatch), // 抵押品不属于所选 E-mode 类别错误
+    PositionNotEmpty(PositionNotEmpty),     // 仓位非空时不能切换保证金模式错误
+    PositionTransferNotAccepted(PositionTransferNotAccepted), // 接收方未同意接收仓位错误
+    MigrationNotAllowed(MigrationNotAllowed), // 未登记的迁移目标或来源错误
+    MigrationFailed(MigrationFailed),       // 新引擎拒绝接收迁移仓位错误
+    AlreadyInitialized(AlreadyInitialized), // 引擎已初始化错误
+    InsufficientCollateral(InsufficientCollateral), // 赎回数量超过已存入抵押品错误
+    RepayExceedsDebt(RepayExceedsDebt),     // 偿还数量超过债务错误
+    BurnFailed(BurnFailed),                 // 销毁失败错误
+    SequencerDown(SequencerDown),           // 排序器停机错误
+    PriceFeedDown(PriceFeedDown),           // 价格预言机中断错误
+    LiquidationGracePeriod(LiquidationGracePeriod), // 清算宽限期内错误
+    MintRateLimited(MintRateLimited),       // 超过本周期铸造上限错误
+    MintCooldownActive(MintCooldownActive), // 大额铸造冷却期内错误
+    PriceCircuitBreakerTripped(PriceCircuitBreakerTripped), // 抵押品价格偏离熔断中错误
+    DscComplianceUpdateFailed(DscComplianceUpdateFailed), // 设置稳定币合规角色失败错误
+    DscBridgeLimitsUpdateFailed(DscBridgeLimitsUpdateFailed), // 设置稳定币跨链桥额度失败错误
+    DscMaxSupplyUpdateFailed(DscMaxSupplyUpdateFailed), // 设置稳定币总供应上限失败错误
+    DscTransferHookUpdateFailed(DscTransferHookUpdateFailed), // 设置稳定币转账钩子失败错误
+    NotAllowlisted(NotAllowlisted),         // 许可模式下地址不在白名单中错误
+    InvalidSignature(InvalidSignature),     // 签名无效或 nonce 不匹配错误
+    OrderExpired(OrderExpired),             // 签名订单已过截止时间错误
+    NotRemoteMessenger(NotRemoteMessenger), // 非跨链消息适配器调用错误
+    InvalidRemoteCollateral(InvalidRemoteCollateral), // 远程抵押品或存入证明无效错误
+    RemoteChainCapExceeded(RemoteChainCapExceeded), // 超出远程链抵押品价值上限错误
+    RemoteReleaseFailed(RemoteReleaseFailed), // 通知远程链释放抵押品失败错误
+    RemoteCollateralUnsupported(RemoteCollateralUnsupported), // 该操作不支持远程抵押品错误
+    InvalidBonusCurve(InvalidBonusCurve),   // 清算奖励曲线参数无效错误
+    InvalidRiskTier(InvalidRiskTier),       // 风险等级无效或未配置错误
+    SupplyCapExceeded(SupplyCapExceeded),   // 超出抵押品存入上限错误
+    InvalidDynamicLtv(InvalidDynamicLtv),   // 动态阈值参数无效错误
+    DynamicLtvNotConfigured(DynamicLtvNotConfigured), // 抵押品未启用动态阈值错误
+    DynamicLtvCooldown(DynamicLtvCooldown), // 动态阈值调整间隔未到错误
+    NegativeValue(NegativeValue),           // 有符号数值为负错误
+    ValueOverflow(ValueOverflow),           // 数值超出可安全运算的范围错误
+    InvalidRateStrategy(InvalidRateStrategy), // 利率策略的最优使用率无效错误
+    WithdrawalDelayRequired(WithdrawalDelayRequired), // 赎回数量过大，必须走延时提取错误
+    WithdrawalNotFound(WithdrawalNotFound), // 提取请求不存在、已执行或已取消错误
+    WithdrawalNotReady(WithdrawalNotReady), // 提取请求延时未结束错误
+    NotGuardian(NotGuardian),               // 非守护者或治理地址错误
+    InvalidWithdrawalDelay(InvalidWithdrawalDelay), // 延时提取参数无效错误
+    OutflowRateLimited(OutflowRateLimited), // 超过本周期抵押品流出上限错误
+    InvalidOutflowLimit(InvalidOutflowLimit), // 流出限制参数无效错误
+    InvalidEmergencyPrice(InvalidEmergencyPrice), // 应急价格为零、偏离过大或有效期过长错误
+    FeedDeviationTooHigh(FeedDeviationTooHigh), // 新预言机报价偏离旧报价过大错误
+    InvalidCriticalHealthFactor(InvalidCriticalHealthFactor), // 临界健康因子高于最小健康因子错误
+    RewardTokenNotSet(RewardTokenNotSet),   // 未设置挖矿奖励代币错误
+    InvalidBorrowThreshold(InvalidBorrowThreshold), // 借款阈值高于清算阈值错误
+    InvalidPriceSource(InvalidPriceSource), // 价格来源无效错误
+    NotAllowedSynth(NotAllowedSynth),       // 合成资产未登记错误
+    SynthDebtCeilingExceeded(SynthDebtCeilingExceeded), // 超出合成资产债务上限错误
+    SynthDebtOutstanding(SynthDebtOutstanding), // 仓位仍有合成资产债务错误
+    InvalidDebtAuctionParams(InvalidDebtAuctionParams), // 债务拍卖参数无效错误
+    NoDebtToAuction(NoDebtToAuction),       // 剩余坏账不足一场拍卖错误
+    DebtAuctionNotFound(DebtAuctionNotFound), // 债务拍卖不存在错误
+    DebtAuctionClosed(DebtAuctionClosed),   // 债务拍卖状态不允许该操作错误
+    InsufficientLotDecrease(InsufficientLotDecrease), // 出价降幅不足错误
+    InsufficientBuybackReserve(InsufficientBuybackReserve), // 回购储备不足错误
+    BuybackCapExceeded(BuybackCapExceeded), // 超过回购上限错误
+    PolNotConfigured(PolNotConfigured),     // 协议自有流动性池未配置错误
+    PolDebtCeilingExceeded(PolDebtCeilingExceeded), // 超过协议自有流动性债务上限错误
+    InsufficientProtocolLiquidity(InsufficientProtocolLiquidity), // 协议自有流动性不足错误
+    PolPositionOpen(PolPositionOpen),       // 仍持有 LP 代币错误
 }
 
 sol_interface! {
@@ -62,6 +515,13 @@ sol_interface! {
     external
     view
     returns (uint80 roundId, int256 answer, uint256 startedAt, uint256 updatedAt, uint80 answeredInRound);
+        // 获取指定一轮的价格数据
+        function getRoundData(uint80 round_id)
+    external
+    view
+    returns (uint80 roundId, int256 answer, uint256 startedAt, uint256 updatedAt, uint80 answeredInRound);
+        // 获取报价精度
+        function decimals() external view returns (uint8);
     }
     // 定义 ERC20 代币接口
     interface IERC20 {
@@ -69,34 +529,270 @@ sol_interface! {
         function transfer(address to, uint256 value) external returns (bool);
         // 转账到目标地址
         function transferFrom(address from, address to, uint256 value) external returns (bool);
+        // 获取代币精度
+        function decimals() external view returns (uint8);
+        // 获取账户余额
+        function balanceOf(address account) external view returns (uint256);
+        // 授权目标地址使用代币
+        function approve(address spender, uint256 value) external returns (bool);
+    }
+    // 定义稳定币合约接口：引擎作为稳定币合约的 owner 铸造和销毁
+    interface IDecentralizedStableCoin {
+        function mint(address to, uint256 amount) external returns (bool);
+        function burn(uint256 amount) external;
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+        function transfer(address to, uint256 value) external returns (bool);
+        function balanceOf(address account) external view returns (uint256);
+        function setCompliance(address compliance) external;
+        function setLimits(address bridge, uint256 minting_limit, uint256 burning_limit) external;
+        function setMaxSupply(uint256 max_supply) external;
+        function setTransferHook(address hook) external;
+    }
+    // 定义 ERC-4626 金库接口：用于为金库份额定价
+    interface IERC4626 {
+        // 获取底层资产地址
+        function asset() external view returns (address);
+        // 份额换算为底层资产数量
+        function convertToAssets(uint256 shares) external view returns (uint256);
     }
+    // 定义汇率合约接口：返回 18 位精度的兑换比例（如 wstETH/stETH）
+    interface IRateProvider {
+        function getRate() external view returns (uint256);
+    }
+}
+
+#[cfg(feature = "export-abi")]
+pub use abi_export::print_abi;
+
+/// 金库 NFT 的固定参数
+pub struct VaultNftParams;
+impl Erc721Params for VaultNftParams {
+    const NAME: &'static str = "DSC Vault";
+    const SYMBOL: &'static str = "DSC-VAULT";
 }
 
+// 基础精度：18 位
+pub(crate) const PRECISION: U256 = U256::from_limbs([1_000_000_000_000_000_000, 0, 0, 0]);
+// 预言机精度调整因子：把 8 位精度的价格调整为 18 位
+pub(crate) const ADDITIONAL_FEED_PRECISION: U256 = U256::from_limbs([10_000_000_000, 0, 0, 0]);
+// 清算精度：清算阈值、清算奖励和平仓系数等比例的分母
+pub(crate) const LIQUIDATION_PRECISION: U256 = U256::from_limbs([100, 0, 0, 0]);
+
+// 当前实现版本，部署在代理之后时用于区分升级前后的实现
+pub const ENGINE_VERSION: u64 = 1;
+// 当前存储布局版本，存储布局变化时递增
+pub const ENGINE_STORAGE_VERSION: u64 = 1;
+// 已发布的 IDSCEngine 接口 ID，见 erc165 模块
+pub use erc165::DSC_ENGINE_INTERFACE_ID;
+// 预设的抵押品风险等级编号，见 risk_tier 模块
+pub use risk_tier::{TIER_A, TIER_B, TIER_C};
+// 价格来源类型编号，见 price_source 模块
+pub use price_source::{
+    PRICE_SOURCE_CHAINLINK, PRICE_SOURCE_COMPOSED, PRICE_SOURCE_PYTH, PRICE_SOURCE_TWAP,
+};
+
 // 定义合约存储结构
+// 存储从槽位 0 开始顺序排列，代理合约的管理数据需放在 ERC-1967 等哈希槽位，避免与之冲突；
+// 升级时只能在末尾追加字段，不能删除或调整已有字段的顺序
+// 引擎合约入口见 meta_tx 模块
 sol_storage! {
-    #[entrypoint]
     pub struct DSCEngine {
-        uint256 additional_feed_precision;    // 预言机精度调整因子：用于调整价格精度
-        uint256 precision;                    // 基础精度：合约基础计算精度
-        uint256 liquidation_threshold;        // 清算阈值：触发清算的阈值
-        uint256 liquidation_precision;        // 清算精度：清算计算精度
-        uint256 min_health_factor;           // 最小健康因子：维持仓位所需的最小健康因子
-        uint256 liquidation_bonus;           // 清算奖励：清算人获得的奖励比例
+        uint256 unused_additional_feed_precision; // 已废弃：预言机精度调整因子改为常量，保留槽位以维持存储布局
+        uint256 unused_precision;             // 已废弃：基础精度改为常量，保留槽位以维持存储布局
+        uint256 unused_liquidation_threshold; // 已废弃：清算阈值改为打包存储，保留槽位以维持存储布局
+        uint256 unused_liquidation_precision; // 已废弃：清算精度改为常量，保留槽位以维持存储布局
+        uint256 unused_min_health_factor;    // 已废弃：最小健康因子改为打包存储，保留槽位以维持存储布局
+        uint256 unused_liquidation_bonus;    // 已废弃：清算奖励改为打包存储，保留槽位以维持存储布局
         mapping(address => address) price_feeds;  // 价格预言机映射：代币地址到预言机地址的映射
         mapping(address => mapping(address => uint256)) collateral_deposited;  // 抵押品存款映射：用户地址到代币地址到数量的映射
         mapping(address => uint256) dsc_minted;   // 已铸造映射：用户地址到已铸造稳定币数量的映射
         address[] collateral_tokens;          // 抵押品列表：支持的抵押品代币地址列表
-        DecentralizedStableCoin dsc;         // DSC实例：稳定币合约实例
+        address dsc;                         // DSC地址：稳定币合约地址
+        uint256 unused_close_factor;         // 已废弃：平仓系数改为打包存储，保留槽位以维持存储布局
+        mapping(address => uint256) total_collateral_deposited; // 抵押品总量：每种抵押品的存入总量
+        uint256 total_dsc_minted;            // 稳定币总债务：所有用户已铸造稳定币之和
+        uint256 active_positions;            // 活跃仓位数：持有抵押品或债务的用户数量
+        mapping(address => bool) has_active_position; // 活跃仓位标记：用户是否持有抵押品或债务
+        mapping(address => uint8) collateral_decimals; // 抵押品精度：上架时记录的代币 decimals
+        address owner;                       // 治理地址：可以调整协议参数的地址
+        mapping(address => bool) collateral_shares_mode; // 份额模式：按引擎持仓份额记账的（可变基）抵押品
+        mapping(address => bool) erc4626_collateral; // ERC-4626 抵押品：按底层资产价格为金库份额定价
+        mapping(address => uint8) vault_asset_decimals; // 金库底层资产精度
+        mapping(address => address) rate_providers; // 汇率合约：与基础预言机相乘得到抵押品价格
+        mapping(address => uint256) stability_fee_rates; // 稳定费率：每种抵押品每秒的费率（基础精度）
+        mapping(address => uint256) debt_rate_index; // 旧版债务累计指数（基础精度）：已由 RAY 精度的 rate_index 取代，只读
+        mapping(address => uint256) last_accrual_time; // 上次计提时间：每种抵押品上次计提稳定费的时间
+        mapping(address => mapping(address => uint256)) normalized_debt; // 标准化债务：用户地址到抵押品分桶的标准化本金
+        mapping(address => uint256) total_normalized_debt; // 标准化债务总量：每种抵押品分桶的标准化本金之和
+        uint256 accrued_stability_fees;      // 累计稳定费：已计提的协议稳定费收入
+        address savings_vault;               // 储蓄金库：由稳定费收入支付储蓄利率的 sDSC 金库
+        address psm_token;                   // PSM 代币：可按 1:1 兑换 DSC 的稳定币（如 USDC）
+        uint8 psm_token_decimals;            // PSM 代币精度
+        uint256 psm_fee_in;                  // PSM 换入手续费（基点）
+        uint256 psm_fee_out;                 // PSM 换出手续费（基点）
+        uint256 psm_debt_ceiling;            // PSM 债务上限：经 PSM 铸造的 DSC 上限
+        uint256 psm_debt;                    // PSM 债务：经 PSM 铸造且尚未换回的 DSC
+        uint256 psm_reserves;                // PSM 储备：合约持有的 PSM 代币数量
+        address[] borrowers;                 // 仓位列表：持有抵押品或债务的用户地址
+        mapping(address => uint256) borrower_index; // 仓位索引：用户在仓位列表中的位置（从 1 开始）
+        uint256 redemption_fee;              // 赎回手续费（基点）：以抵押品计
+        address stability_pool;              // 稳定池：优先承接清算债务的 DSC 存款池
+        mapping(uint256 => Auction) auctions; // 拍卖映射：拍卖编号到拍卖状态的映射
+        uint256 auction_count;               // 拍卖数量：已发起的拍卖总数，用于分配编号
+        mapping(address => uint256) active_auction; // 进行中的拍卖：用户地址到拍卖编号的映射
+        uint256 auction_duration;            // 拍卖时长：价格从起拍价降到零所需的秒数
+        uint256 auction_start_premium;       // 起拍溢价：起拍价相对预言机价格的比例（清算精度）
+        uint256 system_bad_debt;             // 系统坏账：已核销仓位中尚未冲抵的 DSC 本金
+        uint256 insurance_fund;              // 保险基金：引擎持有、用于冲抵坏账的 DSC
+        uint256 surplus_buffer;              // 盈余缓冲：引擎持有、尚未支出的协议收入
+        mapping(uint8 => uint256) surplus_by_source; // 分来源盈余：每种来源累计计入的协议收入
+        address treasury;                    // 国库地址：接收手续费分成的地址
+        bool unused_fee_switch;              // 已废弃：手续费开关改为打包存储的标志位，保留槽位以维持存储布局
+        mapping(uint8 => uint256) treasury_fee_shares; // 国库分成：每种收入来源划给国库的比例（基点）
+        uint256 treasury_fees;               // 待领取的国库手续费
+        uint256 mint_fee;                    // 铸造手续费（基点）：以 DSC 计，计入债务
+        uint256 collateral_redemption_fee;   // 抵押品赎回手续费（基点）：以抵押品计
+        mapping(address => bool) fee_exempt; // 手续费豁免名单：不收取铸造和赎回手续费的集成方
+        uint256 liquidation_protocol_share;  // 清算协议分成：清算奖励中归协议的比例（清算精度）
+        uint256 keeper_reward;               // keeper 奖励：每次成功清算从盈余中支付的固定 DSC
+        address dex_router;                  // DEX 路由：闪电清算和 zap 兑换使用的路由合约
+        address weth;                        // WETH 地址：原生 ETH 开仓时包装使用的代币
+        uint256 vault_count;                 // 金库数量：已开设的金库总数，用于分配编号
+        #[borrow]
+        Erc721<VaultNftParams> vault_nft;   // 金库 NFT：金库编号即 NFT 编号，持有者即金库所有者
+        mapping(uint8 => uint256) emode_thresholds; // E-mode 阈值：每个类别的清算阈值，为零表示未启用
+        mapping(address => uint8) collateral_emode_category; // 抵押品类别：抵押品所属的 E-mode 类别
+        mapping(address => uint8) user_emode_category; // 用户类别：仓位选择的 E-mode 类别，0 表示未启用
+        mapping(address => bool) isolated_margin; // 逐仓模式：每种抵押品只担保自己分桶中的债务
+        mapping(address => mapping(address => bool)) position_transfer_approvals; // 仓位转让同意：接收方到转出方的映射
+        mapping(address => bool) migration_targets; // 迁移目标：允许迁入的新引擎
+        mapping(address => bool) migration_sources; // 迁移来源：允许迁出到本引擎的旧引擎
+        bool initialized;                    // 初始化标记：initialize 只能调用一次
+        uint256 version;                     // 实现版本：初始化或升级后记录的实现版本号
+        uint256 storage_version;             // 存储版本：当前存储布局的版本号
+        uint256 risk_config;                 // 风险参数：打包的最小健康因子、清算阈值、清算奖励、平仓系数和标志位
+        address sequencer_uptime_feed;       // 排序器在线状态预言机：为零地址时不检查排序器
+        uint256 liquidation_grace_period;    // 清算宽限期：排序器或预言机恢复后暂停清算的秒数
+        uint256 oracle_heartbeat;            // 预言机心跳间隔：超过该秒数未更新视为中断，为零时不检查
+        uint256 mint_window;                 // 铸造窗口：铸造速率限制的周期秒数，为零时不限制
+        uint256 global_mint_cap;             // 全协议铸造上限：每个周期的净铸造上限，为零时不限制
+        uint256 account_mint_cap;            // 单账户铸造上限：每个仓位每个周期的净铸造上限，为零时不限制
+        uint256 mint_epoch;                  // 当前周期：全协议净铸造量所属的周期
+        uint256 epoch_minted;                // 周期铸造量：全协议本周期的净铸造量
+        mapping(address => uint256) account_mint_epoch; // 账户周期：仓位净铸造量所属的周期
+        mapping(address => uint256) account_epoch_minted; // 账户周期铸造量：仓位本周期的净铸造量
+        uint256 max_price_deviation;         // 最大价格偏离：一个心跳间隔内允许的价格变动基点，为零时不检查
+        uint256 price_breaker_cooldown;      // 熔断冷却期：熔断自动解除前的秒数，为零时只能由治理解除
+        mapping(address => uint256) last_accepted_price; // 上次接受的价格：预言机地址 => 价格
+        mapping(address => uint256) last_accepted_price_time; // 上次接受价格的时间：预言机地址 => 时间戳
+        mapping(address => uint256) price_breaker_tripped_at; // 熔断时间：抵押品地址 => 触发熔断的时间戳
+        bool permissioned;                   // 许可模式：开启后只有白名单地址可以存入和铸造
+        mapping(address => bool) allowlisted; // 白名单：许可模式下允许存入和铸造的地址
+        address trusted_forwarder;           // 受信任的转发合约：ERC-2771 元交易转发地址，为零时不接受转发
+        mapping(address => uint256) liquidation_order_nonces; // 清算订单 nonce：清算人下一张签名订单的 nonce
+        address remote_messenger;            // 跨链消息适配器：验证远程链存入证明并转发释放通知的合约
+        mapping(address => uint256) remote_collateral_chain; // 远程抵押品：抵押品别名地址 => 所在链 ID，为零表示本链抵押品
+        mapping(uint256 => uint256) remote_chain_cap; // 远程链上限：链 ID => 该链记入的抵押品总价值上限
+        mapping(bytes32 => bool) processed_attestations; // 已处理的存入证明：消息编号 => 是否已处理
+        uint256 large_mint_threshold;        // 大额铸造阈值：单笔达到该数量的铸造受冷却期限制，为零时不限制
+        uint256 mint_cooldown;               // 铸造冷却期：同一仓位两次大额铸造之间的最短秒数
+        mapping(address => uint256) last_large_mint; // 上次大额铸造时间：仓位地址 => 时间戳
+        mapping(address => RateStrategy) rate_strategies; // 分段利率策略：抵押品地址 => 按使用率计算稳定费率的参数
+        mapping(address => uint256) rate_index; // 债务累计指数：每种抵押品按秒复利的利息累计指数（RAY 精度）
+        uint256 min_liquidation_bonus;       // 最低清算奖励：健康因子刚跌破最小健康因子时的清算奖励（清算精度）
+        uint256 full_bonus_health_factor;    // 满额奖励健康因子：健康因子不高于该值时给最高清算奖励，为零时不缩放
+        mapping(uint8 => RiskTier) risk_tiers; // 风险等级：等级编号 => 清算阈值、清算奖励、存入上限和预言机超时
+        mapping(address => uint8) collateral_risk_tier; // 抵押品风险等级：抵押品地址 => 等级编号，0 表示沿用全局参数
+        mapping(address => LtvAdjuster) ltv_adjusters; // 动态阈值：抵押品地址 => 按价格波动调整清算阈值的参数和当前阈值
+        address guardian;                    // 守护者：可以取消延时提取请求的地址
+        uint256 large_withdrawal_share;      // 大额提取比例：单笔赎回超过存款总量的该比例（基点）时必须延时提取，为零时不启用
+        uint256 withdrawal_delay;            // 提取延时：提交请求到可以执行之间的秒数
+        uint256 next_withdrawal_id;          // 最近一笔提取请求的编号
+        mapping(uint256 => PendingWithdrawal) pending_withdrawals; // 提取请求：请求编号 => 待执行的大额提取
+        uint256 outflow_window;              // 流出窗口：抵押品流出限制的周期秒数，为零时不限制
+        uint256 outflow_share;               // 流出比例：每个周期净流出量占周期开始时存款总量的上限（基点），为零时不限制
+        mapping(address => OutflowEpoch) outflow_epochs; // 流出记录：抵押品地址 => 本周期的净流出量和周期开始时的存款总量
+        mapping(address => PriceOverride) price_overrides; // 应急价格：抵押品地址 => 守护者设置的临时价格和失效时间
+        uint256 max_override_deviation;      // 应急价格最大偏离：相对预言机最近报价的偏离上限（基点）
+        uint256 max_override_duration;       // 应急价格最长有效期（秒），为零时不允许设置应急价格
+        uint256 max_feed_replacement_deviation; // 更换预言机最大偏离：新报价相对旧报价的偏离上限（基点），为零时不检查
+        mapping(address => GoodPrice) last_good_prices; // 最近有效价格：预言机地址 => 最近一次有效的报价和更新时间
+        uint256 price_fallback_window;       // 回退窗口：预言机回滚时最近有效价格可使用的最长秒数，为零时不回退
+        mapping(address => uint256) total_deposited; // 存款总量：引擎为存款人持有的每种抵押品代币数量，内部记账，不受直接转入的代币影响
+        mapping(address => address) referrers; // 推荐关系：用户地址 => 推荐人地址
+        uint256 referral_share;              // 返佣比例：被推荐用户的手续费中划给推荐人的比例（基点）
+        mapping(address => uint256) referral_rewards; // 待领取返佣：推荐人地址 => DSC 数量
+        address reward_token;                // 挖矿奖励代币地址
+        uint256 reward_rate;                 // 挖矿发放速率：每秒发放的奖励代币总量
+        uint256 total_reward_weight;         // 各抵押品分桶发放权重之和
+        mapping(address => RewardPool) reward_pools; // 挖矿分桶：抵押品地址 => 发放权重和每单位债务累计的奖励
+        mapping(address => mapping(address => uint256)) user_reward_index; // 用户已结算到的每单位债务累计奖励：用户地址 => 抵押品地址 => 累计奖励
+        mapping(address => uint256) accrued_rewards; // 已结算未领取的挖矿奖励：用户地址 => 奖励代币数量
+        mapping(bytes32 => ParamRecord) param_records; // 参数登记表：参数键 => 当前值、修改前的值和修改时间
+        mapping(address => PriceSourceConfig) price_sources; // 价格来源：抵押品地址 => 来源类型和配置参数，未设置时为 Chainlink 推送
+        address peg_feed;                    // 锚定货币预言机：一单位锚定货币的美元价格，为零地址时以美元为记账单位
+        uint8 peg_feed_decimals;             // 锚定货币预言机的报价精度
+        address[] synth_tokens;              // 附加合成资产列表：DSC 以外由引擎铸造的合成资产
+        mapping(address => Synth) synths;    // 附加合成资产：合成资产地址 => 价格预言机、债务上限和总债务
+        mapping(address => mapping(address => uint256)) synth_debt; // 合成资产债务：用户地址 => 合成资产地址 => 债务数量
+        address debt_auction_token;          // 债务拍卖的协议代币：引擎为其铸造者
+        uint256 debt_auction_lot;            // 债务拍卖的初始协议代币数量
+        uint256 debt_auction_bid;            // 每场债务拍卖募集的 DSC 数量
+        uint256 debt_auction_duration;       // 债务拍卖时长（秒）
+        uint256 debt_auction_min_decrease;   // 每次出价协议代币数量的最小降幅（基点）
+        uint256 debt_auction_count;          // 债务拍卖数量：已发起的债务拍卖总数，用于分配编号
+        mapping(uint256 => DebtAuction) debt_auctions; // 债务拍卖：拍卖编号 => 出价者、协议代币数量、募集数量和结束时间
+        uint256 debt_on_auction;             // 拍卖中的坏账：已发起、尚未收到首次出价的债务拍卖募集数量之和
+        mapping(address => uint256) buyback_reserves; // 回购储备：代币地址 => 可用于回购 DSC 的数量
+        uint256 buyback_window;              // 回购窗口：回购上限的周期秒数，为零时整个时间算一个周期
+        uint256 buyback_epoch_cap;           // 回购上限：每个周期回购卖出的价值上限（DSC 计），为零时不能回购
+        uint256 buyback_epoch;               // 回购周期：本周期回购价值所属的周期
+        uint256 buyback_epoch_spent;         // 周期回购价值：本周期已回购卖出的价值
+        address pol_pair;                    // 协议自有流动性池：DSC/USDC 池的 LP 代币地址
+        uint256 pol_debt_ceiling;            // 协议自有流动性债务上限：注入流动性池的 DSC 上限
+        uint256 pol_debt;                    // 协议自有流动性债务：注入流动性池且尚未结清的 DSC
+        uint256 pol_liquidity;               // 协议自有流动性：引擎持有的 LP 代币数量
+        mapping(address => uint256) action_nonces; // 操作序号：用户地址 => 最近一次仓位事件的序号
+        mapping(address => address) redemption_next; // 赎回列表后继：用户地址 => 下一个仓位，零地址的后继为列表头
+        mapping(address => address) redemption_prev; // 赎回列表前驱：用户地址 => 上一个仓位，零地址的前驱为列表尾
+        mapping(address => uint256) redemption_keys; // 赎回列表排序键：用户地址 => 最近一次插入时的健康因子
+        mapping(address => bool) redemption_listed; // 用户是否在赎回列表中
     }
 }
 
 #[public]
+#[inherit(Erc721<VaultNftParams>)]
 impl DSCEngine {
-    pub fn constructor(
+    /// 初始化引擎：部署在代理之后时通过代理调用，只能调用一次；以美元为记账单位
+    pub fn initialize(
+        &mut self,
+        token_addresses: Vec<Address>,      // 支持的代币地址列表
+        price_feed_addresses: Vec<Address>, // 对应的价格预言机地址列表
+        dsc_address: Address,               // 稳定币合约地址
+    ) -> Result<(), DSCEngineError> {
+        self.initialize_with_peg(
+            token_addresses,
+            price_feed_addresses,
+            dsc_address,
+            Address::ZERO,
+        )
+    }
+
+    /// 初始化引擎并指定锚定货币预言机（一单位锚定货币的美元价格，如 EUR/USD），
+    /// 债务和抵押品价值都以锚定货币计；零地址表示以美元为记账单位
+    pub fn initialize_with_peg(
         &mut self,
         token_addresses: Vec<Address>,      // 支持的代币地址列表
         price_feed_addresses: Vec<Address>, // 对应的价格预言机地址列表
+        dsc_address: Address,               // 稳定币合约地址
+        peg_feed: Address,                  // 锚定货币预言机地址
     ) -> Result<(), DSCEngineError> {
+        if self.initialized.get() {
+            return Err(DSCEngineError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        self.initialized.set(true);
+        self.version.set(U256::from(ENGINE_VERSION));
+        self.storage_version.set(U256::from(ENGINE_STORAGE_VERSION));
         // 检查代币地址和价格预言机地址长度是否匹配
         if token_addresses.len() != price_feed_addresses.len() {
             return Err(
@@ -107,23 +803,34 @@ impl DSCEngine {
         }
         // 初始化价格预言机映射
         for (token, price_feed) in token_addresses.iter().zip(price_feed_addresses.iter()) {
+            // 记录抵押品精度，用于统一换算为 18 位精度的美元价值
+            let decimals = IERC20::new(*token).decimals(Call::new()).map_err(|_| {
+                DSCEngineError::TokenDecimalsUnavailable(TokenDecimalsUnavailable {})
+            })?;
+            self.collateral_decimals.insert(*token, U8::from(decimals));
             self.price_feeds.insert(*token, *price_feed);
             self.collateral_tokens.push(*token);
         }
 
-        let mut dsc: DecentralizedStableCoin = DecentralizedStableCoin::default();
-        dsc.constructor();
-        self.dsc = dsc;
+        self.owner.set(msg_sender());
 
-        self.additional_feed_precision
-            .set(U256::from(10).pow(U256::from(10))); // 设置精度
-        self.precision.set(U256::from(10).pow(U256::from(18)));
-        self.liquidation_threshold.set(U256::from(50)); // 设置清算阈值
-        self.liquidation_precision.set(U256::from(100)); // 设置清算精度
-        self.min_health_factor
-            .set(U256::from(10).pow(U256::from(18))); // 设置最小健康因子
-        self.liquidation_bonus.set(U256::from(10)); // 设置清算奖励
-        Ok(())
+        // 初始化稳定币合约，使引擎成为其 owner
+        call::call(Call::new(), dsc_address, &function_selector!("constructor"))
+            .map_err(|_| DSCEngineError::DscInitializationFailed(DscInitializationFailed {}))?;
+        self.dsc.set(dsc_address);
+
+        self._set_config(RiskConfig {
+            min_health_factor: 1_000_000_000_000_000_000, // 设置最小健康因子
+            liquidation_threshold: 50,                    // 设置清算阈值
+            liquidation_bonus: 10,                        // 设置清算奖励
+            close_factor: 50,                             // 设置平仓系数
+            flags: 0,
+            borrow_threshold: 0,
+            critical_health_factor: 0,
+        });
+        self.auction_duration.set(U256::from(3600)); // 设置拍卖时长
+        self.auction_start_premium.set(U256::from(120)); // 设置起拍溢价
+        self._set_peg_feed(peg_feed) // 设置锚定货币
     }
 
     /// 存入抵押品并铸造稳定币
@@ -133,9 +840,9 @@ impl DSCEngine {
         amount_collateral: U256,           // 抵押品数量
         amount_dsc_to_mint: U256,          // 要铸造的稳定币数量
     ) -> Result<(), DSCEngineError> {
-        let _ = self.deposit_collateral(token_collateral_address, amount_collateral);
-        self.mint_dsc(amount_dsc_to_mint)?;
-        Ok(())
+        // 任一步失败都回滚整个调用
+        self.deposit_collateral(token_collateral_address, amount_collateral)?;
+        self.mint_dsc(amount_dsc_to_mint)
     }
 
     /// 存入抵押品
@@ -153,36 +860,437 @@ impl DSCEngine {
             return Err(DSCEngineError::NotAllowedToken(NotAllowedToken {}));
         }
 
-        let sender = msg::sender();
-        // 获取用户抵押品存款映射
-        let user_collateral_mapping = self.collateral_deposited.getter(sender);
-        // 获取用户特定代币的抵押品数量
-        let user_collateral = user_collateral_mapping.getter(token_collateral_address);
-        let value = user_collateral.get();
-        // 更新用户抵押品存款映射
-        self.collateral_deposited
-            .setter(sender)
-            .setter(token_collateral_address)
-            .set(value + amount_collateral);
+        self._deposit_collateral(msg_sender(), token_collateral_address, amount_collateral)
+    }
 
-        // 记录抵押品存入事件
-        evm::log(CollateralDeposited {
-            user: sender,
-            token: token_collateral_address,
-            amount: amount_collateral,
-        });
+    /// 存入任意代币，经 DEX 路由兑换为抵押品后记入仓位，返回记入的抵押品数量
+    pub fn zap_deposit_collateral(
+        &mut self,
+        token_in: Address,   // 输入代币地址
+        amount_in: U256,     // 输入代币数量
+        collateral: Address, // 目标抵押品地址
+        min_out: U256,       // 最少兑换得到的抵押品数量
+    ) -> Result<U256, DSCEngineError> {
+        self.more_than_zero(amount_in)?;
+        self.is_allowed_token(collateral)?;
+        self._zap_deposit(token_in, amount_in, collateral, min_out)
+    }
 
-        let token = IERC20::new(token_collateral_address);
-        // 从用户地址转账到合约地址
-        if token
-            .transfer_from(Call::new(), sender, contract::address(), amount_collateral)
-            .is_err()
-        {
-            return Err(DSCEngineError::TransferFailed(TransferFailed {}));
+    /// 经 DEX 路由卖出自己的抵押品并用所得 DSC 偿还债务，不需要预先持有 DSC；
+    /// 兑换所得超过债务的部分转给调用者，返回偿还的债务数量
+    pub fn repay_with_collateral(
+        &mut self,
+        token: Address,          // 要卖出的抵押品地址
+        amount_collateral: U256, // 要卖出的抵押品数量
+        min_debt_repaid: U256,   // 最少兑换得到的 DSC 数量
+    ) -> Result<U256, DSCEngineError> {
+        self.more_than_zero(amount_collateral)?;
+        self.is_allowed_token(token)?;
+        self._accrue_all();
+        self._repay_with_collateral(token, amount_collateral, min_debt_repaid)
+    }
+
+    /// 存入 ETH 作为抵押品并铸造 DSC；设置 min_out 时把铸造的 DSC 兑换回 WETH 追加抵押一次
+    #[payable]
+    pub fn open_position_eth(
+        &mut self,
+        dsc_to_mint: U256, // 要铸造的稳定币数量
+        min_out: U256,     // 杠杆兑换最少得到的 WETH 数量，为零时不兑换
+    ) -> Result<U256, DSCEngineError> {
+        self._accrue_all();
+        self._open_position_eth(dsc_to_mint, min_out)
+    }
+
+    /// 开设新金库并向调用者铸造对应的 NFT，返回金库编号
+    pub fn open_vault(&mut self) -> Result<U256, DSCEngineError> {
+        self._open_vault(msg_sender())
+    }
+
+    /// 向金库存入抵押品
+    pub fn vault_deposit_collateral(
+        &mut self,
+        id: U256,                          // 金库编号
+        token_collateral_address: Address, // 抵押品地址
+        amount_collateral: U256,           // 抵押品数量
+    ) -> Result<(), DSCEngineError> {
+        let position = self._only_vault_owner(id)?;
+        self.more_than_zero(amount_collateral)?;
+        self.is_allowed_token(token_collateral_address)?;
+        self._deposit_collateral(position, token_collateral_address, amount_collateral)
+    }
+
+    /// 以金库为债务人铸造稳定币给调用者
+    pub fn vault_mint_dsc(
+        &mut self,
+        id: U256,                 // 金库编号
+        amount_dsc_to_mint: U256, // 要铸造的稳定币数量
+    ) -> Result<(), DSCEngineError> {
+        let position = self._only_vault_owner(id)?;
+        self.more_than_zero(amount_dsc_to_mint)?;
+        self._accrue_all();
+        self._mint_dsc(position, amount_dsc_to_mint, msg_sender(), None)
+    }
+
+    /// 销毁调用者的稳定币偿还金库债务
+    pub fn vault_burn_dsc(
+        &mut self,
+        id: U256,     // 金库编号
+        amount: U256, // 要销毁的稳定币数量
+    ) -> Result<(), DSCEngineError> {
+        let position = self._only_vault_owner(id)?;
+        self.more_than_zero(amount)?;
+        self._accrue_all();
+        // 偿还只会提高健康因子，金库仍低于最小健康因子时也允许部分偿还
+        self._burn_dsc(amount, position, msg_sender(), None)?;
+        Ok(())
+    }
+
+    /// 从金库赎回抵押品给调用者
+    pub fn vault_redeem_collateral(
+        &mut self,
+        id: U256,                          // 金库编号
+        token_collateral_address: Address, // 抵押品地址
+        amount_collateral: U256,           // 抵押品数量
+    ) -> Result<(), DSCEngineError> {
+        let position = self._only_vault_owner(id)?;
+        self.more_than_zero(amount_collateral)?;
+        self.is_allowed_token(token_collateral_address)?;
+        self._accrue_all();
+        self._redeem_collateral_with_fee(
+            token_collateral_address,
+            amount_collateral,
+            position,
+            msg_sender(),
+        )?;
+        self._revert_if_borrow_health_factor_is_broken(position)
+    }
+
+    pub fn get_vault_position(&self, id: U256) -> Address {
+        // 获取金库的仓位地址，可用于查询健康因子和发起清算
+        Self::_vault_position(id)
+    }
+
+    pub fn get_vault_owner(&self, id: U256) -> Address {
+        // 获取金库所有者，即金库 NFT 的持有者
+        self.vault_nft.owners.get(id)
+    }
+
+    pub fn get_vault_count(&self) -> U256 {
+        // 获取已开设的金库总数
+        self.vault_count.get()
+    }
+
+    /// 设置 E-mode 类别的清算阈值（清算精度），阈值为零表示停用该类别
+    pub fn set_emode_category(
+        &mut self,
+        category: u8,
+        threshold: U256,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self._set_emode_category(category, threshold)?;
+        self._record_param_of("emodeThreshold", id_subject(category.into()), threshold);
+        Ok(())
+    }
+
+    /// 设置抵押品所属的 E-mode 类别，0 表示不属于任何类别
+    pub fn set_collateral_emode_category(
+        &mut self,
+        token: Address,
+        category: u8,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.is_allowed_token(token)?;
+        self._set_collateral_emode_category(token, category);
+        self._record_param_of("emodeCategory", token.into_word(), U256::from(category));
+        Ok(())
+    }
+
+    /// 为调用者的仓位选择 E-mode 类别，0 表示退出 E-mode
+    pub fn set_user_emode(&mut self, category: u8) -> Result<(), DSCEngineError> {
+        self._accrue_all();
+        self._set_user_emode(msg_sender(), category)
+    }
+
+    /// 为金库选择 E-mode 类别
+    pub fn vault_set_user_emode(&mut self, id: U256, category: u8) -> Result<(), DSCEngineError> {
+        let position = self._only_vault_owner(id)?;
+        self._accrue_all();
+        self._set_user_emode(position, category)
+    }
+
+    /// 设置风险等级的清算阈值、最高清算奖励、存入上限和预言机超时，清算阈值为零表示停用该等级
+    pub fn set_risk_tier(
+        &mut self,
+        tier: u8,
+        liquidation_threshold: U256,
+        liquidation_bonus: U256,
+        supply_cap: U256,
+        oracle_timeout: U256,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self._set_risk_tier(
+            tier,
+            liquidation_threshold,
+            liquidation_bonus,
+            supply_cap,
+            oracle_timeout,
+        )?;
+        let subject = id_subject(tier.into());
+        self._record_param_of("tierLiquidationThreshold", subject, liquidation_threshold);
+        self._record_param_of("tierLiquidationBonus", subject, liquidation_bonus);
+        self._record_param_of("tierSupplyCap", subject, supply_cap);
+        self._record_param_of("tierOracleTimeout", subject, oracle_timeout);
+        Ok(())
+    }
+
+    /// 将抵押品分配到已配置的风险等级，0 表示沿用全局参数
+    pub fn set_collateral_risk_tier(
+        &mut self,
+        token: Address,
+        tier: u8,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.is_allowed_token(token)?;
+        self._set_collateral_risk_tier(token, tier)?;
+        self._record_param_of("riskTier", token.into_word(), U256::from(tier));
+        Ok(())
+    }
+
+    pub fn get_risk_tier(&self, tier: u8) -> (U256, U256, U256, U256) {
+        // 获取风险等级的参数：(清算阈值, 最高清算奖励, 存入上限, 预言机超时)
+        let params = self.risk_tiers.getter(U8::from(tier));
+        (
+            params.liquidation_threshold.get(),
+            params.liquidation_bonus.get(),
+            params.supply_cap.get(),
+            params.oracle_timeout.get(),
+        )
+    }
+
+    pub fn get_collateral_risk_tier(&self, token: Address) -> u8 {
+        // 获取抵押品所属的风险等级
+        self.collateral_risk_tier.get(token).to::<u8>()
+    }
+
+    pub fn get_collateral_liquidation_threshold(&self, token: Address) -> U256 {
+        // 获取抵押品生效的清算阈值（未分配风险等级时为默认阈值）
+        self._collateral_threshold(token)
+    }
+
+    /// 设置抵押品的动态阈值：阈值上下限、触发收紧的价格偏离（基点）、每次调整的步长和最短调整间隔；
+    /// 启用时阈值从上限开始，上限为零时停用
+    pub fn set_dynamic_ltv(
+        &mut self,
+        token: Address,
+        min_threshold: U256,
+        max_threshold: U256,
+        volatility_trigger: U256,
+        step: U256,
+        cooldown: U256,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.is_allowed_token(token)?;
+        self._set_dynamic_ltv(
+            token,
+            min_threshold,
+            max_threshold,
+            volatility_trigger,
+            step,
+            cooldown,
+        )?;
+        let subject = token.into_word();
+        self._record_param_of("ltvMinThreshold", subject, min_threshold);
+        self._record_param_of("ltvMaxThreshold", subject, max_threshold);
+        self._record_param_of("ltvVolatilityTrigger", subject, volatility_trigger);
+        self._record_param_of("ltvStep", subject, step);
+        self._record_param_of("ltvCooldown", subject, cooldown);
+        Ok(())
+    }
+
+    /// 按预言机最新的价格偏离调整一次抵押品的动态阈值，任何人都可以调用，返回调整后的阈值
+    pub fn poke_dynamic_threshold(&mut self, token: Address) -> Result<U256, DSCEngineError> {
+        self._poke_dynamic_threshold(token)
+    }
+
+    pub fn get_dynamic_ltv(&self, token: Address) -> (U256, U256, U256, U256, U256, U256, U256) {
+        // 获取抵押品的动态阈值参数：(下限, 上限, 触发偏离, 步长, 最短间隔, 当前阈值, 上次调整时间)
+        let adjuster = self.ltv_adjusters.getter(token);
+        (
+            adjuster.min_threshold.get(),
+            adjuster.max_threshold.get(),
+            adjuster.volatility_trigger.get(),
+            adjuster.step.get(),
+            adjuster.cooldown.get(),
+            adjuster.threshold.get(),
+            adjuster.last_poke.get(),
+        )
+    }
+
+    pub fn get_price_deviation_bps(&self, token: Address) -> Result<U256, DSCEngineError> {
+        // 获取抵押品预言机最新一轮相对上一轮的价格偏离（基点）
+        self._price_deviation_bps(token)
+    }
+
+    pub fn get_emode_category_threshold(&self, category: u8) -> U256 {
+        // 获取 E-mode 类别的清算阈值
+        self.emode_thresholds.get(U8::from(category))
+    }
+
+    pub fn get_collateral_emode_category(&self, token: Address) -> u8 {
+        // 获取抵押品所属的 E-mode 类别
+        self.collateral_emode_category.get(token).to::<u8>()
+    }
+
+    pub fn get_user_emode(&self, user: Address) -> u8 {
+        // 获取仓位选择的 E-mode 类别
+        self.user_emode_category.get(user).to::<u8>()
+    }
+
+    pub fn get_user_liquidation_threshold(&self, user: Address) -> U256 {
+        // 获取仓位当前适用的清算阈值
+        self._liquidation_threshold_of(user)
+    }
+
+    /// 为调用者的空仓位选择逐仓（true）或全仓（false）模式
+    pub fn set_margin_mode(&mut self, isolated: bool) -> Result<(), DSCEngineError> {
+        self._set_margin_mode(msg_sender(), isolated)
+    }
+
+    /// 为空金库选择逐仓（true）或全仓（false）模式
+    pub fn vault_set_margin_mode(
+        &mut self,
+        id: U256,
+        isolated: bool,
+    ) -> Result<(), DSCEngineError> {
+        let position = self._only_vault_owner(id)?;
+        self._set_margin_mode(position, isolated)
+    }
+
+    /// 铸造稳定币，债务只记入指定抵押品的分桶
+    pub fn mint_dsc_against_collateral(
+        &mut self,
+        token_collateral_address: Address, // 担保债务的抵押品地址
+        amount_dsc_to_mint: U256,          // 要铸造的稳定币数量
+    ) -> Result<(), DSCEngineError> {
+        self.more_than_zero(amount_dsc_to_mint)?;
+        self.is_allowed_token(token_collateral_address)?;
+        self._accrue_all();
+        self._mint_dsc(
+            msg_sender(),
+            amount_dsc_to_mint,
+            msg_sender(),
+            Some(token_collateral_address),
+        )
+    }
+
+    /// 以金库为债务人铸造稳定币，债务只记入指定抵押品的分桶
+    pub fn vault_mint_dsc_against_collateral(
+        &mut self,
+        id: U256,                          // 金库编号
+        token_collateral_address: Address, // 担保债务的抵押品地址
+        amount_dsc_to_mint: U256,          // 要铸造的稳定币数量
+    ) -> Result<(), DSCEngineError> {
+        let position = self._only_vault_owner(id)?;
+        self.more_than_zero(amount_dsc_to_mint)?;
+        self.is_allowed_token(token_collateral_address)?;
+        self._accrue_all();
+        self._mint_dsc(
+            position,
+            amount_dsc_to_mint,
+            msg_sender(),
+            Some(token_collateral_address),
+        )
+    }
+
+    /// 同意或撤销接收来自 `from` 的仓位转让
+    pub fn accept_position_transfer(&mut self, from: Address, accepted: bool) {
+        self._accept_position_transfer(msg_sender(), from, accepted);
+    }
+
+    /// 把调用者的整个仓位（抵押品和债务）转给已同意接收的 `to`
+    pub fn transfer_position(&mut self, to: Address) -> Result<(), DSCEngineError> {
+        if to == msg_sender() || to.is_zero() {
+            return Err(DSCEngineError::PositionTransferNotAccepted(
+                PositionTransferNotAccepted {},
+            ));
         }
+        self._accrue_all();
+        self._transfer_position(msg_sender(), to)
+    }
+
+    /// 把调用者仓位中的指定抵押品和全部债务迁移到已登记的新引擎，返回迁移的债务
+    pub fn migrate_to(
+        &mut self,
+        new_engine: Address,  // 新引擎地址
+        tokens: Vec<Address>, // 要迁移的抵押品列表
+    ) -> Result<U256, DSCEngineError> {
+        self._accrue_all();
+        self._migrate_to(new_engine, tokens)
+    }
+
+    /// 新引擎的迁移接口：由已登记的旧引擎调用，记入迁来的抵押品和债务
+    pub fn receive_migrated_position(
+        &mut self,
+        user: Address,
+        tokens: Vec<Address>,
+        amounts: Vec<U256>,
+        debt: U256,
+    ) -> Result<bool, DSCEngineError> {
+        self._accrue_all();
+        self._receive_migrated_position(user, tokens, amounts, debt)?;
+        Ok(true)
+    }
+
+    /// 登记或移除允许迁入的新引擎
+    pub fn set_migration_target(
+        &mut self,
+        engine: Address,
+        allowed: bool,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.migration_targets.setter(engine).set(allowed);
+        self._record_param_of("migrationTarget", engine.into_word(), U256::from(allowed));
+        Ok(())
+    }
+
+    /// 登记或移除允许迁出到本引擎的旧引擎
+    pub fn set_migration_source(
+        &mut self,
+        engine: Address,
+        allowed: bool,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.migration_sources.setter(engine).set(allowed);
+        self._record_param_of("migrationSource", engine.into_word(), U256::from(allowed));
         Ok(())
     }
 
+    pub fn is_migration_target(&self, engine: Address) -> bool {
+        // 获取是否允许迁入该新引擎
+        self.migration_targets.get(engine)
+    }
+
+    pub fn is_migration_source(&self, engine: Address) -> bool {
+        // 获取是否允许该旧引擎迁出到本引擎
+        self.migration_sources.get(engine)
+    }
+
+    pub fn is_position_transfer_accepted(&self, from: Address, to: Address) -> bool {
+        // 获取接收方是否同意接收来自转出方的仓位
+        self.position_transfer_approvals.getter(to).get(from)
+    }
+
+    pub fn is_isolated_margin(&self, user: Address) -> bool {
+        // 获取仓位是否为逐仓模式
+        self.isolated_margin.get(user)
+    }
+
+    pub fn get_bucket_health_factor(&self, user: Address, token: Address) -> U256 {
+        // 获取仓位某抵押品分桶的健康因子，价格不可用时为零
+        self._bucket_health_factor(user, token)
+            .unwrap_or(U256::ZERO)
+    }
+
     /// 赎回抵押品并销毁稳定币
     pub fn redeem_collateral_for_dsc(
         &mut self,
@@ -194,16 +1302,18 @@ impl DSCEngine {
         self.more_than_zero(amount_collateral)?;
         // 检查代币是否在支持列表中
         self.is_allowed_token(token_collateral_address)?;
+        // 计提稳定费
+        self._accrue_all();
         // 销毁稳定币
-        self._burn_dsc(amount_dsc_to_burn, msg::sender(), msg::sender());
+        self._burn_dsc(amount_dsc_to_burn, msg_sender(), msg_sender(), None)?;
         // 赎回抵押品
-        let _ = self._redeem_collateral(
+        self._redeem_collateral_with_fee(
             token_collateral_address,
             amount_collateral,
-            msg::sender(),
-            msg::sender(),
-        );
-        self._revert_if_health_factor_is_broken(msg::sender())?;
+            msg_sender(),
+            msg_sender(),
+        )?;
+        self._revert_if_borrow_health_factor_is_broken(msg_sender())?;
         Ok(())
     }
 
@@ -214,14 +1324,16 @@ impl DSCEngine {
         amount_collateral: U256,           // 抵押品数量
     ) -> Result<(), DSCEngineError> {
         self.more_than_zero(amount_collateral)?;
+        // 计提稳定费，按最新债务检查健康因子
+        self._accrue_all();
         // 赎回抵押品
-        let _ = self._redeem_collateral(
+        self._redeem_collateral_with_fee(
             token_collateral_address,
             amount_collateral,
-            msg::sender(),
-            msg::sender(),
-        );
-        self._revert_if_health_factor_is_broken(msg::sender())?;
+            msg_sender(),
+            msg_sender(),
+        )?;
+        self._revert_if_borrow_health_factor_is_broken(msg_sender())?;
         Ok(())
     }
 
@@ -232,19 +1344,21 @@ impl DSCEngine {
     ) -> Result<(), DSCEngineError> {
         // 检查铸造数量是否大于零
         self.more_than_zero(amount_dsc_to_mint)?;
-        // 获取用户已铸造的稳定币数量
-        let user_dsc_minted = self.dsc_minted.get(msg::sender());
-        // 更新用户已铸造的稳定币数量
-        self.dsc_minted
-            .setter(msg::sender())
-            .set(user_dsc_minted + amount_dsc_to_mint);
-        // 检查健康因子是否正常
-        self._revert_if_health_factor_is_broken(msg::sender())?;
-        // 铸造稳定币
-        self.dsc
-            .mint(msg::sender(), amount_dsc_to_mint)
-            .map_err(|e| DSCEngineError::DecentralizedStableCoinError(e))?;
-        Ok(())
+        // 计提稳定费并按抵押品分摊新增债务（含铸造手续费）
+        self._accrue_all();
+        self._mint_dsc(msg_sender(), amount_dsc_to_mint, msg_sender(), None)
+    }
+
+    /// 铸造稳定币并附带推荐人：调用者尚无推荐人时记录该推荐人，之后的手续费按返佣比例分给推荐人
+    pub fn mint_dsc_with_referral(
+        &mut self,
+        amount_dsc_to_mint: U256, // 要铸造的稳定币数量
+        referrer: Address,        // 推荐人地址
+    ) -> Result<(), DSCEngineError> {
+        self.more_than_zero(amount_dsc_to_mint)?;
+        self._set_referrer(msg_sender(), referrer);
+        self._accrue_all();
+        self._mint_dsc(msg_sender(), amount_dsc_to_mint, msg_sender(), None)
     }
 
     pub fn burn_dsc(
@@ -252,11 +1366,11 @@ impl DSCEngine {
         amount: U256, // 要销毁的稳定币数量
     ) -> Result<(), DSCEngineError> {
         self.more_than_zero(amount)?;
-        self.dsc
-            .burn(amount)
-            .map_err(|e| DSCEngineError::DecentralizedStableCoinError(e))?;
-        // ... 其他逻辑
-        Ok(())
+        // 计提稳定费
+        self._accrue_all();
+        // 销毁调用者的稳定币并减少其债务
+        // 偿还只会提高健康因子，仓位仍低于最小健康因子时也允许部分偿还
+        self._burn_dsc(amount, msg_sender(), msg_sender(), None)
     }
 
     /// 清算功能
@@ -266,72 +1380,2582 @@ impl DSCEngine {
         user: Address,       // 要清算的用户地址
         debt_to_cover: U256, // 要清算的债务数量
     ) -> Result<(), DSCEngineError> {
-        // 检查债务数量是否大于零
-        self.more_than_zero(debt_to_cover)?;
-        // 检查健康因子是否正常
-        let starting_user_health_factor = self._health_factor(user);
-        if starting_user_health_factor >= self.min_health_factor.get() {
-            return Err(DSCEngineError::HealthFactorOk(HealthFactorOk {}));
-        }
-        // 获取债务对应的抵押品数量
-        let token_amount_from_debt_covered =
-            self.get_token_amount_from_usd(collateral, debt_to_cover);
-        // 计算清算奖励
-        let bonus_collateral =
-            (token_amount_from_debt_covered * self.liquidation_bonus.get()) / U256::from(100);
-        let total_collateral_to_redeem = token_amount_from_debt_covered + bonus_collateral;
-        // 赎回抵押品
-        let _ =
-            self._redeem_collateral(collateral, total_collateral_to_redeem, user, msg::sender());
-        // 销毁稳定币
-        self._burn_dsc(debt_to_cover, user, msg::sender());
+        self._liquidate(collateral, user, debt_to_cover, msg_sender())?;
+        Ok(())
+    }
 
-        let ending_user_health_factor = self._health_factor(user);
-        if ending_user_health_factor <= starting_user_health_factor {
-            return Err(DSCEngineError::HealthFactorNotImproved(
-                HealthFactorNotImproved {},
-            ));
-        }
-        self._revert_if_health_factor_is_broken(msg::sender())?;
+    /// 登记附加合成资产：引擎成为其稳定币合约的 owner；价格预言机报价一单位合成资产的美元价格，
+    /// 零地址表示锚定美元
+    pub fn add_synth(
+        &mut self,
+        synth: Address,
+        price_feed: Address,
+        debt_ceiling: U256,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self._add_synth(synth, price_feed, debt_ceiling)?;
+        self._record_param_of(
+            "synthPriceFeed",
+            synth.into_word(),
+            address_value(price_feed),
+        );
+        self._record_param_of("synthDebtCeiling", synth.into_word(), debt_ceiling);
         Ok(())
     }
 
-    // 内部辅助函数
-    fn more_than_zero(&self, amount: U256) -> Result<(), DSCEngineError> {
-        if amount == U256::ZERO {
-            Err(DSCEngineError::NeedsMoreThanZero(NeedsMoreThanZero {}))
-        } else {
-            Ok(())
-        }
+    /// 设置合成资产的债务上限
+    pub fn set_synth_debt_ceiling(
+        &mut self,
+        synth: Address,
+        debt_ceiling: U256,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self._set_synth_debt_ceiling(synth, debt_ceiling)?;
+        self._record_param_of("synthDebtCeiling", synth.into_word(), debt_ceiling);
+        Ok(())
     }
 
-    fn is_allowed_token(&self, token: Address) -> Result<(), DSCEngineError> {
-        if self.price_feeds.get(token).is_zero() {
-            Err(DSCEngineError::NotAllowedToken(NotAllowedToken {}))
-        } else {
+    /// 以调用者的抵押品铸造合成资产，铸造后健康因子须达到借款健康因子
+    pub fn mint_synth(&mut self, synth: Address, amount: U256) -> Result<(), DSCEngineError> {
+        self.more_than_zero(amount)?;
+        self._accrue_all();
+        self._mint_synth(msg_sender(), synth, amount)
+    }
+
+    /// 销毁调用者的合成资产，偿还其合成资产债务
+    pub fn burn_synth(&mut self, synth: Address, amount: U256) -> Result<(), DSCEngineError> {
+        self.more_than_zero(amount)?;
+        self._burn_synth(synth, amount, msg_sender(), msg_sender())
+    }
+
+    /// 以合成资产清算健康因子低于最小健康因子的仓位，返回清算人获得的抵押品数量
+    pub fn liquidate_synth(
+        &mut self,
+        collateral: Address, // 抵押品地址
+        user: Address,       // 要清算的用户地址
+        synth: Address,      // 偿还的合成资产地址
+        debt_to_cover: U256, // 要偿还的合成资产债务数量
+    ) -> Result<U256, DSCEngineError> {
+        self._liquidate_synth(collateral, user, synth, debt_to_cover, msg_sender())
+    }
+
+    pub fn get_synths(&self) -> Vec<Address> {
+        // 获取附加合成资产列表
+        (0..self.synth_tokens.len())
+            .filter_map(|i| self.synth_tokens.get(i))
+            .collect()
+    }
+
+    /// 合成资产的价格预言机、债务上限和总债务
+    pub fn get_synth(&self, synth: Address) -> (Address, U256, U256) {
+        let config = self.synths.getter(synth);
+        (
+            config.price_feed.get(),
+            config.debt_ceiling.get(),
+            config.total_debt.get(),
+        )
+    }
+
+    /// 一单位合成资产以记账单位计的价格（预言机精度）
+    pub fn get_synth_price(&self, synth: Address) -> Result<U256, DSCEngineError> {
+        self._require_synth(synth)?;
+        self._synth_price(synth)
+    }
+
+    pub fn get_synth_debt(&self, user: Address, synth: Address) -> U256 {
+        // 获取用户的合成资产债务
+        self.synth_debt.getter(user).get(synth)
+    }
+
+    /// 用户全部合成资产债务以记账单位计的价值
+    pub fn get_synth_debt_value(&self, user: Address) -> U256 {
+        self._synth_debt_value(user)
+    }
+
+    /// 提交清算人签署的 EIP-712 清算订单（使用清算人当前的 nonce），返回清算人获得的抵押品数量
+    pub fn liquidate_with_signature(
+        &mut self,
+        // 订单：(清算人, 被清算用户, 抵押品, 最多偿还的债务, 最少获得的抵押品, 截止时间)
+        order: (Address, Address, Address, U256, U256, U256),
+        signature: Bytes,
+    ) -> Result<U256, DSCEngineError> {
+        let (liquidator, user, collateral, max_debt, min_collateral_out, deadline) = order;
+        let order = LiquidationOrder {
+            liquidator,
+            user,
+            collateral,
+            maxDebt: max_debt,
+            minCollateralOut: min_collateral_out,
+            nonce: self.liquidation_order_nonces.get(liquidator),
+            deadline,
+        };
+        self._liquidate_with_signature(order, &signature)
+    }
+
+    /// 作废调用者尚未提交的签名清算订单
+    pub fn cancel_liquidation_orders(&mut self) {
+        self._cancel_liquidation_orders(msg_sender());
+    }
+
+    pub fn get_liquidation_order_nonce(&self, liquidator: Address) -> U256 {
+        // 获取清算人下一张签名清算订单的 nonce
+        self.liquidation_order_nonces.get(liquidator)
+    }
+
+    /// 闪电清算：以闪电铸造的 DSC 偿还债务，通过 DEX 路由兑换扣押的抵押品，返回调用者获得的 DSC 利润
+    pub fn flash_liquidate(
+        &mut self,
+        collateral: Address,      // 抵押品地址
+        user: Address,            // 要清算的用户地址
+        debt_to_cover: U256,      // 要清算的债务数量
+        collateral_to_swap: U256, // 卖出的抵押品数量，超过扣押数量时按扣押数量卖出
+        min_dsc_out: U256,        // 兑换最少买入的 DSC 数量
+    ) -> Result<U256, DSCEngineError> {
+        self.more_than_zero(debt_to_cover)?;
+        self.is_allowed_token(collateral)?;
+        self._accrue_all();
+        self._flash_liquidate(
+            collateral,
+            user,
+            debt_to_cover,
+            collateral_to_swap,
+            min_dsc_out,
+        )
+    }
+
+    /// 通过稳定池清算：用池中的 DSC 偿还债务，抵押品和清算奖励转入稳定池
+    pub fn liquidate_with_stability_pool(
+        &mut self,
+        collateral: Address, // 抵押品地址
+        user: Address,       // 要清算的用户地址
+    ) -> Result<U256, DSCEngineError> {
+        let stability_pool = self.stability_pool.get();
+        if stability_pool.is_zero() {
+            return Err(DSCEngineError::StabilityPoolNotSet(StabilityPoolNotSet {}));
+        }
+        self.is_allowed_token(collateral)?;
+        self._require_local_collateral(collateral)?;
+        if self.active_auction.get(user) != U256::ZERO {
+            return Err(DSCEngineError::AuctionActive(AuctionActive {}));
+        }
+        self._check_liquidation_grace(collateral)?;
+        // 计提稳定费
+        self._accrue_all();
+        let starting_user_health_factor = self._liquidation_health_factor(user, collateral)?;
+        if starting_user_health_factor >= self._min_health_factor() {
+            return Err(DSCEngineError::HealthFactorOk(HealthFactorOk {}));
+        }
+        // 不超过单次可清算上限和稳定池中的 DSC（需同时支付协议分成）
+        let bonus = self._liquidation_bonus_at(collateral, starting_user_health_factor);
+        let protocol_cut = bonus * self.liquidation_protocol_share.get();
+        let debt_to_cover = self._max_liquidatable_debt(user, collateral)?.min(
+            (self._stability_pool_balance()? * LIQUIDATION_PRECISION * LIQUIDATION_PRECISION)
+                / (LIQUIDATION_PRECISION * LIQUIDATION_PRECISION + protocol_cut),
+        );
+        self.more_than_zero(debt_to_cover)?;
+        let token_amount_from_debt_covered =
+            self._token_amount_from_usd(collateral, debt_to_cover)?;
+        let bonus_collateral = (token_amount_from_debt_covered * bonus) / LIQUIDATION_PRECISION;
+        let total_collateral_to_redeem = token_amount_from_debt_covered + bonus_collateral;
+        self._redeem_collateral(collateral, total_collateral_to_redeem, user, stability_pool)?;
+        self._burn_dsc(
+            debt_to_cover,
+            user,
+            stability_pool,
+            self._debt_bucket(user, collateral),
+        )?;
+        self._collect_liquidation_protocol_fee(debt_to_cover, bonus, stability_pool)?;
+
+        let ending_user_health_factor = self._liquidation_health_factor(user, collateral)?;
+        if ending_user_health_factor <= starting_user_health_factor {
+            return Err(DSCEngineError::HealthFactorNotImproved(
+                HealthFactorNotImproved {},
+            ));
+        }
+        self._record_bad_debt(user)?;
+        self._pay_keeper_reward(msg_sender())?;
+        evm::log(StabilityPoolOffset {
+            user,
+            token: collateral,
+            debtOffset: debt_to_cover,
+            collateralAmount: total_collateral_to_redeem,
+        });
+        Ok(debt_to_cover)
+    }
+
+    /// 发起荷兰式拍卖：扣押不健康仓位的抵押品，按递减价格出售，返回拍卖编号
+    pub fn kick(
+        &mut self,
+        collateral: Address, // 抵押品地址
+        user: Address,       // 要清算的用户地址
+    ) -> Result<U256, DSCEngineError> {
+        self.is_allowed_token(collateral)?;
+        self._accrue_all();
+        let id = self._kick(collateral, user)?;
+        self._pay_keeper_reward(msg_sender())?;
+        Ok(id)
+    }
+
+    /// 按当前拍卖价格支付 DSC 购买抵押品，返回购得的抵押品数量
+    pub fn take(
+        &mut self,
+        id: U256,             // 拍卖编号
+        max_collateral: U256, // 最多购买的抵押品数量
+        max_price: U256,      // 可接受的最高价格（每个完整代币的美元价格）
+    ) -> Result<U256, DSCEngineError> {
+        self._accrue_all();
+        self._take(id, max_collateral, max_price)
+    }
+
+    /// 价格降到零的拍卖按最新预言机价格重新起拍
+    pub fn redo(&mut self, id: U256) -> Result<(), DSCEngineError> {
+        self._redo(id)
+    }
+
+    /// 使用引擎自身参数计算健康因子
+    pub fn calculate_health_factor(
+        &self,
+        total_dsc_minted: U256,
+        collateral_value_in_usd: U256,
+    ) -> U256 {
+        self._calculate_health_factor(total_dsc_minted, collateral_value_in_usd)
+    }
+
+    pub fn get_token_amount_from_usd(&self, token: Address, usd_amount_in_wei: U256) -> U256 {
+        // 价格不可用时按零处理（具体原因见 get_collateral_price）
+        self._token_amount_from_usd(token, usd_amount_in_wei)
+            .unwrap_or(U256::ZERO)
+    }
+
+    pub fn get_account_collateral_value_in_usd(&self, user: Address) -> U256 {
+        // 初始化抵押品总价值
+        let mut total_collateral_value_in_usd = U256::ZERO;
+        // 遍历所有抵押品
+        for i in 0..self.collateral_tokens.len() {
+            let token_option = self.collateral_tokens.get(i);
+            match token_option {
+                Some(token) => {
+                    // 获取用户特定代币的抵押品数量
+                    let amount = self._collateral_amount_of(user, token);
+                    // 未存入的抵押品无需查询价格
+                    if amount == U256::ZERO {
+                        continue;
+                    }
+                    // 计算抵押品金额
+                    total_collateral_value_in_usd += self.get_usd_value(token, amount);
+                }
+                None => (),
+            }
+        }
+        total_collateral_value_in_usd
+    }
+
+    // 获取抵押品金额
+    pub fn get_usd_value(&self, token: Address, amount: U256) -> U256 {
+        // 价格不可用时估值为零（具体原因见 get_collateral_price）
+        self._usd_value(token, amount).unwrap_or(U256::ZERO)
+    }
+
+    /// 抵押品的估值，价格不可用时回滚而不是按零估值
+    pub fn quote_usd_value(&self, token: Address, amount: U256) -> Result<U256, DSCEngineError> {
+        self._usd_value(token, amount)
+    }
+
+    /// 一次返回用户的已铸造稳定币数量、抵押品总价值和健康因子，价格不可用的抵押品估值为零
+    pub fn get_account_information(&self, user: Address) -> (U256, U256, U256) {
+        let total_dsc_minted = self._user_debt(user);
+        let collateral_value_in_usd = self.get_account_collateral_value_in_usd(user);
+        let health_factor = self._health_factor(user).unwrap_or(U256::ZERO);
+        (total_dsc_minted, collateral_value_in_usd, health_factor)
+    }
+
+    pub fn get_collateral_tokens(&self) -> Vec<Address> {
+        // 初始化抵押品列表
+        let mut tokens = Vec::new();
+        // 遍历所有抵押品
+        for i in 0..self.collateral_tokens.len() {
+            tokens.push(self.collateral_tokens.get(i));
+        }
+        // 过滤掉 None 值
+        tokens
+            .into_iter()
+            .filter_map(|opt_address| opt_address)
+            .collect()
+    }
+
+    pub fn get_local_collateral_tokens(&self) -> Vec<Address> {
+        // 获取本链抵押品列表（不含远程抵押品别名）
+        (0..self.collateral_tokens.len())
+            .filter_map(|i| self.collateral_tokens.get(i))
+            .filter(|token| !self._is_remote_collateral(*token))
+            .collect()
+    }
+
+    /// 抵押品代币数量
+    pub fn collateral_tokens_length(&self) -> U256 {
+        U256::from(self.collateral_tokens.len())
+    }
+
+    /// 分页获取抵押品代币列表，超出范围的部分会被截断
+    pub fn get_collateral_tokens_paged(&self, offset: U256, limit: U256) -> Vec<Address> {
+        let len = self.collateral_tokens.len();
+        // 起始位置超出列表长度时返回空列表
+        let start = match usize::try_from(offset) {
+            Ok(start) if start < len => start,
+            _ => return Vec::new(),
+        };
+        let end = usize::try_from(limit)
+            .map(|limit| start.saturating_add(limit).min(len))
+            .unwrap_or(len);
+        (start..end)
+            .filter_map(|i| self.collateral_tokens.get(i))
+            .collect()
+    }
+
+    pub fn get_action_nonce(&self, user: Address) -> U256 {
+        // 获取用户最近一次仓位事件的操作序号
+        self.action_nonces.get(user)
+    }
+
+    /// 持有抵押品或债务的用户数量
+    pub fn borrowers_length(&self) -> U256 {
+        U256::from(self.borrowers.len())
+    }
+
+    /// 分页获取仓位列表，一次返回各仓位的地址、债务（含合成资产）、抵押品价值和健康因子，
+    /// 超出范围的部分会被截断
+    pub fn get_borrowers_page(
+        &self,
+        offset: U256,
+        limit: U256,
+    ) -> (Vec<Address>, Vec<U256>, Vec<U256>, Vec<U256>) {
+        let len = self.borrowers.len();
+        let mut page = (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        // 起始位置超出列表长度时返回空列表
+        let start = match usize::try_from(offset) {
+            Ok(start) if start < len => start,
+            _ => return page,
+        };
+        let end = usize::try_from(limit)
+            .map(|limit| start.saturating_add(limit).min(len))
+            .unwrap_or(len);
+        for user in (start..end).filter_map(|i| self.borrowers.get(i)) {
+            page.0.push(user);
+            page.1.push(self._account_debt_value(user));
+            page.2.push(self.get_account_collateral_value_in_usd(user));
+            page.3.push(self._health_factor(user).unwrap_or(U256::ZERO));
+        }
+        page
+    }
+
+    /// 用户实际存入的抵押品代币及数量（只返回非零持仓）
+    pub fn get_user_collateral_tokens(&self, user: Address) -> (Vec<Address>, Vec<U256>) {
+        let mut tokens = Vec::new();
+        let mut amounts = Vec::new();
+        let user_collateral_mapping = self.collateral_deposited.getter(user);
+        for i in 0..self.collateral_tokens.len() {
+            if let Some(token) = self.collateral_tokens.get(i) {
+                let shares = user_collateral_mapping.get(token);
+                if shares > U256::ZERO {
+                    tokens.push(token);
+                    amounts.push(self._shares_to_amount(token, shares));
+                }
+            }
+        }
+        (tokens, amounts)
+    }
+
+    pub fn get_collateral_balance_of_user(&self, user: Address, token: Address) -> U256 {
+        // 获取用户特定代币的抵押品数量
+        self._collateral_amount_of(user, token)
+    }
+
+    pub fn get_additional_feed_precision(&self) -> U256 {
+        // 获取精度调整因子
+        ADDITIONAL_FEED_PRECISION
+    }
+
+    pub fn get_precision(&self) -> U256 {
+        // 获取基础精度
+        PRECISION
+    }
+
+    pub fn get_health_factor(&self, user: Address) -> U256 {
+        // 获取用户健康因子，价格不可用时为零
+        self._health_factor(user).unwrap_or(U256::ZERO)
+    }
+
+    pub fn get_liquidation_bonus(&self) -> U256 {
+        // 获取最高清算奖励
+        self._liquidation_bonus()
+    }
+
+    /// 设置清算奖励曲线：健康因子刚跌破最小健康因子时的最低奖励（清算精度），
+    /// 以及达到最高奖励的健康因子；后者为零时不缩放，始终给最高奖励
+    pub fn set_liquidation_bonus_curve(
+        &mut self,
+        min_bonus: U256,
+        full_bonus_health_factor: U256,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        if min_bonus > self._liquidation_bonus()
+            || full_bonus_health_factor >= self._min_health_factor()
+        {
+            return Err(DSCEngineError::InvalidBonusCurve(InvalidBonusCurve {}));
+        }
+        self.min_liquidation_bonus.set(min_bonus);
+        self.full_bonus_health_factor.set(full_bonus_health_factor);
+        evm::log(LiquidationBonusCurveSet {
+            minBonus: min_bonus,
+            fullBonusHealthFactor: full_bonus_health_factor,
+        });
+        self._record_param("minLiquidationBonus", min_bonus);
+        self._record_param("fullBonusHealthFactor", full_bonus_health_factor);
+        Ok(())
+    }
+
+    pub fn get_liquidation_bonus_curve(&self) -> (U256, U256, U256) {
+        // 获取清算奖励曲线：(最低奖励, 最高奖励, 达到最高奖励的健康因子)
+        (
+            self.min_liquidation_bonus.get(),
+            self._liquidation_bonus(),
+            self.full_bonus_health_factor.get(),
+        )
+    }
+
+    pub fn get_liquidation_bonus_for(&self, user: Address, collateral: Address) -> U256 {
+        // 获取使用指定抵押品清算用户时当前适用的清算奖励，价格不可用时为零
+        self._liquidation_bonus_for(user, collateral)
+            .unwrap_or(U256::ZERO)
+    }
+
+    /// 开启或关闭抵押品的份额记账模式，仅在该抵押品没有存款时允许切换
+    pub fn set_collateral_shares_mode(
+        &mut self,
+        token: Address,
+        enabled: bool,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.is_allowed_token(token)?;
+        if self.total_collateral_deposited.get(token) > U256::ZERO {
+            return Err(DSCEngineError::CollateralModeLocked(
+                CollateralModeLocked {},
+            ));
+        }
+        self.collateral_shares_mode.setter(token).set(enabled);
+        self._record_param_of("sharesMode", token.into_word(), U256::from(enabled));
+        Ok(())
+    }
+
+    /// 同步抵押品的存款总量：份额模式下确认可变基代币的增减值，否则按记账的存入总量重建
+    /// （用于升级前已有存款的抵押品）。份额模式下减值按引擎实际持有量全额确认，
+    /// 增值最多确认 max_increase，直接转入引擎的代币超出部分不计入存款总量
+    pub fn sync_total_deposited(
+        &mut self,
+        token: Address,
+        max_increase: U256, // 份额模式下本次最多确认的增值数量
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.is_allowed_token(token)?;
+        let total = if self.collateral_shares_mode.get(token) {
+            let tracked = self.total_deposited.get(token);
+            self._engine_balance(token)
+                .min(tracked.saturating_add(max_increase))
+        } else {
+            self.total_collateral_deposited.get(token)
+        };
+        self._accrue_all();
+        self.total_deposited.setter(token).set(total);
+        Ok(())
+    }
+
+    /// 将抵押品标记为 ERC-4626 金库份额，价格预言机需为底层资产的预言机
+    pub fn set_erc4626_collateral(
+        &mut self,
+        token: Address,
+        enabled: bool,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.is_allowed_token(token)?;
+        if enabled {
+            // 记录底层资产精度
+            let asset = IERC4626::new(token).asset(Call::new()).map_err(|_| {
+                DSCEngineError::TokenDecimalsUnavailable(TokenDecimalsUnavailable {})
+            })?;
+            let decimals = IERC20::new(asset).decimals(Call::new()).map_err(|_| {
+                DSCEngineError::TokenDecimalsUnavailable(TokenDecimalsUnavailable {})
+            })?;
+            self.vault_asset_decimals.insert(token, U8::from(decimals));
+        }
+        self.erc4626_collateral.insert(token, enabled);
+        self._record_param_of("erc4626Collateral", token.into_word(), U256::from(enabled));
+        Ok(())
+    }
+
+    /// 设置抵押品的汇率合约，设为零地址则只使用基础预言机
+    pub fn set_rate_provider(
+        &mut self,
+        token: Address,
+        rate_provider: Address,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.is_allowed_token(token)?;
+        self.rate_providers.insert(token, rate_provider);
+        self._record_param_of(
+            "rateProvider",
+            token.into_word(),
+            address_value(rate_provider),
+        );
+        Ok(())
+    }
+
+    /// 设置更换预言机时新报价相对旧报价的最大偏离（基点），为零时不检查
+    pub fn set_max_feed_replacement_deviation(
+        &mut self,
+        max_deviation: U256,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.max_feed_replacement_deviation.set(max_deviation);
+        self._record_param("maxFeedReplacementDeviation", max_deviation);
+        Ok(())
+    }
+
+    pub fn get_max_feed_replacement_deviation(&self) -> U256 {
+        // 获取更换预言机时的最大偏离
+        self.max_feed_replacement_deviation.get()
+    }
+
+    /// 更换抵押品的价格预言机，新预言机的报价必须与旧预言机最近的报价足够接近
+    pub fn set_price_feed(
+        &mut self,
+        token: Address,
+        new_feed: Address,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.is_allowed_token(token)?;
+        self._accrue_all();
+        self._set_price_feed(token, new_feed)?;
+        self._record_param_of("priceFeed", token.into_word(), address_value(new_feed));
+        Ok(())
+    }
+
+    /// 设置抵押品的价格来源（类型见 `PRICE_SOURCE_*`）：Pyth 需要来源合约和价格 ID，`period` 为最长报价时间；
+    /// TWAP 需要来源合约和平均窗口 `period`；组合来源以报价预言机的报价乘以登记的预言机报价
+    pub fn set_price_source(
+        &mut self,
+        token: Address,
+        kind: u8,
+        oracle: Address,
+        price_id: FixedBytes<32>,
+        quote_feed: Address,
+        period: U256,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.is_allowed_token(token)?;
+        let source = PriceSource::new(kind, oracle, price_id, quote_feed, period)
+            .ok_or(DSCEngineError::InvalidPriceSource(InvalidPriceSource {}))?;
+        self._accrue_all();
+        self._set_price_source(token, source)?;
+        self._record_param_of("priceSource", token.into_word(), U256::from(kind));
+        Ok(())
+    }
+
+    pub fn get_price_source(&self, token: Address) -> (u8, Address, FixedBytes<32>, Address, U256) {
+        // 获取抵押品的价格来源类型和配置参数
+        let source = self._price_source(token);
+        let (oracle, price_id, quote_feed, period) = source.params();
+        (source.kind(), oracle, price_id, quote_feed, period)
+    }
+
+    /// 计提所有抵押品的稳定费
+    pub fn accrue(&mut self) {
+        self._accrue_all();
+    }
+
+    /// 设置抵押品每秒的稳定费率（基础精度），修改前先按旧费率计提
+    pub fn set_stability_fee(
+        &mut self,
+        token: Address,
+        rate_per_second: U256,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.is_allowed_token(token)?;
+        self._accrue(token);
+        self.stability_fee_rates.setter(token).set(rate_per_second);
+        self._record_param_of("stabilityFee", token.into_word(), rate_per_second);
+        Ok(())
+    }
+
+    /// 设置抵押品的分段利率策略（费率为每秒费率，使用率为基础精度），修改前先按旧费率计提；
+    /// 参数全部为零时取消策略，改用固定稳定费率
+    pub fn set_rate_strategy(
+        &mut self,
+        token: Address,
+        base_rate: U256,
+        slope1: U256,
+        slope2: U256,
+        optimal_utilization: U256,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.is_allowed_token(token)?;
+        let disabled = base_rate == U256::ZERO
+            && slope1 == U256::ZERO
+            && slope2 == U256::ZERO
+            && optimal_utilization == U256::ZERO;
+        if !disabled && (optimal_utilization == U256::ZERO || optimal_utilization >= PRECISION) {
+            return Err(DSCEngineError::InvalidRateStrategy(InvalidRateStrategy {}));
+        }
+        self._accrue(token);
+        let mut strategy = self.rate_strategies.setter(token);
+        strategy.base_rate.set(base_rate);
+        strategy.slope1.set(slope1);
+        strategy.slope2.set(slope2);
+        strategy.optimal_utilization.set(optimal_utilization);
+        evm::log(RateStrategySet {
+            token,
+            baseRate: base_rate,
+            slope1,
+            slope2,
+            optimalUtilization: optimal_utilization,
+        });
+        let subject = token.into_word();
+        self._record_param_of("rateBase", subject, base_rate);
+        self._record_param_of("rateSlope1", subject, slope1);
+        self._record_param_of("rateSlope2", subject, slope2);
+        self._record_param_of("optimalUtilization", subject, optimal_utilization);
+        Ok(())
+    }
+
+    /// 设置由稳定费收入支付收益的储蓄金库
+    pub fn set_savings_vault(&mut self, savings_vault: Address) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.savings_vault.set(savings_vault);
+        self._record_param("savingsVault", address_value(savings_vault));
+        Ok(())
+    }
+
+    /// 储蓄金库申领收益：从盈余缓冲中支付，返回实际支付数量
+    pub fn fund_savings_vault(&mut self, amount: U256) -> Result<U256, DSCEngineError> {
+        let savings_vault = self.savings_vault.get();
+        if msg::sender() != savings_vault {
+            return Err(DSCEngineError::NotSavingsVault(NotSavingsVault {}));
+        }
+        let paid = amount.min(self.surplus_buffer.get());
+        if paid > U256::ZERO {
+            self._withdraw_surplus(savings_vault, paid)?;
+        }
+        Ok(paid)
+    }
+
+    /// 治理从盈余缓冲中提取 DSC
+    pub fn withdraw_surplus(&mut self, to: Address, amount: U256) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.more_than_zero(amount)?;
+        self._withdraw_surplus(to, amount)
+    }
+
+    pub fn get_surplus_buffer(&self) -> U256 {
+        // 获取盈余缓冲余额
+        self.surplus_buffer.get()
+    }
+
+    pub fn get_surplus_by_source(&self, source: u8) -> U256 {
+        // 获取某来源累计计入的协议收入（0：稳定费，1：PSM 手续费，2：清算罚金）
+        self.surplus_by_source.get(U8::from(source))
+    }
+
+    /// 设置国库地址
+    pub fn set_treasury(&mut self, treasury: Address) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.treasury.set(treasury);
+        self._record_param("treasury", address_value(treasury));
+        Ok(())
+    }
+
+    /// 设置跨链消息适配器
+    pub fn set_remote_messenger(&mut self, messenger: Address) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.remote_messenger.set(messenger);
+        self._record_param("remoteMessenger", address_value(messenger));
+        Ok(())
+    }
+
+    /// 登记远程链上的抵押品：别名地址、所在链 ID、价格预言机和代币精度
+    pub fn add_remote_collateral(
+        &mut self,
+        token: Address,
+        chain_id: U256,
+        price_feed: Address,
+        decimals: u8,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self._add_remote_collateral(token, chain_id, price_feed, decimals)
+    }
+
+    /// 设置远程链记入的抵押品总价值上限（18 位精度美元）
+    pub fn set_remote_chain_cap(
+        &mut self,
+        chain_id: U256,
+        cap: U256,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.remote_chain_cap.setter(chain_id).set(cap);
+        self._record_param_of("remoteChainCap", B256::from(chain_id), cap);
+        Ok(())
+    }
+
+    /// 跨链消息适配器转发远程链的抵押品存入证明，记入用户仓位
+    pub fn receive_collateral_attestation(
+        &mut self,
+        message_id: B256,
+        chain_id: U256,
+        user: Address,
+        token: Address,
+        amount: U256,
+    ) -> Result<(), DSCEngineError> {
+        self._accrue_all();
+        self._receive_collateral_attestation(message_id, chain_id, user, token, amount)
+    }
+
+    pub fn get_remote_messenger(&self) -> Address {
+        // 获取跨链消息适配器地址
+        self.remote_messenger.get()
+    }
+
+    pub fn get_remote_collateral_chain(&self, token: Address) -> U256 {
+        // 获取远程抵押品所在链 ID，本链抵押品返回零
+        self.remote_collateral_chain.get(token)
+    }
+
+    pub fn get_remote_chain_usage(&self, chain_id: U256) -> (U256, U256) {
+        // 获取远程链记入的抵押品总价值和上限，价格不可用时总价值为零
+        (
+            self._remote_chain_value(chain_id).unwrap_or(U256::ZERO),
+            self.remote_chain_cap.get(chain_id),
+        )
+    }
+
+    /// 设置 ERC-2771 受信任的转发合约，设为零地址时不接受转发
+    pub fn set_trusted_forwarder(&mut self, forwarder: Address) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.trusted_forwarder.set(forwarder);
+        self._record_param("trustedForwarder", address_value(forwarder));
+        Ok(())
+    }
+
+    pub fn is_valid_signature_of(&self, signer: Address, digest: B256, signature: Bytes) -> bool {
+        // 获取签名是否有效：合约钱包按 EIP-1271 校验，外部账户按 ECDSA 校验
+        self._is_valid_signature(signer, digest, &signature)
+    }
+
+    pub fn is_trusted_forwarder(&self, forwarder: Address) -> bool {
+        // 获取地址是否为受信任的转发合约
+        !forwarder.is_zero() && forwarder == self.trusted_forwarder.get()
+    }
+
+    /// 开启或关闭许可模式
+    pub fn set_permissioned(&mut self, enabled: bool) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.permissioned.set(enabled);
+        evm::log(PermissionedModeSet { enabled });
+        self._record_param("permissioned", U256::from(enabled));
+        Ok(())
+    }
+
+    /// 把地址加入或移出许可模式白名单
+    pub fn set_allowlisted(
+        &mut self,
+        account: Address,
+        allowed: bool,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.allowlisted.setter(account).set(allowed);
+        evm::log(AllowlistUpdated { account, allowed });
+        self._record_param_of("allowlisted", account.into_word(), U256::from(allowed));
+        Ok(())
+    }
+
+    pub fn is_permissioned(&self) -> bool {
+        // 获取是否处于许可模式
+        self.permissioned.get()
+    }
+
+    pub fn is_allowlisted(&self, account: Address) -> bool {
+        // 获取地址是否在白名单中
+        self.allowlisted.get(account)
+    }
+
+    /// 设置 DSC 的合规角色，由合规角色管理 DSC 黑名单
+    pub fn set_dsc_compliance(&mut self, compliance: Address) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        IDecentralizedStableCoin::new(self.dsc.get())
+            .set_compliance(Call::new(), compliance)
+            .map_err(|_| DSCEngineError::DscComplianceUpdateFailed(DscComplianceUpdateFailed {}))?;
+        self._record_param("dscCompliance", address_value(compliance));
+        Ok(())
+    }
+
+    /// 设置跨链桥在 DSC 上的铸造和销毁额度上限
+    pub fn set_dsc_bridge_limits(
+        &mut self,
+        bridge: Address,
+        minting_limit: U256,
+        burning_limit: U256,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        IDecentralizedStableCoin::new(self.dsc.get())
+            .set_limits(Call::new(), bridge, minting_limit, burning_limit)
+            .map_err(|_| {
+                DSCEngineError::DscBridgeLimitsUpdateFailed(DscBridgeLimitsUpdateFailed {})
+            })?;
+        self._record_param_of("dscMintingLimit", bridge.into_word(), minting_limit);
+        self._record_param_of("dscBurningLimit", bridge.into_word(), burning_limit);
+        Ok(())
+    }
+
+    /// 设置 DSC 的总供应上限，设为零时不限制
+    pub fn set_dsc_max_supply(&mut self, max_supply: U256) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        IDecentralizedStableCoin::new(self.dsc.get())
+            .set_max_supply(Call::new(), max_supply)
+            .map_err(|_| DSCEngineError::DscMaxSupplyUpdateFailed(DscMaxSupplyUpdateFailed {}))?;
+        self._record_param("dscMaxSupply", max_supply);
+        Ok(())
+    }
+
+    /// 登记治理批准的 DSC 转账钩子，设为零地址时移除
+    pub fn set_dsc_transfer_hook(&mut self, hook: Address) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        IDecentralizedStableCoin::new(self.dsc.get())
+            .set_transfer_hook(Call::new(), hook)
+            .map_err(|_| {
+                DSCEngineError::DscTransferHookUpdateFailed(DscTransferHookUpdateFailed {})
+            })?;
+        self._record_param("dscTransferHook", address_value(hook));
+        Ok(())
+    }
+
+    /// 开启或关闭手续费开关
+    pub fn set_fee_switch(&mut self, enabled: bool) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self._set_fee_switch(enabled);
+        self._record_param("feeSwitch", U256::from(enabled));
+        Ok(())
+    }
+
+    /// 设置某收入来源划给国库的比例（基点）
+    pub fn set_treasury_fee_share(
+        &mut self,
+        source: u8,
+        share: U256,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        if share > U256::from(psm::BPS) {
+            return Err(DSCEngineError::InvalidFee(InvalidFee {}));
+        }
+        self.treasury_fee_shares.setter(U8::from(source)).set(share);
+        self._record_param_of("treasuryFeeShare", id_subject(source.into()), share);
+        Ok(())
+    }
+
+    /// 把待领取的国库手续费转到国库地址，返回转出的数量
+    pub fn collect_treasury_fees(&mut self) -> Result<U256, DSCEngineError> {
+        self._collect_treasury_fees()
+    }
+
+    /// 设置被推荐用户的手续费中划给推荐人的比例（基点）
+    pub fn set_referral_share(&mut self, share: U256) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        if share > U256::from(psm::BPS) {
+            return Err(DSCEngineError::InvalidFee(InvalidFee {}));
+        }
+        self.referral_share.set(share);
+        self._record_param("referralShare", share);
+        Ok(())
+    }
+
+    /// 领取调用者作为推荐人待领取的返佣，返回领取的数量
+    pub fn claim_referral_rewards(&mut self) -> Result<U256, DSCEngineError> {
+        self._claim_referral_rewards(msg_sender())
+    }
+
+    pub fn get_referral_share(&self) -> U256 {
+        // 获取返佣比例（基点）
+        self.referral_share.get()
+    }
+
+    pub fn get_referrer(&self, user: Address) -> Address {
+        // 获取用户的推荐人地址
+        self.referrers.get(user)
+    }
+
+    pub fn get_referral_rewards(&self, referrer: Address) -> U256 {
+        // 获取推荐人待领取的返佣
+        self.referral_rewards.get(referrer)
+    }
+
+    /// 设置挖矿奖励代币
+    pub fn set_reward_token(&mut self, token: Address) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.reward_token.set(token);
+        self._record_param("rewardToken", address_value(token));
+        Ok(())
+    }
+
+    /// 设置挖矿每秒发放的奖励代币总量，先按原速率结算各分桶
+    pub fn set_reward_rate(&mut self, rate: U256) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self._update_reward_pools();
+        self.reward_rate.set(rate);
+        self._record_param("rewardRate", rate);
+        Ok(())
+    }
+
+    /// 设置抵押品分桶的挖矿发放权重，先按原权重结算各分桶
+    pub fn set_reward_weight(
+        &mut self,
+        token: Address,
+        weight: U256,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.is_allowed_token(token)?;
+        self._update_reward_pools();
+        let old_weight = self.reward_pools.getter(token).weight.get();
+        self.reward_pools.setter(token).weight.set(weight);
+        self.total_reward_weight
+            .set(self.total_reward_weight.get() - old_weight + weight);
+        self._record_param_of("rewardWeight", token.into_word(), weight);
+        Ok(())
+    }
+
+    /// 领取调用者的挖矿奖励，返回领取的数量
+    pub fn claim_rewards(&mut self) -> Result<U256, DSCEngineError> {
+        self._claim_rewards(msg_sender())
+    }
+
+    pub fn get_reward_config(&self) -> (Address, U256, U256) {
+        // 获取挖矿奖励代币、每秒发放总量和总权重
+        (
+            self.reward_token.get(),
+            self.reward_rate.get(),
+            self.total_reward_weight.get(),
+        )
+    }
+
+    pub fn get_reward_weight(&self, token: Address) -> U256 {
+        // 获取抵押品分桶的挖矿发放权重
+        self.reward_pools.getter(token).weight.get()
+    }
+
+    pub fn get_pending_rewards(&self, user: Address) -> U256 {
+        // 获取用户可领取的挖矿奖励（包含尚未结算的部分）
+        self._pending_rewards(user)
+    }
+
+    pub fn get_param(&self, key: B256) -> (U256, U256, U256) {
+        // 获取登记表中的参数：(当前值, 修改前的值, 最近修改时间)，从未修改过的参数均为零
+        let record = self.param_records.getter(key);
+        (
+            record.value.get(),
+            record.previous.get(),
+            record.updated_at.get(),
+        )
+    }
+
+    pub fn get_param_key(&self, name: B256, subject: B256) -> B256 {
+        // 获取参数在登记表中的键，全局参数的对象为零
+        Self::_param_key(name, subject)
+    }
+
+    pub fn get_treasury(&self) -> Address {
+        // 获取国库地址
+        self.treasury.get()
+    }
+
+    pub fn is_fee_switch_on(&self) -> bool {
+        // 获取手续费开关状态
+        self._fee_switch()
+    }
+
+    pub fn get_treasury_fee_share(&self, source: u8) -> U256 {
+        // 获取某收入来源划给国库的比例
+        self.treasury_fee_shares.get(U8::from(source))
+    }
+
+    pub fn get_pending_treasury_fees(&self) -> U256 {
+        // 获取待领取的国库手续费
+        self.treasury_fees.get()
+    }
+
+    /// 设置铸造手续费和抵押品赎回手续费（基点）
+    pub fn set_fees(
+        &mut self,
+        mint_fee: U256,
+        collateral_redemption_fee: U256,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        if mint_fee > U256::from(psm::BPS) || collateral_redemption_fee > U256::from(psm::BPS) {
+            return Err(DSCEngineError::InvalidFee(InvalidFee {}));
+        }
+        self.mint_fee.set(mint_fee);
+        self.collateral_redemption_fee
+            .set(collateral_redemption_fee);
+        self._record_param("mintFee", mint_fee);
+        self._record_param("collateralRedemptionFee", collateral_redemption_fee);
+        Ok(())
+    }
+
+    /// 设置集成方是否豁免铸造和赎回手续费
+    pub fn set_fee_exempt(&mut self, account: Address, exempt: bool) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.fee_exempt.setter(account).set(exempt);
+        evm::log(FeeExemptUpdated { account, exempt });
+        self._record_param_of("feeExempt", account.into_word(), U256::from(exempt));
+        Ok(())
+    }
+
+    pub fn get_mint_fee(&self) -> U256 {
+        // 获取铸造手续费
+        self.mint_fee.get()
+    }
+
+    pub fn get_collateral_redemption_fee(&self) -> U256 {
+        // 获取抵押品赎回手续费
+        self.collateral_redemption_fee.get()
+    }
+
+    pub fn is_fee_exempt(&self, account: Address) -> bool {
+        // 获取账户是否豁免手续费
+        self.fee_exempt.get(account)
+    }
+
+    /// 设置清算奖励中归协议的比例（清算精度，如 50 表示一半），由清算人以 DSC 支付并计入盈余
+    pub fn set_liquidation_protocol_share(&mut self, share: U256) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        if share > LIQUIDATION_PRECISION {
+            return Err(DSCEngineError::InvalidFee(InvalidFee {}));
+        }
+        self.liquidation_protocol_share.set(share);
+        self._record_param("liquidationProtocolShare", share);
+        Ok(())
+    }
+
+    pub fn get_liquidation_protocol_share(&self) -> U256 {
+        // 获取清算奖励中归协议的比例
+        self.liquidation_protocol_share.get()
+    }
+
+    pub fn get_liquidation_protocol_fee(&self, collateral: Address, debt_to_cover: U256) -> U256 {
+        // 获取按该抵押品的最高清算奖励清算指定债务时需额外支付给协议的 DSC
+        self._liquidation_protocol_fee(debt_to_cover, self._collateral_max_bonus(collateral))
+    }
+
+    /// 设置每次成功清算支付给 keeper 的固定 DSC 奖励
+    pub fn set_keeper_reward(&mut self, reward: U256) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.keeper_reward.set(reward);
+        self._record_param("keeperReward", reward);
+        Ok(())
+    }
+
+    pub fn get_keeper_reward(&self) -> U256 {
+        // 获取 keeper 清算奖励
+        self.keeper_reward.get()
+    }
+
+    /// 设置清算宽限期参数：排序器在线状态预言机、恢复后的宽限秒数和预言机心跳间隔
+    pub fn set_liquidation_grace_params(
+        &mut self,
+        sequencer_uptime_feed: Address,
+        grace_period: U256,
+        oracle_heartbeat: U256,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.sequencer_uptime_feed.set(sequencer_uptime_feed);
+        self.liquidation_grace_period.set(grace_period);
+        self.oracle_heartbeat.set(oracle_heartbeat);
+        self._record_param("sequencerUptimeFeed", address_value(sequencer_uptime_feed));
+        self._record_param("liquidationGracePeriod", grace_period);
+        self._record_param("oracleHeartbeat", oracle_heartbeat);
+        Ok(())
+    }
+
+    pub fn get_liquidation_grace_params(&self) -> (Address, U256, U256) {
+        // 获取清算宽限期参数：(排序器在线状态预言机, 宽限秒数, 预言机心跳间隔)
+        (
+            self.sequencer_uptime_feed.get(),
+            self.liquidation_grace_period.get(),
+            self.oracle_heartbeat.get(),
+        )
+    }
+
+    /// 设置铸造速率限制：周期秒数、全协议和单账户每个周期的净铸造上限
+    pub fn set_mint_rate_limit(
+        &mut self,
+        window: U256,
+        global_cap: U256,
+        account_cap: U256,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.mint_window.set(window);
+        self.global_mint_cap.set(global_cap);
+        self.account_mint_cap.set(account_cap);
+        self._record_param("mintWindow", window);
+        self._record_param("globalMintCap", global_cap);
+        self._record_param("accountMintCap", account_cap);
+        Ok(())
+    }
+
+    pub fn get_mint_rate_limit(&self) -> (U256, U256, U256) {
+        // 获取铸造速率限制：(周期秒数, 全协议上限, 单账户上限)
+        (
+            self.mint_window.get(),
+            self.global_mint_cap.get(),
+            self.account_mint_cap.get(),
+        )
+    }
+
+    /// 设置抵押品流出限制：周期秒数和每个周期净流出量占存款总量的上限（基点）
+    pub fn set_outflow_limit(&mut self, window: U256, share: U256) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        if share > U256::from(psm::BPS) {
+            return Err(DSCEngineError::InvalidOutflowLimit(InvalidOutflowLimit {}));
+        }
+        self.outflow_window.set(window);
+        self.outflow_share.set(share);
+        self._record_param("outflowWindow", window);
+        self._record_param("outflowShare", share);
+        Ok(())
+    }
+
+    pub fn get_outflow_limit(&self) -> (U256, U256) {
+        // 获取抵押品流出限制：(周期秒数, 流出比例)
+        (self.outflow_window.get(), self.outflow_share.get())
+    }
+
+    pub fn get_epoch_outflow(&self, token: Address) -> (U256, U256) {
+        // 获取抵押品本周期的流出情况：(净流出量, 流出上限)，未启用时均为零
+        self._epoch_outflow(token).unwrap_or_default()
+    }
+
+    /// 设置大额铸造冷却：单笔大额铸造的阈值和同一仓位两次大额铸造之间的冷却秒数
+    pub fn set_mint_cooldown(
+        &mut self,
+        threshold: U256,
+        cooldown: U256,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.large_mint_threshold.set(threshold);
+        self.mint_cooldown.set(cooldown);
+        self._record_param("largeMintThreshold", threshold);
+        self._record_param("mintCooldown", cooldown);
+        Ok(())
+    }
+
+    pub fn get_mint_cooldown(&self, account: Address) -> (U256, U256, U256) {
+        // 获取大额铸造冷却：(大额阈值, 冷却秒数, 该账户上次大额铸造时间)
+        (
+            self.large_mint_threshold.get(),
+            self.mint_cooldown.get(),
+            self.last_large_mint.get(account),
+        )
+    }
+
+    /// 设置守护者：可以取消延时提取请求的地址
+    pub fn set_guardian(&mut self, guardian: Address) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.guardian.set(guardian);
+        self._record_param("guardian", address_value(guardian));
+        Ok(())
+    }
+
+    pub fn get_guardian(&self) -> Address {
+        // 获取守护者地址
+        self.guardian.get()
+    }
+
+    /// 设置应急价格的上限：相对预言机最近报价的最大偏离（基点）和最长有效期（秒）
+    pub fn set_emergency_price_bounds(
+        &mut self,
+        max_deviation: U256,
+        max_duration: U256,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.max_override_deviation.set(max_deviation);
+        self.max_override_duration.set(max_duration);
+        self._record_param("maxOverrideDeviation", max_deviation);
+        self._record_param("maxOverrideDuration", max_duration);
+        Ok(())
+    }
+
+    pub fn get_emergency_price_bounds(&self) -> (U256, U256) {
+        // 获取应急价格的上限：(最大偏离, 最长有效期)
+        (
+            self.max_override_deviation.get(),
+            self.max_override_duration.get(),
+        )
+    }
+
+    /// 预言机中断时为抵押品设置临时的应急价格（预言机精度），有效期内替代预言机报价，仅守护者或治理可以调用
+    pub fn set_emergency_price(
+        &mut self,
+        token: Address,
+        price: U256,
+        duration: U256,
+    ) -> Result<(), DSCEngineError> {
+        self._only_guardian()?;
+        self.is_allowed_token(token)?;
+        self._accrue_all();
+        self._set_emergency_price(token, price, duration)
+    }
+
+    /// 提前撤销抵押品的应急价格，仅守护者或治理可以调用
+    pub fn clear_emergency_price(&mut self, token: Address) -> Result<(), DSCEngineError> {
+        self._only_guardian()?;
+        self._accrue_all();
+        self._clear_emergency_price(token);
+        Ok(())
+    }
+
+    pub fn get_emergency_price(&self, token: Address) -> (U256, U256) {
+        // 获取抵押品的应急价格：(价格, 失效时间)，失效时间不晚于当前时间表示未生效
+        let price_override = self.price_overrides.getter(token);
+        (price_override.price.get(), price_override.expires_at.get())
+    }
+
+    /// 设置延时提取：触发延时的赎回数量占存款总量的比例（基点，为零时不启用）和延时秒数
+    pub fn set_withdrawal_delay_params(
+        &mut self,
+        share: U256,
+        delay: U256,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        if share > U256::from(psm::BPS) {
+            return Err(DSCEngineError::InvalidWithdrawalDelay(
+                InvalidWithdrawalDelay {},
+            ));
+        }
+        self.large_withdrawal_share.set(share);
+        self.withdrawal_delay.set(delay);
+        self._record_param("largeWithdrawalShare", share);
+        self._record_param("withdrawalDelay", delay);
+        Ok(())
+    }
+
+    pub fn get_withdrawal_delay_params(&self) -> (U256, U256) {
+        // 获取延时提取参数：(大额提取比例, 延时秒数)
+        (
+            self.large_withdrawal_share.get(),
+            self.withdrawal_delay.get(),
+        )
+    }
+
+    /// 提交大额提取请求，延时结束后可执行，返回请求编号
+    pub fn request_withdrawal(
+        &mut self,
+        token_collateral_address: Address, // 抵押品地址
+        amount_collateral: U256,           // 抵押品数量
+    ) -> Result<U256, DSCEngineError> {
+        self.more_than_zero(amount_collateral)?;
+        self.is_allowed_token(token_collateral_address)?;
+        self._request_withdrawal(
+            msg_sender(),
+            token_collateral_address,
+            amount_collateral,
+            msg_sender(),
+            U256::ZERO,
+        )
+    }
+
+    /// 为金库提交大额提取请求，抵押品在执行时转给金库所有者，返回请求编号
+    pub fn vault_request_withdrawal(
+        &mut self,
+        id: U256,                          // 金库编号
+        token_collateral_address: Address, // 抵押品地址
+        amount_collateral: U256,           // 抵押品数量
+    ) -> Result<U256, DSCEngineError> {
+        let position = self._only_vault_owner(id)?;
+        self.more_than_zero(amount_collateral)?;
+        self.is_allowed_token(token_collateral_address)?;
+        // 接收者在执行时按金库 NFT 的持有者确定，这里只记录当前持有者
+        self._request_withdrawal(
+            position,
+            token_collateral_address,
+            amount_collateral,
+            self.vault_nft.owners.get(id),
+            id,
+        )
+    }
+
+    /// 执行延时结束的提取请求，任何人都可以调用；执行后仓位健康因子必须仍然达标
+    pub fn execute_withdrawal(&mut self, id: U256) -> Result<(), DSCEngineError> {
+        self._execute_withdrawal(id)
+    }
+
+    /// 取消提取请求，仅守护者或治理可以调用
+    pub fn cancel_withdrawal(&mut self, id: U256) -> Result<(), DSCEngineError> {
+        self._cancel_withdrawal(id)
+    }
+
+    pub fn get_pending_withdrawal(&self, id: U256) -> (Address, Address, Address, U256, U256) {
+        // 获取提取请求：(仓位, 抵押品, 接收者, 数量, 可执行时间)，可执行时间为零表示不存在、已执行或已取消；
+        // 金库请求的接收者为当前的金库所有者
+        let request = self.pending_withdrawals.getter(id);
+        (
+            request.position.get(),
+            request.token.get(),
+            self._withdrawal_receiver(id),
+            request.amount.get(),
+            request.executable_at.get(),
+        )
+    }
+
+    pub fn get_epoch_minted(&self, account: Address) -> (U256, U256) {
+        // 获取本周期的净铸造量：(全协议, 指定账户)
+        self._epoch_minted(account)
+    }
+
+    /// 设置价格偏离熔断参数：最大偏离基点和熔断冷却秒数
+    pub fn set_price_deviation_params(
+        &mut self,
+        max_deviation: U256,
+        cooldown: U256,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.max_price_deviation.set(max_deviation);
+        self.price_breaker_cooldown.set(cooldown);
+        self._record_param("maxPriceDeviation", max_deviation);
+        self._record_param("priceBreakerCooldown", cooldown);
+        Ok(())
+    }
+
+    pub fn get_price_deviation_params(&self) -> (U256, U256) {
+        // 获取价格偏离熔断参数：(最大偏离基点, 熔断冷却秒数)
+        (
+            self.max_price_deviation.get(),
+            self.price_breaker_cooldown.get(),
+        )
+    }
+
+    /// 设置预言机回滚时最近有效价格可使用的最长秒数，为零时不回退
+    pub fn set_price_fallback_window(&mut self, window: U256) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.price_fallback_window.set(window);
+        self._record_param("priceFallbackWindow", window);
+        Ok(())
+    }
+
+    pub fn get_price_fallback_window(&self) -> U256 {
+        // 获取最近有效价格的回退窗口
+        self.price_fallback_window.get()
+    }
+
+    pub fn get_last_good_price(&self, token: Address) -> (U256, U256) {
+        // 获取抵押品预言机最近有效的报价：(价格, 更新时间)
+        let good_price = self.last_good_prices.getter(self.price_feeds.get(token));
+        (good_price.price.get(), good_price.updated_at.get())
+    }
+
+    /// 检测抵押品价格是否异常偏离，偏离时触发熔断，返回熔断是否生效
+    pub fn poke_price_breaker(&mut self, token: Address) -> Result<bool, DSCEngineError> {
+        self.is_allowed_token(token)?;
+        self._poke_price_breaker(token)
+    }
+
+    /// 解除抵押品的价格偏离熔断
+    pub fn clear_price_breaker(&mut self, token: Address) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.is_allowed_token(token)?;
+        self._clear_price_breaker(token)
+    }
+
+    pub fn is_price_breaker_tripped(&self, token: Address) -> bool {
+        // 获取抵押品是否处于价格偏离熔断中
+        self._price_breaker_active(token)
+    }
+
+    pub fn get_last_accepted_price(&self, token: Address) -> (U256, U256) {
+        // 获取抵押品预言机上次接受的价格和时间
+        let feed = self.price_feeds.get(token);
+        (
+            self.last_accepted_price.get(feed),
+            self.last_accepted_price_time.get(feed),
+        )
+    }
+
+    pub fn is_liquidation_paused(&self, collateral: Address) -> bool {
+        // 获取清算该抵押品是否因排序器或预言机中断而暂停
+        self._check_liquidation_grace(collateral).is_err()
+    }
+
+    /// 设置闪电清算和 zap 使用的 DEX 路由
+    pub fn set_dex_router(&mut self, router: Address) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.dex_router.set(router);
+        self._record_param("dexRouter", address_value(router));
+        Ok(())
+    }
+
+    /// 设置原生 ETH 开仓使用的 WETH 地址
+    pub fn set_weth(&mut self, weth: Address) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.weth.set(weth);
+        self._record_param("weth", address_value(weth));
+        Ok(())
+    }
+
+    pub fn get_weth(&self) -> Address {
+        // 获取 WETH 地址
+        self.weth.get()
+    }
+
+    pub fn get_dex_router(&self) -> Address {
+        // 获取 DEX 路由地址
+        self.dex_router.get()
+    }
+
+    /// 向保险基金存入 DSC
+    pub fn fund_insurance(&mut self, amount: U256) -> Result<(), DSCEngineError> {
+        self.more_than_zero(amount)?;
+        if IDecentralizedStableCoin::new(self.dsc.get())
+            .transfer_from(Call::new(), msg_sender(), contract::address(), amount)
+            .is_err()
+        {
+            return Err(DSCEngineError::TransferFailed(TransferFailed {}));
+        }
+        self._add_to_insurance(msg_sender(), amount);
+        Ok(())
+    }
+
+    /// 从盈余缓冲中划拨 DSC 到保险基金
+    pub fn allocate_fees_to_insurance(&mut self, amount: U256) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self._spend_surplus(amount)?;
+        self._add_to_insurance(contract::address(), amount);
+        Ok(())
+    }
+
+    /// 用保险基金销毁 DSC 冲抵坏账，返回实际冲抵的数量
+    pub fn cover_bad_debt(&mut self, amount: U256) -> Result<U256, DSCEngineError> {
+        self._cover_bad_debt(amount)
+    }
+
+    /// 设置债务拍卖参数：协议代币、初始协议代币数量、每场募集的 DSC 数量、时长和每次出价的最小降幅（基点）
+    pub fn set_debt_auction_params(
+        &mut self,
+        token: Address,
+        lot: U256,
+        bid: U256,
+        duration: U256,
+        min_decrease: U256,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self._set_debt_auction_params(token, lot, bid, duration, min_decrease)?;
+        self._record_param("debtAuctionToken", address_value(token));
+        self._record_param("debtAuctionLot", lot);
+        self._record_param("debtAuctionBid", bid);
+        self._record_param("debtAuctionDuration", duration);
+        self._record_param("debtAuctionMinDecrease", min_decrease);
+        Ok(())
+    }
+
+    pub fn get_debt_auction_params(&self) -> (Address, U256, U256, U256, U256) {
+        // 获取债务拍卖的协议代币、初始数量、募集数量、时长和最小降幅
+        (
+            self.debt_auction_token.get(),
+            self.debt_auction_lot.get(),
+            self.debt_auction_bid.get(),
+            self.debt_auction_duration.get(),
+            self.debt_auction_min_decrease.get(),
+        )
+    }
+
+    /// 保险基金冲抵后剩余坏账足够时发起债务拍卖，返回拍卖编号
+    pub fn kick_debt_auction(&mut self) -> Result<U256, DSCEngineError> {
+        self._kick_debt_auction()
+    }
+
+    /// 以更少的协议代币数量参与债务拍卖，出价者支付该场募集的 DSC
+    pub fn bid_debt_auction(&mut self, id: U256, lot: U256) -> Result<(), DSCEngineError> {
+        self._bid_debt_auction(id, lot)
+    }
+
+    /// 结算已结束的债务拍卖
+    pub fn settle_debt_auction(&mut self, id: U256) -> Result<(), DSCEngineError> {
+        self._settle_debt_auction(id)
+    }
+
+    pub fn get_debt_auction(&self, id: U256) -> (Address, U256, U256, U256) {
+        // 获取债务拍卖的当前出价者、协议代币数量、募集数量和结束时间
+        let auction = self.debt_auctions.getter(id);
+        (
+            auction.bidder.get(),
+            auction.lot.get(),
+            auction.bid.get(),
+            auction.ends_at.get(),
+        )
+    }
+
+    pub fn get_debt_on_auction(&self) -> U256 {
+        // 获取拍卖中尚未募得的坏账
+        self.debt_on_auction.get()
+    }
+
+    /// 设置回购上限：周期秒数和每个周期回购卖出的价值上限（DSC 计）
+    pub fn set_buyback_limit(
+        &mut self,
+        window: U256,
+        epoch_cap: U256,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.buyback_window.set(window);
+        self.buyback_epoch_cap.set(epoch_cap);
+        self._record_param("buybackWindow", window);
+        self._record_param("buybackEpochCap", epoch_cap);
+        Ok(())
+    }
+
+    pub fn get_buyback_limit(&self) -> (U256, U256, U256) {
+        // 获取回购上限：(周期秒数, 每个周期的上限, 本周期已回购的价值)
+        (
+            self.buyback_window.get(),
+            self.buyback_epoch_cap.get(),
+            self._epoch_buyback_spent(),
+        )
+    }
+
+    /// 向回购储备转入支持的抵押品或 PSM 稳定币
+    pub fn fund_buyback(&mut self, token: Address, amount: U256) -> Result<(), DSCEngineError> {
+        self.more_than_zero(amount)?;
+        self._fund_buyback(token, amount)
+    }
+
+    /// DSC 低于锚定价格时卖出回购储备买入 DSC 并销毁，返回销毁的 DSC 数量
+    pub fn buyback_and_burn(
+        &mut self,
+        token: Address,    // 卖出的储备代币
+        amount_in: U256,   // 卖出数量
+        min_dsc_out: U256, // 最少买入的 DSC 数量，不低于卖出数量按锚定价格折算的价值
+    ) -> Result<U256, DSCEngineError> {
+        self.more_than_zero(amount_in)?;
+        self._buyback_and_burn(token, amount_in, min_dsc_out)
+    }
+
+    pub fn get_buyback_reserve(&self, token: Address) -> U256 {
+        // 获取代币的回购储备
+        self.buyback_reserves.get(token)
+    }
+
+    /// 设置协议自有流动性的 DSC/USDC 池 LP 代币和债务上限，仍持有 LP 代币时不能更换池
+    pub fn set_pol_config(
+        &mut self,
+        pair: Address,
+        debt_ceiling: U256,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        if self.pol_pair.get() != pair && self.pol_liquidity.get() > U256::ZERO {
+            return Err(DSCEngineError::PolPositionOpen(PolPositionOpen {}));
+        }
+        self.pol_pair.set(pair);
+        self.pol_debt_ceiling.set(debt_ceiling);
+        self._record_param("polPair", address_value(pair));
+        self._record_param("polDebtCeiling", debt_ceiling);
+        Ok(())
+    }
+
+    /// 转入 USDC 并配对铸造等值 DSC 注入流动性池，返回得到的 LP 代币数量
+    pub fn add_protocol_liquidity(
+        &mut self,
+        usdc_amount: U256,   // 转入的 USDC 数量
+        min_liquidity: U256, // 最少得到的 LP 代币数量
+    ) -> Result<U256, DSCEngineError> {
+        self.only_owner()?;
+        self.more_than_zero(usdc_amount)?;
+        self._add_protocol_liquidity(usdc_amount, min_liquidity)
+    }
+
+    /// 撤出流动性并结清对应债务，返回退给治理的 USDC 数量
+    pub fn remove_protocol_liquidity(
+        &mut self,
+        liquidity: U256, // 撤出的 LP 代币数量
+        min_usdc: U256,  // 最少取回的 USDC 数量
+    ) -> Result<U256, DSCEngineError> {
+        self.only_owner()?;
+        self.more_than_zero(liquidity)?;
+        self._remove_protocol_liquidity(liquidity, min_usdc)
+    }
+
+    pub fn get_protocol_liquidity(&self) -> (Address, U256, U256, U256) {
+        // 获取协议自有流动性：(LP 代币, 持有的 LP 代币数量, 债务, 债务上限)
+        (
+            self.pol_pair.get(),
+            self.pol_liquidity.get(),
+            self.pol_debt.get(),
+            self.pol_debt_ceiling.get(),
+        )
+    }
+
+    pub fn get_system_bad_debt(&self) -> U256 {
+        // 获取尚未冲抵的系统坏账
+        self.system_bad_debt.get()
+    }
+
+    pub fn get_insurance_fund(&self) -> U256 {
+        // 获取保险基金余额
+        self.insurance_fund.get()
+    }
+
+    /// 配置 PSM：兑换代币、换入/换出手续费（基点）和债务上限
+    pub fn set_psm_config(
+        &mut self,
+        token: Address,
+        fee_in: U256,
+        fee_out: U256,
+        debt_ceiling: U256,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        if fee_in > U256::from(psm::BPS) || fee_out > U256::from(psm::BPS) {
+            return Err(DSCEngineError::InvalidFee(InvalidFee {}));
+        }
+        // PSM 代币不能同时作为抵押品，避免储备与抵押品混账
+        if !self.price_feeds.get(token).is_zero() {
+            return Err(DSCEngineError::NotAllowedToken(NotAllowedToken {}));
+        }
+        if !self._psm_token_replaceable(token) {
+            return Err(DSCEngineError::PsmTokenLocked(PsmTokenLocked {}));
+        }
+        if self.psm_token.get() != token {
+            let decimals = IERC20::new(token).decimals(Call::new()).map_err(|_| {
+                DSCEngineError::TokenDecimalsUnavailable(TokenDecimalsUnavailable {})
+            })?;
+            self.psm_token_decimals.set(U8::from(decimals));
+            self.psm_token.set(token);
+        }
+        self.psm_fee_in.set(fee_in);
+        self.psm_fee_out.set(fee_out);
+        self.psm_debt_ceiling.set(debt_ceiling);
+        self._record_param("psmToken", address_value(token));
+        self._record_param("psmFeeIn", fee_in);
+        self._record_param("psmFeeOut", fee_out);
+        self._record_param("psmDebtCeiling", debt_ceiling);
+        Ok(())
+    }
+
+    /// 存入 PSM 代币按 1:1 换取 DSC，返回获得的 DSC 数量
+    pub fn psm_swap_to_dsc(&mut self, gem_amount: U256) -> Result<U256, DSCEngineError> {
+        self.more_than_zero(gem_amount)?;
+        self._psm_swap_to_dsc(gem_amount)
+    }
+
+    /// 支付 DSC 按 1:1 换回 PSM 代币，返回支付的 DSC 数量
+    pub fn psm_swap_from_dsc(&mut self, gem_amount: U256) -> Result<U256, DSCEngineError> {
+        self.more_than_zero(gem_amount)?;
+        self._psm_swap_from_dsc(gem_amount)
+    }
+
+    pub fn get_psm_config(&self) -> (Address, U256, U256, U256) {
+        // 获取 PSM 代币、换入/换出手续费和债务上限
+        (
+            self.psm_token.get(),
+            self.psm_fee_in.get(),
+            self.psm_fee_out.get(),
+            self.psm_debt_ceiling.get(),
+        )
+    }
+
+    pub fn get_psm_debt(&self) -> U256 {
+        // 获取经 PSM 铸造的 DSC 数量
+        self.psm_debt.get()
+    }
+
+    pub fn get_psm_reserves(&self) -> U256 {
+        // 获取 PSM 持有的代币储备
+        self.psm_reserves.get()
+    }
+
+    /// 按面值销毁 DSC，从赎回列表头部开始依次赎回抵押品，返回获得的抵押品数量
+    pub fn redeem_dsc_for_collateral(
+        &mut self,
+        token: Address,   // 要赎回的抵押品地址
+        dsc_amount: U256, // 要销毁的稳定币数量
+        max_fee: U256,    // 可接受的最高赎回手续费（基点）
+    ) -> Result<U256, DSCEngineError> {
+        self.more_than_zero(dsc_amount)?;
+        self.is_allowed_token(token)?;
+        self._accrue_all();
+        self._redeem_dsc_for_collateral(token, dsc_amount, max_fee)
+    }
+
+    /// 按当前健康因子把仓位重新插入赎回列表，价格变动使列表顺序过期时任何人都可以调用；
+    /// hint 为从其开始查找插入位置的列表中的仓位，零地址表示从仓位原来的位置开始
+    pub fn reinsert_redemption_position(
+        &mut self,
+        user: Address, // 要重新插入的仓位
+        hint: Address, // 查找插入位置的起点
+    ) {
+        self._accrue_all();
+        self._sync_redemption_position(user, hint);
+    }
+
+    /// 赎回列表中 user 之后的仓位，user 为零地址时返回列表头，返回零地址表示已到列表尾
+    pub fn get_next_redemption_position(&self, user: Address) -> Address {
+        self.redemption_next.get(user)
+    }
+
+    /// 仓位在赎回列表中的排序键（最近一次插入时的健康因子）
+    pub fn get_redemption_key(&self, user: Address) -> U256 {
+        self.redemption_keys.get(user)
+    }
+
+    /// 设置赎回手续费（基点）
+    pub fn set_redemption_fee(&mut self, fee: U256) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        if fee > U256::from(psm::BPS) {
+            return Err(DSCEngineError::InvalidFee(InvalidFee {}));
+        }
+        self.redemption_fee.set(fee);
+        self._record_param("redemptionFee", fee);
+        Ok(())
+    }
+
+    pub fn get_redemption_fee(&self) -> U256 {
+        // 获取赎回手续费
+        self.redemption_fee.get()
+    }
+
+    /// 设置优先承接清算的稳定池
+    pub fn set_stability_pool(&mut self, stability_pool: Address) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.stability_pool.set(stability_pool);
+        self._record_param("stabilityPool", address_value(stability_pool));
+        Ok(())
+    }
+
+    pub fn get_stability_pool(&self) -> Address {
+        // 获取稳定池地址
+        self.stability_pool.get()
+    }
+
+    /// 设置拍卖时长（秒）和起拍溢价（清算精度，如 120 表示 120%）
+    pub fn set_auction_params(
+        &mut self,
+        duration: U256,
+        start_premium: U256,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        self.more_than_zero(duration)?;
+        self.auction_duration.set(duration);
+        self.auction_start_premium.set(start_premium);
+        self._record_param("auctionDuration", duration);
+        self._record_param("auctionStartPremium", start_premium);
+        Ok(())
+    }
+
+    pub fn get_auction(&self, id: U256) -> (Address, Address, U256, U256, U256, U256, U256) {
+        // 获取拍卖的用户、抵押品、待偿还债务、清算罚金、抵押品（记账单位）、起拍价和起拍时间
+        let auction = self.auctions.getter(id);
+        (
+            auction.user.get(),
+            auction.token.get(),
+            auction.tab.get(),
+            auction.penalty.get(),
+            auction.lot.get(),
+            auction.start_price.get(),
+            auction.start_time.get(),
+        )
+    }
+
+    pub fn get_auction_price(&self, id: U256) -> U256 {
+        // 获取拍卖当前价格
+        self._auction_price(id)
+    }
+
+    pub fn get_active_auction(&self, user: Address) -> U256 {
+        // 获取用户进行中的拍卖编号，没有时为零
+        self.active_auction.get(user)
+    }
+
+    pub fn get_version(&self) -> U256 {
+        // 获取初始化时记录的实现版本
+        self.version.get()
+    }
+
+    pub fn storage_version(&self) -> U256 {
+        // 获取当前存储布局的版本号
+        self.storage_version.get()
+    }
+
+    pub fn get_implementation_version(&self) -> U256 {
+        // 获取当前代码的实现版本，与 get_version 不同时说明升级后尚未迁移存储
+        U256::from(ENGINE_VERSION)
+    }
+
+    pub fn supports_interface(&self, interface_id: FixedBytes<4>) -> bool {
+        // ERC-165 接口检测：声明支持 ERC-165、金库 NFT 的 ERC-721 和 IDSCEngine
+        erc165::engine_supports_interface(interface_id)
+    }
+
+    pub fn get_savings_vault(&self) -> Address {
+        // 获取储蓄金库地址
+        self.savings_vault.get()
+    }
+
+    pub fn get_stability_fee(&self, token: Address) -> U256 {
+        // 获取抵押品每秒的稳定费率
+        self.stability_fee_rates.get(token)
+    }
+
+    pub fn get_rate_strategy(&self, token: Address) -> (U256, U256, U256, U256) {
+        // 获取抵押品的分段利率策略：基础费率、斜率一、斜率二和最优使用率
+        let strategy = self.rate_strategies.getter(token);
+        (
+            strategy.base_rate.get(),
+            strategy.slope1.get(),
+            strategy.slope2.get(),
+            strategy.optimal_utilization.get(),
+        )
+    }
+
+    pub fn get_utilization(&self, token: Address) -> U256 {
+        // 获取抵押品当前的使用率（基础精度）
+        self._utilization(token)
+    }
+
+    pub fn get_current_rate(&self, token: Address) -> U256 {
+        // 获取抵押品当前生效的每秒稳定费率：设置了利率策略时按当前使用率计算
+        self._current_rate(token)
+    }
+
+    pub fn get_borrow_apr(&self, token: Address) -> U256 {
+        // 获取抵押品当前的年化借款利率（基础精度，单利）
+        self._borrow_apr(token)
+    }
+
+    pub fn get_user_borrow_apr(&self, user: Address) -> U256 {
+        // 获取用户仓位的加权年化借款利率（基础精度）：按分桶债务加权，没有债务时按抵押品价值加权
+        self._user_borrow_apr(user)
+    }
+
+    pub fn get_debt_rate_index(&self, token: Address) -> U256 {
+        // 获取抵押品当前的债务累计指数（RAY 精度）
+        self._current_debt_index(token)
+    }
+
+    pub fn get_accrued_stability_fees(&self) -> U256 {
+        // 获取已计提的稳定费收入
+        self.accrued_stability_fees.get()
+    }
+
+    pub fn get_user_debt(&self, user: Address) -> U256 {
+        // 获取用户当前的总债务（含尚未计提的利息），即全部偿还所需的 DSC 数量
+        self._user_debt(user)
+    }
+
+    pub fn get_debt_by_collateral(&self, token: Address) -> U256 {
+        // 获取记在抵押品分桶上的全部债务（含尚未计提的利息）
+        self._collateral_debt(token)
+    }
+
+    pub fn get_debt_by_collateral_breakdown(&self) -> (Vec<Address>, Vec<U256>) {
+        // 获取各抵押品分桶的债务：(抵押品地址, 分桶债务)，各分桶之和即协议总债务
+        let mut tokens = Vec::new();
+        let mut debts = Vec::new();
+        for i in 0..self.collateral_tokens.len() {
+            if let Some(token) = self.collateral_tokens.get(i) {
+                tokens.push(token);
+                debts.push(self._collateral_debt(token));
+            }
+        }
+        (tokens, debts)
+    }
+
+    pub fn get_user_debt_breakdown(
+        &self,
+        user: Address,
+    ) -> (U256, U256, Vec<Address>, Vec<U256>, Vec<U256>) {
+        // 获取用户债务的本金、累计利息，以及各有债务的抵押品分桶的债务和计算所用的累计指数
+        let mut tokens = Vec::new();
+        let mut debts = Vec::new();
+        let mut indices = Vec::new();
+        let mut total_debt = U256::ZERO;
+        for i in 0..self.collateral_tokens.len() {
+            if let Some(token) = self.collateral_tokens.get(i) {
+                let debt = self._bucket_debt(user, token);
+                if debt > U256::ZERO {
+                    tokens.push(token);
+                    debts.push(debt);
+                    indices.push(self._current_debt_index(token));
+                    total_debt += debt;
+                }
+            }
+        }
+        let principal = self.dsc_minted.get(user);
+        (
+            principal,
+            total_debt.saturating_sub(principal),
+            tokens,
+            debts,
+            indices,
+        )
+    }
+
+    pub fn get_rate_provider(&self, token: Address) -> Address {
+        // 获取抵押品的汇率合约
+        self.rate_providers.get(token)
+    }
+
+    pub fn is_erc4626_collateral(&self, token: Address) -> bool {
+        // 获取抵押品是否为 ERC-4626 金库份额
+        self.erc4626_collateral.get(token)
+    }
+
+    pub fn is_collateral_shares_mode(&self, token: Address) -> bool {
+        // 获取抵押品是否按份额记账
+        self.collateral_shares_mode.get(token)
+    }
+
+    pub fn get_dsc(&self) -> Address {
+        // 获取稳定币合约地址
+        self.dsc.get()
+    }
+
+    pub fn get_owner(&self) -> Address {
+        // 获取治理地址
+        self.owner.get()
+    }
+
+    pub fn get_collateral_decimals(&self, token: Address) -> u8 {
+        // 获取抵押品精度
+        self.collateral_decimals.get(token).to::<u8>()
+    }
+
+    /// 抵押品当前的价格（预言机精度）：预言机不可用、报价为负或过大时返回对应错误
+    pub fn get_collateral_price(&self, token: Address) -> Result<U256, DSCEngineError> {
+        self._collateral_price(token)
+    }
+
+    pub fn get_peg_feed(&self) -> Address {
+        // 获取锚定货币预言机地址，零地址表示以美元为记账单位
+        self.peg_feed.get()
+    }
+
+    pub fn get_collateral_token_price_feed(&self, token: Address) -> Address {
+        // 获取价格预言机地址
+        self.price_feeds.get(token)
+    }
+
+    pub fn get_close_factor(&self) -> U256 {
+        // 获取平仓系数
+        self._close_factor()
+    }
+
+    /// 设置临界健康因子：健康因子低于该值的仓位可一次清算全部债务，不能高于最小健康因子，为零时不启用
+    pub fn set_critical_health_factor(
+        &mut self,
+        health_factor: U256,
+    ) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        if health_factor > self._min_health_factor() {
+            return Err(DSCEngineError::InvalidCriticalHealthFactor(
+                InvalidCriticalHealthFactor {},
+            ));
+        }
+        self._set_critical_health_factor(health_factor.to::<u64>());
+        self._record_param("criticalHealthFactor", health_factor);
+        Ok(())
+    }
+
+    pub fn get_critical_health_factor(&self) -> U256 {
+        // 获取临界健康因子
+        self._critical_health_factor()
+    }
+
+    /// 设置借款阈值（清算精度），不得高于默认清算阈值；铸造和提取后健康因子须达到
+    /// `最小健康因子 × 清算阈值 / 借款阈值`，设为零时不启用
+    pub fn set_borrow_threshold(&mut self, threshold: U256) -> Result<(), DSCEngineError> {
+        self.only_owner()?;
+        if threshold > self._liquidation_threshold() {
+            return Err(DSCEngineError::InvalidBorrowThreshold(
+                InvalidBorrowThreshold {},
+            ));
+        }
+        self._set_borrow_threshold(threshold.to::<u32>());
+        self._record_param("borrowThreshold", threshold);
+        Ok(())
+    }
+
+    pub fn get_borrow_threshold(&self) -> (U256, U256) {
+        // 获取借款阈值和铸造、提取后须达到的借款健康因子
+        (self._borrow_threshold(), self._borrow_health_factor())
+    }
+
+    /// 用户是否可以被清算
+    pub fn is_liquidatable(&self, user: Address) -> bool {
+        self._health_factor(user).unwrap_or(U256::ZERO) < self._min_health_factor()
+    }
+
+    /// 使用指定抵押品清算用户时，单次最多可偿还的债务数量，价格不可用时为零
+    pub fn get_max_liquidatable_debt(&self, user: Address, collateral: Address) -> U256 {
+        self._max_liquidatable_debt(user, collateral)
+            .unwrap_or(U256::ZERO)
+    }
+
+    // 单次最多可偿还的债务数量，价格不可用时回滚
+    pub(crate) fn _max_liquidatable_debt(
+        &self,
+        user: Address,
+        collateral: Address,
+    ) -> Result<U256, DSCEngineError> {
+        let health_factor = self._liquidation_health_factor(user, collateral)?;
+        if health_factor >= self._min_health_factor() {
+            return Ok(U256::ZERO);
+        }
+        // 健康因子低于临界健康因子时不受平仓系数限制
+        let critical_health_factor = self._critical_health_factor();
+        let close_factor = if health_factor < critical_health_factor {
+            LIQUIDATION_PRECISION
+        } else {
+            self._close_factor()
+        };
+        // 平仓系数限制的债务上限（逐仓模式下只计该抵押品分桶的债务）
+        let max_by_close_factor = mul_div(
+            self._liquidatable_debt(user, collateral),
+            close_factor,
+            LIQUIDATION_PRECISION,
+            Rounding::Down,
+        );
+        // 用户该抵押品（含清算奖励）可覆盖的债务上限
+        let collateral_amount = self._collateral_amount_of(user, collateral);
+        let max_by_collateral = mul_div(
+            self._usd_value(collateral, collateral_amount)?,
+            LIQUIDATION_PRECISION,
+            LIQUIDATION_PRECISION + self._liquidation_bonus_at(collateral, health_factor),
+            Rounding::Down,
+        );
+        Ok(max_by_close_factor.min(max_by_collateral))
+    }
+
+    /// 其他抵押品价值不变时，使用户健康因子降到 1.0 的代币预言机价格（与预言机精度相同）
+    pub fn get_liquidation_price(&self, user: Address, token: Address) -> U256 {
+        let amount = self._collateral_amount_of(user, token);
+        let total_dsc_minted = self._account_debt_value(user);
+        if amount == U256::ZERO || total_dsc_minted == U256::ZERO {
+            return U256::ZERO;
+        }
+        // 健康因子为 1.0 时所需的抵押品总价值
+        let required_collateral_value = mul_div(
+            total_dsc_minted,
+            LIQUIDATION_PRECISION,
+            self._liquidation_threshold_of(user),
+            Rounding::Down,
+        );
+        // 其他抵押品的价值
+        let other_collateral_value =
+            self.get_account_collateral_value_in_usd(user) - self.get_usd_value(token, amount);
+        // 其他抵押品已足够覆盖债务，该代币价格归零也不会触发清算
+        if required_collateral_value <= other_collateral_value {
+            return U256::ZERO;
+        }
+        // 反推该代币需要达到的价格
+        mul_div(
+            required_collateral_value - other_collateral_value,
+            self._token_unit(token),
+            amount * ADDITIONAL_FEED_PRECISION,
+            Rounding::Down,
+        )
+    }
+
+    /// 预览用户在抵押品和债务变化后的健康因子（正数为增加，负数为减少）
+    pub fn preview_health_factor_after(
+        &self,
+        user: Address,
+        token: Address,
+        collateral_delta: I256, // 抵押品数量变化
+        debt_delta: I256,       // 稳定币债务变化
+    ) -> U256 {
+        let amount = self._collateral_amount_of(user, token);
+        let new_amount = Self::_apply_delta(amount, collateral_delta);
+        let new_dsc_minted = Self::_apply_delta(self._account_debt_value(user), debt_delta);
+        // 只替换该代币的抵押品价值，其余抵押品保持不变
+        let new_collateral_value = self.get_account_collateral_value_in_usd(user)
+            - self.get_usd_value(token, amount)
+            + self.get_usd_value(token, new_amount);
+        self._calculate_health_factor_with_threshold(
+            new_dsc_minted,
+            new_collateral_value,
+            self._liquidation_threshold_of(user),
+        )
+    }
+
+    /// 用户在健康因子不低于 `借款健康因子 + safety_buffer` 的前提下还能铸造的稳定币数量
+    pub fn get_max_mintable_dsc(&self, user: Address, safety_buffer: U256) -> U256 {
+        let total_dsc_minted = self._user_debt(user);
+        let collateral_value_in_usd = self.get_account_collateral_value_in_usd(user);
+        // 按清算阈值调整后的抵押品价值
+        let collateral_adjusted_for_threshold = mul_div(
+            collateral_value_in_usd,
+            self._liquidation_threshold_of(user),
+            LIQUIDATION_PRECISION,
+            Rounding::Down,
+        );
+        // 目标健康因子下允许的最大债务
+        let target_health_factor = self._borrow_health_factor() + safety_buffer;
+        let max_total_dsc = wad_div(collateral_adjusted_for_threshold, target_health_factor);
+        max_total_dsc
+            .saturating_sub(total_dsc_minted)
+            .saturating_sub(self._synth_debt_value(user))
+    }
+
+    pub fn get_total_collateral(&self, token: Address) -> U256 {
+        // 获取抵押品的存款总量（代币数量），按内部记账，不受直接转入的代币影响
+        self.total_deposited.get(token)
+    }
+
+    pub fn get_total_deposited(&self, token: Address) -> U256 {
+        // 获取抵押品的存款总量（代币数量），与 get_total_collateral 相同
+        self.get_total_collateral(token)
+    }
+
+    /// 所有抵押品存款总量的美元价值（18 位精度），价格不可用的抵押品估值为零
+    pub fn get_total_collateral_value_usd(&self) -> U256 {
+        let mut total_collateral_value_in_usd = U256::ZERO;
+        for i in 0..self.collateral_tokens.len() {
+            if let Some(token) = self.collateral_tokens.get(i) {
+                let amount = self.total_deposited.get(token);
+                // 没有存款的抵押品无需查询价格
+                if amount == U256::ZERO {
+                    continue;
+                }
+                total_collateral_value_in_usd += self.get_usd_value(token, amount);
+            }
+        }
+        total_collateral_value_in_usd
+    }
+
+    /// 协议统计：(抵押品总价值, 稳定币总债务, 全局抵押率, 活跃仓位数)
+    pub fn get_protocol_stats(&self) -> (U256, U256, U256, U256) {
+        let total_collateral_value_in_usd = self.get_total_collateral_value_usd();
+        let total_dsc_minted = self._total_debt();
+        // 全局抵押率（基础精度表示，1e18 即 100%），没有债务时为最大值
+        let collateralization_ratio = if total_dsc_minted == U256::ZERO {
+            U256::MAX
+        } else {
+            wad_div(total_collateral_value_in_usd, total_dsc_minted)
+        };
+        (
+            total_collateral_value_in_usd,
+            total_dsc_minted,
+            collateralization_ratio,
+            self.active_positions.get(),
+        )
+    }
+
+    /// 抵押品的风险指标：(使用率, 抵押率, 清算线, 抵押率距清算线的余量)，抵押率和清算线为基础精度
+    pub fn get_collateral_risk_metrics(&self, token: Address) -> (U256, U256, U256, U256) {
+        let collateral_ratio = self._collateral_ratio(token);
+        let liquidation_ratio = self._liquidation_ratio(token);
+        (
+            self._utilization(token),
+            collateral_ratio,
+            liquidation_ratio,
+            collateral_ratio.saturating_sub(liquidation_ratio),
+        )
+    }
+
+    /// 全协议的风险指标：(抵押率, 按价值加权的清算线, 抵押率距清算线的余量)，均为基础精度
+    pub fn get_global_risk_metrics(&self) -> (U256, U256, U256) {
+        let collateral_ratio = self._global_collateral_ratio();
+        let liquidation_ratio = self._global_liquidation_ratio();
+        (
+            collateral_ratio,
+            liquidation_ratio,
+            collateral_ratio.saturating_sub(liquidation_ratio),
+        )
+    }
+}
+
+// 内部辅助函数：不对外暴露
+impl DSCEngine {
+    // 由 liquidator 偿还债务并获得抵押品，返回 liquidator 获得的抵押品数量
+    fn _liquidate(
+        &mut self,
+        collateral: Address, // 抵押品地址
+        user: Address,       // 要清算的用户地址
+        debt_to_cover: U256, // 要清算的债务数量
+        liquidator: Address, // 支付 DSC 并获得抵押品的清算人
+    ) -> Result<U256, DSCEngineError> {
+        // 检查债务数量是否大于零
+        self.more_than_zero(debt_to_cover)?;
+        // 计提稳定费
+        self._accrue_all();
+        // 检查健康因子是否正常
+        let starting_user_health_factor = self._liquidation_health_factor(user, collateral)?;
+        if starting_user_health_factor >= self._min_health_factor() {
+            return Err(DSCEngineError::HealthFactorOk(HealthFactorOk {}));
+        }
+        // 检查清算数量是否超过平仓系数和抵押品允许的上限
+        let max_debt_to_cover = self._max_liquidatable_debt(user, collateral)?;
+        if debt_to_cover > max_debt_to_cover {
+            return Err(DSCEngineError::ExceedsMaxLiquidatableDebt(
+                ExceedsMaxLiquidatableDebt {
+                    _0: max_debt_to_cover,
+                },
+            ));
+        }
+        // 拍卖中的仓位只能通过拍卖清算
+        if self.active_auction.get(user) != U256::ZERO {
+            return Err(DSCEngineError::AuctionActive(AuctionActive {}));
+        }
+        // 排序器或预言机中断及恢复后的宽限期内不允许清算
+        self._check_liquidation_grace(collateral)?;
+        // 稳定池足以承接时，应先通过稳定池清算
+        if self._stability_pool_balance()? >= debt_to_cover {
+            return Err(DSCEngineError::StabilityPoolCanAbsorb(
+                StabilityPoolCanAbsorb {},
+            ));
+        }
+        // 获取债务对应的抵押品数量
+        let token_amount_from_debt_covered =
+            self._token_amount_from_usd(collateral, debt_to_cover)?;
+        // 按清算前的健康因子计算清算奖励
+        let bonus = self._liquidation_bonus_at(collateral, starting_user_health_factor);
+        let bonus_collateral = (token_amount_from_debt_covered * bonus) / LIQUIDATION_PRECISION;
+        let total_collateral_to_redeem = token_amount_from_debt_covered + bonus_collateral;
+        // 赎回抵押品
+        self._redeem_collateral(collateral, total_collateral_to_redeem, user, liquidator)?;
+        // 销毁稳定币
+        self._burn_dsc(
+            debt_to_cover,
+            user,
+            liquidator,
+            self._debt_bucket(user, collateral),
+        )?;
+        // 收取清算奖励中的协议分成
+        self._collect_liquidation_protocol_fee(debt_to_cover, bonus, liquidator)?;
+
+        let ending_user_health_factor = self._liquidation_health_factor(user, collateral)?;
+        if ending_user_health_factor <= starting_user_health_factor {
+            return Err(DSCEngineError::HealthFactorNotImproved(
+                HealthFactorNotImproved {},
+            ));
+        }
+        self._record_bad_debt(user)?;
+        self._revert_if_health_factor_is_broken(liquidator)?;
+        self._pay_keeper_reward(liquidator)?;
+        Ok(total_collateral_to_redeem)
+    }
+
+    // 根据用户当前的抵押品和债务更新活跃仓位计数
+    fn _sync_active_position(&mut self, user: Address) {
+        self._sync_redemption_position(user, Address::ZERO);
+        let mut is_active = self.dsc_minted.get(user) > U256::ZERO || self._has_synth_debt(user);
+        let user_collateral_mapping = self.collateral_deposited.getter(user);
+        for i in 0..self.collateral_tokens.len() {
+            if is_active {
+                break;
+            }
+            if let Some(token) = self.collateral_tokens.get(i) {
+                is_active = user_collateral_mapping.get(token) > U256::ZERO;
+            }
+        }
+        let was_active = self.has_active_position.get(user);
+        if is_active == was_active {
+            return;
+        }
+        self.has_active_position.setter(user).set(is_active);
+        let active_positions = self.active_positions.get();
+        if is_active {
+            self.active_positions.set(active_positions + U256::from(1));
+            self.borrowers.push(user);
+            self.borrower_index
+                .setter(user)
+                .set(U256::from(self.borrowers.len()));
+        } else {
+            self.active_positions.set(active_positions - U256::from(1));
+            self._remove_borrower(user);
+        }
+    }
+
+    // 从仓位列表中移除用户：用最后一个元素填补空位
+    fn _remove_borrower(&mut self, user: Address) {
+        let index = self.borrower_index.get(user);
+        if index == U256::ZERO {
+            return;
+        }
+        let position = index.to::<usize>() - 1;
+        let last = self.borrowers.len() - 1;
+        if position != last {
+            if let Some(last_user) = self.borrowers.get(last) {
+                if let Some(mut slot) = self.borrowers.setter(position) {
+                    slot.set(last_user);
+                }
+                self.borrower_index.setter(last_user).set(index);
+            }
+        }
+        self.borrowers.pop();
+        self.borrower_index.setter(user).set(U256::ZERO);
+    }
+
+    // 稳定池中可用于清算的 DSC 数量，未设置稳定池时为零
+    fn _stability_pool_balance(&self) -> Result<U256, DSCEngineError> {
+        let stability_pool = self.stability_pool.get();
+        if stability_pool.is_zero() {
+            return Ok(U256::ZERO);
+        }
+        IDecentralizedStableCoin::new(self.dsc.get())
+            .balance_of(Call::new(), stability_pool)
+            .map_err(|_| DSCEngineError::TransferFailed(TransferFailed {}))
+    }
+
+    // 从调用者转入抵押品并记入 position 仓位，按实际到账数量记账
+    fn _deposit_collateral(
+        &mut self,
+        position: Address,
+        token_collateral_address: Address,
+        amount_collateral: U256,
+    ) -> Result<(), DSCEngineError> {
+        let token = IERC20::new(token_collateral_address);
+        // 记录转账前合约持有的代币数量
+        let balance_before = token
+            .balance_of(Call::new(), contract::address())
+            .map_err(|_| DSCEngineError::TransferFailed(TransferFailed {}))?;
+        // 从用户地址转账到合约地址
+        if token
+            .transfer_from(
+                Call::new(),
+                msg_sender(),
+                contract::address(),
+                amount_collateral,
+            )
+            .is_err()
+        {
+            return Err(DSCEngineError::TransferFailed(TransferFailed {}));
+        }
+        // 按实际到账数量记账，兼容转账收费的代币
+        let balance_after = token
+            .balance_of(Call::new(), contract::address())
+            .map_err(|_| DSCEngineError::TransferFailed(TransferFailed {}))?;
+        let amount_received = balance_after.saturating_sub(balance_before);
+        self.more_than_zero(amount_received)?;
+        self._credit_collateral(position, token_collateral_address, amount_received)
+    }
+
+    // 按实际到账数量为用户记入抵押品
+    fn _credit_collateral(
+        &mut self,
+        user: Address,
+        token: Address,
+        amount_received: U256,
+    ) -> Result<(), DSCEngineError> {
+        self._check_permissioned_caller(user)?;
+        self._check_emode_collateral(user, token)?;
+        // 换算为记账单位（份额模式下为份额，按到账前的存款总量折算）
+        let total_assets = self.total_deposited.get(token);
+        let credited = self._amount_to_shares(token, amount_received, total_assets, false);
+        self.total_deposited
+            .setter(token)
+            .set(total_assets + amount_received);
+
+        // 获取用户抵押品存款映射
+        let user_collateral_mapping = self.collateral_deposited.getter(user);
+        // 获取用户特定代币的抵押品数量
+        let user_collateral = user_collateral_mapping.getter(token);
+        let value = user_collateral.get();
+        // 更新用户抵押品存款映射
+        self.collateral_deposited
+            .setter(user)
+            .setter(token)
+            .set(value + credited);
+        // 更新抵押品总量
+        let total_collateral = self.total_collateral_deposited.get(token);
+        self.total_collateral_deposited
+            .setter(token)
+            .set(total_collateral + credited);
+        self._check_supply_cap(token)?;
+        self._record_inflow(token, amount_received);
+        self._sync_active_position(user);
+
+        // 记录抵押品存入事件
+        evm::log(CollateralDeposited {
+            user,
+            token,
+            amount: amount_received,
+            collateralBalance: self._collateral_amount_of(user, token),
+            totalDebt: self._user_debt(user),
+            actionNonce: self._next_action_nonce(user),
+        });
+        Ok(())
+    }
+
+    // 递增并返回用户的操作序号，用于仓位事件
+    fn _next_action_nonce(&mut self, user: Address) -> U256 {
+        let nonce = self.action_nonces.get(user) + U256::from(1);
+        self.action_nonces.setter(user).set(nonce);
+        nonce
+    }
+
+    fn more_than_zero(&self, amount: U256) -> Result<(), DSCEngineError> {
+        if amount == U256::ZERO {
+            Err(DSCEngineError::NeedsMoreThanZero(NeedsMoreThanZero {}))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn only_owner(&self) -> Result<(), DSCEngineError> {
+        if msg_sender() != self.owner.get() {
+            return Err(DSCEngineError::NotOwner(NotOwner {}));
+        }
+        Ok(())
+    }
+
+    fn _only_guardian(&self) -> Result<(), DSCEngineError> {
+        let sender = msg_sender();
+        if sender != self.guardian.get() && sender != self.owner.get() {
+            return Err(DSCEngineError::NotGuardian(NotGuardian {}));
+        }
+        Ok(())
+    }
+
+    fn is_allowed_token(&self, token: Address) -> Result<(), DSCEngineError> {
+        if self.price_feeds.get(token).is_zero() {
+            Err(DSCEngineError::NotAllowedToken(NotAllowedToken {}))
+        } else {
             Ok(())
         }
     }
 
+    // 铸造稳定币的内部实现：债务记在 user 名下（指定分桶时只记入该分桶），稳定币铸造给 to（调用前需先计提）
+    fn _mint_dsc(
+        &mut self,
+        user: Address,
+        amount_dsc_to_mint: U256,
+        to: Address,
+        bucket: Option<Address>,
+    ) -> Result<(), DSCEngineError> {
+        let fee = self._fee_for(user, amount_dsc_to_mint, self.mint_fee.get());
+        let debt_added = amount_dsc_to_mint + fee;
+        // 检查白名单、铸造速率限制、大额铸造冷却期和价格偏离熔断
+        self._check_permissioned_caller(user)?;
+        // 铸造额度和冷却期记在债务所在的仓位上，与偿还时恢复额度的仓位一致
+        self._record_mint(user, debt_added)?;
+        self._check_mint_cooldown(user, debt_added)?;
+        self._check_price_breakers_of(user, bucket)?;
+        self._add_debt(user, debt_added, bucket)?;
+        // 获取用户已铸造的稳定币数量
+        let user_dsc_minted = self.dsc_minted.get(user);
+        // 更新用户已铸造的稳定币数量
+        self.dsc_minted
+            .setter(user)
+            .set(user_dsc_minted + debt_added);
+        // 更新稳定币总债务
+        self.total_dsc_minted
+            .set(self.total_dsc_minted.get() + debt_added);
+        self._sync_active_position(user);
+        // 检查健康因子是否达到借款健康因子
+        self._revert_if_borrow_health_factor_is_broken(user)?;
+        // 铸造稳定币
+        let dsc = IDecentralizedStableCoin::new(self.dsc.get());
+        dsc.mint(Call::new(), to, amount_dsc_to_mint)
+            .map_err(|_| DSCEngineError::MintFailed(MintFailed {}))?;
+        evm::log(DscMinted {
+            user,
+            to,
+            amount: amount_dsc_to_mint,
+            totalDebt: self._user_debt(user),
+            actionNonce: self._next_action_nonce(user),
+        });
+        // 铸造手续费铸造给国库，推荐人份额留在引擎中记为返佣
+        if fee > U256::ZERO {
+            self._distribute_mint_fee(user, fee)?;
+            evm::log(FeeCharged {
+                user,
+                token: self.dsc.get(),
+                amount: fee,
+            });
+        }
+        Ok(())
+    }
+
     // 销毁稳定币的内部实现
-    fn _burn_dsc(&mut self, amount_dsc_to_burn: U256, on_behalf_of: Address, dsc_from: Address) {
+    fn _burn_dsc(
+        &mut self,
+        amount_dsc_to_burn: U256,
+        on_behalf_of: Address,
+        dsc_from: Address,
+        bucket: Option<Address>,
+    ) -> Result<(), DSCEngineError> {
+        let principal_paid = self._reduce_debt(amount_dsc_to_burn, on_behalf_of, bucket)?;
+        // 偿还额度记给债务减少的仓位，而不是支付 DSC 的清算人、稳定池或赎回者
+        self._record_repay(on_behalf_of, principal_paid);
+        let dsc = IDecentralizedStableCoin::new(self.dsc.get());
+        // 从用户地址转账到合约地址，调用失败或返回 false 都视为转账失败
+        let transferred = dsc.transfer_from(
+            Call::new(),
+            dsc_from,
+            contract::address(),
+            amount_dsc_to_burn,
+        );
+        if !matches!(transferred, Ok(true)) {
+            return Err(DSCEngineError::TransferFailed(TransferFailed {}));
+        }
+        self._burn_repaid_dsc(amount_dsc_to_burn, principal_paid, on_behalf_of, dsc_from)
+    }
+
+    // 处理合约已收到的偿还 DSC：只销毁本金部分，利息部分留在合约中计入盈余
+    fn _burn_repaid_dsc(
+        &mut self,
+        amount: U256,
+        principal_paid: U256,
+        on_behalf_of: Address,
+        dsc_from: Address,
+    ) -> Result<(), DSCEngineError> {
+        IDecentralizedStableCoin::new(self.dsc.get())
+            .burn(Call::new(), principal_paid)
+            .map_err(|_| DSCEngineError::BurnFailed(BurnFailed {}))?;
+        self._add_interest_revenue(on_behalf_of, amount - principal_paid);
+        evm::log(DscBurned {
+            onBehalfOf: on_behalf_of,
+            from: dsc_from,
+            amount,
+            totalDebt: self._user_debt(on_behalf_of),
+            actionNonce: self._next_action_nonce(on_behalf_of),
+        });
+        Ok(())
+    }
+
+    // 减少用户债务的记账部分（指定分桶时只减少该分桶），返回其中偿还的本金
+    fn _reduce_debt(
+        &mut self,
+        amount: U256,
+        on_behalf_of: Address,
+        bucket: Option<Address>,
+    ) -> Result<U256, DSCEngineError> {
+        // 偿还数量不能超过（分桶）债务
+        let debt = match bucket {
+            Some(token) => self._bucket_debt(on_behalf_of, token),
+            None => self._user_debt(on_behalf_of),
+        };
+        if amount > debt {
+            return Err(DSCEngineError::RepayExceedsDebt(RepayExceedsDebt {
+                _0: debt,
+            }));
+        }
+        // 减少分桶债务，先偿还利息部分
+        let interest_paid = self._remove_debt(on_behalf_of, amount, bucket);
+        let principal_paid = amount - interest_paid;
         // 获取用户已铸造的稳定币数量
         let user_dsc_minted = self.dsc_minted.getter(on_behalf_of);
         let value = user_dsc_minted.get();
         // 更新用户已铸造的稳定币数量
         self.dsc_minted
             .setter(on_behalf_of)
-            .set(value - amount_dsc_to_burn);
-        // 从用户地址转账到合约地址
-        if !self
-            .dsc
-            .transfer_from(dsc_from, contract::address(), amount_dsc_to_burn)
-            .is_err()
-        {
-            panic!("TransferFailed");
-        }
-        // 销毁稳定币
-        let _ = self.dsc.burn(amount_dsc_to_burn);
+            .set(value.saturating_sub(principal_paid));
+        // 更新稳定币总债务
+        self.total_dsc_minted
+            .set(self.total_dsc_minted.get().saturating_sub(principal_paid));
+        self._sync_active_position(on_behalf_of);
+        Ok(principal_paid)
     }
 
     // 赎回抵押品的内部实现
@@ -342,39 +3966,73 @@ impl DSCEngine {
         from: Address,                     // 赎回者地址
         to: Address,                       // 接收者地址
     ) -> Result<(), DSCEngineError> {
+        // 换算为记账单位（份额模式下向上取整，避免少扣份额）
+        let total_assets = self.total_deposited.get(token_collateral_address);
+        let debited = self._amount_to_shares(
+            token_collateral_address,
+            amount_collateral,
+            total_assets,
+            true,
+        );
         // 获取用户抵押品存款映射
         let user_collateral_mapping = self.collateral_deposited.getter(from);
         // 获取用户特定代币的抵押品数量
         let user_collateral = user_collateral_mapping.getter(token_collateral_address);
         let value = user_collateral.get();
+        // 赎回数量不能超过已存入的抵押品
+        if debited > value {
+            return Err(DSCEngineError::InsufficientCollateral(
+                InsufficientCollateral {
+                    _0: self._shares_to_amount(token_collateral_address, value),
+                },
+            ));
+        }
         // 更新用户抵押品存款映射
         self.collateral_deposited
             .setter(from)
             .setter(token_collateral_address)
-            .set(value - amount_collateral);
-        // 记录抵押品赎回事件
+            .set(value - debited);
+        // 更新抵押品总量
+        let total_collateral = self
+            .total_collateral_deposited
+            .get(token_collateral_address);
+        self.total_collateral_deposited
+            .setter(token_collateral_address)
+            .set(total_collateral - debited);
+        self.total_deposited
+            .setter(token_collateral_address)
+            .set(total_assets.saturating_sub(amount_collateral));
+        self._sync_active_position(from);
+        // 远程抵押品通知所在链释放给接收者
+        if self._is_remote_collateral(token_collateral_address) {
+            self._release_remote_collateral(token_collateral_address, to, amount_collateral)?;
+        } else {
+            // 获取代币实例
+            let token = IERC20::new(token_collateral_address);
+            // 从合约地址转账到接收者地址
+            if token.transfer(Call::new(), to, amount_collateral).is_err() {
+                return Err(DSCEngineError::TransferFailed(TransferFailed {}));
+            }
+        }
+        // 记录抵押品赎回事件：扣减存款总量后再计算余额，份额模式下才能按赎回后的存款总量折算
         evm::log(CollateralRedeemed {
             redeemedFrom: from,
             redeemedTo: to,
             amount: amount_collateral,
             token: token_collateral_address,
+            collateralBalance: self._collateral_amount_of(from, token_collateral_address),
+            totalDebt: self._user_debt(from),
+            actionNonce: self._next_action_nonce(from),
         });
-        // 获取代币实例
-        let token = IERC20::new(token_collateral_address);
-        // 从合约地址转账到接收者地址
-        if token.transfer(Call::new(), to, amount_collateral).is_err() {
-            Err(DSCEngineError::TransferFailed(TransferFailed {}))
-        } else {
-            Ok(())
-        }
+        Ok(())
     }
 
     // 检查健康因子是否正常
     fn _revert_if_health_factor_is_broken(&self, user: Address) -> Result<(), DSCEngineError> {
         // 获取用户健康因子
-        let user_health_factor = self._health_factor(user);
+        let user_health_factor = self._health_factor(user)?;
         // 检查健康因子是否低于最小值
-        if user_health_factor < self.min_health_factor.get() {
+        if user_health_factor < self._min_health_factor() {
             return Err(DSCEngineError::BreaksHealthFactor(BreaksHealthFactor {
                 _0: user_health_factor,
             }));
@@ -382,150 +4040,213 @@ impl DSCEngine {
         Ok(())
     }
 
-    // 获取用户健康因子
-    fn _health_factor(&self, user: Address) -> U256 {
-        // 获取用户账户信息
-        let (total_dsc_minted, collateral_value_in_usd) = self._get_account_info(user);
-        // 计算健康因子
-        self._calculate_health_factor(total_dsc_minted, collateral_value_in_usd)
+    // 获取用户健康因子，有债务时抵押品价格不可用则回滚
+    fn _health_factor(&self, user: Address) -> Result<U256, DSCEngineError> {
+        // 逐仓模式下取各分桶健康因子的最小值
+        if self.isolated_margin.get(user) {
+            return self._isolated_health_factor(user);
+        }
+        // 全部债务（含合成资产债务），没有债务时无需查询价格
+        let total_debt = self._account_debt_value(user);
+        if total_debt == U256::ZERO {
+            return Ok(U256::MAX);
+        }
+        let collateral_value_in_usd = self._account_collateral_value(user)?;
+        // 按用户适用的清算阈值计算健康因子
+        Ok(self._calculate_health_factor_with_threshold(
+            total_debt,
+            collateral_value_in_usd,
+            self._liquidation_threshold_of(user),
+        ))
     }
 
-    // 计算健康因子
+    // 按默认清算阈值计算健康因子
     fn _calculate_health_factor(
         &self,
         total_dsc_minted: U256,
         collateral_value_in_usd: U256,
+    ) -> U256 {
+        self._calculate_health_factor_with_threshold(
+            total_dsc_minted,
+            collateral_value_in_usd,
+            self._liquidation_threshold(),
+        )
+    }
+
+    // 按指定清算阈值计算健康因子
+    fn _calculate_health_factor_with_threshold(
+        &self,
+        total_dsc_minted: U256,
+        collateral_value_in_usd: U256,
+        liquidation_threshold: U256,
     ) -> U256 {
         // 检查稳定币铸造数量是否为零
         if total_dsc_minted == U256::ZERO {
             return U256::MAX;
         }
         // 计算抵押品调整值
-        let collateral_adjusted_for_threshold = (collateral_value_in_usd
-            * self.liquidation_threshold.get())
-            / self.liquidation_precision.get();
+        let collateral_adjusted_for_threshold = mul_div(
+            collateral_value_in_usd,
+            liquidation_threshold,
+            LIQUIDATION_PRECISION,
+            Rounding::Down,
+        );
         // 计算健康因子
-        (collateral_adjusted_for_threshold * self.precision.get()) / total_dsc_minted
+        wad_div(collateral_adjusted_for_threshold, total_dsc_minted)
     }
 
-    // 获取用户账户信息
-    fn _get_account_info(&self, user: Address) -> (U256, U256) {
-        // 获取用户已铸造的稳定币数量
-        let total_dsc_minted = self.dsc_minted.get(user);
+    // 获取用户账户信息，抵押品价格不可用时回滚
+    fn _get_account_info(&self, user: Address) -> Result<(U256, U256), DSCEngineError> {
+        // 获取用户当前债务（含稳定费利息）
+        let total_dsc_minted = self._user_debt(user);
         // 获取用户账户抵押品总价值
-        let collateral_value_in_usd = self.get_account_collateral_value_in_usd(user);
-        (total_dsc_minted, collateral_value_in_usd)
-    }
-
-    /* pub fn calculate_health_factor(
-        &self,
-        total_dsc_minted: U256,
-        collateral_value_in_usd: U256,
-    ) -> U256 {
-        self._calculate_health_factor(total_dsc_minted, collateral_value_in_usd)
-    } */
-
-    pub fn get_token_amount_from_usd(&self, token: Address, usd_amount_in_wei: U256) -> U256 {
-        // 获取价格预言机实例
-        let price_feed = IAggregatorV3::new(self.price_feeds.get(token));
-        // 获取价格预言机最新数据
-        let (_, price, _, _, _) = match price_feed.latest_round_data(Call::new()) {
-            Ok(data) => data,
-            Err(_) => return U256::ZERO,
-        };
-        // 将价格转换为 U256 类型
-        let price_u256 = match U256::try_from(price) {
-            Ok(price) => price,
-            Err(_) => return U256::ZERO,
-        };
-        // 计算抵押品金额
-        (usd_amount_in_wei * self.precision.get())
-            / (price_u256 * self.additional_feed_precision.get())
+        let collateral_value_in_usd = self._account_collateral_value(user)?;
+        Ok((total_dsc_minted, collateral_value_in_usd))
     }
 
-    pub fn get_account_collateral_value_in_usd(&self, user: Address) -> U256 {
-        // 初始化抵押品总价值
+    // 用户账户抵押品总价值，任一已存入的抵押品价格不可用时回滚
+    pub(crate) fn _account_collateral_value(&self, user: Address) -> Result<U256, DSCEngineError> {
         let mut total_collateral_value_in_usd = U256::ZERO;
-        // 遍历所有抵押品
         for i in 0..self.collateral_tokens.len() {
-            let token_option = self.collateral_tokens.get(i);
-            match token_option {
-                Some(token) => {
-                    // 获取用户特定代币的抵押品数量
-                    let amount = self.collateral_deposited.getter(user).get(token);
-                    // 计算抵押品金额
-                    total_collateral_value_in_usd += self.get_usd_value(token, amount);
+            if let Some(token) = self.collateral_tokens.get(i) {
+                let amount = self._collateral_amount_of(user, token);
+                // 未存入的抵押品无需查询价格
+                if amount == U256::ZERO {
+                    continue;
                 }
-                None => (),
+                total_collateral_value_in_usd += self._usd_value(token, amount)?;
             }
         }
-        total_collateral_value_in_usd
+        Ok(total_collateral_value_in_usd)
     }
 
-    // 获取抵押品金额
-    pub fn get_usd_value(&self, token: Address, amount: U256) -> U256 {
-        // 获取价格预言机实例
-        let price_feed = IAggregatorV3::new(self.price_feeds.get(token));
-        // 获取价格预言机最新数据
-        let (_, price, _, _, _) = match price_feed.latest_round_data(Call::new()) {
-            Ok(data) => data,
-            Err(_) => return U256::ZERO,
-        };
-        // 将价格转换为 U256 类型
-        let price_u256 = match U256::try_from(price) {
-            Ok(price) => price,
-            Err(_) => return U256::ZERO,
-        };
-        // 计算抵押品金额
-        ((price_u256 * self.additional_feed_precision.get()) * amount) / self.precision.get()
+    // 抵押品的估值（以锚定货币计），价格不可用时回滚
+    pub(crate) fn _usd_value(&self, token: Address, amount: U256) -> Result<U256, DSCEngineError> {
+        let price_u256 = self._collateral_price(token)?;
+        Ok(mul_div(
+            price_u256 * ADDITIONAL_FEED_PRECISION,
+            amount,
+            self._token_unit(token),
+            Rounding::Down,
+        ))
     }
 
-    /* pub fn get_account_info(&self, user: Address) -> (U256, U256) {
-        self._get_account_info(user)
-    } */
+    // 按估值折算的抵押品数量，价格不可用时回滚
+    pub(crate) fn _token_amount_from_usd(
+        &self,
+        token: Address,
+        usd_amount_in_wei: U256,
+    ) -> Result<U256, DSCEngineError> {
+        let price_u256 = self._collateral_price(token)?;
+        Ok(mul_div(
+            usd_amount_in_wei,
+            self._token_unit(token),
+            price_u256 * ADDITIONAL_FEED_PRECISION,
+            Rounding::Down,
+        ))
+    }
 
-    pub fn get_collateral_tokens(&self) -> Vec<Address> {
-        // 初始化抵押品列表
-        let mut tokens = Vec::new();
-        // 遍历所有抵押品
-        for i in 0..self.collateral_tokens.len() {
-            tokens.push(self.collateral_tokens.get(i));
+    // 获取以锚定货币计的抵押品价格（预言机精度）
+    fn _collateral_price(&self, token: Address) -> Result<U256, DSCEngineError> {
+        let usd_price = self._collateral_usd_price(token)?;
+        self._to_peg_price(usd_price)
+    }
+
+    // 获取抵押品的美元价格（预言机精度），ERC-4626 份额按底层资产价格折算
+    fn _collateral_usd_price(&self, token: Address) -> Result<U256, DSCEngineError> {
+        // 守护者设置的应急价格生效期间替代预言机报价
+        let mut price_u256 = match self._emergency_price(token) {
+            Some(price) => price,
+            None => {
+                // 按抵押品配置的价格来源读取价格，负数或过大的报价返回对应错误
+                self._source_price(token)?
+            }
+        };
+        // 组合汇率合约读数（如 wstETH/stETH × stETH/USD）
+        let rate_provider = self.rate_providers.get(token);
+        if !rate_provider.is_zero() {
+            let rate = IRateProvider::new(rate_provider)
+                .get_rate(Call::new())
+                .map_err(|_| DSCEngineError::PriceFeedError(PriceFeedError {}))?;
+            price_u256 = wad_mul(price_u256, rate);
         }
-        // 过滤掉 None 值
-        tokens
-            .into_iter()
-            .filter_map(|opt_address| opt_address)
-            .collect()
+        if !self.erc4626_collateral.get(token) {
+            return Ok(price_u256);
+        }
+        // 一份完整金库份额可兑换的底层资产数量
+        let assets_per_share = IERC4626::new(token)
+            .convert_to_assets(Call::new(), self._token_unit(token))
+            .map_err(|_| DSCEngineError::PriceFeedError(PriceFeedError {}))?;
+        let asset_unit =
+            U256::from(10).pow(U256::from(self.vault_asset_decimals.get(token).to::<u8>()));
+        Ok(mul_div(
+            price_u256,
+            assets_per_share,
+            asset_unit,
+            Rounding::Down,
+        ))
     }
 
-    pub fn get_collateral_balance_of_user(&self, user: Address, token: Address) -> U256 {
-        // 获取用户特定代币的抵押品数量
-        self.collateral_deposited.getter(user).get(token)
+    // 合约当前持有的代币数量
+    fn _engine_balance(&self, token: Address) -> U256 {
+        IERC20::new(token)
+            .balance_of(Call::new(), contract::address())
+            .unwrap_or(U256::ZERO)
     }
 
-    pub fn get_additional_feed_precision(&self) -> U256 {
-        // 获取精度调整因子
-        self.additional_feed_precision.get()
+    // 用户某抵押品的实际代币数量（份额模式下按份额换算）
+    fn _collateral_amount_of(&self, user: Address, token: Address) -> U256 {
+        let shares = self.collateral_deposited.getter(user).get(token);
+        self._shares_to_amount(token, shares)
     }
 
-    pub fn get_precision(&self) -> U256 {
-        // 获取基础精度
-        self.precision.get()
+    // 记账单位换算为代币数量，非份额模式下两者相同
+    fn _shares_to_amount(&self, token: Address, shares: U256) -> U256 {
+        if shares == U256::ZERO || !self.collateral_shares_mode.get(token) {
+            return shares;
+        }
+        let total_shares = self.total_collateral_deposited.get(token);
+        if total_shares == U256::ZERO {
+            return U256::ZERO;
+        }
+        (shares * self.total_deposited.get(token)) / total_shares
     }
 
-    pub fn get_health_factor(&self, user: Address) -> U256 {
-        // 获取用户健康因子
-        self._health_factor(user)
+    // 代币数量换算为记账单位，total_assets 为换算时的存款总量
+    fn _amount_to_shares(
+        &self,
+        token: Address,
+        amount: U256,
+        total_assets: U256,
+        round_up: bool,
+    ) -> U256 {
+        if !self.collateral_shares_mode.get(token) {
+            return amount;
+        }
+        let total_shares = self.total_collateral_deposited.get(token);
+        if total_shares == U256::ZERO || total_assets == U256::ZERO {
+            return amount;
+        }
+        let shares = (amount * total_shares) / total_assets;
+        if round_up && shares * total_assets < amount * total_shares {
+            shares + U256::from(1)
+        } else {
+            shares
+        }
     }
 
-    pub fn get_liquidation_bonus(&self) -> U256 {
-        // 获取清算奖励
-        self.liquidation_bonus.get()
+    // 一个完整代币对应的最小单位数量（10^decimals）
+    fn _token_unit(&self, token: Address) -> U256 {
+        U256::from(10).pow(U256::from(self.collateral_decimals.get(token).to::<u8>()))
     }
 
-    pub fn get_collateral_token_price_feed(&self, token: Address) -> Address {
-        // 获取价格预言机地址
-        self.price_feeds.get(token)
+    // 将有符号变化量应用到数量上，减少时最多减到零
+    fn _apply_delta(value: U256, delta: I256) -> U256 {
+        if delta.is_negative() {
+            value.saturating_sub(delta.unsigned_abs())
+        } else {
+            value + delta.unsigned_abs()
+        }
     }
 }
 
@@ -534,3 +4255,16 @@ impl MethodError for DSCEngineError {
         From::from(self)
     }
 }
+
+// 金库 NFT 错误逐个展开为引擎错误，导出的 ABI 中才能列出具体的错误定义
+impl From<Erc721Error> for DSCEngineError {
+    fn from(e: Erc721Error) -> Self {
+        match e {
+            Erc721Error::InvalidTokenId(e) => Self::InvalidTokenId(e),
+            Erc721Error::IncorrectOwner(e) => Self::IncorrectOwner(e),
+            Erc721Error::NotApproved(e) => Self::NotApproved(e),
+            Erc721Error::TransferToZero(e) => Self::TransferToZero(e),
+            Erc721Error::ReceiverRefused(e) => Self::ReceiverRefused(e),
+        }
+    }
+}
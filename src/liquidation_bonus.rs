@@ -0,0 +1,46 @@
+//! 按健康因子缩放的清算奖励
+//!
+//! 健康因子刚跌破最小健康因子时只给最低清算奖励，健康因子越低奖励越高，
+//! 降到满额奖励健康因子及以下时给最高清算奖励（抵押品风险等级的清算奖励），中间线性插值。
+//! 轻微违约的仓位不会被过度罚没，严重违约的仓位对清算人更有吸引力。
+//! 奖励以清算精度表示，插值结果向下取整；满额奖励健康因子为零时不缩放，始终给最高奖励。
+
+use stylus_sdk::alloy_primitives::{Address, U256};
+
+use crate::math::{mul_div, Rounding};
+use crate::{DSCEngine, DSCEngineError};
+
+impl DSCEngine {
+    // 健康因子为 health_factor 时清算 collateral 的清算奖励（清算精度）
+    pub(crate) fn _liquidation_bonus_at(&self, collateral: Address, health_factor: U256) -> U256 {
+        let max_bonus = self._collateral_max_bonus(collateral);
+        let full_bonus_health_factor = self.full_bonus_health_factor.get();
+        if full_bonus_health_factor == U256::ZERO || health_factor <= full_bonus_health_factor {
+            return max_bonus;
+        }
+        let min_bonus = self.min_liquidation_bonus.get().min(max_bonus);
+        let min_health_factor = self._min_health_factor();
+        if health_factor >= min_health_factor {
+            return min_bonus;
+        }
+        min_bonus
+            + mul_div(
+                max_bonus - min_bonus,
+                min_health_factor - health_factor,
+                min_health_factor - full_bonus_health_factor,
+                Rounding::Down,
+            )
+    }
+
+    // 使用指定抵押品清算用户时当前适用的清算奖励
+    pub(crate) fn _liquidation_bonus_for(
+        &self,
+        user: Address,
+        collateral: Address,
+    ) -> Result<U256, DSCEngineError> {
+        Ok(self._liquidation_bonus_at(
+            collateral,
+            self._liquidation_health_factor(user, collateral)?,
+        ))
+    }
+}
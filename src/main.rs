@@ -1,6 +1,6 @@
-#![cfg_attr(not(feature = "export-abi"), no_main)]
+#![cfg_attr(not(any(feature = "export-abi", test)), no_main)]
 
 #[cfg(feature = "export-abi")]
 fn main() {
-    stylus_hello_world::print_abi("MIT-OR-APACHE-2.0", "pragma solidity ^0.8.23;");
+    stylus_defi_stablecoin::print_abi("MIT-OR-APACHE-2.0", "pragma solidity ^0.8.23;");
 }
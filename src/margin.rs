@@ -0,0 +1,88 @@
+//! 全仓与逐仓模式
+//!
+//! 默认的全仓模式下，仓位的全部抵押品共同担保全部债务。逐仓模式下，每种抵押品只担保
+//! 自己分桶中的债务：每个分桶有独立的健康因子，仓位健康因子取各分桶的最小值，
+//! 清算、拍卖和赎回只作用于所选抵押品的分桶。模式只能在仓位为空时选择。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    evm,
+};
+
+use crate::{DSCEngine, DSCEngineError, MarginModeSet, PositionNotEmpty};
+
+impl DSCEngine {
+    // 逐仓模式下返回抵押品对应的债务分桶，全仓模式下不限定分桶
+    pub(crate) fn _debt_bucket(&self, user: Address, collateral: Address) -> Option<Address> {
+        if self.isolated_margin.get(user) {
+            Some(collateral)
+        } else {
+            None
+        }
+    }
+
+    // 分桶健康因子：该抵押品的价值相对于分桶债务
+    pub(crate) fn _bucket_health_factor(
+        &self,
+        user: Address,
+        token: Address,
+    ) -> Result<U256, DSCEngineError> {
+        let debt = self._bucket_debt(user, token);
+        // 没有债务的分桶无需查询价格
+        if debt == U256::ZERO {
+            return Ok(U256::MAX);
+        }
+        let collateral_value = self._usd_value(token, self._collateral_amount_of(user, token))?;
+        let threshold = self
+            ._emode_threshold(user)
+            .unwrap_or_else(|| self._collateral_threshold(token));
+        Ok(self._calculate_health_factor_with_threshold(debt, collateral_value, threshold))
+    }
+
+    // 逐仓仓位的健康因子：各分桶健康因子的最小值
+    pub(crate) fn _isolated_health_factor(&self, user: Address) -> Result<U256, DSCEngineError> {
+        let mut health_factor = U256::MAX;
+        for i in 0..self.collateral_tokens.len() {
+            if let Some(token) = self.collateral_tokens.get(i) {
+                health_factor = health_factor.min(self._bucket_health_factor(user, token)?);
+            }
+        }
+        Ok(health_factor)
+    }
+
+    // 清算指定抵押品时使用的健康因子，逐仓模式下为该抵押品分桶的健康因子
+    pub(crate) fn _liquidation_health_factor(
+        &self,
+        user: Address,
+        collateral: Address,
+    ) -> Result<U256, DSCEngineError> {
+        if self.isolated_margin.get(user) {
+            self._bucket_health_factor(user, collateral)
+        } else {
+            self._health_factor(user)
+        }
+    }
+
+    // 清算指定抵押品时可偿还的债务，逐仓模式下为该抵押品分桶的债务
+    pub(crate) fn _liquidatable_debt(&self, user: Address, collateral: Address) -> U256 {
+        if self.isolated_margin.get(user) {
+            self._bucket_debt(user, collateral)
+        } else {
+            self._user_debt(user)
+        }
+    }
+
+    // 为空仓位选择全仓或逐仓模式
+    pub(crate) fn _set_margin_mode(
+        &mut self,
+        user: Address,
+        isolated: bool,
+    ) -> Result<(), DSCEngineError> {
+        if self.has_active_position.get(user) {
+            return Err(DSCEngineError::PositionNotEmpty(PositionNotEmpty {}));
+        }
+        self.isolated_margin.setter(user).set(isolated);
+        evm::log(MarginModeSet { user, isolated });
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+//! 定点数运算：WAD（1e18）和 RAY（1e27）精度的乘除
+//!
+//! 所有乘除都经过 `mul_div`：先计算 512 位的完整乘积再相除，中间结果不会溢出，
+//! 取整方向由调用方显式指定。估值、健康因子和可铸造额度一律向下取整，对协议保守；
+//! 债务按份额折算时向上取整，避免债务被低估。结果超出 256 位时交易回滚。
+
+use stylus_sdk::alloy_primitives::{U256, U512};
+
+// WAD 精度：1e18，与基础精度相同
+pub(crate) const WAD: U256 = U256::from_limbs([1_000_000_000_000_000_000, 0, 0, 0]);
+// RAY 精度：1e27
+pub(crate) const RAY: U256 = U256::from_limbs([11_515_845_246_265_065_472, 54_210_108, 0, 0]);
+
+// 取整方向
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum Rounding {
+    Down, // 向下取整
+    Up,   // 向上取整
+}
+
+// 全精度计算 a × b / denominator，按指定方向取整；除数为零或结果超出 256 位时回滚
+pub(crate) fn mul_div(a: U256, b: U256, denominator: U256, rounding: Rounding) -> U256 {
+    let product: U512 = a.widening_mul(b);
+    let (quotient, remainder) = product.div_rem(U512::from(denominator));
+    let quotient = U256::from(quotient);
+    if rounding == Rounding::Up && remainder > U512::ZERO {
+        quotient + U256::from(1)
+    } else {
+        quotient
+    }
+}
+
+// WAD 精度乘法，向下取整
+pub(crate) fn wad_mul(a: U256, b: U256) -> U256 {
+    mul_div(a, b, WAD, Rounding::Down)
+}
+
+// WAD 精度除法，向下取整
+pub(crate) fn wad_div(a: U256, b: U256) -> U256 {
+    mul_div(a, WAD, b, Rounding::Down)
+}
+
+// RAY 精度乘法，向下取整
+pub(crate) fn ray_mul(a: U256, b: U256) -> U256 {
+    mul_div(a, b, RAY, Rounding::Down)
+}
@@ -0,0 +1,86 @@
+//! ERC-2771 元交易：由受信任的转发合约代用户支付 gas
+//!
+//! 受信任的转发合约在调用数据末尾附加原始调用者的 20 字节地址。引擎入口识别到来自转发合约的调用时，
+//! 截去附加的地址并记为本次调用的实际发起者，其余调用按原样路由。
+//! 引擎中按调用者记账的操作都通过 `msg_sender()` 取得实际发起者，用户只持有 DSC 或抵押品、
+//! 没有 ETH 时也能由中继代付 gas 完成存入和偿还。
+
+use core::cell::Cell;
+use stylus_sdk::{alloy_primitives::Address, msg};
+
+std::thread_local! {
+    // 本次调用由转发合约附加的实际发起者
+    static FORWARDED_SENDER: Cell<Option<Address>> = const { Cell::new(None) };
+}
+
+// 本次调用的实际发起者：经受信任的转发合约转发时为附加的地址，否则为直接调用者
+pub(crate) fn msg_sender() -> Address {
+    FORWARDED_SENDER
+        .with(|sender| sender.get())
+        .unwrap_or_else(msg::sender)
+}
+
+// 测试中模拟本次调用的实际发起者
+#[cfg(test)]
+pub(crate) fn set_msg_sender(sender: Address) {
+    FORWARDED_SENDER.with(|forwarded| forwarded.set(Some(sender)));
+}
+
+#[cfg(not(any(
+    feature = "dsc",
+    feature = "dsc-staking",
+    feature = "mock-erc20",
+    feature = "mock-v3-aggregator",
+    feature = "oft-adapter",
+    feature = "savings-vault",
+    feature = "stability-pool"
+)))]
+unsafe impl stylus_sdk::storage::TopLevelStorage for crate::DSCEngine {}
+
+#[cfg(not(any(
+    feature = "dsc",
+    feature = "dsc-staking",
+    feature = "mock-erc20",
+    feature = "mock-v3-aggregator",
+    feature = "oft-adapter",
+    feature = "savings-vault",
+    feature = "stability-pool"
+)))]
+const _: () = {
+    crate::DSCEngine::__stylus_assert_overrides();
+};
+
+// 引擎合约入口：识别转发的调用并截去附加的发起者地址后路由
+#[cfg(not(any(
+    feature = "dsc",
+    feature = "dsc-staking",
+    feature = "mock-erc20",
+    feature = "mock-v3-aggregator",
+    feature = "oft-adapter",
+    feature = "savings-vault",
+    feature = "stability-pool"
+)))]
+#[stylus_sdk::prelude::entrypoint]
+fn engine_entrypoint(input: alloc::vec::Vec<u8>) -> stylus_sdk::ArbResult {
+    use alloc::vec::Vec;
+    use stylus_sdk::{abi::Router, alloy_primitives::U256, storage::StorageType};
+
+    use crate::DSCEngine;
+
+    if input.len() < 4 {
+        return Err(Vec::new());
+    }
+    let mut storage = unsafe { <DSCEngine as StorageType>::new(U256::ZERO, 0) };
+    let mut calldata = input.as_slice();
+    let forwarder = storage.trusted_forwarder.get();
+    if !forwarder.is_zero() && msg::sender() == forwarder && calldata.len() >= 24 {
+        let (data, sender) = calldata.split_at(calldata.len() - 20);
+        FORWARDED_SENDER.with(|forwarded| forwarded.set(Some(Address::from_slice(sender))));
+        calldata = data;
+    }
+    let selector = u32::from_be_bytes([calldata[0], calldata[1], calldata[2], calldata[3]]);
+    match <DSCEngine as Router<_>>::route(&mut storage, selector, &calldata[4..]) {
+        Some(result) => result,
+        None => Err(Vec::new()),
+    }
+}
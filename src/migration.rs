@@ -0,0 +1,117 @@
+//! 仓位迁移：把仓位一次性迁移到新部署的引擎
+//!
+//! 用户调用 `migrate_to` 后，旧引擎把指定抵押品转给新引擎并移除用户的全部债务记账，
+//! 再调用新引擎的 `receiveMigratedPosition` 接口由其记入抵押品和同等数量的债务。
+//! 流通中的 DSC 不变，新引擎需已接管 DSC 的铸造权。迁移目标和来源都需由治理登记。
+
+use alloc::vec::Vec;
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    call::Call,
+    evm, msg,
+    prelude::*,
+};
+
+use crate::{
+    meta_tx::msg_sender, AuctionActive, DSCEngine, DSCEngineError, MigrationFailed,
+    MigrationNotAllowed, PositionMigrated, PositionReceived,
+};
+
+sol_interface! {
+    // 新引擎的迁移接口：接收旧引擎转来的抵押品和债务
+    interface IMigrationReceiver {
+        function receiveMigratedPosition(
+            address user,
+            address[] calldata tokens,
+            uint256[] calldata amounts,
+            uint256 debt
+        ) external returns (bool);
+    }
+}
+
+impl DSCEngine {
+    // 把调用者的仓位迁移到新引擎，返回迁移的债务（调用前需先计提）
+    pub(crate) fn _migrate_to(
+        &mut self,
+        new_engine: Address,
+        tokens: Vec<Address>,
+    ) -> Result<U256, DSCEngineError> {
+        if !self.migration_targets.get(new_engine) {
+            return Err(DSCEngineError::MigrationNotAllowed(MigrationNotAllowed {}));
+        }
+        let user = msg_sender();
+        if self.active_auction.get(user) != U256::ZERO {
+            return Err(DSCEngineError::AuctionActive(AuctionActive {}));
+        }
+        // 合成资产债务不随仓位迁移
+        self._require_no_synth_debt(user)?;
+        // 转出抵押品到新引擎
+        let mut amounts = Vec::with_capacity(tokens.len());
+        for &token in tokens.iter() {
+            self.is_allowed_token(token)?;
+            self._require_local_collateral(token)?;
+            let amount = self._collateral_amount_of(user, token);
+            if amount > U256::ZERO {
+                self._redeem_collateral(token, amount, user, new_engine)?;
+            }
+            amounts.push(amount);
+        }
+        // 移除全部债务记账，由新引擎按同等数量记入
+        let debt = self._user_debt(user);
+        if debt > U256::ZERO {
+            self._reduce_debt(debt, user, None)?;
+        }
+        let accepted = IMigrationReceiver::new(new_engine)
+            .receive_migrated_position(Call::new(), user, tokens, amounts, debt)
+            .map_err(|_| DSCEngineError::MigrationFailed(MigrationFailed {}))?;
+        if !accepted {
+            return Err(DSCEngineError::MigrationFailed(MigrationFailed {}));
+        }
+        evm::log(PositionMigrated {
+            user,
+            newEngine: new_engine,
+            debt,
+            totalDebt: self._user_debt(user),
+            actionNonce: self._next_action_nonce(user),
+        });
+        Ok(debt)
+    }
+
+    // 接收旧引擎迁移来的仓位，抵押品已由旧引擎转入（调用前需先计提）
+    pub(crate) fn _receive_migrated_position(
+        &mut self,
+        user: Address,
+        tokens: Vec<Address>,
+        amounts: Vec<U256>,
+        debt: U256,
+    ) -> Result<(), DSCEngineError> {
+        let old_engine = msg::sender();
+        if !self.migration_sources.get(old_engine) || tokens.len() != amounts.len() {
+            return Err(DSCEngineError::MigrationNotAllowed(MigrationNotAllowed {}));
+        }
+        for (token, amount) in tokens.into_iter().zip(amounts) {
+            if amount == U256::ZERO {
+                continue;
+            }
+            self.is_allowed_token(token)?;
+            self._credit_collateral(user, token, amount)?;
+        }
+        if debt > U256::ZERO {
+            self._add_debt(user, debt, None)?;
+            let user_dsc_minted = self.dsc_minted.get(user);
+            self.dsc_minted.setter(user).set(user_dsc_minted + debt);
+            self.total_dsc_minted
+                .set(self.total_dsc_minted.get() + debt);
+            self._sync_active_position(user);
+        }
+        self._revert_if_health_factor_is_broken(user)?;
+        evm::log(PositionReceived {
+            user,
+            oldEngine: old_engine,
+            debt,
+            totalDebt: self._user_debt(user),
+            actionNonce: self._next_action_nonce(user),
+        });
+        Ok(())
+    }
+}
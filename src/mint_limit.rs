@@ -0,0 +1,119 @@
+//! 铸造速率限制：按时间窗口限制净铸造的 DSC
+//!
+//! 时间按窗口长度划分为连续的周期，每个周期内全协议和每个仓位的净铸造量（铸造减去偿还的本金）
+//! 分别不能超过上限，防止预言机被操纵时在短时间内铸造大量 DSC。窗口长度为零时不限制，
+//! 上限为零表示该项不限制。偿还的本金只从债务被偿还的仓位的净铸造量中扣除，
+//! 代为偿还（清算、稳定池、赎回）不会增加支付者的铸造额度。
+//!
+//! 铸造和偿还都记在债务所在的仓位上（金库铸造记在金库仓位而不是 NFT 持有者名下），
+//! 偿还后同一仓位的额度随之恢复。
+//!
+//! 此外，单笔铸造达到大额阈值后，同一仓位在冷却期内不能再进行大额铸造，给监控系统留出反应时间。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    block,
+};
+
+use crate::{DSCEngine, DSCEngineError, MintCooldownActive, MintRateLimited};
+
+impl DSCEngine {
+    // 当前所处的铸造周期
+    fn _mint_epoch(&self) -> Option<U256> {
+        let window = self.mint_window.get();
+        if window == U256::ZERO {
+            return None;
+        }
+        Some(U256::from(block::timestamp()) / window)
+    }
+
+    // 本周期内已净铸造的数量：(全协议, 指定仓位)
+    pub(crate) fn _epoch_minted(&self, account: Address) -> (U256, U256) {
+        let Some(epoch) = self._mint_epoch() else {
+            return (U256::ZERO, U256::ZERO);
+        };
+        let global = if self.mint_epoch.get() == epoch {
+            self.epoch_minted.get()
+        } else {
+            U256::ZERO
+        };
+        let account_minted = if self.account_mint_epoch.get(account) == epoch {
+            self.account_epoch_minted.get(account)
+        } else {
+            U256::ZERO
+        };
+        (global, account_minted)
+    }
+
+    // 记录一笔铸造，超过本周期上限时回滚
+    pub(crate) fn _record_mint(
+        &mut self,
+        account: Address,
+        amount: U256,
+    ) -> Result<(), DSCEngineError> {
+        let Some(epoch) = self._mint_epoch() else {
+            return Ok(());
+        };
+        let (global, account_minted) = self._epoch_minted(account);
+        let global_cap = self.global_mint_cap.get();
+        let account_cap = self.account_mint_cap.get();
+        let mut remaining = U256::MAX;
+        if global_cap != U256::ZERO {
+            remaining = remaining.min(global_cap.saturating_sub(global));
+        }
+        if account_cap != U256::ZERO {
+            remaining = remaining.min(account_cap.saturating_sub(account_minted));
+        }
+        if amount > remaining {
+            return Err(DSCEngineError::MintRateLimited(MintRateLimited {
+                _0: remaining,
+            }));
+        }
+        self.mint_epoch.set(epoch);
+        self.epoch_minted.set(global + amount);
+        self.account_mint_epoch.setter(account).set(epoch);
+        self.account_epoch_minted
+            .setter(account)
+            .set(account_minted + amount);
+        Ok(())
+    }
+
+    // 检查并记录大额铸造的冷却期
+    pub(crate) fn _check_mint_cooldown(
+        &mut self,
+        account: Address,
+        amount: U256,
+    ) -> Result<(), DSCEngineError> {
+        let threshold = self.large_mint_threshold.get();
+        let cooldown = self.mint_cooldown.get();
+        if threshold == U256::ZERO || cooldown == U256::ZERO || amount < threshold {
+            return Ok(());
+        }
+        let now = U256::from(block::timestamp());
+        let last_large_mint = self.last_large_mint.get(account);
+        if last_large_mint != U256::ZERO && now < last_large_mint + cooldown {
+            return Err(DSCEngineError::MintCooldownActive(MintCooldownActive {
+                _0: last_large_mint + cooldown,
+            }));
+        }
+        self.last_large_mint.setter(account).set(now);
+        Ok(())
+    }
+
+    // 记录一笔偿还的本金，抵扣本周期的净铸造量
+    pub(crate) fn _record_repay(&mut self, account: Address, amount: U256) {
+        let Some(epoch) = self._mint_epoch() else {
+            return;
+        };
+        if self.mint_epoch.get() == epoch {
+            self.epoch_minted
+                .set(self.epoch_minted.get().saturating_sub(amount));
+        }
+        if self.account_mint_epoch.get(account) == epoch {
+            let account_minted = self.account_epoch_minted.get(account);
+            self.account_epoch_minted
+                .setter(account)
+                .set(account_minted.saturating_sub(amount));
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//! 模拟 ERC-20 抵押品：仅用于本地和测试网部署
+//!
+//! 同一份合约按构造参数部署为 MockWETH（18 位精度）或 MockWBTC（8 位精度），任何人都可以铸造。
+//! 可选开启转账手续费（到账数量少于转出数量），或注入转账失败（返回 `false` 或直接回滚），
+//! 用于集成测试覆盖引擎对非标准抵押品的处理。
+
+use alloc::string::String;
+use alloy_primitives::{Address, U256, U8};
+use alloy_sol_types::sol;
+use stylus_sdk::{call::MethodError, prelude::*};
+
+use crate::erc20::{
+    Erc20, Erc20Error, Erc20Params, InsufficientAllowance, InsufficientBalance, MintFailed,
+};
+use crate::psm::BPS;
+
+// 转账失败模式：不转账并返回 false
+pub const TRANSFER_RETURN_FALSE: u8 = 1;
+// 转账失败模式：直接回滚
+pub const TRANSFER_REVERT: u8 = 2;
+
+sol! {
+    error AlreadyInitialized();                 // 重复初始化错误
+    error TransferReverted();                   // 注入的转账回滚错误
+    error InvalidFee();                         // 手续费超过 100% 错误
+}
+
+sol_storage! {
+    #[cfg_attr(feature = "mock-erc20", entrypoint)]
+    pub struct MockErc20 {
+        Erc20<MockErc20Params> erc20;
+        bool initialized;
+        string token_name;
+        string token_symbol;
+        uint8 token_decimals;
+        uint256 transfer_fee;           // 转账手续费：基点，从到账数量中扣除并销毁
+        uint8 transfer_failure;         // 转账失败模式：0 为正常转账，其余见 TRANSFER_* 常量
+    }
+}
+
+/// 名称、符号和精度在构造时配置，这里只作占位
+pub struct MockErc20Params;
+impl Erc20Params for MockErc20Params {
+    const NAME: &'static str = "Mock Token";
+    const SYMBOL: &'static str = "MOCK";
+    const DECIMALS: u8 = 18;
+}
+
+#[derive(SolidityError)]
+pub enum MockErc20Error {
+    AlreadyInitialized(AlreadyInitialized),
+    TransferReverted(TransferReverted),
+    InvalidFee(InvalidFee),
+    InsufficientBalance(InsufficientBalance),
+    InsufficientAllowance(InsufficientAllowance),
+    MintFailed(MintFailed),
+}
+
+// ERC-20 错误逐个展开，导出的 ABI 中才能列出具体的错误定义
+impl From<Erc20Error> for MockErc20Error {
+    fn from(e: Erc20Error) -> Self {
+        match e {
+            Erc20Error::InsufficientBalance(e) => Self::InsufficientBalance(e),
+            Erc20Error::InsufficientAllowance(e) => Self::InsufficientAllowance(e),
+            Erc20Error::MintFailed(e) => Self::MintFailed(e),
+        }
+    }
+}
+
+impl MethodError for MockErc20Error {
+    fn encode(self) -> Vec<u8> {
+        From::from(self)
+    }
+}
+
+// 名称、符号和精度需要读取存储，因此不继承 Erc20，而是逐个转发其余标准方法
+#[public]
+impl MockErc20 {
+    /// 配置代币名称、符号和精度，例如 ("Wrapped Ether", "WETH", 18) 或 ("Wrapped Bitcoin", "WBTC", 8)
+    pub fn constructor(
+        &mut self,
+        name: String,
+        symbol: String,
+        decimals: u8,
+    ) -> Result<(), MockErc20Error> {
+        if self.initialized.get() {
+            return Err(MockErc20Error::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        self.initialized.set(true);
+        self.token_name.set_str(name);
+        self.token_symbol.set_str(symbol);
+        self.token_decimals.set(U8::from(decimals));
+        Ok(())
+    }
+
+    pub fn name(&self) -> String {
+        self.token_name.get_string()
+    }
+
+    pub fn symbol(&self) -> String {
+        self.token_symbol.get_string()
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.token_decimals.get().to::<u8>()
+    }
+
+    pub fn total_supply(&self) -> U256 {
+        self.erc20.total_supply()
+    }
+
+    pub fn balance_of(&self, owner: Address) -> U256 {
+        self.erc20.balance_of(owner)
+    }
+
+    pub fn allowance(&self, owner: Address, spender: Address) -> U256 {
+        self.erc20.allowance(owner, spender)
+    }
+
+    pub fn approve(&mut self, spender: Address, value: U256) -> bool {
+        self.erc20.approve(spender, value)
+    }
+
+    /// 向任意地址铸造代币
+    pub fn mint(&mut self, to: Address, amount: U256) -> Result<(), MockErc20Error> {
+        self.erc20.mint(to, amount).map_err(Into::into)
+    }
+
+    /// 销毁任意地址的代币
+    pub fn burn(&mut self, from: Address, amount: U256) -> Result<(), MockErc20Error> {
+        self.erc20.burn(from, amount).map_err(Into::into)
+    }
+
+    /// 设置转账手续费（基点），为 0 时关闭
+    pub fn set_transfer_fee(&mut self, fee_bps: U256) -> Result<(), MockErc20Error> {
+        if fee_bps > U256::from(BPS) {
+            return Err(MockErc20Error::InvalidFee(InvalidFee {}));
+        }
+        self.transfer_fee.set(fee_bps);
+        Ok(())
+    }
+
+    /// 设置转账失败模式，为 0 时恢复正常转账
+    pub fn set_transfer_failure(&mut self, mode: u8) {
+        self.transfer_failure.set(U8::from(mode));
+    }
+
+    // 获取转账手续费
+    pub fn transfer_fee(&self) -> U256 {
+        self.transfer_fee.get()
+    }
+
+    // 获取转账失败模式
+    pub fn transfer_failure(&self) -> u8 {
+        self.transfer_failure.get().to::<u8>()
+    }
+
+    pub fn transfer(&mut self, to: Address, value: U256) -> Result<bool, MockErc20Error> {
+        if !self._transfer_allowed()? {
+            return Ok(false);
+        }
+        self.erc20.transfer(to, value)?;
+        self._take_transfer_fee(to, value)?;
+        Ok(true)
+    }
+
+    pub fn transfer_from(
+        &mut self,
+        from: Address,
+        to: Address,
+        value: U256,
+    ) -> Result<bool, MockErc20Error> {
+        if !self._transfer_allowed()? {
+            return Ok(false);
+        }
+        self.erc20.transfer_from(from, to, value)?;
+        self._take_transfer_fee(to, value)?;
+        Ok(true)
+    }
+}
+
+// 内部辅助函数：不对外暴露
+impl MockErc20 {
+    // 按失败模式决定是否执行转账
+    fn _transfer_allowed(&self) -> Result<bool, MockErc20Error> {
+        match self.transfer_failure() {
+            TRANSFER_RETURN_FALSE => Ok(false),
+            TRANSFER_REVERT => Err(MockErc20Error::TransferReverted(TransferReverted {})),
+            _ => Ok(true),
+        }
+    }
+
+    // 从收款方到账的数量中扣除并销毁手续费
+    fn _take_transfer_fee(&mut self, to: Address, value: U256) -> Result<(), MockErc20Error> {
+        let fee = (value * self.transfer_fee.get()) / U256::from(BPS);
+        if fee == U256::ZERO {
+            return Ok(());
+        }
+        self.erc20.burn(to, fee).map_err(Into::into)
+    }
+}
@@ -0,0 +1,186 @@
+//! 模拟 Chainlink 价格预言机：仅用于本地和测试网部署
+//!
+//! 接口与 Chainlink `AggregatorV3Interface` 一致，部署者可以随时更新价格、直接写入任意一轮的数据，
+//! 或把最新一轮的更新时间调早来模拟预言机停更，从而在没有主网预言机的环境中覆盖完整的定价路径。
+
+use alloc::{string::String, vec::Vec};
+use alloy_primitives::{I256, U256};
+use alloy_sol_types::{abi::token::WordToken, private::SolTypeValue, sol_data, SolType, Word};
+use stylus_sdk::{
+    abi::{AbiType, ConstString},
+    block,
+    prelude::*,
+};
+
+/// Chainlink 的轮次编号（`uint80`）
+///
+/// SDK 只把 `u128` 映射为 `uint128`，单独定义该类型才能让 `getRoundData(uint80)` 的选择器与
+/// 真实预言机一致。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct U80(pub u128);
+
+impl SolTypeValue<Self> for U80 {
+    #[inline]
+    fn stv_to_tokens(&self) -> WordToken {
+        <u128 as SolTypeValue<sol_data::Uint<80>>>::stv_to_tokens(&self.0)
+    }
+
+    #[inline]
+    fn stv_eip712_data_word(&self) -> Word {
+        <u128 as SolTypeValue<sol_data::Uint<80>>>::stv_eip712_data_word(&self.0)
+    }
+
+    #[inline]
+    fn stv_abi_encode_packed_to(&self, out: &mut Vec<u8>) {
+        <u128 as SolTypeValue<sol_data::Uint<80>>>::stv_abi_encode_packed_to(&self.0, out)
+    }
+}
+
+impl SolType for U80 {
+    type RustType = U80;
+
+    type Token<'a> = WordToken;
+
+    const ENCODED_SIZE: Option<usize> = Some(32);
+
+    const SOL_NAME: &'static str = "uint80";
+
+    #[inline]
+    fn valid_token(token: &Self::Token<'_>) -> bool {
+        sol_data::Uint::<80>::valid_token(token)
+    }
+
+    #[inline]
+    fn detokenize(token: Self::Token<'_>) -> Self::RustType {
+        U80(sol_data::Uint::<80>::detokenize(token))
+    }
+}
+
+impl AbiType for U80 {
+    type SolType = Self;
+
+    const ABI: ConstString = ConstString::new("uint80");
+}
+
+#[cfg(feature = "export-abi")]
+impl stylus_sdk::abi::export::internal::InnerTypes for U80 {}
+
+sol_storage! {
+    #[cfg_attr(feature = "mock-v3-aggregator", entrypoint)]
+    pub struct MockV3Aggregator {
+        uint8 decimals;
+        bool initialized;
+        int256 latest_answer;
+        uint256 latest_timestamp;
+        uint256 latest_round;
+        // 每一轮的数据：轮次 => 价格 / 更新时间 / 开始时间
+        mapping(uint256 => int256) answers;
+        mapping(uint256 => uint256) timestamps;
+        mapping(uint256 => uint256) started_at;
+    }
+}
+
+#[public]
+impl MockV3Aggregator {
+    pub fn constructor(&mut self, decimals: u8, initial_answer: I256) {
+        if self.initialized.get() {
+            return;
+        }
+        self.initialized.set(true);
+        self.decimals.set(alloy_primitives::U8::from(decimals));
+        self.update_answer(initial_answer);
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals.get().to::<u8>()
+    }
+
+    pub fn description(&self) -> String {
+        String::from("v0.8/tests/MockV3Aggregator.sol")
+    }
+
+    pub fn version(&self) -> U256 {
+        U256::from(0)
+    }
+
+    pub fn latest_answer(&self) -> I256 {
+        self.latest_answer.get()
+    }
+
+    pub fn latest_timestamp(&self) -> U256 {
+        self.latest_timestamp.get()
+    }
+
+    pub fn latest_round(&self) -> U256 {
+        self.latest_round.get()
+    }
+
+    pub fn get_answer(&self, round_id: U256) -> I256 {
+        self.answers.get(round_id)
+    }
+
+    pub fn get_timestamp(&self, round_id: U256) -> U256 {
+        self.timestamps.get(round_id)
+    }
+
+    /// 以当前区块时间写入新的一轮价格
+    pub fn update_answer(&mut self, answer: I256) {
+        let round_id = self.latest_round.get() + U256::from(1);
+        let now = U256::from(block::timestamp());
+        self._write_round(round_id, answer, now, now);
+    }
+
+    /// 直接写入指定轮次的数据，并把它作为最新一轮
+    pub fn update_round_data(
+        &mut self,
+        round_id: U80,
+        answer: I256,
+        timestamp: U256,
+        started_at: U256,
+    ) {
+        self._write_round(U256::from(round_id.0), answer, timestamp, started_at);
+    }
+
+    /// 把最新一轮的更新时间调到 `age` 秒之前，模拟预言机停更
+    pub fn make_stale(&mut self, age: U256) {
+        let round_id = self.latest_round.get();
+        let updated_at = U256::from(block::timestamp()).saturating_sub(age);
+        self.latest_timestamp.set(updated_at);
+        self.timestamps.setter(round_id).set(updated_at);
+    }
+
+    pub fn get_round_data(&self, round_id: U80) -> (U80, I256, U256, U256, U80) {
+        let round = U256::from(round_id.0);
+        (
+            round_id,
+            self.answers.get(round),
+            self.started_at.get(round),
+            self.timestamps.get(round),
+            round_id,
+        )
+    }
+
+    pub fn latest_round_data(&self) -> (U80, I256, U256, U256, U80) {
+        let round = self.latest_round.get();
+        let round_id = U80(round.to::<u128>());
+        (
+            round_id,
+            self.answers.get(round),
+            self.started_at.get(round),
+            self.timestamps.get(round),
+            round_id,
+        )
+    }
+}
+
+// 内部辅助函数：不对外暴露
+impl MockV3Aggregator {
+    fn _write_round(&mut self, round_id: U256, answer: I256, timestamp: U256, started_at: U256) {
+        self.latest_round.set(round_id);
+        self.latest_answer.set(answer);
+        self.latest_timestamp.set(timestamp);
+        self.answers.setter(round_id).set(answer);
+        self.timestamps.setter(round_id).set(timestamp);
+        self.started_at.setter(round_id).set(started_at);
+    }
+}
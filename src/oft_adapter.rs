@@ -0,0 +1,347 @@
+//! LayerZero OFT 适配器：通过 LayerZero V2 在多条链之间原生转移 DSC
+//!
+//! 适配器在 DSC 上登记为 xERC20 跨链桥：发送时按销毁额度销毁用户的 DSC，并通过端点合约
+//! 向目标链的对端适配器发送消息；接收端点转来的消息时校验来源链的对端地址，按铸造额度为接收者铸造 DSC。
+//! 消息格式与 OFT 标准一致（32 字节接收地址 + 8 字节共享精度数量），共享精度为 6 位，
+//! 低于共享精度的尾数不跨链发送。每条消息的 guid 只能处理一次。
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, B256, U256, U32};
+use alloy_sol_types::{sol, SolCall};
+use stylus_sdk::{
+    abi::Bytes,
+    call::{self, Call, MethodError},
+    contract, evm, msg,
+    prelude::*,
+};
+
+// 共享精度：跨链消息中数量的精度
+const SHARED_DECIMALS: u8 = 6;
+// 本地精度与共享精度的换算比例：10^(18 - 6)
+const DECIMAL_CONVERSION_RATE: u64 = 1_000_000_000_000;
+
+sol! {
+    event PeerSet(uint32 eid, bytes32 peer);
+    event OFTSent(
+        bytes32 indexed guid, uint32 dstEid, address indexed fromAddress, uint256 amountSentLD, uint256 amountReceivedLD
+    );
+    event OFTReceived(bytes32 indexed guid, uint32 srcEid, address indexed toAddress, uint256 amountReceivedLD);
+
+    error NotOwner();
+    error OnlyEndpoint(address addr);
+    error NoPeer(uint32 eid);
+    error OnlyPeer(uint32 eid, bytes32 sender);
+    error SlippageExceeded(uint256 amountLD, uint256 minAmountLD);
+    error InvalidMessage();
+    error AlreadyReceived(bytes32 guid);
+    error NotEnoughNative(uint256 msgValue);
+    error LzTokenUnavailable();
+    error EndpointCallFailed();
+    error BurnFailed();
+    error MintFailed();
+
+    // LayerZero V2 端点的消息参数和回执
+    struct MessagingParams {
+        uint32 dstEid;
+        bytes32 receiver;
+        bytes message;
+        bytes options;
+        bool payInLzToken;
+    }
+    struct MessagingFee {
+        uint256 nativeFee;
+        uint256 lzTokenFee;
+    }
+    struct MessagingReceipt {
+        bytes32 guid;
+        uint64 nonce;
+        MessagingFee fee;
+    }
+    interface ILayerZeroEndpointV2 {
+        function send(MessagingParams calldata params, address refundAddress) external payable returns (MessagingReceipt memory);
+        function quote(MessagingParams calldata params, address sender) external view returns (MessagingFee memory);
+    }
+}
+
+sol_interface! {
+    // DSC 的 xERC20 跨链桥接口
+    interface IBridgeableToken {
+        function mint(address to, uint256 amount) external returns (bool);
+        function burn(address from, uint256 amount) external;
+    }
+}
+
+sol_storage! {
+    #[cfg_attr(feature = "oft-adapter", entrypoint)]
+    pub struct OftAdapter {
+        address token;
+        address endpoint;
+        address owner;
+        // 对端适配器：目标链 eid => 对端地址
+        mapping(uint32 => bytes32) peers;
+        // 已处理的消息：guid => 是否已处理
+        mapping(bytes32 => bool) received;
+    }
+}
+
+#[derive(SolidityError)]
+pub enum OftAdapterError {
+    NotOwner(NotOwner),
+    OnlyEndpoint(OnlyEndpoint),
+    NoPeer(NoPeer),
+    OnlyPeer(OnlyPeer),
+    SlippageExceeded(SlippageExceeded),
+    InvalidMessage(InvalidMessage),
+    AlreadyReceived(AlreadyReceived),
+    NotEnoughNative(NotEnoughNative),
+    LzTokenUnavailable(LzTokenUnavailable),
+    EndpointCallFailed(EndpointCallFailed),
+    BurnFailed(BurnFailed),
+    MintFailed(MintFailed),
+}
+
+impl MethodError for OftAdapterError {
+    fn encode(self) -> Vec<u8> {
+        From::from(self)
+    }
+}
+
+// OFT 发送参数：(目标链 eid, 接收地址, 发送数量, 最少到账数量, 附加选项, 组合消息, OFT 指令)
+type SendParam = (u32, B256, U256, U256, Bytes, Bytes, Bytes);
+
+#[public]
+impl OftAdapter {
+    pub fn constructor(
+        &mut self,
+        token: Address,
+        endpoint: Address,
+    ) -> Result<(), OftAdapterError> {
+        if !self.owner.get().is_zero() {
+            return Err(OftAdapterError::NotOwner(NotOwner {}));
+        }
+        self.owner.set(msg::sender());
+        self.token.set(token);
+        self.endpoint.set(endpoint);
+        Ok(())
+    }
+
+    /// 设置目标链上的对端适配器地址，设为零时关闭该链
+    pub fn set_peer(&mut self, eid: u32, peer: B256) -> Result<(), OftAdapterError> {
+        if msg::sender() != self.owner.get() {
+            return Err(OftAdapterError::NotOwner(NotOwner {}));
+        }
+        self.peers.setter(U32::from(eid)).set(peer);
+        evm::log(PeerSet { eid, peer });
+        Ok(())
+    }
+
+    pub fn peers(&self, eid: u32) -> B256 {
+        self.peers.get(U32::from(eid))
+    }
+
+    pub fn token(&self) -> Address {
+        self.token.get()
+    }
+
+    pub fn endpoint(&self) -> Address {
+        self.endpoint.get()
+    }
+
+    pub fn shared_decimals(&self) -> u8 {
+        SHARED_DECIMALS
+    }
+
+    /// 报价：发送消息需支付的原生代币手续费，返回 (原生代币手续费, LayerZero 代币手续费)
+    pub fn quote_send(
+        &self,
+        send_param: SendParam,
+        pay_in_lz_token: bool,
+    ) -> Result<(U256, U256), OftAdapterError> {
+        let (dst_eid, to, amount_ld, min_amount_ld, options, _, _) = send_param;
+        let amount_received = self._debit_view(amount_ld, min_amount_ld)?;
+        let params =
+            self._messaging_params(dst_eid, to, amount_received, options, pay_in_lz_token)?;
+        let data = ILayerZeroEndpointV2::quoteCall {
+            params,
+            sender: contract::address(),
+        }
+        .abi_encode();
+        let returned = call::static_call(Call::new(), self.endpoint.get(), &data)
+            .map_err(|_| OftAdapterError::EndpointCallFailed(EndpointCallFailed {}))?;
+        let fee = ILayerZeroEndpointV2::quoteCall::abi_decode_returns(&returned, true)
+            .map_err(|_| OftAdapterError::EndpointCallFailed(EndpointCallFailed {}))?
+            ._0;
+        Ok((fee.nativeFee, fee.lzTokenFee))
+    }
+
+    /// 销毁调用者的 DSC 并向目标链发送铸造消息，返回 (guid, nonce, 发送数量, 到账数量)
+    #[payable]
+    pub fn send(
+        &mut self,
+        send_param: SendParam,
+        fee: (U256, U256),
+        refund_address: Address,
+    ) -> Result<(B256, u64, U256, U256), OftAdapterError> {
+        let (dst_eid, to, amount_ld, min_amount_ld, options, _, _) = send_param;
+        let (native_fee, lz_token_fee) = fee;
+        if lz_token_fee != U256::ZERO {
+            return Err(OftAdapterError::LzTokenUnavailable(LzTokenUnavailable {}));
+        }
+        if msg::value() != native_fee {
+            return Err(OftAdapterError::NotEnoughNative(NotEnoughNative {
+                msgValue: msg::value(),
+            }));
+        }
+        // 只销毁去除尾数后的数量，尾数留在用户钱包
+        let amount_received = self._debit_view(amount_ld, min_amount_ld)?;
+        let sender = msg::sender();
+        IBridgeableToken::new(self.token.get())
+            .burn(Call::new(), sender, amount_received)
+            .map_err(|_| OftAdapterError::BurnFailed(BurnFailed {}))?;
+
+        let params = self._messaging_params(dst_eid, to, amount_received, options, false)?;
+        let data = ILayerZeroEndpointV2::sendCall {
+            params,
+            refundAddress: refund_address,
+        }
+        .abi_encode();
+        let returned = call::call(Call::new().value(native_fee), self.endpoint.get(), &data)
+            .map_err(|_| OftAdapterError::EndpointCallFailed(EndpointCallFailed {}))?;
+        let receipt = ILayerZeroEndpointV2::sendCall::abi_decode_returns(&returned, true)
+            .map_err(|_| OftAdapterError::EndpointCallFailed(EndpointCallFailed {}))?
+            ._0;
+        evm::log(OFTSent {
+            guid: receipt.guid,
+            dstEid: dst_eid,
+            fromAddress: sender,
+            amountSentLD: amount_received,
+            amountReceivedLD: amount_received,
+        });
+        Ok((
+            receipt.guid,
+            receipt.nonce,
+            amount_received,
+            amount_received,
+        ))
+    }
+
+    /// 端点投递跨链消息：校验来源链的对端地址后为接收者铸造 DSC
+    pub fn lz_receive(
+        &mut self,
+        // 消息来源：(来源链 eid, 发送方地址, nonce)
+        origin: (u32, B256, u64),
+        guid: B256,
+        message: Bytes,
+        _executor: Address,
+        _extra_data: Bytes,
+    ) -> Result<(), OftAdapterError> {
+        if msg::sender() != self.endpoint.get() {
+            return Err(OftAdapterError::OnlyEndpoint(OnlyEndpoint {
+                addr: msg::sender(),
+            }));
+        }
+        let (src_eid, sender, _) = origin;
+        let peer = self.peers.get(U32::from(src_eid));
+        if peer == B256::ZERO || peer != sender {
+            return Err(OftAdapterError::OnlyPeer(OnlyPeer {
+                eid: src_eid,
+                sender,
+            }));
+        }
+        // 同一条消息只能处理一次
+        if self.received.get(guid) {
+            return Err(OftAdapterError::AlreadyReceived(AlreadyReceived { guid }));
+        }
+        self.received.setter(guid).set(true);
+
+        let (to, amount_ld) = Self::_decode_message(&message)?;
+        IBridgeableToken::new(self.token.get())
+            .mint(Call::new(), to, amount_ld)
+            .map_err(|_| OftAdapterError::MintFailed(MintFailed {}))?;
+        evm::log(OFTReceived {
+            guid,
+            srcEid: src_eid,
+            toAddress: to,
+            amountReceivedLD: amount_ld,
+        });
+        Ok(())
+    }
+
+    /// 端点初始化消息通道时的校验：只接受已配置对端的消息
+    pub fn allow_initialize_path(&self, origin: (u32, B256, u64)) -> bool {
+        let (src_eid, sender, _) = origin;
+        let peer = self.peers.get(U32::from(src_eid));
+        peer != B256::ZERO && peer == sender
+    }
+
+    /// 消息不要求按顺序投递
+    pub fn next_nonce(&self, _src_eid: u32, _sender: B256) -> u64 {
+        0
+    }
+
+    pub fn is_received(&self, guid: B256) -> bool {
+        self.received.get(guid)
+    }
+}
+
+// 内部辅助函数：不对外暴露
+impl OftAdapter {
+    // 去除低于共享精度的尾数，返回实际发送的数量
+    fn _debit_view(&self, amount_ld: U256, min_amount_ld: U256) -> Result<U256, OftAdapterError> {
+        let rate = U256::from(DECIMAL_CONVERSION_RATE);
+        let amount = (amount_ld / rate) * rate;
+        if amount < min_amount_ld {
+            return Err(OftAdapterError::SlippageExceeded(SlippageExceeded {
+                amountLD: amount,
+                minAmountLD: min_amount_ld,
+            }));
+        }
+        Ok(amount)
+    }
+
+    // 组装发往目标链对端的消息参数
+    fn _messaging_params(
+        &self,
+        dst_eid: u32,
+        to: B256,
+        amount_ld: U256,
+        options: Bytes,
+        pay_in_lz_token: bool,
+    ) -> Result<MessagingParams, OftAdapterError> {
+        let receiver = self.peers.get(U32::from(dst_eid));
+        if receiver == B256::ZERO {
+            return Err(OftAdapterError::NoPeer(NoPeer { eid: dst_eid }));
+        }
+        Ok(MessagingParams {
+            dstEid: dst_eid,
+            receiver,
+            message: Self::_encode_message(to, amount_ld)?.into(),
+            options: options.0.into(),
+            payInLzToken: pay_in_lz_token,
+        })
+    }
+
+    // OFT 消息：32 字节接收地址 + 8 字节共享精度数量
+    fn _encode_message(to: B256, amount_ld: U256) -> Result<Vec<u8>, OftAdapterError> {
+        let amount_sd: u64 = (amount_ld / U256::from(DECIMAL_CONVERSION_RATE))
+            .try_into()
+            .map_err(|_| OftAdapterError::InvalidMessage(InvalidMessage {}))?;
+        let mut message = Vec::with_capacity(40);
+        message.extend_from_slice(to.as_slice());
+        message.extend_from_slice(&amount_sd.to_be_bytes());
+        Ok(message)
+    }
+
+    // 解析 OFT 消息，返回接收地址和本地精度数量（忽略组合消息）
+    fn _decode_message(message: &[u8]) -> Result<(Address, U256), OftAdapterError> {
+        if message.len() < 40 {
+            return Err(OftAdapterError::InvalidMessage(InvalidMessage {}));
+        }
+        let to = Address::from_slice(&message[12..32]);
+        let mut amount_sd = [0u8; 8];
+        amount_sd.copy_from_slice(&message[32..40]);
+        let amount_ld =
+            U256::from(u64::from_be_bytes(amount_sd)) * U256::from(DECIMAL_CONVERSION_RATE);
+        Ok((to, amount_ld))
+    }
+}
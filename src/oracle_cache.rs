@@ -0,0 +1,69 @@
+//! 交易内的预言机价格缓存
+//!
+//! 一次调用中同一预言机常被读取多次（如清算时先换算抵押品数量，再两次计算健康因子）。
+//! 每个预言机的最新读数在首次读取后缓存在合约实例的内存中，同一调用内不再重复外部调用。
+//! Stylus 为每次调用创建新的合约实例，缓存不会跨调用保留。
+
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use stylus_sdk::{
+    alloy_primitives::{Address, I256, U256},
+    call::Call,
+};
+
+use crate::{DSCEngine, IAggregatorV3};
+
+// 预言机最新一轮的读数
+#[derive(Clone, Copy)]
+pub(crate) struct FeedRound {
+    pub(crate) round_id: u128,
+    pub(crate) answer: I256,
+    pub(crate) started_at: U256,
+    pub(crate) updated_at: U256,
+}
+
+std::thread_local! {
+    // 预言机地址到最新一轮读数的缓存
+    static FEED_CACHE: RefCell<Vec<(Address, FeedRound)>> = const { RefCell::new(Vec::new()) };
+}
+
+// 测试中模拟新的一次调用时清空缓存
+#[cfg(test)]
+pub(crate) fn clear_feed_cache() {
+    FEED_CACHE.with(|cache| cache.borrow_mut().clear());
+}
+
+impl DSCEngine {
+    // 读取预言机最新一轮的数据，同一调用内只进行一次外部调用
+    pub(crate) fn _feed_round(&self, feed: Address) -> Option<FeedRound> {
+        let cached = FEED_CACHE.with(|cache| {
+            cache
+                .borrow()
+                .iter()
+                .find(|(cached_feed, _)| *cached_feed == feed)
+                .map(|&(_, round)| round)
+        });
+        if cached.is_some() {
+            return cached;
+        }
+        let (round_id, answer, started_at, updated_at, _) = IAggregatorV3::new(feed)
+            .latest_round_data(Call::new())
+            .ok()?;
+        let round = FeedRound {
+            round_id,
+            answer,
+            started_at,
+            updated_at,
+        };
+        FEED_CACHE.with(|cache| cache.borrow_mut().push((feed, round)));
+        Some(round)
+    }
+
+    // 读取预言机最新的价格和更新时间，预言机回滚时使用窗口内的最近有效价格
+    pub(crate) fn _feed_answer(&self, feed: Address) -> Option<(I256, U256)> {
+        match self._feed_round(feed) {
+            Some(round) => Some((round.answer, round.updated_at)),
+            None => self._fallback_answer(feed),
+        }
+    }
+}
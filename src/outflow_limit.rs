@@ -0,0 +1,93 @@
+//! 抵押品流出速率限制
+//!
+//! 时间按窗口长度划分为连续的周期，每种抵押品在一个周期内的净流出量（用户提取减去存入）
+//! 不能超过周期开始时该抵押品存款总量的一定比例，进入新周期后自动重置。
+//! 正常规模的提取不受影响，漏洞被利用时单个周期内能被抽走的抵押品有上限。
+//! 只限制用户主动提取，清算不受限制；远程抵押品不在本链持有，也不受限制。
+//! 窗口长度或比例为零时不限制。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    block,
+    prelude::*,
+};
+
+use crate::{psm::BPS, DSCEngine, DSCEngineError, OutflowRateLimited};
+
+sol_storage! {
+    // 单个抵押品本周期的流出记录
+    pub struct OutflowEpoch {
+        uint256 epoch;                       // 记录所属的周期
+        uint256 outflow;                     // 本周期的净流出量
+        uint256 tvl;                         // 周期开始时的存款总量
+    }
+}
+
+impl DSCEngine {
+    // 当前所处的流出限制周期
+    fn _outflow_epoch(&self) -> Option<U256> {
+        let window = self.outflow_window.get();
+        if window == U256::ZERO || self.outflow_share.get() == U256::ZERO {
+            return None;
+        }
+        Some(U256::from(block::timestamp()) / window)
+    }
+
+    // 抵押品本周期的净流出量和流出上限，未启用限制时为 None
+    pub(crate) fn _epoch_outflow(&self, token: Address) -> Option<(U256, U256)> {
+        let epoch = self._outflow_epoch()?;
+        if self._is_remote_collateral(token) {
+            return None;
+        }
+        let record = self.outflow_epochs.getter(token);
+        let (outflow, tvl) = if record.epoch.get() == epoch {
+            (record.outflow.get(), record.tvl.get())
+        } else {
+            (U256::ZERO, self.total_deposited.get(token))
+        };
+        let cap = (tvl * self.outflow_share.get()) / U256::from(BPS);
+        Some((outflow, cap))
+    }
+
+    // 记录一笔提取，超过本周期流出上限时回滚
+    pub(crate) fn _record_outflow(
+        &mut self,
+        token: Address,
+        amount: U256,
+    ) -> Result<(), DSCEngineError> {
+        let Some((outflow, cap)) = self._epoch_outflow(token) else {
+            return Ok(());
+        };
+        let remaining = cap.saturating_sub(outflow);
+        if amount > remaining {
+            return Err(DSCEngineError::OutflowRateLimited(OutflowRateLimited {
+                _0: remaining,
+            }));
+        }
+        let Some(epoch) = self._outflow_epoch() else {
+            return Ok(());
+        };
+        // 进入新周期时记录周期开始时的存款总量
+        if self.outflow_epochs.getter(token).epoch.get() != epoch {
+            let tvl = self.total_deposited.get(token);
+            let mut record = self.outflow_epochs.setter(token);
+            record.epoch.set(epoch);
+            record.tvl.set(tvl);
+        }
+        let mut record = self.outflow_epochs.setter(token);
+        record.outflow.set(outflow + amount);
+        Ok(())
+    }
+
+    // 记录一笔存入，抵扣本周期的净流出量
+    pub(crate) fn _record_inflow(&mut self, token: Address, amount: U256) {
+        let Some(epoch) = self._outflow_epoch() else {
+            return;
+        };
+        let mut record = self.outflow_epochs.setter(token);
+        if record.epoch.get() == epoch {
+            let outflow = record.outflow.get();
+            record.outflow.set(outflow.saturating_sub(amount));
+        }
+    }
+}
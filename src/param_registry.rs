@@ -0,0 +1,76 @@
+//! 治理参数登记表
+//!
+//! 每次治理修改参数时，以参数名为键记录新值、修改前的值和修改时间，并发出 `ParamUpdated` 事件，
+//! 链上风险审计和监控只需 `getParam(bytes32)` 一个入口，不必逐个调用分散的查询函数。
+//! 参数名为右侧补零的 ASCII 字符串（如 `"mintFee"`）；按抵押品、风险等级等对象分别设置的参数，
+//! 键为 keccak256(参数名 ‖ 对象)，对象为左侧补零的地址或编号，可用 `getParamKey` 计算。
+//! 地址和布尔值按 uint256 记录。参数本身仍存放在原有字段中，登记表只是同步记录的副本；
+//! 白名单、手续费豁免等名单类设置按地址分别记录，守护者的临时应急价格不计入。
+
+use stylus_sdk::{
+    alloy_primitives::{keccak256, Address, B256, U256},
+    block, evm,
+    prelude::*,
+};
+
+use crate::{DSCEngine, ParamUpdated};
+
+sol_storage! {
+    // 一项参数的当前值和修改历史
+    pub struct ParamRecord {
+        uint256 value;                       // 当前值
+        uint256 previous;                    // 上一次修改前的值
+        uint256 updated_at;                  // 最近一次修改的时间
+    }
+}
+
+// 参数名编码为右侧补零的 bytes32，超过 32 字节的部分截断
+pub(crate) fn param_name(name: &str) -> B256 {
+    let mut word = B256::ZERO;
+    let len = name.len().min(32);
+    word[..len].copy_from_slice(&name.as_bytes()[..len]);
+    word
+}
+
+// 风险等级、E-mode 类别、链编号等编号对象编码为左侧补零的 bytes32
+pub(crate) fn id_subject(id: u64) -> B256 {
+    B256::from(U256::from(id))
+}
+
+// 地址参数按 uint256 记录
+pub(crate) fn address_value(address: Address) -> U256 {
+    U256::from_be_bytes(address.into_word().0)
+}
+
+impl DSCEngine {
+    // 参数在登记表中的键：全局参数为参数名，按对象设置的参数为 keccak256(参数名 ‖ 对象)
+    pub(crate) fn _param_key(name: B256, subject: B256) -> B256 {
+        if subject == B256::ZERO {
+            return name;
+        }
+        keccak256([name.as_slice(), subject.as_slice()].concat())
+    }
+
+    // 记录一项全局参数的新值
+    pub(crate) fn _record_param(&mut self, name: &str, value: U256) {
+        self._record_param_of(name, B256::ZERO, value);
+    }
+
+    // 记录按对象设置的参数的新值
+    pub(crate) fn _record_param_of(&mut self, name: &str, subject: B256, value: U256) {
+        let name = param_name(name);
+        let key = Self::_param_key(name, subject);
+        let mut record = self.param_records.setter(key);
+        let previous = record.value.get();
+        record.previous.set(previous);
+        record.value.set(value);
+        record.updated_at.set(U256::from(block::timestamp()));
+        evm::log(ParamUpdated {
+            key,
+            name,
+            subject,
+            previous,
+            value,
+        });
+    }
+}
@@ -0,0 +1,61 @@
+//! 锚定货币：以美元以外的货币（如欧元、瑞士法郎）为记账单位
+//!
+//! 初始化时可以指定锚定货币预言机，报价为一单位锚定货币的美元价格（如 EUR/USD）。
+//! 抵押品的美元价格（含应急价格、汇率合约和 ERC-4626 份额换算）除以锚定货币价格，
+//! 得到以锚定货币计的价格，仍以预言机精度（8 位）表示；估值、健康因子、清算和拍卖都按该价格计算，
+//! 债务和抵押品价值因此都以锚定货币计。未指定锚定货币预言机时以美元为记账单位。
+//! PSM 按 1:1 兑换，只应配置锚定同一货币的稳定币。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256, U8},
+    call::Call,
+};
+
+use crate::{
+    casting::answer_to_price,
+    math::{mul_div, Rounding},
+    param_registry::address_value,
+    DSCEngine, DSCEngineError, IAggregatorV3, PriceFeedError,
+};
+
+impl DSCEngine {
+    // 设置锚定货币预言机并记录其报价精度，零地址表示以美元为记账单位
+    pub(crate) fn _set_peg_feed(&mut self, peg_feed: Address) -> Result<(), DSCEngineError> {
+        if !peg_feed.is_zero() {
+            let decimals = IAggregatorV3::new(peg_feed)
+                .decimals(Call::new())
+                .map_err(|_| DSCEngineError::PriceFeedError(PriceFeedError {}))?;
+            self.peg_feed_decimals.set(U8::from(decimals));
+        }
+        self.peg_feed.set(peg_feed);
+        if self._peg_price()? == Some(U256::ZERO) {
+            return Err(DSCEngineError::PriceFeedError(PriceFeedError {}));
+        }
+        self._record_param("pegFeed", address_value(peg_feed));
+        Ok(())
+    }
+
+    // 一单位锚定货币的美元价格（锚定货币预言机精度），以美元为记账单位时为 None
+    pub(crate) fn _peg_price(&self) -> Result<Option<U256>, DSCEngineError> {
+        let peg_feed = self.peg_feed.get();
+        if peg_feed.is_zero() {
+            return Ok(None);
+        }
+        let (answer, _) = self
+            ._feed_answer(peg_feed)
+            .ok_or(DSCEngineError::PriceFeedError(PriceFeedError {}))?;
+        answer_to_price(answer).map(Some)
+    }
+
+    // 把美元价格（预言机精度）换算为以锚定货币计的价格
+    pub(crate) fn _to_peg_price(&self, usd_price: U256) -> Result<U256, DSCEngineError> {
+        let Some(peg_price) = self._peg_price()? else {
+            return Ok(usd_price);
+        };
+        if peg_price == U256::ZERO {
+            return Err(DSCEngineError::PriceFeedError(PriceFeedError {}));
+        }
+        let peg_unit = U256::from(10).pow(U256::from(self.peg_feed_decimals.get().to::<u8>()));
+        Ok(mul_div(usd_price, peg_unit, peg_price, Rounding::Down))
+    }
+}
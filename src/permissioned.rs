@@ -0,0 +1,30 @@
+//! 许可模式：只有白名单地址可以存入抵押品和铸造 DSC
+//!
+//! 治理开启许可模式后，存入抵押品、铸造 DSC 和接收转让仓位都要求调用者在白名单中，
+//! 同一套代码即可部署需要 KYC 的许可实例。清算、偿还和提取不受限制。
+//! 旧引擎迁入仓位或跨链消息适配器记入远程抵押品时，调用者不是用户本人，此时检查仓位所属的用户。
+
+use stylus_sdk::{alloy_primitives::Address, msg};
+
+use crate::{meta_tx::msg_sender, DSCEngine, DSCEngineError, NotAllowlisted};
+
+impl DSCEngine {
+    // 许可模式下检查地址是否在白名单中
+    pub(crate) fn _check_allowlisted(&self, account: Address) -> Result<(), DSCEngineError> {
+        if self.permissioned.get() && !self.allowlisted.get(account) {
+            return Err(DSCEngineError::NotAllowlisted(NotAllowlisted { account }));
+        }
+        Ok(())
+    }
+
+    // 许可模式下检查发起存入或铸造的调用者
+    pub(crate) fn _check_permissioned_caller(&self, user: Address) -> Result<(), DSCEngineError> {
+        let caller = msg::sender();
+        if self.migration_sources.get(caller)
+            || (!caller.is_zero() && caller == self.remote_messenger.get())
+        {
+            return self._check_allowlisted(user);
+        }
+        self._check_allowlisted(msg_sender())
+    }
+}
@@ -0,0 +1,101 @@
+//! 仓位转让：把整个仓位的抵押品和债务一次性转给另一个地址
+//!
+//! 接收方需先同意接收来自转出方的仓位。转让时抵押品记账和各分桶的标准化债务原样合并到
+//! 接收方名下，转出方清空，接收方合并后的仓位必须保持健康。可用于钱包迁移和托管交接。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    evm,
+};
+
+use crate::{
+    AuctionActive, DSCEngine, DSCEngineError, PositionTransferAccepted,
+    PositionTransferNotAccepted, PositionTransferred,
+};
+
+impl DSCEngine {
+    // 接收方同意或撤销接收来自 from 的仓位
+    pub(crate) fn _accept_position_transfer(&mut self, to: Address, from: Address, accepted: bool) {
+        self.position_transfer_approvals
+            .setter(to)
+            .setter(from)
+            .set(accepted);
+        evm::log(PositionTransferAccepted { from, to, accepted });
+    }
+
+    // 把 from 的整个仓位合并到 to 名下（调用前需先计提）
+    pub(crate) fn _transfer_position(
+        &mut self,
+        from: Address,
+        to: Address,
+    ) -> Result<(), DSCEngineError> {
+        if !self.position_transfer_approvals.getter(to).get(from) {
+            return Err(DSCEngineError::PositionTransferNotAccepted(
+                PositionTransferNotAccepted {},
+            ));
+        }
+        // 许可模式下接收方必须在白名单中
+        self._check_allowlisted(to)?;
+        // 合成资产债务不随仓位转让
+        self._require_no_synth_debt(from)?;
+        // 拍卖中的抵押品不在仓位中，不能转让
+        if self.active_auction.get(from) != U256::ZERO || self.active_auction.get(to) != U256::ZERO
+        {
+            return Err(DSCEngineError::AuctionActive(AuctionActive {}));
+        }
+        // 同意只能使用一次
+        self.position_transfer_approvals
+            .setter(to)
+            .setter(from)
+            .set(false);
+
+        for i in 0..self.collateral_tokens.len() {
+            let Some(token) = self.collateral_tokens.get(i) else {
+                continue;
+            };
+            // 抵押品记账（份额模式下为份额）
+            let collateral = self.collateral_deposited.getter(from).get(token);
+            if collateral > U256::ZERO {
+                self._check_emode_collateral(to, token)?;
+                let to_collateral = self.collateral_deposited.getter(to).get(token);
+                self.collateral_deposited
+                    .setter(to)
+                    .setter(token)
+                    .set(to_collateral + collateral);
+                self.collateral_deposited
+                    .setter(from)
+                    .setter(token)
+                    .set(U256::ZERO);
+            }
+            // 分桶的标准化债务，指数相同，可直接合并
+            let normalized = self.normalized_debt.getter(from).get(token);
+            if normalized > U256::ZERO {
+                self._update_user_rewards(from, token);
+                self._update_user_rewards(to, token);
+                let to_normalized = self.normalized_debt.getter(to).get(token);
+                self.normalized_debt
+                    .setter(to)
+                    .setter(token)
+                    .set(to_normalized + normalized);
+                self.normalized_debt
+                    .setter(from)
+                    .setter(token)
+                    .set(U256::ZERO);
+            }
+        }
+        // 已铸造的稳定币本金
+        let principal = self.dsc_minted.get(from);
+        let to_principal = self.dsc_minted.get(to);
+        self.dsc_minted.setter(to).set(to_principal + principal);
+        self.dsc_minted.setter(from).set(U256::ZERO);
+
+        self._sync_active_position(from);
+        self._sync_active_position(to);
+        evm::log(PositionTransferred {
+            from,
+            to,
+            debt: self._user_debt(to),
+        });
+        self._revert_if_borrow_health_factor_is_broken(to)
+    }
+}
@@ -0,0 +1,129 @@
+//! 价格偏离熔断
+//!
+//! 每个预言机记录最近一次接受的价格。新读数在一个心跳间隔内相对上次接受的价格偏离超过阈值时，
+//! 视为异常波动：暂停该抵押品的铸造和提取，并发出告警事件，直到治理解除或冷却期结束。
+//! 任何人都可以调用 `poke_price_breaker` 记录异常并触发熔断；铸造和提取时检测到异常会直接回滚。
+//! 清算不受熔断影响。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    block, evm,
+};
+
+use crate::{
+    psm::BPS, DSCEngine, DSCEngineError, PriceBreakerCleared, PriceCircuitBreakerTripped,
+    PriceDeviationAlert,
+};
+
+impl DSCEngine {
+    // 熔断是否仍在生效（冷却期为零时只能由治理解除）
+    pub(crate) fn _price_breaker_active(&self, token: Address) -> bool {
+        let tripped_at = self.price_breaker_tripped_at.get(token);
+        if tripped_at == U256::ZERO {
+            return false;
+        }
+        let cooldown = self.price_breaker_cooldown.get();
+        cooldown == U256::ZERO || U256::from(block::timestamp()) < tripped_at + cooldown
+    }
+
+    // 按价格来源读取抵押品的最新价格，偏离上次接受的价格超过阈值时返回 (上次价格, 新价格)，否则接受新价格
+    fn _observe_price(&mut self, token: Address) -> Result<Option<(U256, U256)>, DSCEngineError> {
+        let feed = self.price_feeds.get(token);
+        let price = self._source_price(token)?;
+        let now = U256::from(block::timestamp());
+        let last_price = self.last_accepted_price.get(feed);
+        let max_deviation = self.max_price_deviation.get();
+        if max_deviation != U256::ZERO && last_price != U256::ZERO {
+            let heartbeat = self._oracle_timeout(token);
+            let within_heartbeat = heartbeat == U256::ZERO
+                || now.saturating_sub(self.last_accepted_price_time.get(feed)) <= heartbeat;
+            let deviation = price.abs_diff(last_price);
+            if within_heartbeat && deviation * U256::from(BPS) > last_price * max_deviation {
+                return Ok(Some((last_price, price)));
+            }
+        }
+        self.last_accepted_price.setter(feed).set(price);
+        self.last_accepted_price_time.setter(feed).set(now);
+        Ok(None)
+    }
+
+    // 铸造和提取前检查抵押品的熔断状态
+    pub(crate) fn _check_price_breaker(&mut self, token: Address) -> Result<(), DSCEngineError> {
+        if self._price_breaker_active(token) {
+            return Err(DSCEngineError::PriceCircuitBreakerTripped(
+                PriceCircuitBreakerTripped { token },
+            ));
+        }
+        // 冷却期结束后自动解除熔断，并接受当前价格
+        if self.price_breaker_tripped_at.get(token) != U256::ZERO {
+            self.price_breaker_tripped_at.setter(token).set(U256::ZERO);
+            self._accept_current_price(token)?;
+            return Ok(());
+        }
+        // 应急价格生效期间不读取预言机
+        if self._emergency_price(token).is_none() && self._observe_price(token)?.is_some() {
+            return Err(DSCEngineError::PriceCircuitBreakerTripped(
+                PriceCircuitBreakerTripped { token },
+            ));
+        }
+        Ok(())
+    }
+
+    // 铸造前检查仓位（或指定分桶）持有的所有抵押品的熔断状态
+    pub(crate) fn _check_price_breakers_of(
+        &mut self,
+        user: Address,
+        bucket: Option<Address>,
+    ) -> Result<(), DSCEngineError> {
+        for i in 0..self.collateral_tokens.len() {
+            if let Some(token) = self.collateral_tokens.get(i) {
+                if bucket.is_some_and(|bucket| bucket != token) {
+                    continue;
+                }
+                if self._collateral_amount_of(user, token) == U256::ZERO {
+                    continue;
+                }
+                self._check_price_breaker(token)?;
+            }
+        }
+        Ok(())
+    }
+
+    // 检测价格偏离，偏离超过阈值时触发熔断并发出告警，返回熔断是否生效
+    pub(crate) fn _poke_price_breaker(&mut self, token: Address) -> Result<bool, DSCEngineError> {
+        if self._price_breaker_active(token) {
+            return Ok(true);
+        }
+        let Some((last_price, price)) = self._observe_price(token)? else {
+            return Ok(false);
+        };
+        self.price_breaker_tripped_at
+            .setter(token)
+            .set(U256::from(block::timestamp()));
+        evm::log(PriceDeviationAlert {
+            token,
+            lastPrice: last_price,
+            price,
+        });
+        Ok(true)
+    }
+
+    // 解除熔断，并以当前价格作为新的基准
+    pub(crate) fn _clear_price_breaker(&mut self, token: Address) -> Result<(), DSCEngineError> {
+        self.price_breaker_tripped_at.setter(token).set(U256::ZERO);
+        let price = self._accept_current_price(token)?;
+        evm::log(PriceBreakerCleared { token, price });
+        Ok(())
+    }
+
+    // 把价格来源当前的价格记为最近一次接受的价格
+    fn _accept_current_price(&mut self, token: Address) -> Result<U256, DSCEngineError> {
+        let feed = self.price_feeds.get(token);
+        let price = self._source_price(token)?;
+        self.last_accepted_price.setter(feed).set(price);
+        self.last_accepted_price_time
+            .setter(feed)
+            .set(U256::from(block::timestamp()));
+        Ok(price)
+    }
+}
@@ -0,0 +1,59 @@
+//! 预言机回滚时的最近有效价格
+//!
+//! 每次修改状态的调用计提稳定费时，记录各抵押品预言机最近一次有效的报价和更新时间。
+//! 之后 `latestRoundData` 调用回滚时，若最近有效价格的更新时间仍在治理设定的窗口内，
+//! 以它代替预言机报价，避免聚合器短暂故障时偿还和健康因子计算全部停摆。
+//! 窗口为零时不启用回退；清算前的预言机中断检查仍直接读取预言机，不使用回退价格。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, I256, U256},
+    block,
+    prelude::*,
+};
+
+use crate::{casting::answer_to_price, DSCEngine};
+
+sol_storage! {
+    // 预言机最近一次有效的报价
+    pub struct GoodPrice {
+        uint256 price;                       // 报价（预言机精度）
+        uint256 updated_at;                  // 报价的更新时间
+    }
+}
+
+impl DSCEngine {
+    // 记录抵押品预言机当前的报价，报价有效且比已记录的更新时才写入
+    pub(crate) fn _record_good_price(&mut self, token: Address) {
+        let feed = self.price_feeds.get(token);
+        let Some(round) = self._feed_round(feed) else {
+            return;
+        };
+        let Ok(price) = answer_to_price(round.answer) else {
+            return;
+        };
+        if price == U256::ZERO
+            || round.updated_at <= self.last_good_prices.getter(feed).updated_at.get()
+        {
+            return;
+        }
+        let mut good_price = self.last_good_prices.setter(feed);
+        good_price.price.set(price);
+        good_price.updated_at.set(round.updated_at);
+    }
+
+    // 预言机回滚时可用的最近有效报价和更新时间，超出窗口或未启用时为 None
+    pub(crate) fn _fallback_answer(&self, feed: Address) -> Option<(I256, U256)> {
+        let window = self.price_fallback_window.get();
+        if window == U256::ZERO {
+            return None;
+        }
+        let good_price = self.last_good_prices.getter(feed);
+        let updated_at = good_price.updated_at.get();
+        if updated_at == U256::ZERO
+            || U256::from(block::timestamp()).saturating_sub(updated_at) > window
+        {
+            return None;
+        }
+        Some((I256::from_raw(good_price.price.get()), updated_at))
+    }
+}
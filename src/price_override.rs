@@ -0,0 +1,94 @@
+//! 守护者设置的应急价格
+//!
+//! 预言机长时间中断时，守护者可以为抵押品设置临时的人工价格，让铸造、赎回和清算继续进行。
+//! 应急价格相对预言机最近一次报价（读取失败时为熔断模块最近接受的价格）的偏离不能超过治理设定的上限，
+//! 有效期不能超过治理设定的最长时长，到期后自动失效，恢复使用预言机价格。
+//! 设置和撤销都会发出事件，供链下监控。应急价格以预言机精度表示，
+//! 生效期间替代预言机报价，汇率合约和 ERC-4626 份额换算仍照常叠加。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    block, evm,
+    prelude::*,
+};
+
+use crate::{
+    meta_tx::msg_sender, psm::BPS, DSCEngine, DSCEngineError, EmergencyPriceCleared,
+    EmergencyPriceSet, InvalidEmergencyPrice, PriceFeedError,
+};
+
+sol_storage! {
+    // 抵押品的应急价格，失效时间不晚于当前时间表示未生效
+    pub struct PriceOverride {
+        uint256 price;                       // 应急价格（预言机精度）
+        uint256 expires_at;                  // 失效时间
+    }
+}
+
+impl DSCEngine {
+    // 抵押品当前生效的应急价格（预言机精度）
+    pub(crate) fn _emergency_price(&self, token: Address) -> Option<U256> {
+        let price_override = self.price_overrides.getter(token);
+        if U256::from(block::timestamp()) < price_override.expires_at.get() {
+            Some(price_override.price.get())
+        } else {
+            None
+        }
+    }
+
+    // 抵押品价格来源最近一次报价（预言机精度），读取失败时为熔断模块最近接受的价格
+    pub(crate) fn _last_known_price(&self, token: Address) -> Result<U256, DSCEngineError> {
+        match self._source_price(token) {
+            Err(DSCEngineError::PriceFeedError(_)) => {}
+            result => return result,
+        }
+        let last_price = self.last_accepted_price.get(self.price_feeds.get(token));
+        if last_price == U256::ZERO {
+            return Err(DSCEngineError::PriceFeedError(PriceFeedError {}));
+        }
+        Ok(last_price)
+    }
+
+    // 设置应急价格，偏离参考价格或有效期超过上限时回滚
+    pub(crate) fn _set_emergency_price(
+        &mut self,
+        token: Address,
+        price: U256,
+        duration: U256,
+    ) -> Result<(), DSCEngineError> {
+        let reference_price = self._last_known_price(token)?;
+        let max_deviation = self.max_override_deviation.get();
+        if price == U256::ZERO
+            || duration == U256::ZERO
+            || duration > self.max_override_duration.get()
+            || price.abs_diff(reference_price) * U256::from(BPS) > reference_price * max_deviation
+        {
+            return Err(DSCEngineError::InvalidEmergencyPrice(
+                InvalidEmergencyPrice {},
+            ));
+        }
+        let expires_at = U256::from(block::timestamp()) + duration;
+        let mut price_override = self.price_overrides.setter(token);
+        price_override.price.set(price);
+        price_override.expires_at.set(expires_at);
+        evm::log(EmergencyPriceSet {
+            token,
+            price,
+            referencePrice: reference_price,
+            expiresAt: expires_at,
+            guardian: msg_sender(),
+        });
+        Ok(())
+    }
+
+    // 提前撤销应急价格，恢复使用预言机价格
+    pub(crate) fn _clear_emergency_price(&mut self, token: Address) {
+        let mut price_override = self.price_overrides.setter(token);
+        price_override.price.set(U256::ZERO);
+        price_override.expires_at.set(U256::ZERO);
+        evm::log(EmergencyPriceCleared {
+            token,
+            by: msg_sender(),
+        });
+    }
+}
@@ -0,0 +1,248 @@
+//! 可插拔的价格来源
+//!
+//! 每种抵押品按治理配置的价格来源取价，估值和健康因子计算只通过 `_source_price` 读取价格，
+//! 增加新的预言机类型只需扩展 `PriceSource` 及其取价分支。所有来源都返回预言机精度（8 位）的价格：
+//!
+//! - Chainlink 推送（默认）：读取抵押品登记的价格预言机，沿用交易内缓存和回滚时的最近有效价格；
+//! - Pyth：按价格 ID 读取不早于最长报价时间的报价，按报价指数换算为 8 位精度；
+//! - TWAP：读取 TWAP 预言机在平均窗口内的时间加权平均价格；
+//! - 组合：登记的预言机报价（如 token/ETH）乘以报价预言机的报价（如 ETH/USD）。
+//!
+//! 应急价格、汇率合约和 ERC-4626 份额换算仍照常叠加在来源价格之上，价格偏离熔断也按来源价格检测。
+//! 更换来源时新来源必须能给出有效报价。
+
+use alloy_sol_types::{sol, SolCall};
+use stylus_sdk::{
+    alloy_primitives::{Address, B256, I256, U256, U8},
+    call::{self, Call},
+    evm,
+    prelude::*,
+};
+
+use crate::{
+    casting::{answer_to_price, to_u256},
+    math::{mul_div, Rounding},
+    ConversionError, DSCEngine, DSCEngineError, IAggregatorV3, InvalidPriceSource, PriceFeedError,
+    PriceSourceSet, ADDITIONAL_FEED_PRECISION,
+};
+
+// 价格来源类型编号
+pub const PRICE_SOURCE_CHAINLINK: u8 = 0;
+pub const PRICE_SOURCE_PYTH: u8 = 1;
+pub const PRICE_SOURCE_TWAP: u8 = 2;
+pub const PRICE_SOURCE_COMPOSED: u8 = 3;
+
+// 预言机精度的小数位数
+const FEED_DECIMALS: i64 = 8;
+
+sol! {
+    // Pyth 预言机接口：报价超过最长报价时间时回滚。
+    // sol_interface! 按 `bytes[32]` 计算 bytes32 参数的选择器，这里直接编码调用数据
+    interface IPyth {
+        function getPriceNoOlderThan(bytes32 id, uint256 age)
+    external
+    view
+    returns (int64 price, uint64 conf, int32 expo, uint256 publishTime);
+    }
+}
+
+sol_interface! {
+    // TWAP 预言机接口：返回平均窗口内的时间加权平均价格（预言机精度）
+    interface ITwapOracle {
+        function getTwap(uint32 window) external view returns (uint256);
+    }
+}
+
+sol_storage! {
+    // 抵押品的价格来源配置，类型为零表示 Chainlink 推送
+    pub struct PriceSourceConfig {
+        uint8 kind;                          // 来源类型
+        address oracle;                      // 来源合约：Pyth 或 TWAP 预言机
+        bytes32 price_id;                    // Pyth 价格 ID
+        address quote_feed;                  // 组合来源的报价预言机
+        uint256 period;                      // Pyth 最长报价时间或 TWAP 平均窗口（秒）
+    }
+}
+
+// 抵押品的价格来源
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum PriceSource {
+    Chainlink,
+    Pyth {
+        oracle: Address,
+        price_id: B256,
+        max_age: U256,
+    },
+    Twap {
+        oracle: Address,
+        window: u32,
+    },
+    Composed {
+        quote_feed: Address,
+    },
+}
+
+impl PriceSource {
+    // 由来源类型和配置参数构造价格来源，类型未知或参数缺失时返回 None
+    pub(crate) fn new(
+        kind: u8,
+        oracle: Address,
+        price_id: B256,
+        quote_feed: Address,
+        period: U256,
+    ) -> Option<Self> {
+        match kind {
+            PRICE_SOURCE_CHAINLINK => Some(Self::Chainlink),
+            PRICE_SOURCE_PYTH if !oracle.is_zero() && price_id != B256::ZERO => Some(Self::Pyth {
+                oracle,
+                price_id,
+                max_age: period,
+            }),
+            PRICE_SOURCE_TWAP if !oracle.is_zero() && period != U256::ZERO => Some(Self::Twap {
+                oracle,
+                window: u32::try_from(period).ok()?,
+            }),
+            PRICE_SOURCE_COMPOSED if !quote_feed.is_zero() => Some(Self::Composed { quote_feed }),
+            _ => None,
+        }
+    }
+
+    // 来源类型编号
+    pub(crate) fn kind(&self) -> u8 {
+        match self {
+            Self::Chainlink => PRICE_SOURCE_CHAINLINK,
+            Self::Pyth { .. } => PRICE_SOURCE_PYTH,
+            Self::Twap { .. } => PRICE_SOURCE_TWAP,
+            Self::Composed { .. } => PRICE_SOURCE_COMPOSED,
+        }
+    }
+
+    // 存储的配置参数：(来源合约, Pyth 价格 ID, 报价预言机, 最长报价时间或平均窗口)
+    pub(crate) fn params(&self) -> (Address, B256, Address, U256) {
+        match *self {
+            Self::Chainlink => (Address::ZERO, B256::ZERO, Address::ZERO, U256::ZERO),
+            Self::Pyth {
+                oracle,
+                price_id,
+                max_age,
+            } => (oracle, price_id, Address::ZERO, max_age),
+            Self::Twap { oracle, window } => {
+                (oracle, B256::ZERO, Address::ZERO, U256::from(window))
+            }
+            Self::Composed { quote_feed } => (Address::ZERO, B256::ZERO, quote_feed, U256::ZERO),
+        }
+    }
+}
+
+// 检查价格放大到 18 位精度后不会溢出
+fn checked_price(price: Option<U256>) -> Result<U256, DSCEngineError> {
+    price
+        .filter(|price| price.checked_mul(ADDITIONAL_FEED_PRECISION).is_some())
+        .ok_or(DSCEngineError::ConversionError(ConversionError {}))
+}
+
+// 把 Pyth 报价（price × 10^expo）换算为预言机精度
+fn pyth_to_price(price: i64, expo: i32) -> Result<U256, DSCEngineError> {
+    let price = to_u256(I256::try_from(price).unwrap_or_default())?;
+    let shift = i64::from(expo) + FEED_DECIMALS;
+    let scale = U256::from(10).checked_pow(U256::from(shift.unsigned_abs()));
+    checked_price(if shift >= 0 {
+        scale.and_then(|scale| price.checked_mul(scale))
+    } else {
+        Some(scale.map_or(U256::ZERO, |scale| price / scale))
+    })
+}
+
+impl DSCEngine {
+    // 抵押品当前配置的价格来源
+    pub(crate) fn _price_source(&self, token: Address) -> PriceSource {
+        let config = self.price_sources.getter(token);
+        PriceSource::new(
+            config.kind.get().to::<u8>(),
+            config.oracle.get(),
+            config.price_id.get(),
+            config.quote_feed.get(),
+            config.period.get(),
+        )
+        .unwrap_or(PriceSource::Chainlink)
+    }
+
+    // 读取 Chainlink 预言机的价格（预言机精度），回滚时使用窗口内的最近有效价格
+    fn _chainlink_price(&self, feed: Address) -> Result<U256, DSCEngineError> {
+        let (answer, _) = self
+            ._feed_answer(feed)
+            .ok_or(DSCEngineError::PriceFeedError(PriceFeedError {}))?;
+        answer_to_price(answer)
+    }
+
+    // 按抵押品配置的价格来源读取价格（预言机精度）
+    pub(crate) fn _source_price(&self, token: Address) -> Result<U256, DSCEngineError> {
+        let feed = self.price_feeds.get(token);
+        match self._price_source(token) {
+            PriceSource::Chainlink => self._chainlink_price(feed),
+            PriceSource::Pyth {
+                oracle,
+                price_id,
+                max_age,
+            } => {
+                let data = IPyth::getPriceNoOlderThanCall {
+                    id: price_id,
+                    age: max_age,
+                }
+                .abi_encode();
+                let quote = call::static_call(Call::new(), oracle, &data)
+                    .ok()
+                    .and_then(|returned| {
+                        IPyth::getPriceNoOlderThanCall::abi_decode_returns(&returned, true).ok()
+                    })
+                    .ok_or(DSCEngineError::PriceFeedError(PriceFeedError {}))?;
+                pyth_to_price(quote.price, quote.expo)
+            }
+            PriceSource::Twap { oracle, window } => {
+                let price = ITwapOracle::new(oracle)
+                    .get_twap(Call::new(), window)
+                    .map_err(|_| DSCEngineError::PriceFeedError(PriceFeedError {}))?;
+                checked_price(Some(price))
+            }
+            PriceSource::Composed { quote_feed } => {
+                let base_price = self._chainlink_price(feed)?;
+                let quote_price = self._chainlink_price(quote_feed)?;
+                // 登记的预言机以自身精度报价，乘积按其精度还原为报价预言机的精度
+                let base_decimals = IAggregatorV3::new(feed)
+                    .decimals(Call::new())
+                    .map_err(|_| DSCEngineError::PriceFeedError(PriceFeedError {}))?;
+                let unit = U256::from(10).pow(U256::from(base_decimals));
+                checked_price(Some(mul_div(base_price, quote_price, unit, Rounding::Down)))
+            }
+        }
+    }
+
+    // 更换抵押品的价格来源，新来源无法给出有效报价时回滚
+    pub(crate) fn _set_price_source(
+        &mut self,
+        token: Address,
+        source: PriceSource,
+    ) -> Result<(), DSCEngineError> {
+        let (oracle, price_id, quote_feed, period) = source.params();
+        {
+            let mut config = self.price_sources.setter(token);
+            config.kind.set(U8::from(source.kind()));
+            config.oracle.set(oracle);
+            config.price_id.set(price_id);
+            config.quote_feed.set(quote_feed);
+            config.period.set(period);
+        }
+        let price = self._source_price(token)?;
+        if price == U256::ZERO {
+            return Err(DSCEngineError::InvalidPriceSource(InvalidPriceSource {}));
+        }
+        evm::log(PriceSourceSet {
+            token,
+            kind: source.kind(),
+            oracle,
+            quoteFeed: quote_feed,
+            price,
+        });
+        Ok(())
+    }
+}
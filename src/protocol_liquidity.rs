@@ -0,0 +1,239 @@
+//! 协议自有流动性：用国库资金在 DEX 上为 DSC/USDC 池提供流动性
+//!
+//! 治理转入 USDC（PSM 代币），引擎按 1:1 铸造等值的 DSC，一起经 DEX 路由注入流动性池，
+//! 得到的 LP 代币留在引擎中记账。铸造的 DSC 由配对的 USDC 支撑，受单独的债务上限约束。
+//! 路由未用完的 USDC 退回，未用完的 DSC 直接销毁，债务只记实际注入的 DSC。
+//!
+//! 撤出流动性时按比例结清债务：取回的 DSC 先销毁以偿还对应债务，超出部分计入盈余；
+//! 取回的 DSC 不足时，缺口部分仍在市场流通，从取回的 USDC 中划出等值部分转入 PSM 储备，
+//! 使这部分 DSC 仍可按 1:1 换回。其余 USDC 退给治理。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    block,
+    call::Call,
+    contract, evm,
+};
+
+use crate::{
+    math::{mul_div, Rounding},
+    meta_tx::msg_sender,
+    surplus::SURPLUS_PROTOCOL_LIQUIDITY,
+    BurnFailed, DSCEngine, DSCEngineError, DexRouterNotSet, IDecentralizedStableCoin,
+    InsufficientProtocolLiquidity, MintFailed, PolDebtCeilingExceeded, PolNotConfigured,
+    ProtocolLiquidityAdded, ProtocolLiquidityRemoved, PsmNotConfigured, SlippageExceeded,
+    SwapFailed, TransferFailed, IERC20, PRECISION,
+};
+
+use router::IUniswapV2LiquidityRouter;
+
+// 路由接口的参数个数由 Uniswap V2 决定，生成的绑定方法超过 clippy 的参数个数上限
+#[allow(clippy::too_many_arguments)]
+mod router {
+    use stylus_sdk::prelude::*;
+
+    sol_interface! {
+        // Uniswap V2 / Camelot 风格路由的流动性接口
+        interface IUniswapV2LiquidityRouter {
+            function addLiquidity(
+                address token_a,
+                address token_b,
+                uint256 amount_a_desired,
+                uint256 amount_b_desired,
+                uint256 amount_a_min,
+                uint256 amount_b_min,
+                address to,
+                uint256 deadline
+            ) external returns (uint256 amount_a, uint256 amount_b, uint256 liquidity);
+            function removeLiquidity(
+                address token_a,
+                address token_b,
+                uint256 liquidity,
+                uint256 amount_a_min,
+                uint256 amount_b_min,
+                address to,
+                uint256 deadline
+            ) external returns (uint256 amount_a, uint256 amount_b);
+        }
+    }
+}
+
+impl DSCEngine {
+    // 注入流动性所需的配置：(路由, LP 代币, USDC)
+    fn _pol_config(&self) -> Result<(Address, Address, Address), DSCEngineError> {
+        let router = self.dex_router.get();
+        if router.is_zero() {
+            return Err(DSCEngineError::DexRouterNotSet(DexRouterNotSet {}));
+        }
+        let pair = self.pol_pair.get();
+        if pair.is_zero() {
+            return Err(DSCEngineError::PolNotConfigured(PolNotConfigured {}));
+        }
+        let usdc = self.psm_token.get();
+        if usdc.is_zero() {
+            return Err(DSCEngineError::PsmNotConfigured(PsmNotConfigured {}));
+        }
+        Ok((router, pair, usdc))
+    }
+
+    // 转入 USDC、铸造等值 DSC 并注入流动性池，返回得到的 LP 代币数量
+    pub(crate) fn _add_protocol_liquidity(
+        &mut self,
+        usdc_amount: U256,
+        min_liquidity: U256,
+    ) -> Result<U256, DSCEngineError> {
+        let (router, pair, usdc) = self._pol_config()?;
+        let dsc = self.dsc.get();
+        let engine = contract::address();
+        let sender = msg_sender();
+        let dsc_amount = self._psm_to_dsc_amount(usdc_amount);
+        if self.pol_debt.get() + dsc_amount > self.pol_debt_ceiling.get() {
+            return Err(DSCEngineError::PolDebtCeilingExceeded(
+                PolDebtCeilingExceeded {},
+            ));
+        }
+        if IERC20::new(usdc)
+            .transfer_from(Call::new(), sender, engine, usdc_amount)
+            .is_err()
+        {
+            return Err(DSCEngineError::TransferFailed(TransferFailed {}));
+        }
+        IDecentralizedStableCoin::new(dsc)
+            .mint(Call::new(), engine, dsc_amount)
+            .map_err(|_| DSCEngineError::MintFailed(MintFailed {}))?;
+
+        let dsc_before = self._engine_balance(dsc);
+        let usdc_before = self._engine_balance(usdc);
+        let liquidity_before = self._engine_balance(pair);
+        if IERC20::new(dsc)
+            .approve(Call::new(), router, dsc_amount)
+            .is_err()
+            || IERC20::new(usdc)
+                .approve(Call::new(), router, usdc_amount)
+                .is_err()
+        {
+            return Err(DSCEngineError::SwapFailed(SwapFailed {}));
+        }
+        IUniswapV2LiquidityRouter::new(router)
+            .add_liquidity(
+                Call::new(),
+                dsc,
+                usdc,
+                dsc_amount,
+                usdc_amount,
+                U256::ZERO,
+                U256::ZERO,
+                engine,
+                U256::from(block::timestamp()),
+            )
+            .map_err(|_| DSCEngineError::SwapFailed(SwapFailed {}))?;
+        let dsc_used = dsc_before.saturating_sub(self._engine_balance(dsc));
+        let usdc_used = usdc_before.saturating_sub(self._engine_balance(usdc));
+        let liquidity = self._engine_balance(pair).saturating_sub(liquidity_before);
+        if liquidity < min_liquidity {
+            return Err(DSCEngineError::SlippageExceeded(SlippageExceeded {
+                _0: liquidity,
+            }));
+        }
+
+        // 未用完的 DSC 销毁，未用完的 USDC 退回
+        if dsc_amount > dsc_used {
+            IDecentralizedStableCoin::new(dsc)
+                .burn(Call::new(), dsc_amount - dsc_used)
+                .map_err(|_| DSCEngineError::BurnFailed(BurnFailed {}))?;
+        }
+        if usdc_amount > usdc_used
+            && IERC20::new(usdc)
+                .transfer(Call::new(), sender, usdc_amount - usdc_used)
+                .is_err()
+        {
+            return Err(DSCEngineError::TransferFailed(TransferFailed {}));
+        }
+        self.pol_debt.set(self.pol_debt.get() + dsc_used);
+        self.pol_liquidity.set(self.pol_liquidity.get() + liquidity);
+        evm::log(ProtocolLiquidityAdded {
+            dscAmount: dsc_used,
+            usdcAmount: usdc_used,
+            liquidity,
+        });
+        Ok(liquidity)
+    }
+
+    // 撤出流动性并按比例结清债务，返回退给治理的 USDC 数量
+    pub(crate) fn _remove_protocol_liquidity(
+        &mut self,
+        liquidity: U256,
+        min_usdc: U256,
+    ) -> Result<U256, DSCEngineError> {
+        let (router, pair, usdc) = self._pol_config()?;
+        let total_liquidity = self.pol_liquidity.get();
+        if liquidity > total_liquidity {
+            return Err(DSCEngineError::InsufficientProtocolLiquidity(
+                InsufficientProtocolLiquidity {},
+            ));
+        }
+        let dsc = self.dsc.get();
+        let engine = contract::address();
+        let dsc_before = self._engine_balance(dsc);
+        let usdc_before = self._engine_balance(usdc);
+        if IERC20::new(pair)
+            .approve(Call::new(), router, liquidity)
+            .is_err()
+        {
+            return Err(DSCEngineError::SwapFailed(SwapFailed {}));
+        }
+        IUniswapV2LiquidityRouter::new(router)
+            .remove_liquidity(
+                Call::new(),
+                dsc,
+                usdc,
+                liquidity,
+                U256::ZERO,
+                min_usdc,
+                engine,
+                U256::from(block::timestamp()),
+            )
+            .map_err(|_| DSCEngineError::SwapFailed(SwapFailed {}))?;
+        let dsc_out = self._engine_balance(dsc).saturating_sub(dsc_before);
+        let usdc_out = self._engine_balance(usdc).saturating_sub(usdc_before);
+        if usdc_out < min_usdc {
+            return Err(DSCEngineError::SlippageExceeded(SlippageExceeded {
+                _0: usdc_out,
+            }));
+        }
+
+        let debt = self.pol_debt.get();
+        let debt_share = mul_div(debt, liquidity, total_liquidity, Rounding::Up).min(debt);
+        let burned = dsc_out.min(debt_share);
+        if burned > U256::ZERO {
+            IDecentralizedStableCoin::new(dsc)
+                .burn(Call::new(), burned)
+                .map_err(|_| DSCEngineError::BurnFailed(BurnFailed {}))?;
+        }
+        self._add_surplus(SURPLUS_PROTOCOL_LIQUIDITY, dsc_out - burned);
+        // 仍在流通的 DSC 由转入 PSM 储备的 USDC 支撑
+        let shortfall = debt_share - burned;
+        let usdc_unit = U256::from(10).pow(U256::from(self.psm_token_decimals.get().to::<u8>()));
+        let backing = mul_div(shortfall, usdc_unit, PRECISION, Rounding::Up).min(usdc_out);
+        if shortfall > U256::ZERO {
+            self.psm_debt.set(self.psm_debt.get() + shortfall);
+            self.psm_reserves.set(self.psm_reserves.get() + backing);
+        }
+        let returned = usdc_out - backing;
+        if returned > U256::ZERO
+            && IERC20::new(usdc)
+                .transfer(Call::new(), msg_sender(), returned)
+                .is_err()
+        {
+            return Err(DSCEngineError::TransferFailed(TransferFailed {}));
+        }
+        self.pol_debt.set(debt - debt_share);
+        self.pol_liquidity.set(total_liquidity - liquidity);
+        evm::log(ProtocolLiquidityRemoved {
+            liquidity,
+            dscAmount: dsc_out,
+            usdcAmount: usdc_out,
+        });
+        Ok(returned)
+    }
+}
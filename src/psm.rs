@@ -0,0 +1,127 @@
+//! 锚定稳定模块（PSM）：按 1:1 兑换 USDC 与 DSC
+//!
+//! 换入 USDC 时按其精度折算铸造 DSC，换出时销毁 DSC 取回 USDC；
+//! 手续费以 DSC 计，留在引擎中计入盈余缓冲。PSM 铸造的 DSC 受债务上限约束，
+//! USDC 储备按内部记账，不依赖 `balanceOf`。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    call::Call,
+    contract, evm,
+};
+
+use crate::{
+    meta_tx::msg_sender, surplus::SURPLUS_PSM_FEE, BurnFailed, DSCEngine, DSCEngineError,
+    IDecentralizedStableCoin, InsufficientPsmReserves, MintFailed, PsmDebtCeilingExceeded,
+    PsmNotConfigured, PsmSwap, TransferFailed, IERC20, PRECISION,
+};
+
+pub(crate) const BPS: u64 = 10_000;
+
+impl DSCEngine {
+    // 将 PSM 代币数量折算为 18 位精度的 DSC 数量
+    pub(crate) fn _psm_to_dsc_amount(&self, gem_amount: U256) -> U256 {
+        let gem_unit = U256::from(10).pow(U256::from(self.psm_token_decimals.get().to::<u8>()));
+        (gem_amount * PRECISION) / gem_unit
+    }
+
+    // 存入 PSM 代币，按 1:1 铸造扣除手续费后的 DSC，返回用户获得的 DSC 数量
+    pub(crate) fn _psm_swap_to_dsc(&mut self, gem_amount: U256) -> Result<U256, DSCEngineError> {
+        let gem = self.psm_token.get();
+        if gem.is_zero() {
+            return Err(DSCEngineError::PsmNotConfigured(PsmNotConfigured {}));
+        }
+        let dsc_amount = self._psm_to_dsc_amount(gem_amount);
+        // 检查 PSM 债务上限
+        let psm_debt = self.psm_debt.get() + dsc_amount;
+        if psm_debt > self.psm_debt_ceiling.get() {
+            return Err(DSCEngineError::PsmDebtCeilingExceeded(
+                PsmDebtCeilingExceeded {},
+            ));
+        }
+        let fee = (dsc_amount * self.psm_fee_in.get()) / U256::from(BPS);
+        let dsc_out = dsc_amount - fee;
+        self.psm_debt.set(psm_debt);
+        self.psm_reserves.set(self.psm_reserves.get() + gem_amount);
+
+        let sender = msg_sender();
+        // 从用户地址转入 PSM 代币
+        if IERC20::new(gem)
+            .transfer_from(Call::new(), sender, contract::address(), gem_amount)
+            .is_err()
+        {
+            return Err(DSCEngineError::TransferFailed(TransferFailed {}));
+        }
+        // 铸造 DSC，手续费部分铸造给引擎自身
+        let dsc = IDecentralizedStableCoin::new(self.dsc.get());
+        dsc.mint(Call::new(), sender, dsc_out)
+            .map_err(|_| DSCEngineError::MintFailed(MintFailed {}))?;
+        if fee > U256::ZERO {
+            dsc.mint(Call::new(), contract::address(), fee)
+                .map_err(|_| DSCEngineError::MintFailed(MintFailed {}))?;
+            self._add_surplus(SURPLUS_PSM_FEE, fee);
+        }
+        evm::log(PsmSwap {
+            user: sender,
+            toDsc: true,
+            gemAmount: gem_amount,
+            dscAmount: dsc_out,
+            fee,
+        });
+        Ok(dsc_out)
+    }
+
+    // 支付 DSC（含手续费）取回 PSM 代币，返回用户支付的 DSC 数量
+    pub(crate) fn _psm_swap_from_dsc(&mut self, gem_amount: U256) -> Result<U256, DSCEngineError> {
+        let gem = self.psm_token.get();
+        if gem.is_zero() {
+            return Err(DSCEngineError::PsmNotConfigured(PsmNotConfigured {}));
+        }
+        let dsc_amount = self._psm_to_dsc_amount(gem_amount);
+        let fee = (dsc_amount * self.psm_fee_out.get()) / U256::from(BPS);
+        let dsc_in = dsc_amount + fee;
+        // 引擎还以抵押品、回购储备等形式持有同一代币，不能依赖转账失败来限制换出数量
+        let (Some(psm_debt), Some(psm_reserves)) = (
+            self.psm_debt.get().checked_sub(dsc_amount),
+            self.psm_reserves.get().checked_sub(gem_amount),
+        ) else {
+            return Err(DSCEngineError::InsufficientPsmReserves(
+                InsufficientPsmReserves {},
+            ));
+        };
+        self.psm_debt.set(psm_debt);
+        self.psm_reserves.set(psm_reserves);
+
+        let sender = msg_sender();
+        let dsc = IDecentralizedStableCoin::new(self.dsc.get());
+        // 收取 DSC，销毁兑换部分，手续费留在引擎中
+        if dsc
+            .transfer_from(Call::new(), sender, contract::address(), dsc_in)
+            .is_err()
+        {
+            return Err(DSCEngineError::TransferFailed(TransferFailed {}));
+        }
+        dsc.burn(Call::new(), dsc_amount)
+            .map_err(|_| DSCEngineError::BurnFailed(BurnFailed {}))?;
+        self._add_surplus(SURPLUS_PSM_FEE, fee);
+        if IERC20::new(gem)
+            .transfer(Call::new(), sender, gem_amount)
+            .is_err()
+        {
+            return Err(DSCEngineError::TransferFailed(TransferFailed {}));
+        }
+        evm::log(PsmSwap {
+            user: sender,
+            toDsc: false,
+            gemAmount: gem_amount,
+            dscAmount: dsc_in,
+            fee,
+        });
+        Ok(dsc_in)
+    }
+
+    // PSM 代币储备为空时才允许更换代币
+    pub(crate) fn _psm_token_replaceable(&self, token: Address) -> bool {
+        self.psm_token.get() == token || self.psm_reserves.get() == U256::ZERO
+    }
+}
@@ -0,0 +1,42 @@
+//! RAY 精度（1e27）的利率累计指数
+//!
+//! 每种抵押品的债务累计指数以 RAY 精度存储，按每秒费率复利增长：
+//! 新指数 = 旧指数 × (1 + 每秒费率)^经过秒数，幂运算用 `rpow` 二进制求幂，每一步按四舍五入取整。
+//! 治理设定的每秒费率仍为基础精度（1e18），计算前换算为 RAY 精度。
+
+use stylus_sdk::alloy_primitives::U256;
+
+use crate::math::{ray_mul, RAY};
+
+// 基础精度换算为 RAY 精度的倍数：1e9
+pub(crate) const WAD_TO_RAY: U256 = U256::from_limbs([1_000_000_000, 0, 0, 0]);
+
+// 以 base 为精度计算 x 的 n 次幂，每次乘法按四舍五入取整；乘积溢出时取最大值而不回绕
+pub(crate) fn rpow(mut x: U256, mut n: U256, base: U256) -> U256 {
+    if n == U256::ZERO {
+        return base;
+    }
+    if x == U256::ZERO {
+        return U256::ZERO;
+    }
+    let half = base / U256::from(2);
+    let mut z = if n.bit(0) { x } else { base };
+    n >>= 1;
+    while n > U256::ZERO {
+        x = x.saturating_mul(x).saturating_add(half) / base;
+        if n.bit(0) {
+            z = z.saturating_mul(x).saturating_add(half) / base;
+        }
+        n >>= 1;
+    }
+    z
+}
+
+// 按基础精度的每秒费率将指数复利 elapsed 秒
+pub(crate) fn compound(index: U256, rate_per_second: U256, elapsed: U256) -> U256 {
+    if rate_per_second == U256::ZERO || elapsed == U256::ZERO {
+        return index;
+    }
+    let factor = rpow(RAY + rate_per_second * WAD_TO_RAY, elapsed, RAY);
+    ray_mul(index, factor)
+}
@@ -0,0 +1,107 @@
+//! 分段利率策略：按抵押品的使用率计算稳定费率
+//!
+//! 使用率 = 抵押品分桶的总债务 / 该抵押品的借款能力（抵押品总价值 × 默认清算阈值），最高为 100%。
+//! 使用率不超过最优使用率时，费率从基础费率按斜率一线性上升；超过后按更陡的斜率二上升，
+//! 促使借款人偿还或补充抵押品。未设置策略的抵押品沿用治理设定的固定稳定费率。
+//! 费率均为每秒费率（基础精度），使用率以基础精度表示。
+//! 年化借款利率 = 当前每秒费率 × 一年的秒数（单利），供界面在铸造前报价。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    prelude::*,
+};
+
+use crate::math::{mul_div, ray_mul, wad_div, Rounding};
+use crate::{DSCEngine, LIQUIDATION_PRECISION, PRECISION};
+
+// 一年的秒数（365 天）
+const SECONDS_PER_YEAR: u64 = 31_536_000;
+
+sol_storage! {
+    // 单个抵押品的分段利率策略，最优使用率为零表示未设置
+    pub struct RateStrategy {
+        uint256 base_rate;                   // 基础费率：使用率为零时的每秒费率
+        uint256 slope1;                      // 斜率一：使用率达到最优使用率时在基础费率上增加的费率
+        uint256 slope2;                      // 斜率二：使用率达到 100% 时在最优点费率上增加的费率
+        uint256 optimal_utilization;         // 最优使用率：两段斜率的拐点
+    }
+}
+
+impl DSCEngine {
+    // 抵押品当前的每秒稳定费率：设置了分段利率策略时按当前使用率计算
+    pub(crate) fn _current_rate(&self, token: Address) -> U256 {
+        let strategy = self.rate_strategies.getter(token);
+        let optimal = strategy.optimal_utilization.get();
+        if optimal == U256::ZERO {
+            return self.stability_fee_rates.get(token);
+        }
+        let base_rate = strategy.base_rate.get();
+        let slope1 = strategy.slope1.get();
+        let utilization = self._utilization(token);
+        if utilization <= optimal {
+            return base_rate + (slope1 * utilization) / optimal;
+        }
+        let excess = utilization - optimal;
+        base_rate + slope1 + (strategy.slope2.get() * excess) / (PRECISION - optimal)
+    }
+
+    // 抵押品当前的年化借款利率（基础精度）
+    pub(crate) fn _borrow_apr(&self, token: Address) -> U256 {
+        self._current_rate(token) * U256::from(SECONDS_PER_YEAR)
+    }
+
+    // 用户仓位的加权年化借款利率（基础精度）：按各分桶债务加权；
+    // 没有债务时按各抵押品价值加权，即新铸造的债务分摊到各分桶的比例
+    pub(crate) fn _user_borrow_apr(&self, user: Address) -> U256 {
+        let mut weighted_apr = U256::ZERO;
+        let mut total_weight = U256::ZERO;
+        for i in 0..self.collateral_tokens.len() {
+            if let Some(token) = self.collateral_tokens.get(i) {
+                let debt = self._bucket_debt(user, token);
+                if debt > U256::ZERO {
+                    weighted_apr += debt * self._borrow_apr(token);
+                    total_weight += debt;
+                }
+            }
+        }
+        if total_weight == U256::ZERO {
+            for i in 0..self.collateral_tokens.len() {
+                if let Some(token) = self.collateral_tokens.get(i) {
+                    let amount = self._collateral_amount_of(user, token);
+                    if amount == U256::ZERO {
+                        continue;
+                    }
+                    let value = self.get_usd_value(token, amount);
+                    weighted_apr += value * self._borrow_apr(token);
+                    total_weight += value;
+                }
+            }
+        }
+        if total_weight == U256::ZERO {
+            return U256::ZERO;
+        }
+        weighted_apr / total_weight
+    }
+
+    // 抵押品的使用率（基础精度）：没有借款能力（如预言机中断）时视为零
+    pub(crate) fn _utilization(&self, token: Address) -> U256 {
+        let debt = ray_mul(
+            self.total_normalized_debt.get(token),
+            self._stored_debt_index(token),
+        );
+        if debt == U256::ZERO {
+            return U256::ZERO;
+        }
+        let collateral = self.total_deposited.get(token);
+        let capacity = mul_div(
+            self.get_usd_value(token, collateral),
+            self._liquidation_threshold(),
+            LIQUIDATION_PRECISION,
+            Rounding::Down,
+        );
+        if capacity == U256::ZERO {
+            return U256::ZERO;
+        }
+        wad_div(debt, capacity).min(PRECISION)
+    }
+}
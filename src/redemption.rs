@@ -0,0 +1,160 @@
+//! 赎回：DSC 持有者按预言机价格以面值兑换抵押品
+//!
+//! 有债务的仓位按健康因子从低到高链接成赎回列表（类似 Liquity 的 SortedTroves），
+//! 仓位的抵押品或债务变化时按当前健康因子重新插入，插入位置从仓位原来的位置开始查找。
+//! 价格变动不会自动更新列表，任何人都可以调用 `reinsert_redemption_position` 刷新过期的仓位。
+//!
+//! 赎回从列表头部开始，依次销毁赎回者的 DSC 偿还这些仓位的债务，并转出等值抵押品。
+//! 赎回手续费以抵押品计，留在被赎回的仓位中作为补偿。这为 DSC 提供了 1 美元的价格下限。
+//! 没有该抵押品或低于清算线的仓位被跳过，其余仓位的当前健康因子必须不递减，否则回滚；
+//! 单次赎回最多查看固定数量的仓位，gas 消耗不随仓位数量增长。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    evm,
+};
+
+use crate::{
+    meta_tx::msg_sender, psm::BPS, DSCEngine, DSCEngineError, NoRedeemablePositions, Redemption,
+    RedemptionFeeExceeded, UnsortedRedemptionList,
+};
+
+// 单次赎回最多查看的仓位数量，限制 gas 消耗
+const MAX_REDEMPTION_ITERATIONS: usize = 10;
+
+impl DSCEngine {
+    // 从赎回列表中移除用户
+    fn _unlink_redemption_position(&mut self, user: Address) {
+        if !self.redemption_listed.get(user) {
+            return;
+        }
+        let prev = self.redemption_prev.get(user);
+        let next = self.redemption_next.get(user);
+        self.redemption_next.setter(prev).set(next);
+        self.redemption_prev.setter(next).set(prev);
+        self.redemption_next.setter(user).set(Address::ZERO);
+        self.redemption_prev.setter(user).set(Address::ZERO);
+        self.redemption_keys.setter(user).set(U256::ZERO);
+        self.redemption_listed.setter(user).set(false);
+    }
+
+    // 按当前健康因子把有债务的仓位重新插入赎回列表，没有债务时移出列表；
+    // 从 hint（不在列表中时为仓位原来的前驱）开始双向查找插入位置
+    pub(crate) fn _sync_redemption_position(&mut self, user: Address, hint: Address) {
+        let mut prev = if !hint.is_zero() && hint != user && self.redemption_listed.get(hint) {
+            hint
+        } else {
+            self.redemption_prev.get(user)
+        };
+        self._unlink_redemption_position(user);
+        if user.is_zero() || self.dsc_minted.get(user) == U256::ZERO {
+            return;
+        }
+        // 价格不可用时排在列表头部，赎回遇到该仓位时回滚
+        let key = self._health_factor(user).unwrap_or(U256::ZERO);
+        while !prev.is_zero() && self.redemption_keys.get(prev) > key {
+            prev = self.redemption_prev.get(prev);
+        }
+        let mut next = self.redemption_next.get(prev);
+        while !next.is_zero() && self.redemption_keys.get(next) < key {
+            prev = next;
+            next = self.redemption_next.get(next);
+        }
+        self.redemption_next.setter(prev).set(user);
+        self.redemption_prev.setter(next).set(user);
+        self.redemption_next.setter(user).set(next);
+        self.redemption_prev.setter(user).set(prev);
+        self.redemption_keys.setter(user).set(key);
+        self.redemption_listed.setter(user).set(true);
+    }
+
+    // 仓位可被赎回时返回其健康因子：需持有该抵押品和债务，且未达清算线；价格不可用时回滚
+    fn _redeemable_health_factor(
+        &self,
+        user: Address,
+        token: Address,
+    ) -> Result<Option<U256>, DSCEngineError> {
+        if self.dsc_minted.get(user) == U256::ZERO
+            || self._collateral_amount_of(user, token) == U256::ZERO
+        {
+            return Ok(None);
+        }
+        let health_factor = self._health_factor(user)?;
+        // 低于清算线的仓位应通过清算处理
+        Ok((health_factor >= self._min_health_factor()).then_some(health_factor))
+    }
+
+    // 赎回的内部实现，返回赎回者获得的抵押品数量（调用前需先计提）
+    pub(crate) fn _redeem_dsc_for_collateral(
+        &mut self,
+        token: Address,
+        dsc_amount: U256,
+        max_fee: U256,
+    ) -> Result<U256, DSCEngineError> {
+        let fee_rate = self.redemption_fee.get();
+        if fee_rate > max_fee {
+            return Err(DSCEngineError::RedemptionFeeExceeded(
+                RedemptionFeeExceeded { _0: fee_rate },
+            ));
+        }
+        let redeemer = msg_sender();
+        let mut remaining = dsc_amount;
+        let mut collateral_out = U256::ZERO;
+        let mut fee_collateral = U256::ZERO;
+        let mut previous_health_factor = U256::ZERO;
+        let mut borrower = self.redemption_next.get(Address::ZERO);
+        for _ in 0..MAX_REDEMPTION_ITERATIONS {
+            if remaining == U256::ZERO || borrower.is_zero() {
+                break;
+            }
+            // 赎回后仓位会被重新插入，先记下当前的后继
+            let next = self.redemption_next.get(borrower);
+            let Some(health_factor) = self._redeemable_health_factor(borrower, token)? else {
+                borrower = next;
+                continue;
+            };
+            // 价格变动使列表顺序过期时回滚，需先刷新相关仓位
+            if health_factor < previous_health_factor {
+                return Err(DSCEngineError::UnsortedRedemptionList(
+                    UnsortedRedemptionList {},
+                ));
+            }
+            previous_health_factor = health_factor;
+            // 不超过仓位债务和该抵押品的价值
+            let collateral_value =
+                self._usd_value(token, self._collateral_amount_of(borrower, token))?;
+            let redeemed = remaining
+                .min(self._liquidatable_debt(borrower, token))
+                .min(collateral_value);
+            let collateral = self._token_amount_from_usd(token, redeemed)?;
+            if redeemed == U256::ZERO || collateral == U256::ZERO {
+                break;
+            }
+            let fee = (collateral * fee_rate) / U256::from(BPS);
+            self._burn_dsc(
+                redeemed,
+                borrower,
+                redeemer,
+                self._debt_bucket(borrower, token),
+            )?;
+            self._redeem_collateral(token, collateral - fee, borrower, redeemer)?;
+            remaining -= redeemed;
+            collateral_out += collateral - fee;
+            fee_collateral += fee;
+            borrower = next;
+        }
+        if remaining == dsc_amount {
+            return Err(DSCEngineError::NoRedeemablePositions(
+                NoRedeemablePositions {},
+            ));
+        }
+        evm::log(Redemption {
+            redeemer,
+            token,
+            dscAmount: dsc_amount - remaining,
+            collateralAmount: collateral_out,
+            fee: fee_collateral,
+        });
+        Ok(collateral_out)
+    }
+}
@@ -0,0 +1,93 @@
+//! 推荐人返佣
+//!
+//! 用户铸造时可以附带推荐人地址，首次附带的有效推荐人永久记在该用户名下，之后不能更改。
+//! 该用户支付的铸造手续费和偿还的稳定费利息中，治理设定比例（基点）的部分记为推荐人的返佣，
+//! 以 DSC 留在引擎中，由推荐人随时领取；其余部分照常进入国库或盈余缓冲。
+//! 返佣比例为零时不分成；推荐人不能是用户自己。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    call::Call,
+    contract, evm,
+};
+
+use crate::{
+    psm::BPS, surplus::SURPLUS_STABILITY_FEE, DSCEngine, DSCEngineError, IDecentralizedStableCoin,
+    MintFailed, ReferralRewardAccrued, ReferralRewardsClaimed, ReferrerSet, TransferFailed,
+};
+
+impl DSCEngine {
+    // 记录用户的推荐人：只在用户尚无推荐人且推荐人有效时写入
+    pub(crate) fn _set_referrer(&mut self, user: Address, referrer: Address) {
+        if referrer.is_zero() || referrer == user || !self.referrers.get(user).is_zero() {
+            return;
+        }
+        self.referrers.insert(user, referrer);
+        evm::log(ReferrerSet { user, referrer });
+    }
+
+    // 把用户支付的一笔手续费中推荐人的份额记为返佣，返回记入的数量（DSC 须已在引擎中）
+    fn _accrue_referral_reward(&mut self, user: Address, fee: U256) -> U256 {
+        let referrer = self.referrers.get(user);
+        if referrer.is_zero() {
+            return U256::ZERO;
+        }
+        let reward = (fee * self.referral_share.get()) / U256::from(BPS);
+        if reward == U256::ZERO {
+            return U256::ZERO;
+        }
+        let accrued = self.referral_rewards.get(referrer);
+        self.referral_rewards.setter(referrer).set(accrued + reward);
+        evm::log(ReferralRewardAccrued {
+            referrer,
+            user,
+            amount: reward,
+        });
+        reward
+    }
+
+    // 分配用户的铸造手续费：推荐人份额铸造到引擎记为返佣，其余铸造给国库
+    pub(crate) fn _distribute_mint_fee(
+        &mut self,
+        user: Address,
+        fee: U256,
+    ) -> Result<(), DSCEngineError> {
+        let reward = self._accrue_referral_reward(user, fee);
+        let dsc = IDecentralizedStableCoin::new(self.dsc.get());
+        if reward > U256::ZERO {
+            dsc.mint(Call::new(), contract::address(), reward)
+                .map_err(|_| DSCEngineError::MintFailed(MintFailed {}))?;
+        }
+        if fee > reward {
+            dsc.mint(Call::new(), self.treasury.get(), fee - reward)
+                .map_err(|_| DSCEngineError::MintFailed(MintFailed {}))?;
+        }
+        Ok(())
+    }
+
+    // 记录用户偿还的稳定费利息：推荐人份额记为返佣，其余计入盈余
+    pub(crate) fn _add_interest_revenue(&mut self, user: Address, interest: U256) {
+        let reward = self._accrue_referral_reward(user, interest);
+        self._add_surplus(SURPLUS_STABILITY_FEE, interest - reward);
+    }
+
+    // 把推荐人待领取的返佣转给推荐人，返回转出的数量
+    pub(crate) fn _claim_referral_rewards(
+        &mut self,
+        referrer: Address,
+    ) -> Result<U256, DSCEngineError> {
+        let amount = self.referral_rewards.get(referrer);
+        if amount == U256::ZERO {
+            return Ok(U256::ZERO);
+        }
+        self.referral_rewards.setter(referrer).set(U256::ZERO);
+        if IDecentralizedStableCoin::new(self.dsc.get())
+            .transfer(Call::new(), referrer, amount)
+            .is_err()
+        {
+            return Err(DSCEngineError::TransferFailed(TransferFailed {}));
+        }
+        evm::log(ReferralRewardsClaimed { referrer, amount });
+        Ok(amount)
+    }
+}
@@ -0,0 +1,147 @@
+//! 跨链抵押品：把其他链上锁定的抵押品记入本链仓位
+//!
+//! 中心-分支部署中，用户在分支链上把抵押品锁定到分支合约，由治理配置的跨链消息适配器
+//! 验证消息后调用 `receive_collateral_attestation`，把抵押品记入用户在本链的仓位。
+//! 每种远程抵押品在本链登记为一个别名地址，使用自己的价格预言机和精度，与本链抵押品一样计入
+//! 健康因子和清算；赎回、清算或偿付赎回时，别名抵押品不在本链转账，而是通过消息适配器通知
+//! 分支链把抵押品释放给接收者。每条远程链记入的抵押品总价值不能超过该链的上限。
+//! 稳定池清算、拍卖、闪电清算和仓位迁移需要在本链转移抵押品，不支持远程抵押品。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, B256, U256, U8},
+    call::Call,
+    evm, msg,
+    prelude::*,
+};
+
+use crate::{
+    DSCEngine, DSCEngineError, InvalidRemoteCollateral, NotRemoteMessenger, RemoteChainCapExceeded,
+    RemoteCollateralAttested, RemoteCollateralReleased, RemoteCollateralUnsupported,
+    RemoteReleaseFailed,
+};
+
+sol_interface! {
+    // 跨链消息适配器：通知远程链释放锁定的抵押品
+    interface IRemoteCollateralMessenger {
+        function sendRelease(uint256 chain_id, address to, address token, uint256 amount) external;
+    }
+}
+
+impl DSCEngine {
+    // 抵押品是否为远程抵押品的别名
+    pub(crate) fn _is_remote_collateral(&self, token: Address) -> bool {
+        self.remote_collateral_chain.get(token) != U256::ZERO
+    }
+
+    // 需要在本链转移抵押品的操作不支持远程抵押品
+    pub(crate) fn _require_local_collateral(&self, token: Address) -> Result<(), DSCEngineError> {
+        if self._is_remote_collateral(token) {
+            return Err(DSCEngineError::RemoteCollateralUnsupported(
+                RemoteCollateralUnsupported {},
+            ));
+        }
+        Ok(())
+    }
+
+    // 登记远程抵押品的别名地址
+    pub(crate) fn _add_remote_collateral(
+        &mut self,
+        token: Address,
+        chain_id: U256,
+        price_feed: Address,
+        decimals: u8,
+    ) -> Result<(), DSCEngineError> {
+        if token.is_zero()
+            || price_feed.is_zero()
+            || chain_id == U256::ZERO
+            || !self.price_feeds.get(token).is_zero()
+        {
+            return Err(DSCEngineError::InvalidRemoteCollateral(
+                InvalidRemoteCollateral {},
+            ));
+        }
+        self.remote_collateral_chain.insert(token, chain_id);
+        self.collateral_decimals.insert(token, U8::from(decimals));
+        self.price_feeds.insert(token, price_feed);
+        self.collateral_tokens.push(token);
+        Ok(())
+    }
+
+    // 远程链上已记入本链的抵押品总价值，价格不可用时回滚
+    pub(crate) fn _remote_chain_value(&self, chain_id: U256) -> Result<U256, DSCEngineError> {
+        let mut total_value = U256::ZERO;
+        for i in 0..self.collateral_tokens.len() {
+            if let Some(token) = self.collateral_tokens.get(i) {
+                if self.remote_collateral_chain.get(token) != chain_id {
+                    continue;
+                }
+                let amount = self.total_collateral_deposited.get(token);
+                if amount > U256::ZERO {
+                    total_value += self._usd_value(token, amount)?;
+                }
+            }
+        }
+        Ok(total_value)
+    }
+
+    // 消息适配器转来远程链的存入证明，记入用户仓位
+    pub(crate) fn _receive_collateral_attestation(
+        &mut self,
+        message_id: B256,
+        chain_id: U256,
+        user: Address,
+        token: Address,
+        amount: U256,
+    ) -> Result<(), DSCEngineError> {
+        let messenger = self.remote_messenger.get();
+        if messenger.is_zero() || msg::sender() != messenger {
+            return Err(DSCEngineError::NotRemoteMessenger(NotRemoteMessenger {}));
+        }
+        if self.processed_attestations.get(message_id)
+            || chain_id == U256::ZERO
+            || self.remote_collateral_chain.get(token) != chain_id
+        {
+            return Err(DSCEngineError::InvalidRemoteCollateral(
+                InvalidRemoteCollateral {},
+            ));
+        }
+        self.more_than_zero(amount)?;
+        self.processed_attestations.setter(message_id).set(true);
+        self._credit_collateral(user, token, amount)?;
+        // 该链记入的抵押品总价值不能超过上限
+        let cap = self.remote_chain_cap.get(chain_id);
+        if self._remote_chain_value(chain_id)? > cap {
+            return Err(DSCEngineError::RemoteChainCapExceeded(
+                RemoteChainCapExceeded { _0: cap },
+            ));
+        }
+        evm::log(RemoteCollateralAttested {
+            messageId: message_id,
+            chainId: chain_id,
+            user,
+            token,
+            amount,
+        });
+        Ok(())
+    }
+
+    // 通知远程链把抵押品释放给接收者
+    pub(crate) fn _release_remote_collateral(
+        &mut self,
+        token: Address,
+        to: Address,
+        amount: U256,
+    ) -> Result<(), DSCEngineError> {
+        let chain_id = self.remote_collateral_chain.get(token);
+        IRemoteCollateralMessenger::new(self.remote_messenger.get())
+            .send_release(Call::new(), chain_id, to, token, amount)
+            .map_err(|_| DSCEngineError::RemoteReleaseFailed(RemoteReleaseFailed {}))?;
+        evm::log(RemoteCollateralReleased {
+            chainId: chain_id,
+            to,
+            token,
+            amount,
+        });
+        Ok(())
+    }
+}
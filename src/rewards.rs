@@ -0,0 +1,119 @@
+//! 流动性挖矿：按债务向铸造者发放奖励代币
+//!
+//! 治理设定每秒发放的奖励代币总量，并按各抵押品的权重分配到各抵押品分桶；
+//! 同一分桶内按用户的标准化债务（即不含利息的时间加权债务）分配。
+//! 每个分桶维护每单位标准化债务累计的奖励（基础精度），用户的标准化债务变化前先结算已累计的奖励。
+//! 分桶没有债务期间的奖励不发放。奖励代币由治理预先转入引擎，用户随时领取。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    block,
+    call::Call,
+    evm,
+    prelude::*,
+};
+
+use crate::{
+    DSCEngine, DSCEngineError, RewardTokenNotSet, RewardsClaimed, TransferFailed, IERC20, PRECISION,
+};
+
+sol_storage! {
+    // 单个抵押品分桶的奖励发放状态
+    pub struct RewardPool {
+        uint256 weight;                      // 发放权重：该分桶分得的每秒奖励占总量的比例为 权重 / 总权重
+        uint256 reward_per_debt;             // 每单位标准化债务累计的奖励（基础精度）
+        uint256 last_update;                 // 累计奖励的最近更新时间
+    }
+}
+
+impl DSCEngine {
+    // 分桶当前每单位标准化债务累计的奖励（包含尚未写入的时间）
+    fn _reward_per_debt(&self, token: Address) -> U256 {
+        let pool = self.reward_pools.getter(token);
+        let stored = pool.reward_per_debt.get();
+        let total_debt = self.total_normalized_debt.get(token);
+        let total_weight = self.total_reward_weight.get();
+        if total_debt == U256::ZERO || total_weight == U256::ZERO {
+            return stored;
+        }
+        let elapsed = U256::from(block::timestamp()).saturating_sub(pool.last_update.get());
+        let emitted = (elapsed * self.reward_rate.get() * pool.weight.get()) / total_weight;
+        stored + (emitted * PRECISION) / total_debt
+    }
+
+    // 把分桶累计的奖励写入存储
+    fn _update_reward_pool(&mut self, token: Address) {
+        let reward_per_debt = self._reward_per_debt(token);
+        let mut pool = self.reward_pools.setter(token);
+        pool.reward_per_debt.set(reward_per_debt);
+        pool.last_update.set(U256::from(block::timestamp()));
+    }
+
+    // 更新所有分桶的累计奖励，修改发放速率或权重前调用
+    pub(crate) fn _update_reward_pools(&mut self) {
+        for i in 0..self.collateral_tokens.len() {
+            if let Some(token) = self.collateral_tokens.get(i) {
+                self._update_reward_pool(token);
+            }
+        }
+    }
+
+    // 用户在某分桶尚未结算的奖励
+    fn _unsettled_rewards(&self, user: Address, token: Address) -> U256 {
+        let normalized = self.normalized_debt.getter(user).get(token);
+        let paid = self.user_reward_index.getter(user).get(token);
+        (normalized * self._reward_per_debt(token).saturating_sub(paid)) / PRECISION
+    }
+
+    // 结算用户在某分桶已累计的奖励，用户在该分桶的标准化债务变化前调用
+    pub(crate) fn _update_user_rewards(&mut self, user: Address, token: Address) {
+        let earned = self._unsettled_rewards(user, token);
+        self._update_reward_pool(token);
+        if earned > U256::ZERO {
+            let accrued = self.accrued_rewards.get(user);
+            self.accrued_rewards.setter(user).set(accrued + earned);
+        }
+        let reward_per_debt = self.reward_pools.getter(token).reward_per_debt.get();
+        self.user_reward_index
+            .setter(user)
+            .setter(token)
+            .set(reward_per_debt);
+    }
+
+    // 用户可领取的全部奖励（包含尚未结算的部分）
+    pub(crate) fn _pending_rewards(&self, user: Address) -> U256 {
+        let mut pending = self.accrued_rewards.get(user);
+        for i in 0..self.collateral_tokens.len() {
+            if let Some(token) = self.collateral_tokens.get(i) {
+                pending += self._unsettled_rewards(user, token);
+            }
+        }
+        pending
+    }
+
+    // 结算并把用户的全部奖励转给用户，返回转出的数量
+    pub(crate) fn _claim_rewards(&mut self, user: Address) -> Result<U256, DSCEngineError> {
+        let reward_token = self.reward_token.get();
+        if reward_token.is_zero() {
+            return Err(DSCEngineError::RewardTokenNotSet(RewardTokenNotSet {}));
+        }
+        for i in 0..self.collateral_tokens.len() {
+            if let Some(token) = self.collateral_tokens.get(i) {
+                self._update_user_rewards(user, token);
+            }
+        }
+        let amount = self.accrued_rewards.get(user);
+        if amount == U256::ZERO {
+            return Ok(U256::ZERO);
+        }
+        self.accrued_rewards.setter(user).set(U256::ZERO);
+        if IERC20::new(reward_token)
+            .transfer(Call::new(), user, amount)
+            .is_err()
+        {
+            return Err(DSCEngineError::TransferFailed(TransferFailed {}));
+        }
+        evm::log(RewardsClaimed { user, amount });
+        Ok(amount)
+    }
+}
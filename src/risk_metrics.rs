@@ -0,0 +1,70 @@
+//! 抵押率与清算线的风险指标
+//!
+//! 按存款总量和各抵押品分桶的债务计算抵押品和全协议的抵押率（基础精度，1e18 即 100%），
+//! 并给出抵押率距离清算线还有多少余量。清算线是清算阈值对应的抵押率：
+//! 单个抵押品为 LIQUIDATION_PRECISION / 该抵押品的清算阈值，全协议按各抵押品价值加权。
+//! 余量降到零说明按整体计该抵押品（或全协议）已达到清算条件，仪表盘和自动告警可据此提前预警。
+//! 没有债务时抵押率为最大值。
+
+use stylus_sdk::alloy_primitives::{Address, U256};
+
+use crate::math::{mul_div, wad_div, Rounding};
+use crate::{DSCEngine, LIQUIDATION_PRECISION, PRECISION};
+
+impl DSCEngine {
+    // 抵押品存款总量的价值相对于其分桶债务的抵押率
+    pub(crate) fn _collateral_ratio(&self, token: Address) -> U256 {
+        let debt = self._collateral_debt(token);
+        if debt == U256::ZERO {
+            return U256::MAX;
+        }
+        wad_div(
+            self.get_usd_value(token, self.total_deposited.get(token)),
+            debt,
+        )
+    }
+
+    // 抵押品的清算线：清算阈值对应的抵押率
+    pub(crate) fn _liquidation_ratio(&self, token: Address) -> U256 {
+        let threshold = self._collateral_threshold(token);
+        if threshold == U256::ZERO {
+            return U256::MAX;
+        }
+        mul_div(PRECISION, LIQUIDATION_PRECISION, threshold, Rounding::Up)
+    }
+
+    // 全协议的抵押率
+    pub(crate) fn _global_collateral_ratio(&self) -> U256 {
+        let total_debt = self._total_debt();
+        if total_debt == U256::ZERO {
+            return U256::MAX;
+        }
+        wad_div(self.get_total_collateral_value_usd(), total_debt)
+    }
+
+    // 全协议的清算线：按各抵押品价值加权的清算阈值对应的抵押率，没有抵押品价值时按默认阈值
+    pub(crate) fn _global_liquidation_ratio(&self) -> U256 {
+        let mut weighted_value = U256::ZERO;
+        let mut total_value = U256::ZERO;
+        for i in 0..self.collateral_tokens.len() {
+            if let Some(token) = self.collateral_tokens.get(i) {
+                let amount = self.total_deposited.get(token);
+                if amount == U256::ZERO {
+                    continue;
+                }
+                let value = self.get_usd_value(token, amount);
+                weighted_value += value * self._collateral_threshold(token);
+                total_value += value;
+            }
+        }
+        let threshold = if weighted_value == U256::ZERO {
+            self._liquidation_threshold()
+        } else {
+            weighted_value / total_value
+        };
+        if threshold == U256::ZERO {
+            return U256::MAX;
+        }
+        mul_div(PRECISION, LIQUIDATION_PRECISION, threshold, Rounding::Up)
+    }
+}
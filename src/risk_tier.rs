@@ -0,0 +1,167 @@
+//! 抵押品风险等级
+//!
+//! 每个风险等级打包一组参数：清算阈值、最高清算奖励、存入上限和预言机超时。
+//! 上架新抵押品时只需把它分配到某个等级，不用逐个设置参数。未分配等级或等级未配置
+//! （清算阈值为零）的抵押品沿用全局参数。全仓仓位的清算阈值按各抵押品价值加权平均；
+//! 启用 E-mode 时仍使用类别阈值。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256, U8},
+    evm,
+    prelude::*,
+};
+
+use crate::{
+    CollateralRiskTierSet, DSCEngine, DSCEngineError, InvalidRiskTier, RiskTierSet,
+    SupplyCapExceeded, LIQUIDATION_PRECISION,
+};
+
+// 预设的风险等级编号：A 为风险最低的蓝筹抵押品，C 为风险最高的长尾抵押品
+pub const TIER_A: u8 = 1;
+pub const TIER_B: u8 = 2;
+pub const TIER_C: u8 = 3;
+
+sol_storage! {
+    // 单个风险等级的参数，清算阈值为零表示未配置
+    pub struct RiskTier {
+        uint256 liquidation_threshold;       // 清算阈值（清算精度）
+        uint256 liquidation_bonus;           // 最高清算奖励（清算精度）
+        uint256 supply_cap;                  // 存入上限：每种抵押品的存入总量上限（代币数量），为零时不限制
+        uint256 oracle_timeout;              // 预言机超时：超过该秒数未更新视为中断，为零时沿用全局心跳间隔
+    }
+}
+
+impl DSCEngine {
+    // 抵押品所属的已配置风险等级
+    fn _risk_tier_of(&self, token: Address) -> Option<U8> {
+        let tier = self.collateral_risk_tier.get(token);
+        if tier == U8::ZERO
+            || self.risk_tiers.getter(tier).liquidation_threshold.get() == U256::ZERO
+        {
+            None
+        } else {
+            Some(tier)
+        }
+    }
+
+    // 抵押品的清算阈值（清算精度），启用动态阈值时为调整后的阈值
+    pub(crate) fn _collateral_threshold(&self, token: Address) -> U256 {
+        if let Some(threshold) = self._dynamic_threshold(token) {
+            return threshold;
+        }
+        match self._risk_tier_of(token) {
+            Some(tier) => self.risk_tiers.getter(tier).liquidation_threshold.get(),
+            None => self._liquidation_threshold(),
+        }
+    }
+
+    // 清算抵押品时的最高清算奖励（清算精度）
+    pub(crate) fn _collateral_max_bonus(&self, token: Address) -> U256 {
+        match self._risk_tier_of(token) {
+            Some(tier) => self.risk_tiers.getter(tier).liquidation_bonus.get(),
+            None => self._liquidation_bonus(),
+        }
+    }
+
+    // 抵押品预言机的超时秒数，为零时不检查
+    pub(crate) fn _oracle_timeout(&self, token: Address) -> U256 {
+        let timeout = self._risk_tier_of(token).map_or(U256::ZERO, |tier| {
+            self.risk_tiers.getter(tier).oracle_timeout.get()
+        });
+        if timeout == U256::ZERO {
+            self.oracle_heartbeat.get()
+        } else {
+            timeout
+        }
+    }
+
+    // 按用户各抵押品价值加权的清算阈值，没有抵押品价值时为默认阈值
+    pub(crate) fn _weighted_threshold(&self, user: Address) -> U256 {
+        let default_threshold = self._liquidation_threshold();
+        let mut weighted_value = U256::ZERO;
+        let mut total_value = U256::ZERO;
+        let mut tiered = false;
+        for i in 0..self.collateral_tokens.len() {
+            if let Some(token) = self.collateral_tokens.get(i) {
+                let amount = self._collateral_amount_of(user, token);
+                if amount == U256::ZERO {
+                    continue;
+                }
+                let threshold = self._collateral_threshold(token);
+                tiered |= threshold != default_threshold;
+                let value = self.get_usd_value(token, amount);
+                weighted_value += value * threshold;
+                total_value += value;
+            }
+        }
+        if !tiered || total_value == U256::ZERO {
+            return default_threshold;
+        }
+        weighted_value / total_value
+    }
+
+    // 存入后检查抵押品的存入上限
+    pub(crate) fn _check_supply_cap(&self, token: Address) -> Result<(), DSCEngineError> {
+        let Some(tier) = self._risk_tier_of(token) else {
+            return Ok(());
+        };
+        let cap = self.risk_tiers.getter(tier).supply_cap.get();
+        if cap == U256::ZERO {
+            return Ok(());
+        }
+        let total = self.total_deposited.get(token);
+        if total > cap {
+            return Err(DSCEngineError::SupplyCapExceeded(SupplyCapExceeded {
+                _0: cap,
+            }));
+        }
+        Ok(())
+    }
+
+    // 设置风险等级的参数，清算阈值为零表示停用该等级
+    pub(crate) fn _set_risk_tier(
+        &mut self,
+        tier: u8,
+        liquidation_threshold: U256,
+        liquidation_bonus: U256,
+        supply_cap: U256,
+        oracle_timeout: U256,
+    ) -> Result<(), DSCEngineError> {
+        // 清算阈值不能达到 100%，且按奖励清算后健康因子必须能够改善
+        if tier == 0
+            || (liquidation_threshold != U256::ZERO
+                && liquidation_threshold * (LIQUIDATION_PRECISION + liquidation_bonus)
+                    >= LIQUIDATION_PRECISION * LIQUIDATION_PRECISION)
+        {
+            return Err(DSCEngineError::InvalidRiskTier(InvalidRiskTier {}));
+        }
+        let mut params = self.risk_tiers.setter(U8::from(tier));
+        params.liquidation_threshold.set(liquidation_threshold);
+        params.liquidation_bonus.set(liquidation_bonus);
+        params.supply_cap.set(supply_cap);
+        params.oracle_timeout.set(oracle_timeout);
+        evm::log(RiskTierSet {
+            tier,
+            liquidationThreshold: liquidation_threshold,
+            liquidationBonus: liquidation_bonus,
+            supplyCap: supply_cap,
+            oracleTimeout: oracle_timeout,
+        });
+        Ok(())
+    }
+
+    // 将抵押品分配到风险等级，0 表示沿用全局参数；分配前等级必须已配置
+    pub(crate) fn _set_collateral_risk_tier(
+        &mut self,
+        token: Address,
+        tier: u8,
+    ) -> Result<(), DSCEngineError> {
+        let key = U8::from(tier);
+        if tier != 0 && self.risk_tiers.getter(key).liquidation_threshold.get() == U256::ZERO {
+            return Err(DSCEngineError::InvalidRiskTier(InvalidRiskTier {}));
+        }
+        self.collateral_risk_tier.setter(token).set(key);
+        evm::log(CollateralRiskTierSet { token, tier });
+        Ok(())
+    }
+}
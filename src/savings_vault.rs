@@ -0,0 +1,359 @@
+//! sDSC 储蓄金库：锁定 DSC 赚取储蓄利率的 ERC-4626 金库
+//!
+//! 收益由引擎收取的稳定费支付：`drip()` 按治理设定的储蓄利率计算应得收益并向引擎申领，
+//! 实际到账的 DSC 计入金库资产。金库资产按内部记账，直接转入的代币不影响份额价格。
+
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::sol;
+use stylus_sdk::{
+    block,
+    call::{Call, MethodError},
+    contract, evm, msg,
+    prelude::*,
+};
+
+use crate::erc20::{
+    Erc20, Erc20Error, Erc20Params, InsufficientAllowance, InsufficientBalance, MintFailed,
+};
+
+sol! {
+    event Deposit(address indexed sender, address indexed owner, uint256 assets, uint256 shares);
+    event Withdraw(
+        address indexed sender, address indexed receiver, address indexed owner, uint256 assets, uint256 shares
+    );
+    event Drip(uint256 assets, uint256 totalAssets);
+    event SavingsRateUpdated(uint256 rate);
+
+    error NotOwner();
+    error ZeroShares();
+    error ZeroAssets();
+    error TransferFailed();
+}
+
+sol_interface! {
+    interface IERC20 {
+        function transfer(address to, uint256 value) external returns (bool);
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+    // 储蓄收益来源：由引擎用收取的稳定费支付
+    interface ISavingsFunder {
+        function fundSavingsVault(uint256 amount) external returns (uint256);
+    }
+}
+
+sol_storage! {
+    #[cfg_attr(feature = "savings-vault", entrypoint)]
+    pub struct SavingsVault {
+        #[borrow]
+        Erc20<SavingsVaultParams> erc20;
+        address asset;
+        address engine;
+        address owner;
+        uint256 savings_rate;
+        uint256 last_drip;
+        uint256 total_assets;
+    }
+}
+
+/// Immutable definitions
+pub struct SavingsVaultParams;
+impl Erc20Params for SavingsVaultParams {
+    const NAME: &'static str = "Savings DecentralizedStableCoin";
+    const SYMBOL: &'static str = "sDSC";
+    const DECIMALS: u8 = 18;
+}
+
+#[derive(SolidityError)]
+pub enum SavingsVaultError {
+    NotOwner(NotOwner),
+    ZeroShares(ZeroShares),
+    ZeroAssets(ZeroAssets),
+    TransferFailed(TransferFailed),
+    InsufficientBalance(InsufficientBalance),
+    InsufficientAllowance(InsufficientAllowance),
+    MintFailed(MintFailed),
+}
+
+// ERC-20 错误逐个展开为金库错误，导出的 ABI 中才能列出具体的错误定义
+impl From<Erc20Error> for SavingsVaultError {
+    fn from(e: Erc20Error) -> Self {
+        match e {
+            Erc20Error::InsufficientBalance(e) => Self::InsufficientBalance(e),
+            Erc20Error::InsufficientAllowance(e) => Self::InsufficientAllowance(e),
+            Erc20Error::MintFailed(e) => Self::MintFailed(e),
+        }
+    }
+}
+
+impl MethodError for SavingsVaultError {
+    fn encode(self) -> Vec<u8> {
+        From::from(self)
+    }
+}
+
+#[public]
+#[inherit(Erc20<SavingsVaultParams>)]
+impl SavingsVault {
+    pub fn constructor(
+        &mut self,
+        asset: Address,
+        engine: Address,
+    ) -> Result<(), SavingsVaultError> {
+        if !self.owner.get().is_zero() {
+            return Err(SavingsVaultError::NotOwner(NotOwner {}));
+        }
+        self.owner.set(msg::sender());
+        self.asset.set(asset);
+        self.engine.set(engine);
+        self.last_drip.set(U256::from(block::timestamp()));
+        Ok(())
+    }
+
+    /// 设置每秒储蓄利率（18 位精度），修改前先按旧利率结算
+    pub fn set_savings_rate(&mut self, rate_per_second: U256) -> Result<(), SavingsVaultError> {
+        self.only_owner()?;
+        self.drip();
+        self.savings_rate.set(rate_per_second);
+        evm::log(SavingsRateUpdated {
+            rate: rate_per_second,
+        });
+        Ok(())
+    }
+
+    /// 结算储蓄收益，返回实际到账的 DSC 数量
+    pub fn drip(&mut self) -> U256 {
+        let now = U256::from(block::timestamp());
+        let elapsed = now.saturating_sub(self.last_drip.get());
+        if elapsed == U256::ZERO {
+            return U256::ZERO;
+        }
+        self.last_drip.set(now);
+        let total_assets = self.total_assets.get();
+        let owed = (total_assets * self.savings_rate.get() * elapsed) / Self::precision();
+        if owed == U256::ZERO {
+            return U256::ZERO;
+        }
+        // 收益以引擎实际支付的数量为准
+        let paid = ISavingsFunder::new(self.engine.get())
+            .fund_savings_vault(Call::new(), owed)
+            .unwrap_or(U256::ZERO);
+        if paid > U256::ZERO {
+            self.total_assets.set(total_assets + paid);
+            evm::log(Drip {
+                assets: paid,
+                totalAssets: total_assets + paid,
+            });
+        }
+        paid
+    }
+
+    pub fn asset(&self) -> Address {
+        self.asset.get()
+    }
+
+    pub fn total_assets(&self) -> U256 {
+        self.total_assets.get()
+    }
+
+    pub fn get_savings_rate(&self) -> U256 {
+        self.savings_rate.get()
+    }
+
+    pub fn convert_to_shares(&self, assets: U256) -> U256 {
+        self._convert_to_shares(assets, false)
+    }
+
+    pub fn convert_to_assets(&self, shares: U256) -> U256 {
+        self._convert_to_assets(shares, false)
+    }
+
+    pub fn max_deposit(&self, _receiver: Address) -> U256 {
+        U256::MAX
+    }
+
+    pub fn max_mint(&self, _receiver: Address) -> U256 {
+        U256::MAX
+    }
+
+    pub fn max_withdraw(&self, owner: Address) -> U256 {
+        self._convert_to_assets(self.erc20.balance_of(owner), false)
+    }
+
+    pub fn max_redeem(&self, owner: Address) -> U256 {
+        self.erc20.balance_of(owner)
+    }
+
+    pub fn preview_deposit(&self, assets: U256) -> U256 {
+        self._convert_to_shares(assets, false)
+    }
+
+    pub fn preview_mint(&self, shares: U256) -> U256 {
+        self._convert_to_assets(shares, true)
+    }
+
+    pub fn preview_withdraw(&self, assets: U256) -> U256 {
+        self._convert_to_shares(assets, true)
+    }
+
+    pub fn preview_redeem(&self, shares: U256) -> U256 {
+        self._convert_to_assets(shares, false)
+    }
+
+    /// 存入 `assets` 数量的 DSC，为 `receiver` 铸造份额
+    pub fn deposit(&mut self, assets: U256, receiver: Address) -> Result<U256, SavingsVaultError> {
+        self.drip();
+        let shares = self._convert_to_shares(assets, false);
+        if shares == U256::ZERO {
+            return Err(SavingsVaultError::ZeroShares(ZeroShares {}));
+        }
+        self._deposit(assets, shares, receiver)?;
+        Ok(shares)
+    }
+
+    /// 为 `receiver` 铸造 `shares` 数量的份额，返回需要存入的 DSC 数量
+    pub fn mint(&mut self, shares: U256, receiver: Address) -> Result<U256, SavingsVaultError> {
+        self.drip();
+        let assets = self._convert_to_assets(shares, true);
+        if assets == U256::ZERO {
+            return Err(SavingsVaultError::ZeroAssets(ZeroAssets {}));
+        }
+        self._deposit(assets, shares, receiver)?;
+        Ok(assets)
+    }
+
+    /// 从 `owner` 的份额中取回 `assets` 数量的 DSC，返回销毁的份额
+    pub fn withdraw(
+        &mut self,
+        assets: U256,
+        receiver: Address,
+        owner: Address,
+    ) -> Result<U256, SavingsVaultError> {
+        self.drip();
+        let shares = self._convert_to_shares(assets, true);
+        self._withdraw(assets, shares, receiver, owner)?;
+        Ok(shares)
+    }
+
+    /// 赎回 `owner` 的 `shares` 数量的份额，返回取回的 DSC 数量
+    pub fn redeem(
+        &mut self,
+        shares: U256,
+        receiver: Address,
+        owner: Address,
+    ) -> Result<U256, SavingsVaultError> {
+        self.drip();
+        let assets = self._convert_to_assets(shares, false);
+        if assets == U256::ZERO {
+            return Err(SavingsVaultError::ZeroAssets(ZeroAssets {}));
+        }
+        self._withdraw(assets, shares, receiver, owner)?;
+        Ok(assets)
+    }
+}
+
+// 内部辅助函数：不对外暴露
+impl SavingsVault {
+    fn precision() -> U256 {
+        U256::from(10).pow(U256::from(18))
+    }
+
+    fn only_owner(&self) -> Result<(), SavingsVaultError> {
+        if msg::sender() != self.owner.get() {
+            return Err(SavingsVaultError::NotOwner(NotOwner {}));
+        }
+        Ok(())
+    }
+
+    fn _convert_to_shares(&self, assets: U256, round_up: bool) -> U256 {
+        let total_supply = self.erc20.total_supply.get();
+        let total_assets = self.total_assets.get();
+        if total_supply == U256::ZERO || total_assets == U256::ZERO {
+            return assets;
+        }
+        if round_up {
+            (assets * total_supply).div_ceil(total_assets)
+        } else {
+            (assets * total_supply) / total_assets
+        }
+    }
+
+    fn _convert_to_assets(&self, shares: U256, round_up: bool) -> U256 {
+        let total_supply = self.erc20.total_supply.get();
+        if total_supply == U256::ZERO {
+            return shares;
+        }
+        let total_assets = self.total_assets.get();
+        if round_up {
+            (shares * total_assets).div_ceil(total_supply)
+        } else {
+            (shares * total_assets) / total_supply
+        }
+    }
+
+    fn _deposit(
+        &mut self,
+        assets: U256,
+        shares: U256,
+        receiver: Address,
+    ) -> Result<(), SavingsVaultError> {
+        let sender = msg::sender();
+        if IERC20::new(self.asset.get())
+            .transfer_from(Call::new(), sender, contract::address(), assets)
+            .is_err()
+        {
+            return Err(SavingsVaultError::TransferFailed(TransferFailed {}));
+        }
+        self.total_assets.set(self.total_assets.get() + assets);
+        self.erc20.mint(receiver, shares)?;
+        evm::log(Deposit {
+            sender,
+            owner: receiver,
+            assets,
+            shares,
+        });
+        Ok(())
+    }
+
+    fn _withdraw(
+        &mut self,
+        assets: U256,
+        shares: U256,
+        receiver: Address,
+        owner: Address,
+    ) -> Result<(), SavingsVaultError> {
+        let sender = msg::sender();
+        // 代他人赎回时消耗份额授权
+        if sender != owner {
+            let mut allowance = self.erc20.allowances.setter(owner);
+            let mut allowance = allowance.setter(sender);
+            let old_allowance = allowance.get();
+            if old_allowance < shares {
+                return Err(SavingsVaultError::InsufficientAllowance(
+                    InsufficientAllowance {
+                        owner,
+                        spender: sender,
+                        have: old_allowance,
+                        want: shares,
+                    },
+                ));
+            }
+            allowance.set(old_allowance - shares);
+        }
+        self.erc20.burn(owner, shares)?;
+        self.total_assets.set(self.total_assets.get() - assets);
+        if IERC20::new(self.asset.get())
+            .transfer(Call::new(), receiver, assets)
+            .is_err()
+        {
+            return Err(SavingsVaultError::TransferFailed(TransferFailed {}));
+        }
+        evm::log(Withdraw {
+            sender,
+            receiver,
+            owner,
+            assets,
+            shares,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+//! 签名校验：外部账户使用 ECDSA 签名，合约钱包回退到 EIP-1271
+//!
+//! 签名者地址有代码时调用其 `isValidSignature`，返回 EIP-1271 魔数即视为有效，
+//! Safe 等智能账户因此也能使用免 gas 的签名路径；否则按 65 字节 (r, s, v) 格式通过
+//! ecrecover 预编译合约恢复签名者。为防止签名延展性，只接受 s 位于曲线阶低半区的签名。
+
+use stylus_sdk::{
+    alloy_primitives::{address, b256, Address, Bytes, FixedBytes, B256, U256},
+    call::{self, Call},
+    prelude::*,
+};
+
+use crate::DSCEngine;
+
+sol_interface! {
+    // EIP-1271 合约钱包签名校验接口
+    interface IERC1271 {
+        function isValidSignature(bytes32 hash, bytes calldata signature) external view returns (bytes4);
+    }
+}
+
+// EIP-1271 签名有效时返回的魔数：bytes4(keccak256("isValidSignature(bytes32,bytes)"))
+const EIP1271_MAGIC_VALUE: [u8; 4] = [0x16, 0x26, 0xba, 0x7e];
+// ecrecover 预编译合约地址
+const ECRECOVER: Address = address!("0000000000000000000000000000000000000001");
+// secp256k1 曲线阶的一半，s 超过该值的签名视为延展签名
+const SECP256K1_HALF_ORDER: B256 =
+    b256!("7fffffffffffffffffffffffffffffff5d576e7357a4501ddfe92f46681b20a0");
+
+impl DSCEngine {
+    // 校验 signer 对 digest 的签名
+    pub(crate) fn _is_valid_signature(
+        &self,
+        signer: Address,
+        digest: B256,
+        signature: &[u8],
+    ) -> bool {
+        if signer.is_zero() {
+            return false;
+        }
+        if signer.has_code() {
+            return IERC1271::new(signer)
+                .is_valid_signature(Call::new(), digest, Bytes::copy_from_slice(signature))
+                .is_ok_and(|magic| magic == FixedBytes(EIP1271_MAGIC_VALUE));
+        }
+        ecrecover(digest, signature) == Some(signer)
+    }
+}
+
+// 通过 ecrecover 预编译合约恢复 65 字节 (r, s, v) 签名的签名者
+fn ecrecover(digest: B256, signature: &[u8]) -> Option<Address> {
+    if signature.len() != 65 {
+        return None;
+    }
+    let (r, s, v) = (&signature[..32], &signature[32..64], signature[64]);
+    if U256::from_be_slice(s) > U256::from_be_bytes(SECP256K1_HALF_ORDER.0) {
+        return None;
+    }
+    let v = if v < 27 { v + 27 } else { v };
+    if v != 27 && v != 28 {
+        return None;
+    }
+    let mut input = [0u8; 128];
+    input[..32].copy_from_slice(digest.as_slice());
+    input[63] = v;
+    input[64..96].copy_from_slice(r);
+    input[96..].copy_from_slice(s);
+    let output = call::static_call(Call::new(), ECRECOVER, &input).ok()?;
+    if output.len() != 32 {
+        return None;
+    }
+    let recovered = Address::from_slice(&output[12..]);
+    (!recovered.is_zero()).then_some(recovered)
+}
@@ -0,0 +1,91 @@
+//! 签名清算订单：清算人预先签署 EIP-712 订单，由任意中继提交
+//!
+//! 订单指定被清算用户、抵押品、最多偿还的债务、最少获得的抵押品、nonce 和截止时间。
+//! 中继提交后按订单清算，债务由签名者的 DSC 偿还，抵押品和 keeper 奖励都归签名者，
+//! 清算人可以通过私有中继提交订单，避免在公开内存池中被抢跑。
+//! 每个清算人的订单按 nonce 顺序使用，递增 nonce 即可作废尚未提交的订单。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    block, contract, evm,
+};
+
+use alloy_sol_types::{eip712_domain, sol, SolStruct};
+
+use crate::{
+    DSCEngine, DSCEngineError, InvalidSignature, LiquidationOrderFilled, OrderExpired,
+    SlippageExceeded,
+};
+
+sol! {
+    // 签名清算订单
+    struct LiquidationOrder {
+        address liquidator;
+        address user;
+        address collateral;
+        uint256 maxDebt;
+        uint256 minCollateralOut;
+        uint256 nonce;
+        uint256 deadline;
+    }
+}
+
+impl DSCEngine {
+    // 按签名订单清算，返回签名者获得的抵押品数量
+    pub(crate) fn _liquidate_with_signature(
+        &mut self,
+        order: LiquidationOrder,
+        signature: &[u8],
+    ) -> Result<U256, DSCEngineError> {
+        if U256::from(block::timestamp()) > order.deadline {
+            return Err(DSCEngineError::OrderExpired(OrderExpired {}));
+        }
+        let nonce = self.liquidation_order_nonces.get(order.liquidator);
+        let domain = eip712_domain! {
+            name: "DSCEngine",
+            version: "1",
+            chain_id: block::chainid(),
+            verifying_contract: contract::address(),
+        };
+        let digest = order.eip712_signing_hash(&domain);
+        if order.nonce != nonce || !self._is_valid_signature(order.liquidator, digest, signature) {
+            return Err(DSCEngineError::InvalidSignature(InvalidSignature {}));
+        }
+        self.liquidation_order_nonces
+            .setter(order.liquidator)
+            .set(nonce + U256::from(1));
+
+        // 订单最多偿还的债务不超过本次可清算的上限
+        let debt_to_cover = order
+            .maxDebt
+            .min(self._max_liquidatable_debt(order.user, order.collateral)?);
+        let collateral_out = self._liquidate(
+            order.collateral,
+            order.user,
+            debt_to_cover,
+            order.liquidator,
+        )?;
+        if collateral_out < order.minCollateralOut {
+            return Err(DSCEngineError::SlippageExceeded(SlippageExceeded {
+                _0: collateral_out,
+            }));
+        }
+        evm::log(LiquidationOrderFilled {
+            liquidator: order.liquidator,
+            user: order.user,
+            nonce,
+            debtCovered: debt_to_cover,
+            collateralOut: collateral_out,
+            actionNonce: self._next_action_nonce(order.user),
+        });
+        Ok(collateral_out)
+    }
+
+    // 作废清算人尚未提交的签名订单
+    pub(crate) fn _cancel_liquidation_orders(&mut self, liquidator: Address) {
+        let nonce = self.liquidation_order_nonces.get(liquidator);
+        self.liquidation_order_nonces
+            .setter(liquidator)
+            .set(nonce + U256::from(1));
+    }
+}
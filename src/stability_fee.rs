@@ -0,0 +1,208 @@
+//! 稳定费：按抵押品类别计息的稳定币债务
+//!
+//! 每种抵押品有独立的每秒费率和债务累计指数（RAY 精度，按秒复利，见 `rate_index`），
+//! 用户债务按抵押品分桶记为标准化本金，实际债务 = 标准化本金 × 当前指数。
+//! 指数增长产生的利息计入协议收入。
+
+use alloc::vec::Vec;
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    block, evm,
+};
+
+use crate::math::RAY;
+use crate::rate_index::{compound, WAD_TO_RAY};
+use crate::{BreaksHealthFactor, DSCEngine, DSCEngineError, StabilityFeeAccrued};
+
+impl DSCEngine {
+    // 抵押品当前的债务累计指数（RAY 精度，包含尚未计提的时间），未初始化的指数视为 1.0
+    pub(crate) fn _current_debt_index(&self, token: Address) -> U256 {
+        let elapsed =
+            U256::from(block::timestamp()).saturating_sub(self.last_accrual_time.get(token));
+        compound(
+            self._stored_debt_index(token),
+            self._current_rate(token),
+            elapsed,
+        )
+    }
+
+    // 存储中的债务累计指数（RAY 精度）：尚未写入时沿用旧版基础精度的指数
+    pub(crate) fn _stored_debt_index(&self, token: Address) -> U256 {
+        let index = self.rate_index.get(token);
+        if index > U256::ZERO {
+            return index;
+        }
+        let legacy_index = self.debt_rate_index.get(token);
+        if legacy_index > U256::ZERO {
+            legacy_index * WAD_TO_RAY
+        } else {
+            RAY
+        }
+    }
+
+    // 计提某抵押品的稳定费，更新指数并记录协议收入
+    pub(crate) fn _accrue(&mut self, token: Address) {
+        let old_index = self._stored_debt_index(token);
+        let new_index = self._current_debt_index(token);
+        self.last_accrual_time
+            .setter(token)
+            .set(U256::from(block::timestamp()));
+        if new_index == old_index {
+            return;
+        }
+        self.rate_index.setter(token).set(new_index);
+        // 指数增长部分即为新增利息
+        let fee = (self.total_normalized_debt.get(token) * (new_index - old_index)) / RAY;
+        self.accrued_stability_fees
+            .set(self.accrued_stability_fees.get() + fee);
+        evm::log(StabilityFeeAccrued {
+            token,
+            fee,
+            index: new_index,
+        });
+    }
+
+    // 计提所有抵押品的稳定费，并记录各抵押品预言机最近有效的价格
+    pub(crate) fn _accrue_all(&mut self) {
+        for i in 0..self.collateral_tokens.len() {
+            if let Some(token) = self.collateral_tokens.get(i) {
+                self._accrue(token);
+                self._record_good_price(token);
+            }
+        }
+    }
+
+    // 用户某抵押品分桶的当前债务（含利息）
+    pub(crate) fn _bucket_debt(&self, user: Address, token: Address) -> U256 {
+        let normalized = self.normalized_debt.getter(user).get(token);
+        if normalized == U256::ZERO {
+            return U256::ZERO;
+        }
+        (normalized * self._current_debt_index(token)).div_ceil(RAY)
+    }
+
+    // 用户当前的总债务（含利息）
+    pub(crate) fn _user_debt(&self, user: Address) -> U256 {
+        let mut total_debt = U256::ZERO;
+        for i in 0..self.collateral_tokens.len() {
+            if let Some(token) = self.collateral_tokens.get(i) {
+                total_debt += self._bucket_debt(user, token);
+            }
+        }
+        total_debt
+    }
+
+    // 记在某抵押品分桶上的全部债务（含利息），即该抵押品支撑的 DSC 数量
+    pub(crate) fn _collateral_debt(&self, token: Address) -> U256 {
+        (self.total_normalized_debt.get(token) * self._current_debt_index(token)) / RAY
+    }
+
+    // 协议当前的总债务（含利息）
+    pub(crate) fn _total_debt(&self) -> U256 {
+        let mut total_debt = U256::ZERO;
+        for i in 0..self.collateral_tokens.len() {
+            if let Some(token) = self.collateral_tokens.get(i) {
+                total_debt += self._collateral_debt(token);
+            }
+        }
+        total_debt
+    }
+
+    // 按用户各抵押品的价值比例分摊新增债务，指定分桶时只记入该分桶（调用前需先计提）
+    pub(crate) fn _add_debt(
+        &mut self,
+        user: Address,
+        amount: U256,
+        bucket: Option<Address>,
+    ) -> Result<(), DSCEngineError> {
+        let mut buckets = Vec::new();
+        let mut total_value = U256::ZERO;
+        for i in 0..self.collateral_tokens.len() {
+            if let Some(token) = self.collateral_tokens.get(i) {
+                if bucket.is_some_and(|bucket| bucket != token) {
+                    continue;
+                }
+                let amount = self._collateral_amount_of(user, token);
+                if amount == U256::ZERO {
+                    continue;
+                }
+                let value = self._usd_value(token, amount)?;
+                if value > U256::ZERO {
+                    buckets.push((token, value));
+                    total_value += value;
+                }
+            }
+        }
+        // 没有抵押品价值时不允许产生债务
+        if total_value == U256::ZERO {
+            return Err(DSCEngineError::BreaksHealthFactor(BreaksHealthFactor {
+                _0: U256::ZERO,
+            }));
+        }
+        let mut remaining = amount;
+        let last = buckets.len() - 1;
+        for (i, (token, value)) in buckets.into_iter().enumerate() {
+            let part = if i == last {
+                remaining
+            } else {
+                (amount * value) / total_value
+            };
+            remaining -= part;
+            // 向上取整，避免债务被低估
+            let normalized = (part * RAY).div_ceil(self._current_debt_index(token));
+            self._update_user_rewards(user, token);
+            let user_normalized = self.normalized_debt.getter(user).get(token);
+            self.normalized_debt
+                .setter(user)
+                .setter(token)
+                .set(user_normalized + normalized);
+            let total_normalized = self.total_normalized_debt.get(token);
+            self.total_normalized_debt
+                .setter(token)
+                .set(total_normalized + normalized);
+        }
+        Ok(())
+    }
+
+    // 按比例减少用户各分桶的债务，指定分桶时只减少该分桶，返回其中用于偿还利息的部分（调用前需先计提）
+    pub(crate) fn _remove_debt(
+        &mut self,
+        user: Address,
+        amount: U256,
+        bucket: Option<Address>,
+    ) -> U256 {
+        let total_debt = self._user_debt(user);
+        let scoped_debt = match bucket {
+            Some(token) => self._bucket_debt(user, token),
+            None => total_debt,
+        };
+        if scoped_debt == U256::ZERO {
+            return U256::ZERO;
+        }
+        let remaining_debt = scoped_debt.saturating_sub(amount);
+        for i in 0..self.collateral_tokens.len() {
+            if let Some(token) = self.collateral_tokens.get(i) {
+                if bucket.is_some_and(|bucket| bucket != token) {
+                    continue;
+                }
+                let normalized = self.normalized_debt.getter(user).get(token);
+                if normalized == U256::ZERO {
+                    continue;
+                }
+                let new_normalized = (normalized * remaining_debt).div_ceil(scoped_debt);
+                self._update_user_rewards(user, token);
+                self.normalized_debt
+                    .setter(user)
+                    .setter(token)
+                    .set(new_normalized);
+                let total_normalized = self.total_normalized_debt.get(token);
+                self.total_normalized_debt
+                    .setter(token)
+                    .set(total_normalized - (normalized - new_normalized));
+            }
+        }
+        // 先偿还利息，再偿还本金
+        let interest = total_debt.saturating_sub(self.dsc_minted.get(user));
+        amount.min(interest)
+    }
+}
@@ -0,0 +1,234 @@
+//! 稳定池：DSC 存款人作为清算的第一道防线
+//!
+//! 存款人存入 DSC 获得份额。引擎清算时从池中取出 DSC 偿还被清算仓位的债务，
+//! 并把折价的抵押品转入池中。池内资产（DSC 与各类抵押品）按份额比例归属存款人，
+//! 取款时按比例取回 DSC 和累计的抵押品收益。
+//!
+//! 份额换算带有虚拟份额和虚拟资产偏移：首个存款人存入极少量 DSC 后直接转入 DSC 抬高每份价值，
+//! 也无法让后续存款向下取整到接近零的份额，这类通胀攻击的成本远高于收益。
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::sol;
+use stylus_sdk::{
+    call::{Call, MethodError},
+    contract, evm, msg,
+    prelude::*,
+};
+
+sol! {
+    event StabilityDeposit(address indexed depositor, uint256 amount, uint256 shares);
+    event StabilityWithdraw(address indexed depositor, uint256 amount, uint256 shares);
+
+    error NotOwner();
+    error ZeroShares();
+    error InsufficientShares();
+    error TransferFailed();
+    error EngineCallFailed();
+}
+
+sol_interface! {
+    interface IERC20 {
+        function transfer(address to, uint256 value) external returns (bool);
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+        function approve(address spender, uint256 value) external returns (bool);
+        function balanceOf(address account) external view returns (uint256);
+    }
+    // 引擎接口：读取抵押品列表并为池内抵押品定价
+    interface IDSCEngine {
+        function getLocalCollateralTokens() external view returns (address[] memory);
+        function quoteUsdValue(address token, uint256 amount) external view returns (uint256);
+    }
+}
+
+// 份额换算时计入的虚拟份额和虚拟资产，防止首个存款人的通胀攻击
+const VIRTUAL_SHARES: u64 = 1_000_000;
+const VIRTUAL_ASSETS: u64 = 1;
+
+sol_storage! {
+    #[cfg_attr(feature = "stability-pool", entrypoint)]
+    pub struct StabilityPool {
+        address dsc;
+        address engine;
+        address owner;
+        uint256 total_shares;
+        mapping(address => uint256) shares;
+    }
+}
+
+#[derive(SolidityError)]
+pub enum StabilityPoolError {
+    NotOwner(NotOwner),
+    ZeroShares(ZeroShares),
+    InsufficientShares(InsufficientShares),
+    TransferFailed(TransferFailed),
+    EngineCallFailed(EngineCallFailed),
+}
+
+impl MethodError for StabilityPoolError {
+    fn encode(self) -> Vec<u8> {
+        From::from(self)
+    }
+}
+
+#[public]
+impl StabilityPool {
+    /// 初始化稳定池，并授权引擎在清算时动用池中的 DSC
+    pub fn constructor(&mut self, dsc: Address, engine: Address) -> Result<(), StabilityPoolError> {
+        if !self.owner.get().is_zero() {
+            return Err(StabilityPoolError::NotOwner(NotOwner {}));
+        }
+        self.owner.set(msg::sender());
+        self.dsc.set(dsc);
+        self.engine.set(engine);
+        if IERC20::new(dsc)
+            .approve(Call::new(), engine, U256::MAX)
+            .is_err()
+        {
+            return Err(StabilityPoolError::TransferFailed(TransferFailed {}));
+        }
+        Ok(())
+    }
+
+    /// 存入 DSC，返回获得的份额
+    pub fn deposit(&mut self, amount: U256) -> Result<U256, StabilityPoolError> {
+        let total_shares = self.total_shares.get();
+        let total_value = self.total_value()?;
+        let shares = (amount * (total_shares + U256::from(VIRTUAL_SHARES)))
+            / (total_value + U256::from(VIRTUAL_ASSETS));
+        if shares == U256::ZERO {
+            return Err(StabilityPoolError::ZeroShares(ZeroShares {}));
+        }
+        let sender = msg::sender();
+        if IERC20::new(self.dsc.get())
+            .transfer_from(Call::new(), sender, contract::address(), amount)
+            .is_err()
+        {
+            return Err(StabilityPoolError::TransferFailed(TransferFailed {}));
+        }
+        let user_shares = self.shares.get(sender);
+        self.shares.setter(sender).set(user_shares + shares);
+        self.total_shares.set(total_shares + shares);
+        evm::log(StabilityDeposit {
+            depositor: sender,
+            amount,
+            shares,
+        });
+        Ok(shares)
+    }
+
+    /// 赎回份额，按比例取回 DSC 和累计的抵押品收益，返回取回的 DSC 数量
+    pub fn withdraw(&mut self, shares: U256) -> Result<U256, StabilityPoolError> {
+        let sender = msg::sender();
+        let user_shares = self.shares.get(sender);
+        if shares == U256::ZERO || shares > user_shares {
+            return Err(StabilityPoolError::InsufficientShares(
+                InsufficientShares {},
+            ));
+        }
+        let total_shares = self.total_shares.get();
+        let virtual_total = total_shares + U256::from(VIRTUAL_SHARES);
+        let dsc_amount = (self._balance_of(self.dsc.get())? * shares) / virtual_total;
+        let mut gains = Vec::new();
+        for token in self._collateral_tokens()? {
+            let gain = (self._balance_of(token)? * shares) / virtual_total;
+            if gain > U256::ZERO {
+                gains.push((token, gain));
+            }
+        }
+        self.shares.setter(sender).set(user_shares - shares);
+        self.total_shares.set(total_shares - shares);
+        if dsc_amount > U256::ZERO {
+            self._transfer(self.dsc.get(), sender, dsc_amount)?;
+        }
+        for (token, gain) in gains {
+            self._transfer(token, sender, gain)?;
+        }
+        evm::log(StabilityWithdraw {
+            depositor: sender,
+            amount: dsc_amount,
+            shares,
+        });
+        Ok(dsc_amount)
+    }
+
+    /// 池内资产的美元总价值（DSC 按面值计），抵押品价格不可用时回滚
+    pub fn total_value(&self) -> Result<U256, StabilityPoolError> {
+        let engine = IDSCEngine::new(self.engine.get());
+        let mut total_value = self._balance_of(self.dsc.get())?;
+        for token in self._collateral_tokens()? {
+            let balance = self._balance_of(token)?;
+            if balance > U256::ZERO {
+                total_value += engine
+                    .quote_usd_value(Call::new(), token, balance)
+                    .map_err(|_| StabilityPoolError::EngineCallFailed(EngineCallFailed {}))?;
+            }
+        }
+        Ok(total_value)
+    }
+
+    /// 存款人当前可取回的 DSC 数量
+    pub fn get_deposit(&self, depositor: Address) -> Result<U256, StabilityPoolError> {
+        self._pro_rata(self.dsc.get(), depositor)
+    }
+
+    /// 存款人累计的某种抵押品收益
+    pub fn get_collateral_gain(
+        &self,
+        depositor: Address,
+        token: Address,
+    ) -> Result<U256, StabilityPoolError> {
+        self._pro_rata(token, depositor)
+    }
+
+    pub fn get_shares(&self, depositor: Address) -> U256 {
+        self.shares.get(depositor)
+    }
+
+    pub fn get_total_shares(&self) -> U256 {
+        self.total_shares.get()
+    }
+
+    pub fn get_engine(&self) -> Address {
+        self.engine.get()
+    }
+}
+
+// 内部辅助函数：不对外暴露
+impl StabilityPool {
+    fn _collateral_tokens(&self) -> Result<Vec<Address>, StabilityPoolError> {
+        IDSCEngine::new(self.engine.get())
+            .get_local_collateral_tokens(Call::new())
+            .map_err(|_| StabilityPoolError::EngineCallFailed(EngineCallFailed {}))
+    }
+
+    fn _balance_of(&self, token: Address) -> Result<U256, StabilityPoolError> {
+        IERC20::new(token)
+            .balance_of(Call::new(), contract::address())
+            .map_err(|_| StabilityPoolError::TransferFailed(TransferFailed {}))
+    }
+
+    fn _pro_rata(&self, token: Address, depositor: Address) -> Result<U256, StabilityPoolError> {
+        let shares = self.shares.get(depositor);
+        if shares == U256::ZERO {
+            return Ok(U256::ZERO);
+        }
+        let virtual_total = self.total_shares.get() + U256::from(VIRTUAL_SHARES);
+        Ok((self._balance_of(token)? * shares) / virtual_total)
+    }
+
+    fn _transfer(
+        &self,
+        token: Address,
+        to: Address,
+        amount: U256,
+    ) -> Result<(), StabilityPoolError> {
+        if IERC20::new(token)
+            .transfer(Call::new(), to, amount)
+            .is_err()
+        {
+            return Err(StabilityPoolError::TransferFailed(TransferFailed {}));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+//! 盈余缓冲：按来源记录协议收入
+//!
+//! 稳定费利息、PSM 手续费和清算罚金以 DSC 形式留在引擎中，并计入内部盈余缓冲。
+//! 清算罚金包括拍卖罚金，以及清算人按协议分成比例为清算奖励额外支付的 DSC。
+//! 撤出协议自有流动性时取回的 DSC 超过对应债务的部分也计入盈余。
+//! 储蓄金库收益、保险基金划拨、keeper 清算奖励和治理提取都从盈余缓冲中支出，
+//! 直接转入引擎的 DSC 不计入盈余。
+//!
+//! 开启手续费开关后，每笔收入按来源配置的基点比例划给国库，待领取部分由任何人推送到国库地址。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256, U8},
+    call::Call,
+    contract, evm,
+};
+
+use crate::{
+    psm::BPS, DSCEngine, DSCEngineError, IDecentralizedStableCoin, InsufficientSurplus,
+    KeeperRewardPaid, SurplusAccrued, SurplusWithdrawn, TransferFailed, TreasuryFeeCollected,
+    TreasuryFeesClaimed, TreasuryNotSet, LIQUIDATION_PRECISION,
+};
+
+// 盈余来源：稳定费利息
+pub(crate) const SURPLUS_STABILITY_FEE: u8 = 0;
+// 盈余来源：PSM 手续费
+pub(crate) const SURPLUS_PSM_FEE: u8 = 1;
+// 盈余来源：清算罚金
+pub(crate) const SURPLUS_LIQUIDATION_PENALTY: u8 = 2;
+// 盈余来源：协议自有流动性
+pub(crate) const SURPLUS_PROTOCOL_LIQUIDITY: u8 = 3;
+
+impl DSCEngine {
+    // 记录一笔协议收入，手续费开关开启时按比例划给国库
+    pub(crate) fn _add_surplus(&mut self, source: u8, amount: U256) {
+        if amount == U256::ZERO {
+            return;
+        }
+        let key = U8::from(source);
+        let accrued = self.surplus_by_source.get(key);
+        self.surplus_by_source.setter(key).set(accrued + amount);
+        let mut treasury_fee = U256::ZERO;
+        if self._fee_switch() {
+            treasury_fee = (amount * self.treasury_fee_shares.get(key)) / U256::from(BPS);
+        }
+        if treasury_fee > U256::ZERO {
+            self.treasury_fees
+                .set(self.treasury_fees.get() + treasury_fee);
+            evm::log(TreasuryFeeCollected {
+                source,
+                amount: treasury_fee,
+            });
+        }
+        let retained = amount - treasury_fee;
+        self.surplus_buffer
+            .set(self.surplus_buffer.get() + retained);
+        evm::log(SurplusAccrued {
+            source,
+            amount: retained,
+        });
+    }
+
+    // 把待领取的国库手续费转到国库地址，返回转出的数量
+    pub(crate) fn _collect_treasury_fees(&mut self) -> Result<U256, DSCEngineError> {
+        let treasury = self.treasury.get();
+        if treasury.is_zero() {
+            return Err(DSCEngineError::TreasuryNotSet(TreasuryNotSet {}));
+        }
+        let amount = self.treasury_fees.get();
+        if amount == U256::ZERO {
+            return Ok(U256::ZERO);
+        }
+        self.treasury_fees.set(U256::ZERO);
+        if IDecentralizedStableCoin::new(self.dsc.get())
+            .transfer(Call::new(), treasury, amount)
+            .is_err()
+        {
+            return Err(DSCEngineError::TransferFailed(TransferFailed {}));
+        }
+        evm::log(TreasuryFeesClaimed { treasury, amount });
+        Ok(amount)
+    }
+
+    // 按清算奖励 bonus 清算时，奖励中协议分成对应的 DSC 数量
+    pub(crate) fn _liquidation_protocol_fee(&self, debt_to_cover: U256, bonus: U256) -> U256 {
+        (debt_to_cover * bonus * self.liquidation_protocol_share.get())
+            / (LIQUIDATION_PRECISION * LIQUIDATION_PRECISION)
+    }
+
+    // 向清算人收取清算奖励中的协议分成，计入盈余
+    pub(crate) fn _collect_liquidation_protocol_fee(
+        &mut self,
+        debt_to_cover: U256,
+        bonus: U256,
+        payer: Address,
+    ) -> Result<(), DSCEngineError> {
+        let fee = self._liquidation_protocol_fee(debt_to_cover, bonus);
+        if fee == U256::ZERO {
+            return Ok(());
+        }
+        if IDecentralizedStableCoin::new(self.dsc.get())
+            .transfer_from(Call::new(), payer, contract::address(), fee)
+            .is_err()
+        {
+            return Err(DSCEngineError::TransferFailed(TransferFailed {}));
+        }
+        self._add_surplus(SURPLUS_LIQUIDATION_PENALTY, fee);
+        Ok(())
+    }
+
+    // 从盈余缓冲中向发起清算的 keeper 支付固定奖励，盈余不足时按余额支付
+    pub(crate) fn _pay_keeper_reward(&mut self, keeper: Address) -> Result<(), DSCEngineError> {
+        let reward = self.keeper_reward.get().min(self.surplus_buffer.get());
+        if reward == U256::ZERO {
+            return Ok(());
+        }
+        self._spend_surplus(reward)?;
+        if IDecentralizedStableCoin::new(self.dsc.get())
+            .transfer(Call::new(), keeper, reward)
+            .is_err()
+        {
+            return Err(DSCEngineError::TransferFailed(TransferFailed {}));
+        }
+        evm::log(KeeperRewardPaid {
+            keeper,
+            amount: reward,
+        });
+        Ok(())
+    }
+
+    // 从盈余缓冲中扣减支出
+    pub(crate) fn _spend_surplus(&mut self, amount: U256) -> Result<(), DSCEngineError> {
+        let surplus = self.surplus_buffer.get();
+        if amount > surplus {
+            return Err(DSCEngineError::InsufficientSurplus(InsufficientSurplus {}));
+        }
+        self.surplus_buffer.set(surplus - amount);
+        Ok(())
+    }
+
+    // 从盈余缓冲中提取 DSC 到指定地址
+    pub(crate) fn _withdraw_surplus(
+        &mut self,
+        to: Address,
+        amount: U256,
+    ) -> Result<(), DSCEngineError> {
+        self._spend_surplus(amount)?;
+        if IDecentralizedStableCoin::new(self.dsc.get())
+            .transfer(Call::new(), to, amount)
+            .is_err()
+        {
+            return Err(DSCEngineError::TransferFailed(TransferFailed {}));
+        }
+        evm::log(SurplusWithdrawn { to, amount });
+        Ok(())
+    }
+}
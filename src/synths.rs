@@ -0,0 +1,322 @@
+//! 附加合成资产：同一组抵押品同时支撑 DSC 以外的多种合成资产（如 dEUR、dBTC）
+//!
+//! 治理登记的每种合成资产都是一个以引擎为 owner 的稳定币合约，带有价格预言机（一单位合成资产的美元价格，
+//! 零地址表示锚定美元）和债务上限。用户的合成资产债务按资产分别记账，不计稳定费，
+//! 按当前价格折算为记账单位后与 DSC 债务一起计入全仓健康因子；价格不可用时视为债务无穷大，
+//! 暂停铸造和提取。合成资产债务由偿还的合成资产销毁，健康因子跌破最小健康因子时
+//! 清算人可以用合成资产清算，按清算奖励获得抵押品。
+//! 逐仓模式的仓位不能铸造合成资产，持有合成资产债务的仓位不能转让或迁移。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    call::{self, Call},
+    contract, evm, function_selector,
+    prelude::*,
+};
+
+use crate::{
+    casting::answer_to_price,
+    math::{mul_div, Rounding},
+    AuctionActive, BurnFailed, DSCEngine, DSCEngineError, DscInitializationFailed,
+    ExceedsMaxLiquidatableDebt, HealthFactorNotImproved, HealthFactorOk, IAggregatorV3,
+    IDecentralizedStableCoin, MintFailed, NotAllowedSynth, PriceFeedError, RepayExceedsDebt,
+    SynthAdded, SynthBurned, SynthDebtCeilingExceeded, SynthDebtCeilingSet, SynthDebtOutstanding,
+    SynthLiquidated, SynthMinted, TransferFailed, ADDITIONAL_FEED_PRECISION, LIQUIDATION_PRECISION,
+    PRECISION,
+};
+
+// 锚定美元的合成资产价格：1 美元（预言机精度）
+const ONE_USD: U256 = U256::from_limbs([100_000_000, 0, 0, 0]);
+
+sol_storage! {
+    // 合成资产的登记信息
+    pub struct Synth {
+        bool listed;                         // 是否已登记
+        address price_feed;                  // 价格预言机：一单位合成资产的美元价格，零地址表示锚定美元
+        uint256 debt_ceiling;                // 债务上限：该合成资产的总债务上限
+        uint256 total_debt;                  // 总债务：所有仓位的该合成资产债务之和
+    }
+}
+
+impl DSCEngine {
+    // 检查合成资产已登记
+    pub(crate) fn _require_synth(&self, synth: Address) -> Result<(), DSCEngineError> {
+        if !self.synths.getter(synth).listed.get() {
+            return Err(DSCEngineError::NotAllowedSynth(NotAllowedSynth {}));
+        }
+        Ok(())
+    }
+
+    // 登记合成资产并成为其稳定币合约的 owner
+    pub(crate) fn _add_synth(
+        &mut self,
+        synth: Address,
+        price_feed: Address,
+        debt_ceiling: U256,
+    ) -> Result<(), DSCEngineError> {
+        if synth.is_zero() || synth == self.dsc.get() || self.synths.getter(synth).listed.get() {
+            return Err(DSCEngineError::NotAllowedSynth(NotAllowedSynth {}));
+        }
+        call::call(Call::new(), synth, &function_selector!("constructor"))
+            .map_err(|_| DSCEngineError::DscInitializationFailed(DscInitializationFailed {}))?;
+        {
+            let mut config = self.synths.setter(synth);
+            config.listed.set(true);
+            config.price_feed.set(price_feed);
+            config.debt_ceiling.set(debt_ceiling);
+        }
+        self.synth_tokens.push(synth);
+        // 新合成资产必须能给出有效报价
+        if self._synth_price(synth)? == U256::ZERO {
+            return Err(DSCEngineError::PriceFeedError(PriceFeedError {}));
+        }
+        evm::log(SynthAdded {
+            synth,
+            priceFeed: price_feed,
+            debtCeiling: debt_ceiling,
+        });
+        Ok(())
+    }
+
+    // 设置合成资产的债务上限，低于当前总债务时只阻止新增铸造
+    pub(crate) fn _set_synth_debt_ceiling(
+        &mut self,
+        synth: Address,
+        debt_ceiling: U256,
+    ) -> Result<(), DSCEngineError> {
+        self._require_synth(synth)?;
+        self.synths.setter(synth).debt_ceiling.set(debt_ceiling);
+        evm::log(SynthDebtCeilingSet {
+            synth,
+            debtCeiling: debt_ceiling,
+        });
+        Ok(())
+    }
+
+    // 一单位合成资产以记账单位计的价格（预言机精度）
+    pub(crate) fn _synth_price(&self, synth: Address) -> Result<U256, DSCEngineError> {
+        let price_feed = self.synths.getter(synth).price_feed.get();
+        if price_feed.is_zero() {
+            return self._to_peg_price(ONE_USD);
+        }
+        let (answer, _) = self
+            ._feed_answer(price_feed)
+            .ok_or(DSCEngineError::PriceFeedError(PriceFeedError {}))?;
+        let price = answer_to_price(answer)?;
+        // 报价统一换算为 8 位精度
+        let decimals = IAggregatorV3::new(price_feed)
+            .decimals(Call::new())
+            .map_err(|_| DSCEngineError::PriceFeedError(PriceFeedError {}))?;
+        let usd_price = mul_div(
+            price,
+            ONE_USD,
+            U256::from(10).pow(U256::from(decimals)),
+            Rounding::Up,
+        );
+        self._to_peg_price(usd_price)
+    }
+
+    // 合成资产数量以记账单位计的价值（18 位精度），债务按价格向上取整
+    pub(crate) fn _synth_value(
+        &self,
+        synth: Address,
+        amount: U256,
+    ) -> Result<U256, DSCEngineError> {
+        let price = self._synth_price(synth)?;
+        Ok(mul_div(
+            amount,
+            price * ADDITIONAL_FEED_PRECISION,
+            PRECISION,
+            Rounding::Up,
+        ))
+    }
+
+    // 用户全部合成资产债务以记账单位计的价值，价格不可用时为 U256::MAX
+    pub(crate) fn _synth_debt_value(&self, user: Address) -> U256 {
+        let mut total_value = U256::ZERO;
+        for i in 0..self.synth_tokens.len() {
+            let Some(synth) = self.synth_tokens.get(i) else {
+                continue;
+            };
+            let debt = self.synth_debt.getter(user).get(synth);
+            if debt == U256::ZERO {
+                continue;
+            }
+            let value = self._synth_value(synth, debt).unwrap_or(U256::MAX);
+            total_value = total_value.saturating_add(value);
+        }
+        total_value
+    }
+
+    // 用户是否持有合成资产债务
+    pub(crate) fn _has_synth_debt(&self, user: Address) -> bool {
+        (0..self.synth_tokens.len()).any(|i| {
+            self.synth_tokens
+                .get(i)
+                .is_some_and(|synth| self.synth_debt.getter(user).get(synth) > U256::ZERO)
+        })
+    }
+
+    // 持有合成资产债务的仓位不能整体转出
+    pub(crate) fn _require_no_synth_debt(&self, user: Address) -> Result<(), DSCEngineError> {
+        if self._has_synth_debt(user) {
+            return Err(DSCEngineError::SynthDebtOutstanding(
+                SynthDebtOutstanding {},
+            ));
+        }
+        Ok(())
+    }
+
+    // 用户全部债务（DSC 与合成资产）以记账单位计的价值
+    pub(crate) fn _account_debt_value(&self, user: Address) -> U256 {
+        self._user_debt(user)
+            .saturating_add(self._synth_debt_value(user))
+    }
+
+    // 为用户铸造合成资产并记入债务（调用前需先计提）
+    pub(crate) fn _mint_synth(
+        &mut self,
+        user: Address,
+        synth: Address,
+        amount: U256,
+    ) -> Result<(), DSCEngineError> {
+        self._require_synth(synth)?;
+        if self.isolated_margin.get(user) {
+            return Err(DSCEngineError::NotAllowedSynth(NotAllowedSynth {}));
+        }
+        self._check_permissioned_caller(user)?;
+        self._check_price_breakers_of(user, None)?;
+        let total_debt = self.synths.getter(synth).total_debt.get() + amount;
+        let debt_ceiling = self.synths.getter(synth).debt_ceiling.get();
+        if total_debt > debt_ceiling {
+            return Err(DSCEngineError::SynthDebtCeilingExceeded(
+                SynthDebtCeilingExceeded { _0: debt_ceiling },
+            ));
+        }
+        self.synths.setter(synth).total_debt.set(total_debt);
+        let debt = self.synth_debt.getter(user).get(synth) + amount;
+        self.synth_debt.setter(user).setter(synth).set(debt);
+        self._sync_active_position(user);
+        self._revert_if_borrow_health_factor_is_broken(user)?;
+        IDecentralizedStableCoin::new(synth)
+            .mint(Call::new(), user, amount)
+            .map_err(|_| DSCEngineError::MintFailed(MintFailed {}))?;
+        evm::log(SynthMinted {
+            user,
+            synth,
+            amount,
+            totalDebt: debt,
+            actionNonce: self._next_action_nonce(user),
+        });
+        Ok(())
+    }
+
+    // 从 from 收取合成资产并销毁，减少 on_behalf_of 的合成资产债务
+    pub(crate) fn _burn_synth(
+        &mut self,
+        synth: Address,
+        amount: U256,
+        on_behalf_of: Address,
+        from: Address,
+    ) -> Result<(), DSCEngineError> {
+        self._require_synth(synth)?;
+        let debt = self.synth_debt.getter(on_behalf_of).get(synth);
+        if amount > debt {
+            return Err(DSCEngineError::RepayExceedsDebt(RepayExceedsDebt {
+                _0: debt,
+            }));
+        }
+        self.synth_debt
+            .setter(on_behalf_of)
+            .setter(synth)
+            .set(debt - amount);
+        let total_debt = self.synths.getter(synth).total_debt.get();
+        self.synths
+            .setter(synth)
+            .total_debt
+            .set(total_debt.saturating_sub(amount));
+        self._sync_active_position(on_behalf_of);
+        let token = IDecentralizedStableCoin::new(synth);
+        let transferred = token.transfer_from(Call::new(), from, contract::address(), amount);
+        if !matches!(transferred, Ok(true)) {
+            return Err(DSCEngineError::TransferFailed(TransferFailed {}));
+        }
+        token
+            .burn(Call::new(), amount)
+            .map_err(|_| DSCEngineError::BurnFailed(BurnFailed {}))?;
+        evm::log(SynthBurned {
+            onBehalfOf: on_behalf_of,
+            from,
+            synth,
+            amount,
+            totalDebt: debt - amount,
+            actionNonce: self._next_action_nonce(on_behalf_of),
+        });
+        Ok(())
+    }
+
+    // 清算人以合成资产偿还用户的合成资产债务并获得抵押品，返回获得的抵押品数量
+    pub(crate) fn _liquidate_synth(
+        &mut self,
+        collateral: Address,
+        user: Address,
+        synth: Address,
+        debt_to_cover: U256,
+        liquidator: Address,
+    ) -> Result<U256, DSCEngineError> {
+        self.more_than_zero(debt_to_cover)?;
+        self._require_synth(synth)?;
+        self._accrue_all();
+        let starting_user_health_factor = self._health_factor(user)?;
+        if starting_user_health_factor >= self._min_health_factor() {
+            return Err(DSCEngineError::HealthFactorOk(HealthFactorOk {}));
+        }
+        // 健康因子低于临界健康因子时不受平仓系数限制
+        let close_factor = if starting_user_health_factor < self._critical_health_factor() {
+            LIQUIDATION_PRECISION
+        } else {
+            self._close_factor()
+        };
+        let max_debt_to_cover = mul_div(
+            self.synth_debt.getter(user).get(synth),
+            close_factor,
+            LIQUIDATION_PRECISION,
+            Rounding::Down,
+        );
+        if debt_to_cover > max_debt_to_cover {
+            return Err(DSCEngineError::ExceedsMaxLiquidatableDebt(
+                ExceedsMaxLiquidatableDebt {
+                    _0: max_debt_to_cover,
+                },
+            ));
+        }
+        if self.active_auction.get(user) != U256::ZERO {
+            return Err(DSCEngineError::AuctionActive(AuctionActive {}));
+        }
+        self._require_local_collateral(collateral)?;
+        self._check_liquidation_grace(collateral)?;
+        // 按偿还的合成资产价值和清算奖励换算抵押品
+        let debt_value = self._synth_value(synth, debt_to_cover)?;
+        let token_amount = self._token_amount_from_usd(collateral, debt_value)?;
+        let bonus = self._liquidation_bonus_at(collateral, starting_user_health_factor);
+        let collateral_seized = token_amount + (token_amount * bonus) / LIQUIDATION_PRECISION;
+        self._redeem_collateral(collateral, collateral_seized, user, liquidator)?;
+        self._burn_synth(synth, debt_to_cover, user, liquidator)?;
+        if self._health_factor(user)? <= starting_user_health_factor {
+            return Err(DSCEngineError::HealthFactorNotImproved(
+                HealthFactorNotImproved {},
+            ));
+        }
+        self._revert_if_health_factor_is_broken(liquidator)?;
+        evm::log(SynthLiquidated {
+            user,
+            liquidator,
+            synth,
+            collateral,
+            debtCovered: debt_to_cover,
+            collateralSeized: collateral_seized,
+            actionNonce: self._next_action_nonce(user),
+        });
+        Ok(collateral_seized)
+    }
+}
@@ -0,0 +1,664 @@
+//! 原生单元测试工具：在内存中模拟 Stylus 宿主环境
+//!
+//! 测试以原生目标编译时，SDK 的宿主函数没有 WASM 运行时提供，这里以同名符号实现它们：
+//! 存储读写落在当前线程的内存映射中，对外调用按目标地址分发给模拟的 ERC-20 代币、
+//! Chainlink 预言机、DEX 路由或按选择器登记的固定返回值。每个测试运行在独立线程上，互不影响。
+//!
+//! 原生调用返回错误时不会像链上那样回滚，修改状态的调用应通过 `transact` 执行，
+//! 失败时恢复调用前的存储和代币余额。
+//!
+//! SDK 会在进程内缓存区块时间、合约地址和直接调用者，因此这些值在测试中是固定的常量；
+//! 调用者通过 `call_as` 经 `msg_sender()` 模拟，区块时间不能前进。
+
+use alloc::{vec, vec::Vec};
+use alloy_sol_types::{sol, SolCall, SolEvent};
+use core::cell::RefCell;
+use std::collections::HashMap;
+use stylus_sdk::{
+    alloy_primitives::{address, Address, B256, I256, U256},
+    keccak_const::Keccak256,
+    storage::StorageType,
+};
+
+use crate::DSCEngine;
+
+// 固定的区块时间
+pub(crate) const NOW: u64 = 1_700_000_000;
+// 固定的链 ID
+pub(crate) const CHAIN_ID: u64 = 42_161;
+// 引擎合约地址
+pub(crate) const ENGINE: Address = address!("00000000000000000000000000000000000e0001");
+// 治理地址
+pub(crate) const OWNER: Address = address!("0000000000000000000000000000000000000a01");
+// 普通用户地址
+pub(crate) const USER: Address = address!("0000000000000000000000000000000000000a02");
+// 清算人地址
+pub(crate) const LIQUIDATOR: Address = address!("0000000000000000000000000000000000000a03");
+// 稳定币合约地址
+pub(crate) const DSC: Address = address!("0000000000000000000000000000000000000d5c");
+// WETH 抵押品地址
+pub(crate) const WETH: Address = address!("0000000000000000000000000000000000000e70");
+// WETH/USD 预言机地址
+pub(crate) const WETH_FEED: Address = address!("0000000000000000000000000000000000000f01");
+// WETH 初始价格：2000 美元（8 位精度）
+pub(crate) const WETH_PRICE: i64 = 200_000_000_000;
+
+sol! {
+    function constructor() external;
+    function decimals() external view returns (uint8);
+    function totalSupply() external view returns (uint256);
+    function balanceOf(address account) external view returns (uint256);
+    function transfer(address to, uint256 value) external returns (bool);
+    function transferFrom(address from, address to, uint256 value) external returns (bool);
+    function approve(address spender, uint256 value) external returns (bool);
+    function mint(address to, uint256 amount) external returns (bool);
+    function burn(uint256 amount) external;
+    function latestRoundData() external view returns (uint80, int256, uint256, uint256, uint80);
+    function getRoundData(uint80 round_id) external view returns (uint80, int256, uint256, uint256, uint80);
+    function getPriceNoOlderThan(bytes32 id, uint256 age) external view returns (int64, uint64, int32, uint256);
+    function swapExactTokensForTokens(
+        uint256 amount_in,
+        uint256 amount_out_min,
+        address[] path,
+        address to,
+        uint256 deadline
+    ) external returns (uint256[] amounts);
+    function addLiquidity(
+        address token_a,
+        address token_b,
+        uint256 amount_a_desired,
+        uint256 amount_b_desired,
+        uint256 amount_a_min,
+        uint256 amount_b_min,
+        address to,
+        uint256 deadline
+    ) external returns (uint256, uint256, uint256);
+    function removeLiquidity(
+        address token_a,
+        address token_b,
+        uint256 liquidity,
+        uint256 amount_a_min,
+        uint256 amount_b_min,
+        address to,
+        uint256 deadline
+    ) external returns (uint256, uint256);
+}
+
+// 模拟的 ERC-20 代币：转账不检查授权，余额不足时回滚
+#[derive(Clone, Default)]
+pub(crate) struct FakeToken {
+    pub(crate) decimals: u8,
+    pub(crate) total_supply: U256,
+    pub(crate) balances: HashMap<Address, U256>,
+}
+
+impl FakeToken {
+    fn balance(&self, account: Address) -> U256 {
+        self.balances.get(&account).copied().unwrap_or_default()
+    }
+
+    fn move_tokens(&mut self, from: Address, to: Address, value: U256) -> Result<(), ()> {
+        let from_balance = self.balance(from);
+        if from_balance < value {
+            return Err(());
+        }
+        self.balances.insert(from, from_balance - value);
+        let to_balance = self.balance(to);
+        self.balances.insert(to, to_balance + value);
+        Ok(())
+    }
+
+    fn handle(&mut self, caller: Address, calldata: &[u8]) -> Result<Vec<u8>, ()> {
+        match selector_of(calldata) {
+            constructorCall::SELECTOR => Ok(Vec::new()),
+            decimalsCall::SELECTOR => Ok(decimalsCall::abi_encode_returns(&(self.decimals,))),
+            totalSupplyCall::SELECTOR => {
+                Ok(totalSupplyCall::abi_encode_returns(&(self.total_supply,)))
+            }
+            balanceOfCall::SELECTOR => {
+                let call = balanceOfCall::abi_decode(calldata, true).map_err(|_| ())?;
+                Ok(balanceOfCall::abi_encode_returns(&(
+                    self.balance(call.account),
+                )))
+            }
+            transferCall::SELECTOR => {
+                let call = transferCall::abi_decode(calldata, true).map_err(|_| ())?;
+                self.move_tokens(caller, call.to, call.value)?;
+                Ok(transferCall::abi_encode_returns(&(true,)))
+            }
+            transferFromCall::SELECTOR => {
+                let call = transferFromCall::abi_decode(calldata, true).map_err(|_| ())?;
+                self.move_tokens(call.from, call.to, call.value)?;
+                Ok(transferFromCall::abi_encode_returns(&(true,)))
+            }
+            approveCall::SELECTOR => Ok(approveCall::abi_encode_returns(&(true,))),
+            mintCall::SELECTOR => {
+                let call = mintCall::abi_decode(calldata, true).map_err(|_| ())?;
+                self.total_supply += call.amount;
+                let balance = self.balance(call.to);
+                self.balances.insert(call.to, balance + call.amount);
+                Ok(mintCall::abi_encode_returns(&(true,)))
+            }
+            burnCall::SELECTOR => {
+                let call = burnCall::abi_decode(calldata, true).map_err(|_| ())?;
+                self.move_tokens(caller, Address::ZERO, call.amount)?;
+                let burned = self.balance(Address::ZERO);
+                self.balances.remove(&Address::ZERO);
+                self.total_supply -= burned;
+                Ok(Vec::new())
+            }
+            _ => Err(()),
+        }
+    }
+}
+
+// 模拟的 Chainlink 预言机：始终返回最新一轮
+pub(crate) struct FakeFeed {
+    pub(crate) round_id: u128,
+    pub(crate) answer: I256,
+    pub(crate) updated_at: U256,
+}
+
+impl FakeFeed {
+    fn handle(&self, calldata: &[u8]) -> Result<Vec<u8>, ()> {
+        let round = (
+            self.round_id,
+            self.answer,
+            self.updated_at,
+            self.updated_at,
+            self.round_id,
+        );
+        match selector_of(calldata) {
+            latestRoundDataCall::SELECTOR => Ok(latestRoundDataCall::abi_encode_returns(&round)),
+            getRoundDataCall::SELECTOR => Ok(getRoundDataCall::abi_encode_returns(&round)),
+            decimalsCall::SELECTOR => Ok(decimalsCall::abi_encode_returns(&(8u8,))),
+            _ => Err(()),
+        }
+    }
+}
+
+// 模拟的 DEX 路由：按登记的固定兑换比例（18 位精度）收取输入代币并铸造输出代币。
+// 流动性池的储备就是路由持有的代币余额：注入时全额收取两种代币并铸造与第一种代币等量的 LP 代币，
+// 撤出时按 LP 代币占总供应量的比例返还储备
+#[derive(Default)]
+pub(crate) struct FakeRouter {
+    pub(crate) rates: HashMap<(Address, Address), U256>,
+    pub(crate) pairs: HashMap<(Address, Address), Address>,
+}
+
+impl FakeRouter {
+    fn handle(
+        &self,
+        router: Address,
+        tokens: &mut HashMap<Address, FakeToken>,
+        calldata: &[u8],
+    ) -> Result<Vec<u8>, ()> {
+        match selector_of(calldata) {
+            swapExactTokensForTokensCall::SELECTOR => {}
+            addLiquidityCall::SELECTOR => return self.add_liquidity(router, tokens, calldata),
+            removeLiquidityCall::SELECTOR => {
+                return self.remove_liquidity(router, tokens, calldata)
+            }
+            _ => return Err(()),
+        }
+        let call = swapExactTokensForTokensCall::abi_decode(calldata, true).map_err(|_| ())?;
+        let (token_in, token_out) = (call.path[0], call.path[call.path.len() - 1]);
+        let rate = self.rates.get(&(token_in, token_out)).ok_or(())?;
+        let amount_out = call.amount_in * rate / U256::from(10).pow(U256::from(18));
+        if amount_out < call.amount_out_min {
+            return Err(());
+        }
+        tokens
+            .get_mut(&token_in)
+            .ok_or(())?
+            .move_tokens(ENGINE, router, call.amount_in)?;
+        let out = tokens.get_mut(&token_out).ok_or(())?;
+        out.total_supply += amount_out;
+        let balance = out.balance(call.to);
+        out.balances.insert(call.to, balance + amount_out);
+        Ok(swapExactTokensForTokensCall::abi_encode_returns(&(vec![
+            call.amount_in,
+            amount_out,
+        ],)))
+    }
+
+    fn add_liquidity(
+        &self,
+        router: Address,
+        tokens: &mut HashMap<Address, FakeToken>,
+        calldata: &[u8],
+    ) -> Result<Vec<u8>, ()> {
+        let call = addLiquidityCall::abi_decode(calldata, true).map_err(|_| ())?;
+        let pair = *self.pairs.get(&(call.token_a, call.token_b)).ok_or(())?;
+        let (amount_a, amount_b) = (call.amount_a_desired, call.amount_b_desired);
+        for (token, amount) in [(call.token_a, amount_a), (call.token_b, amount_b)] {
+            tokens
+                .get_mut(&token)
+                .ok_or(())?
+                .move_tokens(ENGINE, router, amount)?;
+        }
+        let lp = tokens.get_mut(&pair).ok_or(())?;
+        lp.total_supply += amount_a;
+        let balance = lp.balance(call.to);
+        lp.balances.insert(call.to, balance + amount_a);
+        Ok(addLiquidityCall::abi_encode_returns(&(
+            amount_a, amount_b, amount_a,
+        )))
+    }
+
+    fn remove_liquidity(
+        &self,
+        router: Address,
+        tokens: &mut HashMap<Address, FakeToken>,
+        calldata: &[u8],
+    ) -> Result<Vec<u8>, ()> {
+        let call = removeLiquidityCall::abi_decode(calldata, true).map_err(|_| ())?;
+        let pair = *self.pairs.get(&(call.token_a, call.token_b)).ok_or(())?;
+        let lp = tokens.get_mut(&pair).ok_or(())?;
+        let supply = lp.total_supply;
+        lp.move_tokens(ENGINE, Address::ZERO, call.liquidity)?;
+        lp.balances.remove(&Address::ZERO);
+        lp.total_supply -= call.liquidity;
+        let mut amounts = [U256::ZERO; 2];
+        for (i, (token, min)) in [
+            (call.token_a, call.amount_a_min),
+            (call.token_b, call.amount_b_min),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let fake = tokens.get_mut(&token).ok_or(())?;
+            amounts[i] = call.liquidity * fake.balance(router) / supply;
+            if amounts[i] < min {
+                return Err(());
+            }
+            fake.move_tokens(router, call.to, amounts[i])?;
+        }
+        Ok(removeLiquidityCall::abi_encode_returns(&(
+            amounts[0], amounts[1],
+        )))
+    }
+}
+
+// 对外调用的结果：Ok 为返回数据，Err 为回滚数据
+pub(crate) type CallResult = Result<Vec<u8>, Vec<u8>>;
+
+// 当前线程的宿主状态
+#[derive(Default)]
+struct Host {
+    storage: HashMap<B256, B256>,
+    return_data: Vec<u8>,
+    tokens: HashMap<Address, FakeToken>,
+    feeds: HashMap<Address, FakeFeed>,
+    routers: HashMap<Address, FakeRouter>,
+    // 按 (目标地址, 选择器) 登记的固定调用结果，优先于模拟代币和预言机
+    mocked_calls: HashMap<(Address, [u8; 4]), CallResult>,
+    // 合约发出的事件：(主题, 数据)
+    logs: Vec<(Vec<B256>, Vec<u8>)>,
+}
+
+std::thread_local! {
+    static HOST: RefCell<Host> = RefCell::new(Host::default());
+}
+
+fn selector_of(calldata: &[u8]) -> [u8; 4] {
+    let mut selector = [0u8; 4];
+    if calldata.len() >= 4 {
+        selector.copy_from_slice(&calldata[..4]);
+    }
+    selector
+}
+
+// 读取挂在 ENGINE 地址上的引擎合约实例
+pub(crate) fn engine() -> DSCEngine {
+    unsafe { <DSCEngine as StorageType>::new(U256::ZERO, 0) }
+}
+
+// 模拟一次新的外部调用：设置调用者并清空交易内的预言机缓存
+pub(crate) fn call_as(sender: Address) {
+    crate::meta_tx::set_msg_sender(sender);
+    crate::oracle_cache::clear_feed_cache();
+}
+
+// 以 sender 身份执行一次修改状态的调用，返回错误时回滚存储、代币余额和发出的事件
+pub(crate) fn transact<T, E>(sender: Address, f: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+    call_as(sender);
+    let snapshot = HOST.with(|host| {
+        let host = host.borrow();
+        (host.storage.clone(), host.tokens.clone(), host.logs.len())
+    });
+    let result = f();
+    if result.is_err() {
+        HOST.with(|host| {
+            let mut host = host.borrow_mut();
+            let (storage, tokens, logs) = snapshot;
+            (host.storage, host.tokens) = (storage, tokens);
+            host.logs.truncate(logs);
+        });
+    }
+    result
+}
+
+// 按顺序解码合约发出的某类事件
+pub(crate) fn events<E: SolEvent>() -> Vec<E> {
+    HOST.with(|host| {
+        host.borrow()
+            .logs
+            .iter()
+            .filter(|(topics, _)| topics.first() == Some(&E::SIGNATURE_HASH))
+            .map(|(topics, data)| {
+                E::decode_raw_log(topics.iter().copied(), data, true).expect("malformed event")
+            })
+            .collect()
+    })
+}
+
+// 登记一个模拟代币
+pub(crate) fn add_token(token: Address, decimals: u8) {
+    HOST.with(|host| {
+        host.borrow_mut().tokens.insert(
+            token,
+            FakeToken {
+                decimals,
+                ..Default::default()
+            },
+        )
+    });
+}
+
+// 向账户发放模拟代币
+pub(crate) fn mint_token(token: Address, to: Address, amount: U256) {
+    HOST.with(|host| {
+        let mut host = host.borrow_mut();
+        let fake = host.tokens.get_mut(&token).expect("token not registered");
+        fake.total_supply += amount;
+        let balance = fake.balance(to);
+        fake.balances.insert(to, balance + amount);
+    });
+}
+
+// 账户持有的模拟代币数量
+pub(crate) fn token_balance(token: Address, account: Address) -> U256 {
+    HOST.with(|host| host.borrow().tokens[&token].balance(account))
+}
+
+// 模拟代币的总供应量
+pub(crate) fn token_supply(token: Address) -> U256 {
+    HOST.with(|host| host.borrow().tokens[&token].total_supply)
+}
+
+// 设置预言机最新价格（8 位精度），更新时间为当前区块时间
+pub(crate) fn set_price(feed: Address, answer: i64) {
+    HOST.with(|host| {
+        let mut host = host.borrow_mut();
+        let round_id = host.feeds.get(&feed).map_or(1, |feed| feed.round_id + 1);
+        host.feeds.insert(
+            feed,
+            FakeFeed {
+                round_id,
+                answer: I256::try_from(answer).unwrap(),
+                updated_at: U256::from(NOW),
+            },
+        );
+    });
+}
+
+// 登记模拟 DEX 路由的兑换比例：每单位输入代币兑换的输出代币数量（18 位精度）
+pub(crate) fn set_swap_rate(router: Address, token_in: Address, token_out: Address, rate: U256) {
+    HOST.with(|host| {
+        host.borrow_mut()
+            .routers
+            .entry(router)
+            .or_default()
+            .rates
+            .insert((token_in, token_out), rate)
+    });
+}
+
+// 登记模拟 DEX 路由的流动性池及其 LP 代币
+pub(crate) fn set_pair(router: Address, token_a: Address, token_b: Address, pair: Address) {
+    add_token(pair, 18);
+    HOST.with(|host| {
+        host.borrow_mut()
+            .routers
+            .entry(router)
+            .or_default()
+            .pairs
+            .insert((token_a, token_b), pair)
+    });
+}
+
+// 登记某合约某方法的固定调用结果，Err 表示调用回滚
+pub(crate) fn mock_call(to: Address, selector: [u8; 4], result: CallResult) {
+    HOST.with(|host| {
+        host.borrow_mut()
+            .mocked_calls
+            .insert((to, selector), result)
+    });
+}
+
+// 清空当前线程的宿主状态，部署 WETH、预言机和稳定币，并由 OWNER 初始化引擎
+pub(crate) fn setup() -> DSCEngine {
+    deploy(None)
+}
+
+// 同 setup，但以报价为 peg_price（8 位精度）的锚定货币预言机初始化引擎
+pub(crate) fn setup_pegged(peg_feed: Address, peg_price: i64) -> DSCEngine {
+    deploy(Some((peg_feed, peg_price)))
+}
+
+fn deploy(peg: Option<(Address, i64)>) -> DSCEngine {
+    HOST.with(|host| *host.borrow_mut() = Host::default());
+    add_token(WETH, 18);
+    add_token(DSC, 18);
+    set_price(WETH_FEED, WETH_PRICE);
+    let peg_feed = peg.map_or(Address::ZERO, |(peg_feed, peg_price)| {
+        set_price(peg_feed, peg_price);
+        peg_feed
+    });
+    let mut engine = engine();
+    transact(OWNER, || {
+        engine.initialize_with_peg(vec![WETH], vec![WETH_FEED], DSC, peg_feed)
+    })
+    .unwrap_or_else(|_| panic!("initialize failed"));
+    engine
+}
+
+// 分发一次对外调用，返回 (是否成功, 返回数据)
+fn dispatch(to: Address, calldata: &[u8]) -> (bool, Vec<u8>) {
+    HOST.with(|host| {
+        let mut host = host.borrow_mut();
+        if let Some(result) = host.mocked_calls.get(&(to, selector_of(calldata))) {
+            return match result.clone() {
+                Ok(data) => (true, data),
+                Err(data) => (false, data),
+            };
+        }
+        let result = if let Some(token) = host.tokens.get_mut(&to) {
+            token.handle(ENGINE, calldata)
+        } else if let Some(feed) = host.feeds.get(&to) {
+            feed.handle(calldata)
+        } else if host.routers.contains_key(&to) {
+            let Host {
+                routers, tokens, ..
+            } = &mut *host;
+            routers[&to].handle(to, tokens, calldata)
+        } else {
+            Err(())
+        };
+        match result {
+            Ok(data) => (true, data),
+            Err(()) => (false, Vec::new()),
+        }
+    })
+}
+
+unsafe fn do_call(
+    contract: *const u8,
+    calldata: *const u8,
+    calldata_len: usize,
+    return_data_len: *mut usize,
+) -> u8 {
+    let to = Address::from_slice(core::slice::from_raw_parts(contract, 20));
+    let calldata = core::slice::from_raw_parts(calldata, calldata_len);
+    let (success, data) = dispatch(to, calldata);
+    *return_data_len = data.len();
+    HOST.with(|host| host.borrow_mut().return_data = data);
+    u8::from(!success)
+}
+
+unsafe fn write_address(dest: *mut u8, address: Address) {
+    core::ptr::copy_nonoverlapping(address.as_ptr(), dest, 20);
+}
+
+unsafe fn write_word(dest: *mut u8, word: B256) {
+    core::ptr::copy_nonoverlapping(word.as_ptr(), dest, 32);
+}
+
+// 以下为 SDK 宿主函数在原生目标上的实现
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_load_bytes32(key: *const u8, dest: *mut u8) {
+    let key = B256::from_slice(core::slice::from_raw_parts(key, 32));
+    let value = HOST.with(|host| host.borrow().storage.get(&key).copied().unwrap_or_default());
+    write_word(dest, value);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_cache_bytes32(key: *const u8, value: *const u8) {
+    let key = B256::from_slice(core::slice::from_raw_parts(key, 32));
+    let value = B256::from_slice(core::slice::from_raw_parts(value, 32));
+    HOST.with(|host| host.borrow_mut().storage.insert(key, value));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn storage_flush_cache(_clear: bool) {}
+
+#[no_mangle]
+pub unsafe extern "C" fn call_contract(
+    contract: *const u8,
+    calldata: *const u8,
+    calldata_len: usize,
+    _value: *const u8,
+    _gas: u64,
+    return_data_len: *mut usize,
+) -> u8 {
+    do_call(contract, calldata, calldata_len, return_data_len)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn static_call_contract(
+    contract: *const u8,
+    calldata: *const u8,
+    calldata_len: usize,
+    _gas: u64,
+    return_data_len: *mut usize,
+) -> u8 {
+    do_call(contract, calldata, calldata_len, return_data_len)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn delegate_call_contract(
+    contract: *const u8,
+    calldata: *const u8,
+    calldata_len: usize,
+    _gas: u64,
+    return_data_len: *mut usize,
+) -> u8 {
+    do_call(contract, calldata, calldata_len, return_data_len)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn read_return_data(dest: *mut u8, offset: usize, size: usize) -> usize {
+    HOST.with(|host| {
+        let host = host.borrow();
+        let data = host.return_data.get(offset..).unwrap_or_default();
+        let len = data.len().min(size);
+        core::ptr::copy_nonoverlapping(data.as_ptr(), dest, len);
+        len
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn return_data_size() -> usize {
+    HOST.with(|host| host.borrow().return_data.len())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn emit_log(data: *const u8, len: usize, topics: usize) {
+    // 前 topics 个 32 字节为主题，其余为事件数据
+    let bytes = core::slice::from_raw_parts(data, len);
+    let (topic_bytes, data) = bytes.split_at(topics * 32);
+    let topics = topic_bytes.chunks(32).map(B256::from_slice).collect();
+    HOST.with(|host| host.borrow_mut().logs.push((topics, data.to_vec())));
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn native_keccak256(bytes: *const u8, len: usize, output: *mut u8) {
+    let bytes = core::slice::from_raw_parts(bytes, len);
+    let digest = Keccak256::new().update(bytes).finalize();
+    core::ptr::copy_nonoverlapping(digest.as_ptr(), output, 32);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn contract_address(address: *mut u8) {
+    write_address(address, ENGINE);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn msg_sender(sender: *mut u8) {
+    write_address(sender, Address::ZERO);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn msg_value(value: *mut u8) {
+    write_word(value, B256::ZERO);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn msg_reentrant() -> bool {
+    false
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn tx_origin(origin: *mut u8) {
+    write_address(origin, Address::ZERO);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn block_timestamp() -> u64 {
+    NOW
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn block_number() -> u64 {
+    1
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn chainid() -> u64 {
+    CHAIN_ID
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn account_code_size(_address: *const u8) -> usize {
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn account_balance(_address: *const u8, dest: *mut u8) {
+    write_word(dest, B256::ZERO);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn evm_gas_left() -> u64 {
+    u64::MAX
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn evm_ink_left() -> u64 {
+    u64::MAX
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pay_for_memory_grow(_pages: u16) {}
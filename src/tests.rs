@@ -0,0 +1,2182 @@
+//! 引擎核心流程的原生单元测试：存入 → 铸造 → 赎回 → 清算
+
+use alloy_sol_types::SolCall;
+use stylus_sdk::alloy_primitives::{Address, FixedBytes, I256, U256};
+use stylus_sdk::function_selector;
+use stylus_sdk::storage::StorageType;
+
+use crate::casting::answer_to_price;
+use crate::erc165::{
+    xor_selectors, DSC_ENGINE_INTERFACE_ID, ERC165_INTERFACE_ID, ERC20_INTERFACE_ID,
+    ERC721_INTERFACE_ID,
+};
+use crate::erc20_votes::{DelegateVotesChanged, Votes};
+use crate::math::{mul_div, ray_mul, wad_div, wad_mul, Rounding, RAY};
+use crate::param_registry::param_name;
+use crate::rate_index::{compound, rpow};
+use crate::test_utils::{
+    add_token, call_as, events, mint_token, mock_call, set_pair, set_price, set_swap_rate, setup,
+    setup_pegged, token_balance, token_supply, transact, DSC, ENGINE, LIQUIDATOR, NOW, OWNER, USER,
+    WETH, WETH_FEED,
+};
+use crate::test_utils::{
+    getPriceNoOlderThanCall, getRoundDataCall, latestRoundDataCall, transferFromCall,
+};
+use crate::{
+    CollateralDeposited, CollateralRedeemed, DSCEngineError, DscBurned, DscMinted,
+    EmergencyPriceCleared, EmergencyPriceSet, FeeExemptUpdated, ParamUpdated, PriceFeedUpdated,
+    RepaidWithCollateral, PRICE_SOURCE_CHAINLINK, PRICE_SOURCE_COMPOSED, PRICE_SOURCE_PYTH, TIER_A,
+    TIER_B,
+};
+
+fn ether(amount: u64) -> U256 {
+    U256::from(amount) * U256::from(10).pow(U256::from(18))
+}
+
+#[test]
+fn initialize_registers_collateral_and_owner() {
+    let mut engine = setup();
+    assert_eq!(engine.get_collateral_tokens(), vec![WETH]);
+    assert_eq!(engine.owner.get(), OWNER);
+    assert_eq!(engine.dsc.get(), DSC);
+    // 只能初始化一次
+    assert!(matches!(
+        transact(OWNER, || engine.initialize(
+            vec![WETH],
+            vec![WETH_FEED],
+            DSC
+        )),
+        Err(DSCEngineError::AlreadyInitialized(_))
+    ));
+}
+
+#[test]
+fn usd_value_and_token_amount_round_trip() {
+    let engine = setup();
+    // 15 ETH × 2000 美元 = 30000 美元
+    assert_eq!(engine.get_usd_value(WETH, ether(15)), ether(30_000));
+    // 100 美元 / 2000 美元 = 0.05 ETH
+    assert_eq!(
+        engine.get_token_amount_from_usd(WETH, ether(100)),
+        ether(5) / U256::from(100)
+    );
+}
+
+#[test]
+fn deposit_moves_tokens_and_records_collateral() {
+    let mut engine = setup();
+    mint_token(WETH, USER, ether(10));
+    assert!(transact(USER, || engine.deposit_collateral(WETH, ether(10))).is_ok());
+    assert_eq!(engine.get_collateral_balance_of_user(USER, WETH), ether(10));
+    assert_eq!(token_balance(WETH, USER), U256::ZERO);
+    assert_eq!(token_balance(WETH, ENGINE), ether(10));
+    assert_eq!(
+        engine.get_account_collateral_value_in_usd(USER),
+        ether(20_000)
+    );
+}
+
+#[test]
+fn deposit_rejects_zero_and_unknown_tokens() {
+    let mut engine = setup();
+    assert!(matches!(
+        transact(USER, || engine.deposit_collateral(WETH, U256::ZERO)),
+        Err(DSCEngineError::NeedsMoreThanZero(_))
+    ));
+    assert!(matches!(
+        transact(USER, || engine.deposit_collateral(DSC, ether(1))),
+        Err(DSCEngineError::NotAllowedToken(_))
+    ));
+}
+
+#[test]
+fn failed_deposit_rolls_back() {
+    let mut engine = setup();
+    mint_token(WETH, USER, ether(10));
+    // 代币转账回滚时引擎报告转账失败，不记入抵押品
+    mock_call(WETH, transferFromCall::SELECTOR, Err(Vec::new()));
+    assert!(matches!(
+        transact(USER, || engine.deposit_collateral(WETH, ether(10))),
+        Err(DSCEngineError::TransferFailed(_))
+    ));
+    assert_eq!(
+        engine.get_collateral_balance_of_user(USER, WETH),
+        U256::ZERO
+    );
+    assert_eq!(token_balance(WETH, USER), ether(10));
+}
+
+#[test]
+fn mint_tracks_debt_and_health_factor() {
+    let mut engine = setup();
+    mint_token(WETH, USER, ether(10));
+    assert!(transact(USER, || engine.deposit_collateral_and_mint_dsc(
+        WETH,
+        ether(10),
+        ether(5_000)
+    ))
+    .is_ok());
+    let (minted, collateral_value, health_factor) = engine.get_account_information(USER);
+    assert_eq!(minted, ether(5_000));
+    assert_eq!(collateral_value, ether(20_000));
+    // 20000 美元 × 50% 清算阈值 / 5000 DSC = 2.0
+    assert_eq!(health_factor, ether(2));
+    assert_eq!(token_balance(DSC, USER), ether(5_000));
+    assert_eq!(token_supply(DSC), ether(5_000));
+}
+
+#[test]
+fn debt_views_include_accrued_interest() {
+    let mut engine = setup();
+    mint_token(WETH, USER, ether(10));
+    assert!(transact(USER, || engine.deposit_collateral_and_mint_dsc(
+        WETH,
+        ether(10),
+        ether(5_000)
+    ))
+    .is_ok());
+    // 每秒 1e-7 的费率，区块时间不能前进，改为把上次计提时间提前 1,000,000 秒：按秒复利约 e^0.1
+    assert!(transact(OWNER, || engine
+        .set_stability_fee(WETH, U256::from(100_000_000_000u64)))
+    .is_ok());
+    engine
+        .last_accrual_time
+        .setter(WETH)
+        .set(U256::from(NOW - 1_000_000));
+
+    let index = engine.get_debt_rate_index(WETH);
+    // (1 + 1e-7)^1000000 = 1.10517091254...
+    assert!(index > U256::from(1_105_170_912u64) * U256::from(10).pow(U256::from(18)));
+    assert!(index < U256::from(1_105_170_913u64) * U256::from(10).pow(U256::from(18)));
+    let debt = (ether(5_000) * index).div_ceil(RAY);
+    assert_eq!(engine.get_user_debt(USER), debt);
+    let (principal, interest, tokens, debts, indices) = engine.get_user_debt_breakdown(USER);
+    assert_eq!(principal, ether(5_000));
+    assert_eq!(interest, debt - ether(5_000));
+    assert_eq!(tokens, vec![WETH]);
+    assert_eq!(debts, vec![debt]);
+    assert_eq!(indices, vec![index]);
+}
+
+#[test]
+fn redeem_collateral_accrues_before_checking_health_factor() {
+    let mut engine = setup();
+    mint_token(WETH, USER, ether(10));
+    assert!(transact(USER, || engine.deposit_collateral_and_mint_dsc(
+        WETH,
+        ether(10),
+        ether(5_000)
+    ))
+    .is_ok());
+    assert!(transact(OWNER, || engine
+        .set_stability_fee(WETH, U256::from(100_000_000_000u64)))
+    .is_ok());
+    engine
+        .last_accrual_time
+        .setter(WETH)
+        .set(U256::from(NOW - 1_000_000));
+
+    assert!(transact(USER, || engine.redeem_collateral(WETH, ether(1))).is_ok());
+    // 赎回前已计提，债务按最新的利率指数记账
+    assert_eq!(engine.last_accrual_time.get(WETH), U256::from(NOW));
+    assert_eq!(
+        engine.get_debt_rate_index(WETH),
+        engine._stored_debt_index(WETH)
+    );
+}
+
+#[test]
+fn mul_div_keeps_full_precision_and_rounds_as_requested() {
+    // a × b 超出 256 位时仍能得到精确结果
+    assert_eq!(
+        mul_div(U256::MAX, U256::MAX, U256::MAX, Rounding::Down),
+        U256::MAX
+    );
+    assert_eq!(
+        mul_div(
+            U256::from(10),
+            U256::from(10),
+            U256::from(3),
+            Rounding::Down
+        ),
+        U256::from(33)
+    );
+    assert_eq!(
+        mul_div(U256::from(10), U256::from(10), U256::from(3), Rounding::Up),
+        U256::from(34)
+    );
+    // 整除时两个方向结果相同
+    assert_eq!(
+        mul_div(U256::from(9), U256::from(10), U256::from(3), Rounding::Up),
+        U256::from(30)
+    );
+    assert_eq!(wad_mul(ether(3), ether(2)), ether(6));
+    assert_eq!(
+        wad_div(ether(1), ether(3)),
+        U256::from(333_333_333_333_333_333u64)
+    );
+    assert_eq!(ray_mul(RAY * U256::from(3), ether(2)), ether(6));
+}
+
+#[test]
+fn rpow_compounds_at_ray_precision() {
+    assert_eq!(
+        rpow(U256::from(2) * RAY, U256::from(10), RAY),
+        U256::from(1024) * RAY
+    );
+    assert_eq!(rpow(U256::ZERO, U256::ZERO, RAY), RAY);
+    assert_eq!(rpow(U256::ZERO, U256::from(3), RAY), U256::ZERO);
+    // 1.5^3 = 3.375
+    assert_eq!(
+        rpow(U256::from(15) * RAY / U256::from(10), U256::from(3), RAY),
+        U256::from(3_375) * RAY / U256::from(1_000)
+    );
+    // 零费率或零时间不改变指数
+    assert_eq!(compound(RAY, U256::ZERO, U256::from(1_000)), RAY);
+    assert_eq!(compound(RAY, U256::from(1), U256::ZERO), RAY);
+    // 每秒 1e-9 复利一天 ≈ e^0.0000864，与单利 1 + 0.0000864 相差不到 1e-8
+    let daily = compound(RAY, U256::from(1_000_000_000u64), U256::from(86_400));
+    let simple =
+        RAY + U256::from(86_400) * U256::from(1_000_000_000u64) * U256::from(1_000_000_000u64);
+    assert!(daily > simple);
+    assert!(daily - simple < RAY / U256::from(100_000_000));
+}
+
+#[test]
+fn rate_strategy_follows_utilization_kink() {
+    let mut engine = setup();
+    mint_token(WETH, USER, ether(10));
+    // 10 ETH × 2000 美元 × 50% 清算阈值 = 10000 美元借款能力，铸造 5000 即 50% 使用率
+    assert!(transact(USER, || engine.deposit_collateral_and_mint_dsc(
+        WETH,
+        ether(10),
+        ether(5_000)
+    ))
+    .is_ok());
+    let optimal = ether(8) / U256::from(10);
+    assert!(matches!(
+        transact(OWNER, || engine.set_rate_strategy(
+            WETH,
+            U256::from(1),
+            U256::ZERO,
+            U256::ZERO,
+            ether(1)
+        )),
+        Err(DSCEngineError::InvalidRateStrategy(_))
+    ));
+    assert!(transact(OWNER, || engine.set_rate_strategy(
+        WETH,
+        U256::from(1_000_000_000u64),
+        U256::from(4_000_000_000u64),
+        U256::from(100_000_000_000u64),
+        optimal
+    ))
+    .is_ok());
+    assert_eq!(engine.get_utilization(WETH), ether(5) / U256::from(10));
+    // 拐点以下：1e9 + 4e9 × 0.5 / 0.8
+    assert_eq!(engine.get_current_rate(WETH), U256::from(3_500_000_000u64));
+
+    // 90% 使用率：1e9 + 4e9 + 1e11 × 0.1 / 0.2
+    assert!(transact(USER, || engine.mint_dsc(ether(4_000))).is_ok());
+    assert_eq!(engine.get_current_rate(WETH), U256::from(55_000_000_000u64));
+    assert_eq!(
+        engine.get_borrow_apr(WETH),
+        U256::from(55_000_000_000u64 * 31_536_000)
+    );
+    // 仓位只有 WETH 分桶的债务，加权利率等于该抵押品的利率
+    assert_eq!(
+        engine.get_user_borrow_apr(USER),
+        engine.get_borrow_apr(WETH)
+    );
+    assert_eq!(engine.get_user_borrow_apr(LIQUIDATOR), U256::ZERO);
+
+    // 取消策略后改用固定稳定费率
+    assert!(transact(OWNER, || engine.set_rate_strategy(
+        WETH,
+        U256::ZERO,
+        U256::ZERO,
+        U256::ZERO,
+        U256::ZERO
+    ))
+    .is_ok());
+    assert_eq!(engine.get_current_rate(WETH), U256::ZERO);
+}
+
+#[test]
+fn invalid_feed_answers_revert_liquidation_and_redemption() {
+    let mut engine = setup();
+    mint_token(WETH, USER, ether(10));
+    mint_token(WETH, LIQUIDATOR, ether(20));
+    assert!(transact(USER, || engine.deposit_collateral_and_mint_dsc(
+        WETH,
+        ether(10),
+        ether(8_000)
+    ))
+    .is_ok());
+    assert!(
+        transact(LIQUIDATOR, || engine.deposit_collateral_and_mint_dsc(
+            WETH,
+            ether(20),
+            ether(8_000)
+        ))
+        .is_ok()
+    );
+
+    // 负数报价不能把抵押品按零估值：清算和赎回回滚，视图按零处理
+    set_price(WETH_FEED, -1);
+    call_as(LIQUIDATOR);
+    assert_eq!(engine.get_health_factor(USER), U256::ZERO);
+    assert!(matches!(
+        transact(LIQUIDATOR, || engine.liquidate(WETH, USER, ether(1_000))),
+        Err(DSCEngineError::NegativeValue(_))
+    ));
+    assert!(matches!(
+        transact(LIQUIDATOR, || engine.redeem_dsc_for_collateral(
+            WETH,
+            ether(1_000),
+            U256::ZERO
+        )),
+        Err(DSCEngineError::NegativeValue(_))
+    ));
+    assert_eq!(engine.get_account_information(USER).0, ether(8_000));
+    assert_eq!(engine.get_collateral_balance_of_user(USER, WETH), ether(10));
+}
+
+#[test]
+fn invalid_feed_answers_surface_explicit_errors() {
+    let engine = setup();
+    assert_eq!(
+        engine.get_collateral_price(WETH).ok(),
+        Some(U256::from(200_000_000_000u64))
+    );
+    set_price(WETH_FEED, -1);
+    call_as(USER);
+    assert!(matches!(
+        engine.get_collateral_price(WETH),
+        Err(DSCEngineError::NegativeValue(_))
+    ));
+    // 估值接口在价格不可用时按零处理
+    assert_eq!(engine.get_usd_value(WETH, ether(1)), U256::ZERO);
+
+    assert!(matches!(
+        answer_to_price(I256::MAX),
+        Err(DSCEngineError::ValueOverflow(_))
+    ));
+    assert_eq!(
+        answer_to_price(I256::try_from(2_000i64).unwrap()).ok(),
+        Some(U256::from(2_000))
+    );
+}
+
+#[test]
+fn mint_reverts_when_health_factor_breaks() {
+    let mut engine = setup();
+    mint_token(WETH, USER, ether(10));
+    assert!(transact(USER, || engine.deposit_collateral(WETH, ether(10))).is_ok());
+    assert!(matches!(
+        transact(USER, || engine.mint_dsc(ether(10_001))),
+        Err(DSCEngineError::BreaksHealthFactor(_))
+    ));
+    assert_eq!(token_supply(DSC), U256::ZERO);
+}
+
+#[test]
+fn redeem_for_dsc_burns_debt_and_returns_collateral() {
+    let mut engine = setup();
+    mint_token(WETH, USER, ether(10));
+    assert!(transact(USER, || engine.deposit_collateral_and_mint_dsc(
+        WETH,
+        ether(10),
+        ether(5_000)
+    ))
+    .is_ok());
+    assert!(transact(USER, || engine.redeem_collateral_for_dsc(
+        WETH,
+        ether(4),
+        ether(2_000)
+    ))
+    .is_ok());
+    let (minted, collateral_value, _) = engine.get_account_information(USER);
+    assert_eq!(minted, ether(3_000));
+    assert_eq!(collateral_value, ether(12_000));
+    assert_eq!(token_balance(WETH, USER), ether(4));
+    assert_eq!(token_supply(DSC), ether(3_000));
+}
+
+#[test]
+fn large_withdrawals_go_through_guardian_cancellable_delay() {
+    let mut engine = setup();
+    mint_token(WETH, USER, ether(10));
+    assert!(transact(USER, || engine.deposit_collateral(WETH, ether(10))).is_ok());
+    assert!(transact(OWNER, || engine.set_guardian(LIQUIDATOR)).is_ok());
+    // 单笔赎回超过持有量的 20% 时需要延时一小时
+    assert!(matches!(
+        transact(OWNER, || engine.set_withdrawal_delay_params(
+            U256::from(10_001),
+            U256::from(3_600)
+        )),
+        Err(DSCEngineError::InvalidWithdrawalDelay(_))
+    ));
+    assert!(transact(OWNER, || engine
+        .set_withdrawal_delay_params(U256::from(2_000), U256::from(3_600)))
+    .is_ok());
+
+    // 小额赎回不受影响，大额赎回必须走延时提取
+    assert!(transact(USER, || engine.redeem_collateral(WETH, ether(2))).is_ok());
+    assert!(matches!(
+        transact(USER, || engine.redeem_collateral(WETH, ether(5))),
+        Err(DSCEngineError::WithdrawalDelayRequired(_))
+    ));
+    assert!(matches!(
+        transact(USER, || engine.request_withdrawal(WETH, ether(9))),
+        Err(DSCEngineError::InsufficientCollateral(_))
+    ));
+
+    let Ok(id) = transact(USER, || engine.request_withdrawal(WETH, ether(5))) else {
+        panic!("request_withdrawal failed");
+    };
+    let executable_at = U256::from(NOW + 3_600);
+    assert_eq!(
+        engine.get_pending_withdrawal(id),
+        (USER, WETH, USER, ether(5), executable_at)
+    );
+    assert!(matches!(
+        transact(LIQUIDATOR, || engine.execute_withdrawal(id)),
+        Err(DSCEngineError::WithdrawalNotReady(err)) if err._0 == executable_at
+    ));
+
+    // 只有守护者或治理可以取消
+    let Ok(second) = transact(USER, || engine.request_withdrawal(WETH, ether(3))) else {
+        panic!("request_withdrawal failed");
+    };
+    assert!(matches!(
+        transact(USER, || engine.cancel_withdrawal(second)),
+        Err(DSCEngineError::NotGuardian(_))
+    ));
+    assert!(transact(LIQUIDATOR, || engine.cancel_withdrawal(second)).is_ok());
+    assert!(matches!(
+        transact(USER, || engine.execute_withdrawal(second)),
+        Err(DSCEngineError::WithdrawalNotFound(_))
+    ));
+
+    // 区块时间不能前进，直接把可执行时间改到当前时间模拟延时结束
+    engine
+        .pending_withdrawals
+        .setter(id)
+        .executable_at
+        .set(U256::from(NOW));
+    assert!(transact(LIQUIDATOR, || engine.execute_withdrawal(id)).is_ok());
+    assert_eq!(token_balance(WETH, USER), ether(7));
+    assert_eq!(engine.get_collateral_balance_of_user(USER, WETH), ether(3));
+    // 执行后不能重复执行
+    assert!(matches!(
+        transact(LIQUIDATOR, || engine.execute_withdrawal(id)),
+        Err(DSCEngineError::WithdrawalNotFound(_))
+    ));
+}
+
+#[test]
+fn vault_withdrawals_pay_the_current_vault_owner() {
+    let mut engine = setup();
+    mint_token(WETH, USER, ether(10));
+    let Ok(vault) = transact(USER, || engine.open_vault()) else {
+        panic!("open vault failed");
+    };
+    assert!(transact(USER, || engine.vault_deposit_collateral(
+        vault,
+        WETH,
+        ether(10)
+    ))
+    .is_ok());
+    assert!(transact(OWNER, || engine
+        .set_withdrawal_delay_params(U256::from(2_000), U256::from(3_600)))
+    .is_ok());
+
+    // 获授权的操作者提交的请求仍转给金库所有者
+    engine.vault_nft.token_approvals.insert(vault, LIQUIDATOR);
+    let Ok(id) = transact(LIQUIDATOR, || {
+        engine.vault_request_withdrawal(vault, WETH, ether(5))
+    }) else {
+        panic!("vault_request_withdrawal failed");
+    };
+    let position = engine.get_vault_position(vault);
+    let executable_at = U256::from(NOW + 3_600);
+    assert_eq!(
+        engine.get_pending_withdrawal(id),
+        (position, WETH, USER, ether(5), executable_at)
+    );
+
+    // 金库转手后，执行时转给新的所有者
+    assert!(engine.vault_nft.transfer(vault, USER, OWNER).is_ok());
+    assert_eq!(engine.get_pending_withdrawal(id).2, OWNER);
+    engine
+        .pending_withdrawals
+        .setter(id)
+        .executable_at
+        .set(U256::from(NOW));
+    assert!(transact(LIQUIDATOR, || engine.execute_withdrawal(id)).is_ok());
+    assert_eq!(token_balance(WETH, OWNER), ether(5));
+    assert_eq!(token_balance(WETH, USER), U256::ZERO);
+    assert_eq!(token_balance(WETH, LIQUIDATOR), U256::ZERO);
+    assert_eq!(
+        engine.get_collateral_balance_of_user(position, WETH),
+        ether(5)
+    );
+}
+
+#[test]
+fn collateral_outflows_are_capped_per_epoch() {
+    let mut engine = setup();
+    mint_token(WETH, USER, ether(11));
+    assert!(transact(USER, || engine.deposit_collateral(WETH, ether(10))).is_ok());
+    assert!(matches!(
+        transact(OWNER, || engine
+            .set_outflow_limit(U256::from(86_400), U256::from(10_001))),
+        Err(DSCEngineError::InvalidOutflowLimit(_))
+    ));
+    // 每天最多净流出周期开始时持有量的 30%
+    assert!(transact(OWNER, || engine
+        .set_outflow_limit(U256::from(86_400), U256::from(3_000)))
+    .is_ok());
+    assert_eq!(engine.get_epoch_outflow(WETH), (U256::ZERO, ether(3)));
+
+    assert!(transact(USER, || engine.redeem_collateral(WETH, ether(2))).is_ok());
+    assert_eq!(engine.get_epoch_outflow(WETH), (ether(2), ether(3)));
+    assert!(matches!(
+        transact(USER, || engine.redeem_collateral(WETH, ether(2))),
+        Err(DSCEngineError::OutflowRateLimited(err)) if err._0 == ether(1)
+    ));
+
+    // 存入抵扣净流出量，上限仍按周期开始时的持有量计算
+    assert!(transact(USER, || engine.deposit_collateral(WETH, ether(1))).is_ok());
+    assert_eq!(engine.get_epoch_outflow(WETH), (ether(1), ether(3)));
+    assert!(transact(USER, || engine.redeem_collateral(WETH, ether(2))).is_ok());
+    assert_eq!(token_balance(WETH, USER), ether(4));
+}
+
+#[test]
+fn repay_with_collateral_sells_collateral_to_burn_debt() {
+    let mut engine = setup();
+    let router = Address::repeat_byte(0xde);
+    assert!(transact(OWNER, || engine.set_dex_router(router)).is_ok());
+    // DEX 上 1 WETH 可换 1990 DSC
+    set_swap_rate(router, WETH, DSC, ether(1_990));
+    mint_token(WETH, USER, ether(10));
+    assert!(transact(USER, || engine.deposit_collateral_and_mint_dsc(
+        WETH,
+        ether(10),
+        ether(5_000)
+    ))
+    .is_ok());
+
+    assert!(matches!(
+        transact(USER, || engine.repay_with_collateral(
+            WETH,
+            ether(1),
+            ether(2_000)
+        )),
+        Err(DSCEngineError::SwapFailed(_))
+    ));
+    assert_eq!(
+        transact(USER, || engine.repay_with_collateral(
+            WETH,
+            ether(1),
+            ether(1_990)
+        ))
+        .ok(),
+        Some(ether(1_990))
+    );
+    let (minted, _, _) = engine.get_account_information(USER);
+    assert_eq!(minted, ether(3_010));
+    assert_eq!(engine.get_collateral_balance_of_user(USER, WETH), ether(9));
+    // 用户持有的 DSC 不变，兑换所得全部销毁
+    assert_eq!(token_balance(DSC, USER), ether(5_000));
+    assert_eq!(token_supply(DSC), ether(5_000));
+
+    // 兑换所得超过债务时，多余的 DSC 转给用户
+    assert_eq!(
+        transact(USER, || engine.repay_with_collateral(
+            WETH,
+            ether(2),
+            U256::ZERO
+        ))
+        .ok(),
+        Some(ether(3_010))
+    );
+    let (minted, _, _) = engine.get_account_information(USER);
+    assert_eq!(minted, U256::ZERO);
+    assert_eq!(token_balance(DSC, USER), ether(5_970));
+    let repaid = events::<RepaidWithCollateral>();
+    assert_eq!(repaid.len(), 2);
+    assert_eq!(
+        (repaid[1].collateralSold, repaid[1].debtRepaid),
+        (ether(2), ether(3_010))
+    );
+    let burned = events::<DscBurned>();
+    assert_eq!(burned.len(), 2);
+    assert_eq!(
+        (burned[1].onBehalfOf, burned[1].amount),
+        (USER, ether(3_010))
+    );
+    assert_eq!(burned[1].totalDebt, U256::ZERO);
+}
+
+#[test]
+fn donations_do_not_move_share_valuations() {
+    let mut engine = setup();
+    assert!(transact(OWNER, || engine.set_collateral_shares_mode(WETH, true)).is_ok());
+    mint_token(WETH, USER, ether(10));
+    mint_token(WETH, LIQUIDATOR, ether(10));
+    assert!(transact(USER, || engine.deposit_collateral(WETH, ether(10))).is_ok());
+
+    // 直接转给引擎的代币不计入存款总量，也不改变份额的价值
+    mint_token(WETH, ENGINE, ether(10));
+    call_as(USER);
+    assert_eq!(engine.get_total_deposited(WETH), ether(10));
+    assert_eq!(engine.get_collateral_balance_of_user(USER, WETH), ether(10));
+    assert!(transact(LIQUIDATOR, || engine.deposit_collateral(WETH, ether(10))).is_ok());
+    assert_eq!(
+        engine.get_collateral_balance_of_user(LIQUIDATOR, WETH),
+        ether(10)
+    );
+    assert!(transact(USER, || engine.redeem_collateral(WETH, ether(4))).is_ok());
+    assert_eq!(engine.get_total_deposited(WETH), ether(16));
+    assert_eq!(engine.get_collateral_balance_of_user(USER, WETH), ether(6));
+
+    // 治理同步后才按实际持有量确认增值
+    assert!(matches!(
+        transact(USER, || engine.sync_total_deposited(WETH, ether(10))),
+        Err(DSCEngineError::NotOwner(_))
+    ));
+    assert!(transact(OWNER, || engine.sync_total_deposited(WETH, ether(10))).is_ok());
+    assert_eq!(engine.get_total_deposited(WETH), ether(26));
+    assert_eq!(
+        engine.get_collateral_balance_of_user(USER, WETH)
+            + engine.get_collateral_balance_of_user(LIQUIDATOR, WETH),
+        ether(26)
+    );
+}
+
+#[test]
+fn total_deposited_matches_total_collateral() {
+    let mut engine = setup();
+    assert!(transact(OWNER, || engine.set_collateral_shares_mode(WETH, true)).is_ok());
+    mint_token(WETH, USER, ether(10));
+    assert!(transact(USER, || engine.deposit_collateral(WETH, ether(10))).is_ok());
+    assert_eq!(engine.get_total_deposited(WETH), ether(10));
+    assert_eq!(
+        engine.get_total_deposited(WETH),
+        engine.get_total_collateral(WETH)
+    );
+
+    // 单次同步确认的增值不超过治理给出的上限，两个查询始终一致
+    mint_token(WETH, ENGINE, ether(10));
+    assert!(transact(OWNER, || engine.sync_total_deposited(WETH, ether(4))).is_ok());
+    assert_eq!(engine.get_total_deposited(WETH), ether(14));
+    assert_eq!(
+        engine.get_total_deposited(WETH),
+        engine.get_total_collateral(WETH)
+    );
+}
+
+#[test]
+fn tvl_views_follow_tracked_totals() {
+    let mut engine = setup();
+    mint_token(WETH, USER, ether(10));
+    mint_token(WETH, LIQUIDATOR, ether(5));
+    assert!(transact(USER, || engine.deposit_collateral(WETH, ether(10))).is_ok());
+    assert!(transact(LIQUIDATOR, || engine.deposit_collateral(WETH, ether(5))).is_ok());
+    assert!(transact(USER, || engine.redeem_collateral(WETH, ether(3))).is_ok());
+    mint_token(WETH, ENGINE, ether(100));
+    call_as(USER);
+    assert_eq!(engine.get_total_collateral(WETH), ether(12));
+    assert_eq!(engine.get_total_collateral_value_usd(), ether(24_000));
+    assert_eq!(engine.get_protocol_stats().0, ether(24_000));
+
+    set_price(WETH_FEED, 150_000_000_000);
+    call_as(USER);
+    assert_eq!(engine.get_total_collateral_value_usd(), ether(18_000));
+}
+
+#[test]
+fn debt_is_attributed_to_collateral_buckets() {
+    let mut engine = setup();
+    mint_token(WETH, USER, ether(10));
+    assert!(transact(USER, || engine.deposit_collateral_and_mint_dsc(
+        WETH,
+        ether(10),
+        ether(5_000)
+    ))
+    .is_ok());
+    assert_eq!(engine.get_debt_by_collateral(WETH), ether(5_000));
+    assert!(transact(USER, || engine.burn_dsc(ether(2_000))).is_ok());
+    assert_eq!(
+        engine.get_debt_by_collateral_breakdown(),
+        (vec![WETH], vec![ether(3_000)])
+    );
+    assert_eq!(
+        engine.get_debt_by_collateral(WETH),
+        engine.get_protocol_stats().1
+    );
+}
+
+#[test]
+fn risk_metrics_report_distance_to_liquidation_line() {
+    let mut engine = setup();
+    mint_token(WETH, USER, ether(10));
+    assert_eq!(engine.get_global_risk_metrics().0, U256::MAX);
+    assert!(transact(USER, || engine.deposit_collateral_and_mint_dsc(
+        WETH,
+        ether(10),
+        ether(5_000)
+    ))
+    .is_ok());
+    // 20000 美元抵押 5000 DSC：抵押率 400%，50% 阈值对应清算线 200%
+    assert_eq!(
+        engine.get_collateral_risk_metrics(WETH),
+        (ether(1) / U256::from(2), ether(4), ether(2), ether(2))
+    );
+    assert_eq!(
+        engine.get_global_risk_metrics(),
+        (ether(4), ether(2), ether(2))
+    );
+
+    // 价格下跌到 900 美元后抵押率低于清算线，余量为零
+    set_price(WETH_FEED, 90_000_000_000);
+    call_as(USER);
+    let (_, collateral_ratio, _, buffer) = engine.get_collateral_risk_metrics(WETH);
+    assert_eq!(collateral_ratio, ether(18) / U256::from(10));
+    assert_eq!(buffer, U256::ZERO);
+}
+
+#[test]
+fn position_events_carry_resulting_balances() {
+    let mut engine = setup();
+    mint_token(WETH, USER, ether(10));
+    assert!(transact(USER, || engine.deposit_collateral_and_mint_dsc(
+        WETH,
+        ether(10),
+        ether(5_000)
+    ))
+    .is_ok());
+    assert!(transact(USER, || engine.redeem_collateral_for_dsc(
+        WETH,
+        ether(4),
+        ether(2_000)
+    ))
+    .is_ok());
+    // 失败的调用不留下事件
+    assert!(transact(USER, || engine.redeem_collateral(WETH, ether(6))).is_err());
+
+    let deposited = events::<CollateralDeposited>();
+    assert_eq!(deposited.len(), 1);
+    assert_eq!(deposited[0].collateralBalance, ether(10));
+    assert_eq!(deposited[0].totalDebt, U256::ZERO);
+
+    let minted = events::<DscMinted>();
+    assert_eq!(minted.len(), 1);
+    assert_eq!((minted[0].user, minted[0].to), (USER, USER));
+    assert_eq!(minted[0].totalDebt, ether(5_000));
+
+    let burned = events::<DscBurned>();
+    assert_eq!(burned.len(), 1);
+    assert_eq!(burned[0].amount, ether(2_000));
+    assert_eq!(burned[0].totalDebt, ether(3_000));
+
+    let redeemed = events::<CollateralRedeemed>();
+    assert_eq!(redeemed.len(), 1);
+    assert_eq!(redeemed[0].amount, ether(4));
+    assert_eq!(redeemed[0].collateralBalance, ether(6));
+    assert_eq!(redeemed[0].totalDebt, ether(3_000));
+
+    // 操作序号按用户逐个递增，失败的调用不占用序号
+    assert_eq!(
+        (
+            deposited[0].actionNonce,
+            minted[0].actionNonce,
+            burned[0].actionNonce,
+            redeemed[0].actionNonce
+        ),
+        (U256::from(1), U256::from(2), U256::from(3), U256::from(4))
+    );
+    assert_eq!(engine.get_action_nonce(USER), U256::from(4));
+    assert_eq!(engine.get_action_nonce(LIQUIDATOR), U256::ZERO);
+}
+
+#[test]
+fn redeem_reverts_when_health_factor_breaks() {
+    let mut engine = setup();
+    mint_token(WETH, USER, ether(10));
+    assert!(transact(USER, || engine.deposit_collateral_and_mint_dsc(
+        WETH,
+        ether(10),
+        ether(5_000)
+    ))
+    .is_ok());
+    assert!(matches!(
+        transact(USER, || engine.redeem_collateral(WETH, ether(6))),
+        Err(DSCEngineError::BreaksHealthFactor(_))
+    ));
+    assert_eq!(engine.get_collateral_balance_of_user(USER, WETH), ether(10));
+}
+
+#[test]
+fn liquidate_rejects_healthy_positions() {
+    let mut engine = setup();
+    mint_token(WETH, USER, ether(10));
+    assert!(transact(USER, || engine.deposit_collateral_and_mint_dsc(
+        WETH,
+        ether(10),
+        ether(5_000)
+    ))
+    .is_ok());
+    assert!(matches!(
+        transact(LIQUIDATOR, || engine.liquidate(WETH, USER, ether(1_000))),
+        Err(DSCEngineError::HealthFactorOk(_))
+    ));
+}
+
+#[test]
+fn liquidation_bonus_scales_with_health_factor() {
+    let mut engine = setup();
+    mint_token(WETH, USER, ether(10));
+    mint_token(WETH, LIQUIDATOR, ether(20));
+    assert!(transact(USER, || engine.deposit_collateral_and_mint_dsc(
+        WETH,
+        ether(10),
+        ether(10_000)
+    ))
+    .is_ok());
+    assert!(
+        transact(LIQUIDATOR, || engine.deposit_collateral_and_mint_dsc(
+            WETH,
+            ether(20),
+            ether(10_000)
+        ))
+        .is_ok()
+    );
+    // 满额奖励健康因子不能高于最小健康因子，最低奖励不能超过最高奖励
+    assert!(matches!(
+        transact(OWNER, || engine
+            .set_liquidation_bonus_curve(U256::from(2), ether(1))),
+        Err(DSCEngineError::InvalidBonusCurve(_))
+    ));
+    assert!(matches!(
+        transact(OWNER, || engine
+            .set_liquidation_bonus_curve(U256::from(11), U256::ZERO)),
+        Err(DSCEngineError::InvalidBonusCurve(_))
+    ));
+    // 健康因子从 1.0 降到 0.8 时奖励从 2% 线性升到 10%
+    let full_bonus_health_factor = ether(8) / U256::from(10);
+    assert!(transact(OWNER, || engine
+        .set_liquidation_bonus_curve(U256::from(2), full_bonus_health_factor))
+    .is_ok());
+    assert_eq!(
+        engine.get_liquidation_bonus_curve(),
+        (U256::from(2), U256::from(10), full_bonus_health_factor)
+    );
+
+    // ETH 跌到 1800 美元，健康因子 0.9：奖励 2% + 8% × 0.1 / 0.2 = 6%
+    set_price(WETH_FEED, 180_000_000_000);
+    call_as(LIQUIDATOR);
+    assert_eq!(engine.get_liquidation_bonus_for(USER, WETH), U256::from(6));
+    assert!(transact(LIQUIDATOR, || engine.liquidate(WETH, USER, ether(5_000))).is_ok());
+    let covered = engine.get_token_amount_from_usd(WETH, ether(5_000));
+    let seized = covered + (covered * U256::from(6)) / U256::from(100);
+    assert_eq!(token_balance(WETH, LIQUIDATOR), seized);
+
+    // 健康因子低于 0.8 时给最高奖励
+    set_price(WETH_FEED, 100_000_000_000);
+    call_as(LIQUIDATOR);
+    assert_eq!(engine.get_liquidation_bonus_for(USER, WETH), U256::from(10));
+}
+
+#[test]
+fn risk_tier_bundles_collateral_parameters() {
+    let mut engine = setup();
+    mint_token(WETH, USER, ether(20));
+    // 按奖励清算后健康因子无法改善的等级参数无效，未配置的等级不能分配
+    assert!(matches!(
+        transact(OWNER, || engine.set_risk_tier(
+            TIER_A,
+            U256::from(95),
+            U256::from(10),
+            U256::ZERO,
+            U256::ZERO
+        )),
+        Err(DSCEngineError::InvalidRiskTier(_))
+    ));
+    assert!(matches!(
+        transact(OWNER, || engine.set_collateral_risk_tier(WETH, TIER_B)),
+        Err(DSCEngineError::InvalidRiskTier(_))
+    ));
+    assert!(transact(OWNER, || engine.set_risk_tier(
+        TIER_A,
+        U256::from(80),
+        U256::from(5),
+        ether(15),
+        U256::from(3_600)
+    ))
+    .is_ok());
+    assert!(transact(OWNER, || engine.set_collateral_risk_tier(WETH, TIER_A)).is_ok());
+    assert_eq!(engine.get_collateral_risk_tier(WETH), TIER_A);
+    assert_eq!(
+        engine.get_collateral_liquidation_threshold(WETH),
+        U256::from(80)
+    );
+
+    // 80% 阈值下 10 ETH（20000 美元）可以铸造 15000 DSC，默认 50% 阈值下不行
+    assert!(transact(USER, || engine.deposit_collateral_and_mint_dsc(
+        WETH,
+        ether(10),
+        ether(15_000)
+    ))
+    .is_ok());
+    assert_eq!(engine.get_liquidation_bonus_for(USER, WETH), U256::from(5));
+    // 存入总量不能超过 15 ETH
+    assert!(matches!(
+        transact(USER, || engine.deposit_collateral(WETH, ether(6))),
+        Err(DSCEngineError::SupplyCapExceeded(_))
+    ));
+    assert!(transact(USER, || engine.deposit_collateral(WETH, ether(5))).is_ok());
+
+    // 拍卖罚金和协议分成报价都按等级的 5% 奖励计算
+    assert!(transact(OWNER, || engine
+        .set_liquidation_protocol_share(U256::from(50)))
+    .is_ok());
+    assert_eq!(
+        engine.get_liquidation_protocol_fee(WETH, ether(1_000)),
+        ether(25)
+    );
+    set_price(WETH_FEED, 120_000_000_000);
+    let Ok(id) = transact(LIQUIDATOR, || engine.kick(WETH, USER)) else {
+        panic!("kick failed");
+    };
+    let (_, _, tab, penalty, _, _, _) = engine.get_auction(id);
+    assert_eq!(penalty, tab * U256::from(5) / U256::from(100));
+}
+
+// 让预言机上一轮的报价为 answer
+fn mock_previous_round(answer: i64) {
+    let round = (
+        1u128,
+        I256::try_from(answer).unwrap(),
+        U256::from(NOW),
+        U256::from(NOW),
+        1u128,
+    );
+    mock_call(
+        WETH_FEED,
+        getRoundDataCall::SELECTOR,
+        Ok(getRoundDataCall::abi_encode_returns(&round)),
+    );
+}
+
+#[test]
+fn dynamic_threshold_tightens_on_volatility() {
+    let mut engine = setup();
+    assert!(matches!(
+        transact(OWNER, || engine.set_dynamic_ltv(
+            WETH,
+            U256::from(60),
+            U256::from(50),
+            U256::from(500),
+            U256::from(5),
+            U256::from(3_600)
+        )),
+        Err(DSCEngineError::InvalidDynamicLtv(_))
+    ));
+    // 阈值在 40% ~ 50% 之间，价格偏离达到 5% 时每次下调 5%
+    assert!(transact(OWNER, || engine.set_dynamic_ltv(
+        WETH,
+        U256::from(40),
+        U256::from(50),
+        U256::from(500),
+        U256::from(5),
+        U256::from(3_600)
+    ))
+    .is_ok());
+
+    // ETH 从 2000 美元跌到 1800 美元：偏离 10%
+    set_price(WETH_FEED, 180_000_000_000);
+    mock_previous_round(200_000_000_000);
+    call_as(USER);
+    assert_eq!(
+        engine.get_price_deviation_bps(WETH).ok(),
+        Some(U256::from(1_000))
+    );
+    assert_eq!(
+        transact(USER, || engine.poke_dynamic_threshold(WETH)).ok(),
+        Some(U256::from(45))
+    );
+    assert_eq!(
+        engine.get_collateral_liquidation_threshold(WETH),
+        U256::from(45)
+    );
+    assert!(matches!(
+        transact(USER, || engine.poke_dynamic_threshold(WETH)),
+        Err(DSCEngineError::DynamicLtvCooldown(_))
+    ));
+
+    // 行情平稳后逐步恢复，不超过上限
+    mock_previous_round(180_000_000_000);
+    engine
+        .ltv_adjusters
+        .setter(WETH)
+        .last_poke
+        .set(U256::from(NOW - 3_600));
+    assert_eq!(
+        transact(USER, || engine.poke_dynamic_threshold(WETH)).ok(),
+        Some(U256::from(50))
+    );
+}
+
+#[test]
+fn guardian_emergency_price_is_bounded_and_expires() {
+    let mut engine = setup();
+    assert!(transact(OWNER, || engine.set_guardian(LIQUIDATOR)).is_ok());
+    // 偏离上限 20%，最长有效期一天
+    assert!(transact(OWNER, || engine
+        .set_emergency_price_bounds(U256::from(2_000), U256::from(86_400)))
+    .is_ok());
+    let price = U256::from(170_000_000_000u64);
+    assert!(matches!(
+        transact(USER, || engine.set_emergency_price(
+            WETH,
+            price,
+            U256::from(3_600)
+        )),
+        Err(DSCEngineError::NotGuardian(_))
+    ));
+    for (bad_price, duration) in [
+        (U256::from(130_000_000_000u64), 3_600u64),
+        (price, 86_401),
+        (price, 0),
+    ] {
+        assert!(matches!(
+            transact(LIQUIDATOR, || engine.set_emergency_price(
+                WETH,
+                bad_price,
+                U256::from(duration)
+            )),
+            Err(DSCEngineError::InvalidEmergencyPrice(_))
+        ));
+    }
+    assert!(transact(LIQUIDATOR, || engine.set_emergency_price(
+        WETH,
+        price,
+        U256::from(3_600)
+    ))
+    .is_ok());
+    let set = events::<EmergencyPriceSet>();
+    assert_eq!(set.len(), 1);
+    assert_eq!(set[0].referencePrice, U256::from(200_000_000_000u64));
+    assert_eq!(set[0].expiresAt, U256::from(NOW + 3_600));
+    assert_eq!(set[0].guardian, LIQUIDATOR);
+
+    // 预言机中断期间仍按应急价格估值
+    set_price(WETH_FEED, -1);
+    call_as(USER);
+    assert_eq!(engine.get_collateral_price(WETH).ok(), Some(price));
+    assert_eq!(engine.get_usd_value(WETH, ether(2)), ether(3_400));
+
+    // 撤销后恢复使用预言机报价
+    assert!(transact(LIQUIDATOR, || engine.clear_emergency_price(WETH)).is_ok());
+    assert_eq!(events::<EmergencyPriceCleared>().len(), 1);
+    call_as(USER);
+    assert!(matches!(
+        engine.get_collateral_price(WETH),
+        Err(DSCEngineError::NegativeValue(_))
+    ));
+
+    // 到期后自动失效
+    set_price(WETH_FEED, 200_000_000_000);
+    assert!(transact(LIQUIDATOR, || engine.set_emergency_price(
+        WETH,
+        price,
+        U256::from(3_600)
+    ))
+    .is_ok());
+    engine
+        .price_overrides
+        .setter(WETH)
+        .expires_at
+        .set(U256::from(NOW));
+    call_as(USER);
+    assert_eq!(
+        engine.get_collateral_price(WETH).ok(),
+        Some(U256::from(200_000_000_000u64))
+    );
+}
+
+#[test]
+fn price_feed_replacement_rejects_large_deviation() {
+    let mut engine = setup();
+    let new_feed = Address::repeat_byte(0xf2);
+    assert!(transact(OWNER, || engine
+        .set_max_feed_replacement_deviation(U256::from(500)))
+    .is_ok());
+    assert!(matches!(
+        transact(USER, || engine.set_price_feed(WETH, new_feed)),
+        Err(DSCEngineError::NotOwner(_))
+    ));
+    // 新预言机报价偏离 10%，超过 5% 的上限
+    set_price(new_feed, 220_000_000_000);
+    assert!(matches!(
+        transact(OWNER, || engine.set_price_feed(WETH, new_feed)),
+        Err(DSCEngineError::FeedDeviationTooHigh(err)) if err._0 == U256::from(1_000)
+    ));
+    assert_eq!(engine.get_collateral_token_price_feed(WETH), WETH_FEED);
+
+    set_price(new_feed, 204_000_000_000);
+    assert!(transact(OWNER, || engine.set_price_feed(WETH, new_feed)).is_ok());
+    assert_eq!(engine.get_collateral_token_price_feed(WETH), new_feed);
+    let updated = events::<PriceFeedUpdated>();
+    assert_eq!(updated.len(), 1);
+    assert_eq!(
+        (updated[0].oldFeed, updated[0].newFeed),
+        (WETH_FEED, new_feed)
+    );
+    assert_eq!(updated[0].oldPrice, U256::from(200_000_000_000u64));
+    call_as(USER);
+    assert_eq!(engine.get_usd_value(WETH, ether(1)), ether(2_040));
+}
+
+#[test]
+fn reverting_feed_falls_back_to_last_good_price() {
+    let mut engine = setup();
+    assert!(transact(OWNER, || engine
+        .set_price_fallback_window(U256::from(3_600)))
+    .is_ok());
+    mint_token(WETH, USER, ether(10));
+    assert!(transact(USER, || engine.deposit_collateral_and_mint_dsc(
+        WETH,
+        ether(10),
+        ether(5_000)
+    ))
+    .is_ok());
+    assert_eq!(
+        engine.get_last_good_price(WETH),
+        (U256::from(200_000_000_000u64), U256::from(NOW))
+    );
+
+    // 预言机回滚时仍可计算健康因子和偿还债务
+    mock_call(WETH_FEED, latestRoundDataCall::SELECTOR, Err(Vec::new()));
+    call_as(USER);
+    assert_eq!(engine.get_usd_value(WETH, ether(1)), ether(2_000));
+    assert!(transact(USER, || engine.redeem_collateral_for_dsc(
+        WETH,
+        ether(1),
+        ether(1_000)
+    ))
+    .is_ok());
+    // 9 WETH × 2000 × 50% / 4000 DSC = 2.25
+    assert_eq!(engine.get_health_factor(USER), ether(9) / U256::from(4));
+
+    // 超出窗口后不再回退
+    engine
+        .last_good_prices
+        .setter(WETH_FEED)
+        .updated_at
+        .set(U256::from(NOW - 3_601));
+    call_as(USER);
+    assert!(matches!(
+        engine.get_collateral_price(WETH),
+        Err(DSCEngineError::PriceFeedError(_))
+    ));
+}
+
+#[test]
+fn liquidate_pays_bonus_and_improves_health_factor() {
+    let mut engine = setup();
+    mint_token(WETH, USER, ether(10));
+    mint_token(WETH, LIQUIDATOR, ether(20));
+    assert!(transact(USER, || engine.deposit_collateral_and_mint_dsc(
+        WETH,
+        ether(10),
+        ether(10_000)
+    ))
+    .is_ok());
+    assert!(
+        transact(LIQUIDATOR, || engine.deposit_collateral_and_mint_dsc(
+            WETH,
+            ether(20),
+            ether(10_000)
+        ))
+        .is_ok()
+    );
+
+    // ETH 跌到 1800 美元，用户健康因子降到 0.9
+    set_price(WETH_FEED, 180_000_000_000);
+    call_as(LIQUIDATOR);
+    assert!(engine.get_health_factor(USER) < ether(1));
+    // 平仓系数 50%：最多清算一半债务
+    call_as(LIQUIDATOR);
+    assert_eq!(engine.get_max_liquidatable_debt(USER, WETH), ether(5_000));
+    assert!(matches!(
+        transact(LIQUIDATOR, || engine.liquidate(WETH, USER, ether(5_001))),
+        Err(DSCEngineError::ExceedsMaxLiquidatableDebt(_))
+    ));
+
+    assert!(transact(LIQUIDATOR, || engine.liquidate(WETH, USER, ether(5_000))).is_ok());
+    // 清算人获得 5000 / 1800 ETH 外加 10% 奖励
+    let covered = engine.get_token_amount_from_usd(WETH, ether(5_000));
+    let seized = covered + covered / U256::from(10);
+    assert_eq!(token_balance(WETH, LIQUIDATOR), seized);
+    assert_eq!(
+        engine.get_collateral_balance_of_user(USER, WETH),
+        ether(10) - seized
+    );
+    let (minted, _, health_factor) = engine.get_account_information(USER);
+    assert_eq!(minted, ether(5_000));
+    assert!(health_factor >= ether(1));
+    assert_eq!(token_supply(DSC), ether(15_000));
+}
+
+#[test]
+fn flash_liquidation_swaps_seized_collateral_through_router() {
+    let mut engine = setup();
+    let router = Address::repeat_byte(0xde);
+    assert!(transact(OWNER, || engine.set_dex_router(router)).is_ok());
+    // DEX 上 1 WETH 可换 1800 DSC
+    set_swap_rate(router, WETH, DSC, ether(1_800));
+    mint_token(WETH, USER, ether(10));
+    assert!(transact(USER, || engine.deposit_collateral_and_mint_dsc(
+        WETH,
+        ether(10),
+        ether(10_000)
+    ))
+    .is_ok());
+    set_price(WETH_FEED, 180_000_000_000);
+
+    // 兑换所得低于调用者给出的最少买入量时回滚
+    assert!(matches!(
+        transact(LIQUIDATOR, || engine.flash_liquidate(
+            WETH,
+            USER,
+            ether(5_000),
+            ether(3),
+            ether(5_401)
+        )),
+        Err(DSCEngineError::SwapFailed(_))
+    ));
+    // 卖出 3 WETH 换得 5400 DSC，偿还 5000 后利润和未卖出的抵押品归调用者
+    assert_eq!(
+        transact(LIQUIDATOR, || engine.flash_liquidate(
+            WETH,
+            USER,
+            ether(5_000),
+            ether(3),
+            ether(5_400)
+        ))
+        .ok(),
+        Some(ether(400))
+    );
+    let covered = engine.get_token_amount_from_usd(WETH, ether(5_000));
+    let seized = covered + covered / U256::from(10);
+    assert_eq!(token_balance(DSC, LIQUIDATOR), ether(400));
+    assert_eq!(token_balance(WETH, LIQUIDATOR), seized - ether(3));
+    let (minted, _, _) = engine.get_account_information(USER);
+    assert_eq!(minted, ether(5_000));
+    assert_eq!(token_supply(DSC), ether(10_400));
+    // 闪电清算减少的债务同样发出偿还事件
+    let burned = events::<DscBurned>();
+    assert_eq!(burned.len(), 1);
+    assert_eq!(
+        (burned[0].onBehalfOf, burned[0].amount),
+        (USER, ether(5_000))
+    );
+    assert_eq!(burned[0].totalDebt, ether(5_000));
+}
+
+#[test]
+fn critical_health_factor_waives_close_factor() {
+    let mut engine = setup();
+    mint_token(WETH, USER, ether(10));
+    mint_token(WETH, LIQUIDATOR, ether(20));
+    assert!(transact(USER, || engine.deposit_collateral_and_mint_dsc(
+        WETH,
+        ether(10),
+        ether(10_000)
+    ))
+    .is_ok());
+    assert!(
+        transact(LIQUIDATOR, || engine.deposit_collateral_and_mint_dsc(
+            WETH,
+            ether(20),
+            ether(10_000)
+        ))
+        .is_ok()
+    );
+    assert!(matches!(
+        transact(OWNER, || engine.set_critical_health_factor(ether(2))),
+        Err(DSCEngineError::InvalidCriticalHealthFactor(_))
+    ));
+    // 临界健康因子 0.85：健康因子 0.9 时仍受平仓系数限制
+    assert!(transact(OWNER, || engine
+        .set_critical_health_factor(ether(85) / U256::from(100)))
+    .is_ok());
+    set_price(WETH_FEED, 180_000_000_000);
+    call_as(LIQUIDATOR);
+    assert_eq!(engine.get_max_liquidatable_debt(USER, WETH), ether(5_000));
+
+    // 临界健康因子 0.95：健康因子 0.9 时可一次清算全部债务
+    assert!(transact(OWNER, || engine
+        .set_critical_health_factor(ether(95) / U256::from(100)))
+    .is_ok());
+    assert_eq!(
+        engine.get_critical_health_factor(),
+        ether(95) / U256::from(100)
+    );
+    call_as(LIQUIDATOR);
+    assert_eq!(engine.get_max_liquidatable_debt(USER, WETH), ether(10_000));
+    assert!(transact(LIQUIDATOR, || engine.liquidate(WETH, USER, ether(10_000))).is_ok());
+    let (minted, _, _) = engine.get_account_information(USER);
+    assert_eq!(minted, U256::ZERO);
+    // 其他风险参数不受影响
+    assert_eq!(engine.get_close_factor(), U256::from(50));
+}
+
+#[test]
+fn supports_interface_advertises_engine_and_vault_nft() {
+    // 标准接口 ID 与按选择器计算的结果一致
+    assert_eq!(
+        xor_selectors(&[function_selector!("supportsInterface", FixedBytes<4>)]),
+        ERC165_INTERFACE_ID
+    );
+    assert_eq!(
+        xor_selectors(&[
+            function_selector!("totalSupply"),
+            function_selector!("balanceOf", Address),
+            function_selector!("transfer", Address, U256),
+            function_selector!("transferFrom", Address, Address, U256),
+            function_selector!("approve", Address, U256),
+            function_selector!("allowance", Address, Address),
+        ]),
+        ERC20_INTERFACE_ID
+    );
+
+    let engine = setup();
+    let id = |id: u32| FixedBytes::from(id.to_be_bytes());
+    assert!(engine.supports_interface(id(ERC165_INTERFACE_ID)));
+    assert!(engine.supports_interface(id(ERC721_INTERFACE_ID)));
+    assert!(engine.supports_interface(id(DSC_ENGINE_INTERFACE_ID)));
+    assert!(!engine.supports_interface(id(ERC20_INTERFACE_ID)));
+    // ERC-165 要求对 0xffffffff 返回 false
+    assert!(!engine.supports_interface(id(0xffffffff)));
+}
+
+#[test]
+fn referrers_earn_a_share_of_mint_fees() {
+    let mut engine = setup();
+    mint_token(WETH, USER, ether(10));
+    assert!(transact(OWNER, || engine.set_treasury(OWNER)).is_ok());
+    assert!(transact(OWNER, || engine.set_fees(U256::from(100), U256::ZERO)).is_ok());
+    assert!(matches!(
+        transact(OWNER, || engine.set_referral_share(U256::from(10_001))),
+        Err(DSCEngineError::InvalidFee(_))
+    ));
+    assert!(transact(OWNER, || engine.set_referral_share(U256::from(5_000))).is_ok());
+    assert!(transact(USER, || engine.deposit_collateral(WETH, ether(10))).is_ok());
+
+    // 1% 铸造手续费的一半归推荐人，另一半铸造给国库
+    assert!(transact(USER, || engine
+        .mint_dsc_with_referral(ether(1_000), LIQUIDATOR))
+    .is_ok());
+    assert_eq!(engine.get_referrer(USER), LIQUIDATOR);
+    assert_eq!(engine.get_referral_rewards(LIQUIDATOR), ether(5));
+    assert_eq!(token_balance(DSC, OWNER), ether(5));
+    assert_eq!(token_balance(DSC, ENGINE), ether(5));
+
+    // 推荐关系不能更改
+    assert!(transact(USER, || engine.mint_dsc_with_referral(ether(1_000), OWNER)).is_ok());
+    assert_eq!(engine.get_referrer(USER), LIQUIDATOR);
+    assert_eq!(engine.get_referral_rewards(LIQUIDATOR), ether(10));
+
+    let Ok(claimed) = transact(LIQUIDATOR, || engine.claim_referral_rewards()) else {
+        panic!("claim failed");
+    };
+    assert_eq!(claimed, ether(10));
+    assert_eq!(token_balance(DSC, LIQUIDATOR), ether(10));
+    assert_eq!(engine.get_referral_rewards(LIQUIDATOR), U256::ZERO);
+}
+
+#[test]
+fn minters_earn_rewards_proportional_to_debt() {
+    let mut engine = setup();
+    let reward = Address::repeat_byte(0x7e);
+    add_token(reward, 18);
+    mint_token(reward, ENGINE, ether(1_000));
+    mint_token(WETH, USER, ether(10));
+    assert!(matches!(
+        transact(USER, || engine.claim_rewards()),
+        Err(DSCEngineError::RewardTokenNotSet(_))
+    ));
+    assert!(transact(OWNER, || engine.set_reward_token(reward)).is_ok());
+    assert!(transact(OWNER, || engine.set_reward_rate(ether(1))).is_ok());
+    assert!(transact(OWNER, || engine.set_reward_weight(WETH, U256::from(1))).is_ok());
+    assert!(transact(USER, || engine.deposit_collateral_and_mint_dsc(
+        WETH,
+        ether(10),
+        ether(1_000)
+    ))
+    .is_ok());
+
+    // 区块时间固定，回拨分桶的更新时间模拟经过 100 秒：USER 是唯一的借款人，获得全部奖励
+    engine
+        .reward_pools
+        .setter(WETH)
+        .last_update
+        .set(U256::from(NOW - 100));
+    assert_eq!(engine.get_pending_rewards(USER), ether(100));
+    assert_eq!(engine.get_pending_rewards(LIQUIDATOR), U256::ZERO);
+
+    let Ok(claimed) = transact(USER, || engine.claim_rewards()) else {
+        panic!("claim failed");
+    };
+    assert_eq!(claimed, ether(100));
+    assert_eq!(token_balance(reward, USER), ether(100));
+    assert_eq!(engine.get_pending_rewards(USER), U256::ZERO);
+}
+
+#[test]
+fn votes_follow_delegated_balances_with_checkpoints() {
+    let mut votes = unsafe { Votes::new(U256::from(0x5ee7), 0) };
+    // 未委托的余额没有投票权
+    votes.transfer_voting_units(Address::ZERO, USER, ether(100));
+    assert_eq!(votes.get_votes(USER), U256::ZERO);
+
+    votes.delegate(USER, USER, ether(100));
+    votes.delegate(LIQUIDATOR, OWNER, U256::ZERO);
+    votes.transfer_voting_units(USER, LIQUIDATOR, ether(30));
+    assert_eq!(votes.get_votes(USER), ether(70));
+    assert_eq!(votes.get_votes(OWNER), ether(30));
+    // 同一区块内的变化合并为一个检查点
+    assert_eq!(votes.num_checkpoints(USER), U256::from(1));
+    assert_eq!(events::<DelegateVotesChanged>().len(), 3);
+
+    votes.transfer_voting_units(LIQUIDATOR, Address::ZERO, ether(10));
+    assert_eq!(votes.get_votes(OWNER), ether(20));
+    // 只能查询已经结束的区块：检查点都在当前区块，之前的区块没有票数
+    let clock = Votes::clock();
+    assert!(votes.get_past_total_supply(clock).is_err());
+    assert_eq!(
+        votes.get_past_votes(USER, clock - U256::from(1)).ok(),
+        Some(U256::ZERO)
+    );
+    assert_eq!(
+        votes.get_past_total_supply(clock - U256::from(1)).ok(),
+        Some(U256::ZERO)
+    );
+}
+
+#[test]
+fn param_registry_keeps_previous_value_and_update_time() {
+    let mut engine = setup();
+    let mint_fee = param_name("mintFee");
+    // 从未修改过的参数均为零
+    assert_eq!(
+        engine.get_param(mint_fee),
+        (U256::ZERO, U256::ZERO, U256::ZERO)
+    );
+    assert!(transact(OWNER, || engine.set_fees(U256::from(50), U256::ZERO)).is_ok());
+    assert!(transact(OWNER, || engine.set_fees(U256::from(80), U256::ZERO)).is_ok());
+    assert_eq!(
+        engine.get_param(mint_fee),
+        (U256::from(80), U256::from(50), U256::from(NOW))
+    );
+    let logged = events::<ParamUpdated>();
+    let last = logged.iter().rev().find(|e| e.key == mint_fee).unwrap();
+    assert_eq!(
+        (last.previous, last.value),
+        (U256::from(50), U256::from(80))
+    );
+
+    // 按抵押品设置的参数以参数名和抵押品地址计算键
+    assert!(transact(OWNER, || engine.set_stability_fee(WETH, U256::from(7))).is_ok());
+    let key = engine.get_param_key(param_name("stabilityFee"), WETH.into_word());
+    assert_ne!(key, param_name("stabilityFee"));
+    assert_eq!(engine.get_param(key).0, U256::from(7));
+    assert_eq!(engine.get_param_key(mint_fee, FixedBytes::ZERO), mint_fee);
+
+    // 白名单和手续费豁免按地址记录
+    assert!(transact(OWNER, || engine.set_allowlisted(USER, true)).is_ok());
+    assert!(transact(OWNER, || engine.set_fee_exempt(USER, true)).is_ok());
+    let allowlisted = engine.get_param_key(param_name("allowlisted"), USER.into_word());
+    let fee_exempt = engine.get_param_key(param_name("feeExempt"), USER.into_word());
+    assert_eq!(engine.get_param(allowlisted).0, U256::from(1));
+    assert_eq!(engine.get_param(fee_exempt).0, U256::from(1));
+    let exempted = events::<FeeExemptUpdated>();
+    assert_eq!(exempted.len(), 1);
+    assert_eq!((exempted[0].account, exempted[0].exempt), (USER, true));
+}
+
+#[test]
+fn borrow_threshold_leaves_buffer_above_liquidation() {
+    let mut engine = setup();
+    // 借款阈值不得高于清算阈值
+    assert!(matches!(
+        transact(OWNER, || engine.set_borrow_threshold(U256::from(60))),
+        Err(DSCEngineError::InvalidBorrowThreshold(_))
+    ));
+    assert!(transact(OWNER, || engine.set_borrow_threshold(U256::from(40))).is_ok());
+    assert_eq!(
+        engine.get_borrow_threshold(),
+        (U256::from(40), ether(5) / U256::from(4))
+    );
+
+    // 10 WETH 价值 20000 美元，按清算阈值调整后为 10000，借款健康因子 1.25 下最多铸造 8000
+    mint_token(WETH, USER, ether(10));
+    assert!(transact(USER, || engine.deposit_collateral(WETH, ether(10))).is_ok());
+    assert_eq!(engine.get_max_mintable_dsc(USER, U256::ZERO), ether(8_000));
+    assert!(matches!(
+        transact(USER, || engine.mint_dsc(ether(8_001))),
+        Err(DSCEngineError::BreaksHealthFactor(_))
+    ));
+    assert!(transact(USER, || engine.mint_dsc(ether(8_000))).is_ok());
+
+    // 价格下跌 10% 后仍高于最小健康因子，不可清算，但也不能再铸造或提取
+    set_price(WETH_FEED, 180_000_000_000);
+    call_as(USER);
+    assert!(!engine.is_liquidatable(USER));
+    assert!(matches!(
+        transact(USER, || engine
+            .redeem_collateral(WETH, ether(1) / U256::from(10))),
+        Err(DSCEngineError::BreaksHealthFactor(_))
+    ));
+    // 只偿还债务不受借款阈值限制
+    assert!(transact(USER, || engine.burn_dsc(ether(100))).is_ok());
+}
+
+#[test]
+fn price_source_selects_pyth_or_composed_feed() {
+    let mut engine = setup();
+    let pyth = Address::repeat_byte(0xb1);
+    let price_id = FixedBytes::repeat_byte(0x01);
+    // 未知类型和缺少价格 ID 的 Pyth 来源都会被拒绝
+    assert!(matches!(
+        transact(OWNER, || engine.set_price_source(
+            WETH,
+            9,
+            pyth,
+            price_id,
+            Address::ZERO,
+            U256::ZERO
+        )),
+        Err(DSCEngineError::InvalidPriceSource(_))
+    ));
+    assert!(matches!(
+        transact(OWNER, || engine.set_price_source(
+            WETH,
+            PRICE_SOURCE_PYTH,
+            pyth,
+            FixedBytes::ZERO,
+            Address::ZERO,
+            U256::from(60)
+        )),
+        Err(DSCEngineError::InvalidPriceSource(_))
+    ));
+
+    // Pyth 报价 1900.00000（指数 -5）换算为 8 位精度
+    mock_call(
+        pyth,
+        getPriceNoOlderThanCall::SELECTOR,
+        Ok(getPriceNoOlderThanCall::abi_encode_returns(&(
+            190_000_000i64,
+            0u64,
+            -5i32,
+            U256::from(NOW),
+        ))),
+    );
+    assert!(transact(OWNER, || engine.set_price_source(
+        WETH,
+        PRICE_SOURCE_PYTH,
+        pyth,
+        price_id,
+        Address::ZERO,
+        U256::from(60)
+    ))
+    .is_ok());
+    assert_eq!(
+        engine.get_price_source(WETH),
+        (
+            PRICE_SOURCE_PYTH,
+            pyth,
+            price_id,
+            Address::ZERO,
+            U256::from(60)
+        )
+    );
+    assert_eq!(
+        engine.get_collateral_price(WETH).ok(),
+        Some(U256::from(190_000_000_000u64))
+    );
+    assert_eq!(engine.get_usd_value(WETH, ether(1)), ether(1_900));
+    // 报价过期时 Pyth 回滚
+    mock_call(pyth, getPriceNoOlderThanCall::SELECTOR, Err(Vec::new()));
+    assert!(matches!(
+        engine.get_collateral_price(WETH),
+        Err(DSCEngineError::PriceFeedError(_))
+    ));
+
+    // 组合来源：WETH/X 报价 2000 乘以 X/USD 报价 0.5
+    let quote_feed = Address::repeat_byte(0xb2);
+    set_price(quote_feed, 50_000_000);
+    assert!(transact(OWNER, || engine.set_price_source(
+        WETH,
+        PRICE_SOURCE_COMPOSED,
+        Address::ZERO,
+        FixedBytes::ZERO,
+        quote_feed,
+        U256::ZERO
+    ))
+    .is_ok());
+    assert_eq!(
+        engine.get_collateral_price(WETH).ok(),
+        Some(U256::from(100_000_000_000u64))
+    );
+    assert!(transact(OWNER, || engine.set_price_source(
+        WETH,
+        PRICE_SOURCE_CHAINLINK,
+        Address::ZERO,
+        FixedBytes::ZERO,
+        Address::ZERO,
+        U256::ZERO
+    ))
+    .is_ok());
+    assert_eq!(
+        engine.get_collateral_price(WETH).ok(),
+        Some(U256::from(200_000_000_000u64))
+    );
+}
+
+#[test]
+fn peg_feed_values_collateral_in_peg_currency() {
+    // EUR/USD 报价 1.25：2000 美元的 WETH 值 1600 欧元
+    let eur_feed = Address::repeat_byte(0xe0);
+    let mut engine = setup_pegged(eur_feed, 125_000_000);
+    assert_eq!(engine.get_peg_feed(), eur_feed);
+    assert_eq!(
+        engine.get_collateral_price(WETH).ok(),
+        Some(U256::from(160_000_000_000u64))
+    );
+    assert_eq!(engine.get_usd_value(WETH, ether(1)), ether(1_600));
+    assert_eq!(
+        engine.get_token_amount_from_usd(WETH, ether(1_600)),
+        ether(1)
+    );
+
+    // 10 WETH 值 16000 欧元，按清算阈值最多铸造 8000 dEUR
+    mint_token(WETH, USER, ether(10));
+    assert!(transact(USER, || engine.deposit_collateral(WETH, ether(10))).is_ok());
+    assert_eq!(engine.get_max_mintable_dsc(USER, U256::ZERO), ether(8_000));
+    assert!(matches!(
+        transact(USER, || engine.mint_dsc(ether(8_001))),
+        Err(DSCEngineError::BreaksHealthFactor(_))
+    ));
+
+    // 欧元走强后同样的抵押品折合的欧元减少
+    set_price(eur_feed, 200_000_000);
+    call_as(USER);
+    assert_eq!(engine.get_usd_value(WETH, ether(1)), ether(1_000));
+}
+
+#[test]
+fn synths_share_collateral_with_dsc_debt() {
+    let mut engine = setup();
+    let deur = Address::repeat_byte(0xde);
+    let eur_feed = Address::repeat_byte(0xe0);
+    add_token(deur, 18);
+    set_price(eur_feed, 125_000_000);
+    assert!(transact(OWNER, || engine.add_synth(deur, eur_feed, ether(5_000))).is_ok());
+    assert_eq!(engine.get_synths(), vec![deur]);
+    assert_eq!(
+        engine.get_synth_price(deur).ok(),
+        Some(U256::from(125_000_000u64))
+    );
+    // 同一合成资产不能重复登记
+    assert!(matches!(
+        transact(OWNER, || engine.add_synth(deur, eur_feed, ether(5_000))),
+        Err(DSCEngineError::NotAllowedSynth(_))
+    ));
+
+    // 10 WETH 值 20000 美元，按清算阈值调整后可支撑 10000 美元的债务
+    mint_token(WETH, USER, ether(10));
+    assert!(transact(USER, || engine.deposit_collateral(WETH, ether(10))).is_ok());
+    assert!(transact(USER, || engine.mint_synth(deur, ether(4_000))).is_ok());
+    assert_eq!(token_balance(deur, USER), ether(4_000));
+    assert_eq!(engine.get_synth_debt_value(USER), ether(5_000));
+    assert_eq!(engine.get_health_factor(USER), ether(2));
+    assert!(matches!(
+        transact(USER, || engine.mint_synth(deur, ether(1_001))),
+        Err(DSCEngineError::SynthDebtCeilingExceeded(_))
+    ));
+    // 合成资产债务占用借款能力
+    assert_eq!(engine.get_max_mintable_dsc(USER, U256::ZERO), ether(5_000));
+    assert!(matches!(
+        transact(USER, || engine.mint_dsc(ether(5_001))),
+        Err(DSCEngineError::BreaksHealthFactor(_))
+    ));
+    assert!(transact(USER, || engine.mint_dsc(ether(5_000))).is_ok());
+
+    // 欧元走强后仓位跌破最小健康因子，清算人用 dEUR 清算
+    set_price(eur_feed, 150_000_000);
+    call_as(LIQUIDATOR);
+    assert!(engine.is_liquidatable(USER));
+    mint_token(deur, LIQUIDATOR, ether(1_000));
+    assert!(matches!(
+        transact(LIQUIDATOR, || engine.liquidate_synth(
+            WETH,
+            USER,
+            deur,
+            ether(2_001)
+        )),
+        Err(DSCEngineError::ExceedsMaxLiquidatableDebt(_))
+    ));
+    // 偿还 1000 dEUR（1500 美元），获得 0.75 WETH 加 10% 奖励
+    assert_eq!(
+        transact(LIQUIDATOR, || engine.liquidate_synth(
+            WETH,
+            USER,
+            deur,
+            ether(1_000)
+        ))
+        .ok(),
+        Some(ether(825) / U256::from(1_000))
+    );
+    assert_eq!(engine.get_synth_debt(USER, deur), ether(3_000));
+    assert_eq!(
+        engine.get_synth(deur),
+        (eur_feed, ether(5_000), ether(3_000))
+    );
+    assert_eq!(token_supply(deur), ether(4_000));
+
+    // 偿还剩余的合成资产债务
+    assert!(transact(USER, || engine.burn_synth(deur, ether(3_000))).is_ok());
+    assert_eq!(engine.get_synth_debt_value(USER), U256::ZERO);
+}
+
+#[test]
+fn debt_auction_sells_protocol_tokens_for_dsc_to_burn() {
+    let mut engine = setup();
+    let gov = Address::repeat_byte(0x90);
+    add_token(gov, 18);
+    assert!(matches!(
+        transact(USER, || engine.kick_debt_auction()),
+        Err(DSCEngineError::InvalidDebtAuctionParams(_))
+    ));
+    assert!(transact(OWNER, || engine.set_debt_auction_params(
+        gov,
+        ether(100),
+        ether(1_000),
+        U256::from(3_600),
+        U256::from(500)
+    ))
+    .is_ok());
+
+    // 保险基金的 300 DSC 先冲抵坏账，剩余 1000 DSC 坏账进入拍卖
+    engine.system_bad_debt.set(ether(1_300));
+    engine.insurance_fund.set(ether(300));
+    mint_token(DSC, ENGINE, ether(300));
+    let Ok(id) = transact(USER, || engine.kick_debt_auction()) else {
+        panic!("kick failed");
+    };
+    assert_eq!(engine.get_insurance_fund(), U256::ZERO);
+    assert_eq!(engine.get_debt_on_auction(), ether(1_000));
+    assert!(matches!(
+        transact(USER, || engine.kick_debt_auction()),
+        Err(DSCEngineError::NoDebtToAuction(_))
+    ));
+
+    // 首次出价的 DSC 立即销毁并冲抵坏账
+    mint_token(DSC, USER, ether(1_000));
+    mint_token(DSC, LIQUIDATOR, ether(1_000));
+    assert!(transact(USER, || engine.bid_debt_auction(id, ether(95))).is_ok());
+    assert_eq!(engine.get_system_bad_debt(), U256::ZERO);
+    assert_eq!(engine.get_debt_on_auction(), U256::ZERO);
+    assert_eq!(token_supply(DSC), ether(1_000));
+
+    // 之后的出价须至少减少 5%，并把 DSC 退给被超越的出价者
+    assert!(matches!(
+        transact(LIQUIDATOR, || engine.bid_debt_auction(id, ether(91))),
+        Err(DSCEngineError::InsufficientLotDecrease(_))
+    ));
+    assert!(transact(LIQUIDATOR, || engine.bid_debt_auction(id, ether(90))).is_ok());
+    assert_eq!(token_balance(DSC, USER), ether(1_000));
+    assert_eq!(token_balance(DSC, LIQUIDATOR), U256::ZERO);
+    assert!(matches!(
+        transact(USER, || engine.settle_debt_auction(id)),
+        Err(DSCEngineError::DebtAuctionClosed(_))
+    ));
+
+    // 区块时间固定，把结束时间回拨到当前区块模拟拍卖结束
+    engine.debt_auctions.setter(id).ends_at.set(U256::from(NOW));
+    assert!(transact(USER, || engine.settle_debt_auction(id)).is_ok());
+    assert_eq!(token_balance(gov, LIQUIDATOR), ether(90));
+    assert_eq!(engine.get_debt_auction(id).2, U256::ZERO);
+}
+
+#[test]
+fn buyback_burns_dsc_only_below_peg_within_epoch_cap() {
+    let mut engine = setup();
+    let router = Address::repeat_byte(0xde);
+    assert!(transact(OWNER, || engine.set_dex_router(router)).is_ok());
+    mint_token(WETH, OWNER, ether(2));
+    assert!(matches!(
+        transact(OWNER, || engine.fund_buyback(DSC, ether(1))),
+        Err(DSCEngineError::NotAllowedToken(_))
+    ));
+    assert!(transact(OWNER, || engine.fund_buyback(WETH, ether(2))).is_ok());
+    assert_eq!(engine.get_buyback_reserve(WETH), ether(2));
+
+    // 未设置上限时不能回购
+    set_swap_rate(router, WETH, DSC, ether(2_020));
+    assert!(matches!(
+        transact(USER, || engine.buyback_and_burn(WETH, ether(1), U256::ZERO)),
+        Err(DSCEngineError::BuybackCapExceeded(_))
+    ));
+    assert!(transact(OWNER, || engine
+        .set_buyback_limit(U256::from(3_600), ether(3_000)))
+    .is_ok());
+
+    // DSC 高于锚定价格（1 WETH 只能换 1990 DSC）时回购不能成交
+    set_swap_rate(router, WETH, DSC, ether(1_990));
+    assert!(matches!(
+        transact(USER, || engine.buyback_and_burn(WETH, ether(1), U256::ZERO)),
+        Err(DSCEngineError::SwapFailed(_))
+    ));
+    set_swap_rate(router, WETH, DSC, ether(2_020));
+    assert_eq!(
+        transact(USER, || engine.buyback_and_burn(WETH, ether(1), U256::ZERO)).ok(),
+        Some(ether(2_020))
+    );
+    assert_eq!(token_supply(DSC), U256::ZERO);
+    assert_eq!(engine.get_buyback_reserve(WETH), ether(1));
+    assert_eq!(
+        engine.get_buyback_limit(),
+        (U256::from(3_600), ether(3_000), ether(2_000))
+    );
+    assert!(matches!(
+        transact(USER, || engine.buyback_and_burn(WETH, ether(1), U256::ZERO)),
+        Err(DSCEngineError::BuybackCapExceeded(_))
+    ));
+
+    // 区块时间固定，回拨记录的周期模拟进入下一个周期
+    engine.buyback_epoch.set(U256::ZERO);
+    assert!(transact(USER, || engine.buyback_and_burn(WETH, ether(1), U256::ZERO)).is_ok());
+    assert_eq!(engine.get_buyback_reserve(WETH), U256::ZERO);
+}
+
+#[test]
+fn protocol_liquidity_pairs_minted_dsc_with_treasury_usdc() {
+    let mut engine = setup();
+    let router = Address::repeat_byte(0xde);
+    let usdc = Address::repeat_byte(0xc0);
+    let lp = Address::repeat_byte(0x19);
+    let usdc_unit = U256::from(1_000_000u64);
+    add_token(usdc, 6);
+    set_pair(router, DSC, usdc, lp);
+    mint_token(usdc, OWNER, U256::from(2_000) * usdc_unit);
+    assert!(transact(OWNER, || engine.set_dex_router(router)).is_ok());
+    assert!(transact(OWNER, || engine.set_psm_config(
+        usdc,
+        U256::ZERO,
+        U256::ZERO,
+        ether(1_000)
+    ))
+    .is_ok());
+    assert!(matches!(
+        transact(OWNER, || engine.add_protocol_liquidity(
+            U256::from(1_000) * usdc_unit,
+            U256::ZERO
+        )),
+        Err(DSCEngineError::PolNotConfigured(_))
+    ));
+    assert!(transact(OWNER, || engine.set_pol_config(lp, ether(1_000))).is_ok());
+
+    // 1000 USDC 配对铸造 1000 DSC 注入池中
+    assert_eq!(
+        transact(OWNER, || engine.add_protocol_liquidity(
+            U256::from(1_000) * usdc_unit,
+            ether(1_000)
+        ))
+        .ok(),
+        Some(ether(1_000))
+    );
+    assert_eq!(token_balance(DSC, router), ether(1_000));
+    assert_eq!(token_balance(lp, ENGINE), ether(1_000));
+    assert!(matches!(
+        transact(OWNER, || engine
+            .add_protocol_liquidity(usdc_unit, U256::ZERO)),
+        Err(DSCEngineError::PolDebtCeilingExceeded(_))
+    ));
+    assert!(matches!(
+        transact(OWNER, || engine.set_pol_config(usdc, ether(1_000))),
+        Err(DSCEngineError::PolPositionOpen(_))
+    ));
+
+    // 市场卖入 DSC 后撤出一半流动性：结清 500 DSC 债务，多取回的 DSC 计入盈余
+    mint_token(DSC, router, ether(100));
+    assert_eq!(
+        transact(OWNER, || engine
+            .remove_protocol_liquidity(ether(500), U256::ZERO))
+        .ok(),
+        Some(U256::from(500) * usdc_unit)
+    );
+    assert_eq!(token_balance(usdc, OWNER), U256::from(1_500) * usdc_unit);
+    assert_eq!(engine.get_surplus_buffer(), ether(50));
+    assert_eq!(
+        engine.get_protocol_liquidity(),
+        (lp, ether(500), ether(500), ether(1_000))
+    );
+    assert!(matches!(
+        transact(OWNER, || engine
+            .remove_protocol_liquidity(ether(501), U256::ZERO)),
+        Err(DSCEngineError::InsufficientProtocolLiquidity(_))
+    ));
+}
+
+#[test]
+fn borrowers_page_reports_debt_collateral_and_health() {
+    let mut engine = setup();
+    mint_token(WETH, USER, ether(10));
+    mint_token(WETH, LIQUIDATOR, ether(1));
+    assert!(transact(USER, || engine.deposit_collateral_and_mint_dsc(
+        WETH,
+        ether(10),
+        ether(5_000)
+    ))
+    .is_ok());
+    assert!(transact(LIQUIDATOR, || engine.deposit_collateral(WETH, ether(1))).is_ok());
+    assert_eq!(engine.borrowers_length(), U256::from(2));
+
+    assert_eq!(
+        engine.get_borrowers_page(U256::ZERO, U256::from(10)),
+        (
+            vec![USER, LIQUIDATOR],
+            vec![ether(5_000), U256::ZERO],
+            vec![ether(20_000), ether(2_000)],
+            vec![ether(2), U256::MAX]
+        )
+    );
+    assert_eq!(
+        engine.get_borrowers_page(U256::from(1), U256::from(1)).0,
+        vec![LIQUIDATOR]
+    );
+    assert!(engine
+        .get_borrowers_page(U256::from(2), U256::MAX)
+        .0
+        .is_empty());
+}
+
+#[test]
+fn psm_swap_out_is_limited_to_psm_reserves() {
+    let mut engine = setup();
+    let usdc = Address::repeat_byte(0xc0);
+    let usdc_unit = U256::from(1_000_000u64);
+    add_token(usdc, 6);
+    assert!(transact(OWNER, || engine.set_psm_config(
+        usdc,
+        U256::ZERO,
+        U256::ZERO,
+        ether(1_000)
+    ))
+    .is_ok());
+    mint_token(usdc, USER, U256::from(100) * usdc_unit);
+    mint_token(usdc, OWNER, U256::from(500) * usdc_unit);
+    assert!(transact(USER, || engine.psm_swap_to_dsc(U256::from(100) * usdc_unit)).is_ok());
+    // 引擎还持有作为回购储备的 USDC，但 PSM 只能换出自己的储备
+    assert!(transact(OWNER, || engine
+        .fund_buyback(usdc, U256::from(500) * usdc_unit))
+    .is_ok());
+    mint_token(DSC, USER, ether(50));
+    assert!(matches!(
+        transact(USER, || engine
+            .psm_swap_from_dsc(U256::from(150) * usdc_unit)),
+        Err(DSCEngineError::InsufficientPsmReserves(_))
+    ));
+    assert_eq!(token_balance(usdc, ENGINE), U256::from(600) * usdc_unit);
+    assert!(transact(USER, || engine
+        .psm_swap_from_dsc(U256::from(100) * usdc_unit))
+    .is_ok());
+    assert_eq!(engine.get_psm_debt(), U256::ZERO);
+    assert_eq!(token_balance(usdc, USER), U256::from(100) * usdc_unit);
+}
+
+#[test]
+fn underwater_position_can_partially_repay() {
+    let mut engine = setup();
+    mint_token(WETH, USER, ether(10));
+    assert!(transact(USER, || engine.deposit_collateral_and_mint_dsc(
+        WETH,
+        ether(10),
+        ether(10_000)
+    ))
+    .is_ok());
+    // 价格下跌后仓位低于最小健康因子，部分偿还仍然可以执行
+    set_price(WETH_FEED, 150_000_000_000);
+    call_as(USER);
+    assert!(engine.get_health_factor(USER) < ether(1));
+    assert!(transact(USER, || engine.burn_dsc(ether(1_000))).is_ok());
+    assert_eq!(engine.get_account_information(USER).0, ether(9_000));
+
+    // 金库仓位同样可以部分偿还
+    set_price(WETH_FEED, 200_000_000_000);
+    mint_token(WETH, USER, ether(10));
+    let Ok(id) = transact(USER, || engine.open_vault()) else {
+        panic!("open vault failed");
+    };
+    assert!(transact(USER, || engine.vault_deposit_collateral(
+        id,
+        WETH,
+        ether(10)
+    ))
+    .is_ok());
+    assert!(transact(USER, || engine.vault_mint_dsc(id, ether(10_000))).is_ok());
+    set_price(WETH_FEED, 150_000_000_000);
+    assert!(transact(USER, || engine.vault_burn_dsc(id, ether(1_000))).is_ok());
+}
+
+#[test]
+fn liquidation_repay_does_not_free_liquidator_mint_headroom() {
+    let mut engine = setup();
+    assert!(transact(OWNER, || engine.set_mint_rate_limit(
+        U256::from(3_600),
+        U256::ZERO,
+        ether(10_000)
+    ))
+    .is_ok());
+    mint_token(WETH, USER, ether(10));
+    mint_token(WETH, LIQUIDATOR, ether(30));
+    assert!(transact(USER, || engine.deposit_collateral_and_mint_dsc(
+        WETH,
+        ether(10),
+        ether(9_000)
+    ))
+    .is_ok());
+    assert!(
+        transact(LIQUIDATOR, || engine.deposit_collateral_and_mint_dsc(
+            WETH,
+            ether(30),
+            ether(10_000)
+        ))
+        .is_ok()
+    );
+
+    set_price(WETH_FEED, 150_000_000_000);
+    assert!(transact(LIQUIDATOR, || engine.liquidate(WETH, USER, ether(1_000))).is_ok());
+    // 偿还额度记给被清算的仓位，清算人仍然用满了本周期的额度
+    assert_eq!(engine.get_epoch_minted(USER).1, ether(8_000));
+    assert_eq!(engine.get_epoch_minted(LIQUIDATOR).1, ether(10_000));
+    assert!(matches!(
+        transact(LIQUIDATOR, || engine.mint_dsc(ether(1_000))),
+        Err(DSCEngineError::MintRateLimited(_))
+    ));
+}
+
+#[test]
+fn vault_repay_restores_vault_mint_headroom() {
+    let mut engine = setup();
+    assert!(transact(OWNER, || engine.set_mint_rate_limit(
+        U256::from(3_600),
+        U256::ZERO,
+        ether(5_000)
+    ))
+    .is_ok());
+    mint_token(WETH, USER, ether(10));
+    let Ok(id) = transact(USER, || engine.open_vault()) else {
+        panic!("open vault failed");
+    };
+    assert!(transact(USER, || engine.vault_deposit_collateral(
+        id,
+        WETH,
+        ether(10)
+    ))
+    .is_ok());
+    assert!(transact(USER, || engine.vault_mint_dsc(id, ether(5_000))).is_ok());
+    // 金库铸造记在金库仓位上，NFT 持有者自己的额度不受影响
+    let position = engine.get_vault_position(id);
+    assert_eq!(engine.get_epoch_minted(position).1, ether(5_000));
+    assert_eq!(engine.get_epoch_minted(USER).1, U256::ZERO);
+    assert!(matches!(
+        transact(USER, || engine.vault_mint_dsc(id, ether(1_000))),
+        Err(DSCEngineError::MintRateLimited(_))
+    ));
+
+    // 偿还后同一金库的额度恢复，可以再次铸造
+    assert!(transact(USER, || engine.vault_burn_dsc(id, ether(2_000))).is_ok());
+    assert_eq!(engine.get_epoch_minted(position).1, ether(3_000));
+    assert!(transact(USER, || engine.vault_mint_dsc(id, ether(2_000))).is_ok());
+    assert_eq!(engine.get_account_information(position).0, ether(5_000));
+}
+
+#[test]
+fn redemption_starts_at_the_riskiest_listed_position() {
+    let mut engine = setup();
+    mint_token(WETH, USER, ether(10));
+    mint_token(WETH, LIQUIDATOR, ether(10));
+    assert!(transact(USER, || engine.deposit_collateral_and_mint_dsc(
+        WETH,
+        ether(10),
+        ether(8_000)
+    ))
+    .is_ok());
+    assert!(
+        transact(LIQUIDATOR, || engine.deposit_collateral_and_mint_dsc(
+            WETH,
+            ether(10),
+            ether(5_000)
+        ))
+        .is_ok()
+    );
+    mint_token(DSC, OWNER, ether(6_000));
+
+    // 赎回列表按健康因子从低到高排列
+    assert_eq!(engine.get_next_redemption_position(Address::ZERO), USER);
+    assert_eq!(engine.get_next_redemption_position(USER), LIQUIDATOR);
+    assert_eq!(
+        engine.get_next_redemption_position(LIQUIDATOR),
+        Address::ZERO
+    );
+
+    // 赎回从列表头部开始，赎回者不能挑选健康的仓位
+    assert_eq!(
+        transact(OWNER, || engine.redeem_dsc_for_collateral(
+            WETH,
+            ether(1_000),
+            U256::ZERO
+        ))
+        .ok(),
+        Some(ether(1) / U256::from(2))
+    );
+    assert_eq!(engine.get_account_information(USER).0, ether(7_000));
+    assert_eq!(engine.get_account_information(LIQUIDATOR).0, ether(5_000));
+    assert_eq!(
+        engine.get_redemption_key(USER),
+        engine.get_health_factor(USER)
+    );
+
+    // 低于清算线的仓位被跳过，由清算处理
+    set_price(WETH_FEED, 140_000_000_000);
+    call_as(OWNER);
+    assert!(engine.get_health_factor(USER) < ether(1));
+    assert!(transact(OWNER, || engine.redeem_dsc_for_collateral(
+        WETH,
+        ether(1_000),
+        U256::ZERO
+    ))
+    .is_ok());
+    assert_eq!(engine.get_account_information(USER).0, ether(7_000));
+    assert_eq!(engine.get_account_information(LIQUIDATOR).0, ether(4_000));
+
+    // 债务还清的仓位移出列表
+    assert!(transact(USER, || engine.burn_dsc(ether(7_000))).is_ok());
+    assert_eq!(
+        engine.get_next_redemption_position(Address::ZERO),
+        LIQUIDATOR
+    );
+    assert_eq!(
+        engine.get_next_redemption_position(LIQUIDATOR),
+        Address::ZERO
+    );
+}
@@ -0,0 +1,43 @@
+//! DSC 转账钩子
+//!
+//! 治理可以为 DSC 登记一个钩子合约，铸造、销毁和转账完成后以 `onTransfer(operator, from, to, value)`
+//! 通知钩子（铸造时 from 为零地址，销毁时 to 为零地址），合规筛查、奖励记账等集成无需分叉代币即可响应资金流动。
+//! 钩子在余额、投票权更新和 `Transfer` 事件之后最后调用，即使钩子重入 DSC 也只能看到已完成的状态；
+//! 钩子回滚时整笔操作回滚。未登记钩子时不调用。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    call::Call,
+    msg,
+    prelude::*,
+};
+
+use crate::decentralized_stable_coin::{
+    DecentralizedStableCoin, DecentralizedStableCoinError, TransferHookFailed,
+};
+
+sol_interface! {
+    // 转账钩子接口
+    interface ITransferHook {
+        function onTransfer(address operator, address from, address to, uint256 value) external;
+    }
+}
+
+impl DecentralizedStableCoin {
+    // 余额变化后的处理：先同步投票权，最后通知转账钩子
+    pub(crate) fn _after_token_transfer(
+        &mut self,
+        from: Address,
+        to: Address,
+        value: U256,
+    ) -> Result<(), DecentralizedStableCoinError> {
+        self.votes.transfer_voting_units(from, to, value);
+        let hook = self.transfer_hook.get();
+        if hook.is_zero() {
+            return Ok(());
+        }
+        ITransferHook::new(hook)
+            .on_transfer(Call::new(), msg::sender(), from, to, value)
+            .map_err(|_| DecentralizedStableCoinError::TransferHookFailed(TransferHookFailed {}))
+    }
+}
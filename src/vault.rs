@@ -0,0 +1,46 @@
+//! 金库（CDP）编号：同一用户可以开设多个相互独立的仓位
+//!
+//! 每个金库有全局唯一的编号，并映射到一个派生的仓位地址。引擎中按地址记账的抵押品、
+//! 债务、健康因子和清算逻辑都直接作用于该仓位地址，因此每个金库拥有独立的抵押品和债务。
+//! 用户自己的地址仍是默认仓位，原有接口保持不变。
+//!
+//! 每个金库同时是一枚 ERC-721 NFT，编号与金库编号相同。NFT 的持有者、单个授权地址
+//! 和全局操作员都可以管理金库；转让 NFT 即转让金库中的抵押品和债务。
+
+use stylus_sdk::{
+    alloy_primitives::{keccak256, Address, U256},
+    evm,
+};
+
+use crate::{meta_tx::msg_sender, DSCEngine, DSCEngineError, NotVaultOwner, VaultOpened};
+
+impl DSCEngine {
+    // 金库编号对应的仓位地址
+    pub(crate) fn _vault_position(id: U256) -> Address {
+        let mut preimage = [0u8; 47];
+        preimage[..15].copy_from_slice(b"DSCEngine.vault");
+        preimage[15..].copy_from_slice(&id.to_be_bytes::<32>());
+        Address::from_slice(&keccak256(preimage)[12..])
+    }
+
+    // 开设新金库并铸造对应的 NFT，返回金库编号
+    pub(crate) fn _open_vault(&mut self, owner: Address) -> Result<U256, DSCEngineError> {
+        let id = self.vault_count.get() + U256::from(1);
+        self.vault_count.set(id);
+        self.vault_nft.mint(owner, id)?;
+        evm::log(VaultOpened {
+            id,
+            owner,
+            position: Self::_vault_position(id),
+        });
+        Ok(id)
+    }
+
+    // 检查调用者是否为金库 NFT 的持有者、授权地址或操作员，返回金库的仓位地址
+    pub(crate) fn _only_vault_owner(&self, id: U256) -> Result<Address, DSCEngineError> {
+        if !self.vault_nft.is_approved_or_owner(msg_sender(), id) {
+            return Err(DSCEngineError::NotVaultOwner(NotVaultOwner {}));
+        }
+        Ok(Self::_vault_position(id))
+    }
+}
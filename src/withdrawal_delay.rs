@@ -0,0 +1,148 @@
+//! 大额赎回的延时提取
+//!
+//! 单笔赎回超过该抵押品存款总量的一定比例时，不能直接赎回：用户先提交提取请求，
+//! 延时结束后任何人都可以执行，抵押品转给请求时指定的接收者；延时期间守护者或治理可以取消请求。
+//! 金库的提取请求不固定接收者，执行时转给当时的金库 NFT 持有者，
+//! 获授权的操作者不能把抵押品转给自己，金库转手后旧持有者的请求也不会再转给旧持有者。
+//! 执行时才扣减抵押品并检查健康因子，请求本身不锁定抵押品。
+//! 攻击者利用漏洞快速抽干抵押品时，守护者有时间介入。比例为零时不启用。
+
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    block, evm,
+    prelude::*,
+};
+
+use crate::{
+    meta_tx::msg_sender, psm::BPS, DSCEngine, DSCEngineError, InsufficientCollateral,
+    WithdrawalCancelled, WithdrawalDelayRequired, WithdrawalExecuted, WithdrawalNotFound,
+    WithdrawalNotReady, WithdrawalRequested,
+};
+
+sol_storage! {
+    // 待执行的提取请求，可执行时间为零表示不存在、已执行或已取消
+    pub struct PendingWithdrawal {
+        address position;                    // 赎回抵押品的仓位
+        address token;                       // 抵押品地址
+        address to;                          // 接收者地址
+        uint256 amount;                      // 抵押品数量
+        uint256 executable_at;               // 可执行时间
+        uint256 vault_id;                    // 金库编号：金库的请求执行时转给金库所有者，零表示普通仓位的请求
+    }
+}
+
+impl DSCEngine {
+    // 赎回数量是否超过需要延时提取的比例（远程抵押品不在本链持有，不受限制）
+    pub(crate) fn _requires_withdrawal_delay(&self, token: Address, amount: U256) -> bool {
+        let share = self.large_withdrawal_share.get();
+        if share == U256::ZERO || self._is_remote_collateral(token) {
+            return false;
+        }
+        amount * U256::from(BPS) > self.total_deposited.get(token) * share
+    }
+
+    // 直接赎回前检查是否需要走延时提取
+    pub(crate) fn _check_withdrawal_delay(
+        &self,
+        token: Address,
+        amount: U256,
+    ) -> Result<(), DSCEngineError> {
+        if self._requires_withdrawal_delay(token, amount) {
+            return Err(DSCEngineError::WithdrawalDelayRequired(
+                WithdrawalDelayRequired {},
+            ));
+        }
+        Ok(())
+    }
+
+    // 提取请求的接收者：金库的请求为当前的金库所有者
+    pub(crate) fn _withdrawal_receiver(&self, id: U256) -> Address {
+        let request = self.pending_withdrawals.getter(id);
+        let vault_id = request.vault_id.get();
+        if vault_id == U256::ZERO {
+            request.to.get()
+        } else {
+            self.vault_nft.owners.get(vault_id)
+        }
+    }
+
+    // 提交提取请求，返回请求编号；vault_id 非零时接收者为执行时的金库所有者
+    pub(crate) fn _request_withdrawal(
+        &mut self,
+        position: Address,
+        token: Address,
+        amount: U256,
+        to: Address,
+        vault_id: U256,
+    ) -> Result<U256, DSCEngineError> {
+        let available = self._collateral_amount_of(position, token);
+        if amount > available {
+            return Err(DSCEngineError::InsufficientCollateral(
+                InsufficientCollateral { _0: available },
+            ));
+        }
+        let id = self.next_withdrawal_id.get() + U256::from(1);
+        self.next_withdrawal_id.set(id);
+        let executable_at = U256::from(block::timestamp()) + self.withdrawal_delay.get();
+        let mut request = self.pending_withdrawals.setter(id);
+        request.position.set(position);
+        request.token.set(token);
+        request.to.set(to);
+        request.amount.set(amount);
+        request.executable_at.set(executable_at);
+        request.vault_id.set(vault_id);
+        evm::log(WithdrawalRequested {
+            id,
+            position,
+            token,
+            to,
+            amount,
+            executableAt: executable_at,
+        });
+        Ok(id)
+    }
+
+    // 延时结束后执行提取请求
+    pub(crate) fn _execute_withdrawal(&mut self, id: U256) -> Result<(), DSCEngineError> {
+        let request = self.pending_withdrawals.getter(id);
+        let executable_at = request.executable_at.get();
+        if executable_at == U256::ZERO {
+            return Err(DSCEngineError::WithdrawalNotFound(WithdrawalNotFound {}));
+        }
+        if U256::from(block::timestamp()) < executable_at {
+            return Err(DSCEngineError::WithdrawalNotReady(WithdrawalNotReady {
+                _0: executable_at,
+            }));
+        }
+        let position = request.position.get();
+        let token = request.token.get();
+        let amount = request.amount.get();
+        let to = self._withdrawal_receiver(id);
+        self.pending_withdrawals
+            .setter(id)
+            .executable_at
+            .set(U256::ZERO);
+        self._accrue_all();
+        self._withdraw_collateral_with_fee(token, amount, position, to)?;
+        self._revert_if_borrow_health_factor_is_broken(position)?;
+        evm::log(WithdrawalExecuted { id });
+        Ok(())
+    }
+
+    // 守护者或治理取消提取请求
+    pub(crate) fn _cancel_withdrawal(&mut self, id: U256) -> Result<(), DSCEngineError> {
+        self._only_guardian()?;
+        if self.pending_withdrawals.getter(id).executable_at.get() == U256::ZERO {
+            return Err(DSCEngineError::WithdrawalNotFound(WithdrawalNotFound {}));
+        }
+        self.pending_withdrawals
+            .setter(id)
+            .executable_at
+            .set(U256::ZERO);
+        evm::log(WithdrawalCancelled {
+            id,
+            by: msg_sender(),
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+//! 抵押品 zap：存入任意代币，经 DEX 路由兑换为支持的抵押品后一次记入仓位
+//!
+//! 路由使用 Uniswap V2 / Camelot 风格的 `swapExactTokensForTokens` 接口，
+//! 兑换所得按引擎实际到账的数量记账，低于 `min_out` 时回滚。
+//!
+//! 反方向的去杠杆同样经过路由：卖出用户自己的抵押品换成 DSC，直接偿还其债务，
+//! 用户不必先从市场买入 DSC。抵押品的提取与普通赎回一样收取赎回手续费并受熔断、延时和流出限制约束。
+
+use alloc::vec;
+use stylus_sdk::{
+    alloy_primitives::{Address, U256},
+    block,
+    call::Call,
+    contract, evm,
+    prelude::*,
+};
+
+use crate::{
+    meta_tx::msg_sender, DSCEngine, DSCEngineError, DexRouterNotSet, IDecentralizedStableCoin,
+    RepaidWithCollateral, SlippageExceeded, SwapFailed, TransferFailed, IERC20,
+};
+
+sol_interface! {
+    // Uniswap V2 / Camelot 风格的路由接口
+    interface IUniswapV2Router {
+        function swapExactTokensForTokens(
+            uint256 amount_in,
+            uint256 amount_out_min,
+            address[] calldata path,
+            address to,
+            uint256 deadline
+        ) external returns (uint256[] memory amounts);
+    }
+}
+
+impl DSCEngine {
+    // 通过 DEX 路由把引擎持有的 `token_in` 兑换为 `token_out`，返回实际到账数量
+    pub(crate) fn _swap_via_router(
+        &mut self,
+        token_in: Address,
+        amount_in: U256,
+        token_out: Address,
+        min_out: U256,
+    ) -> Result<U256, DSCEngineError> {
+        let router = self.dex_router.get();
+        if router.is_zero() {
+            return Err(DSCEngineError::DexRouterNotSet(DexRouterNotSet {}));
+        }
+        let engine = contract::address();
+        let balance_before = self._engine_balance(token_out);
+        if IERC20::new(token_in)
+            .approve(Call::new(), router, amount_in)
+            .is_err()
+        {
+            return Err(DSCEngineError::SwapFailed(SwapFailed {}));
+        }
+        IUniswapV2Router::new(router)
+            .swap_exact_tokens_for_tokens(
+                Call::new(),
+                amount_in,
+                min_out,
+                vec![token_in, token_out],
+                engine,
+                U256::from(block::timestamp()),
+            )
+            .map_err(|_| DSCEngineError::SwapFailed(SwapFailed {}))?;
+        let amount_out = self
+            ._engine_balance(token_out)
+            .saturating_sub(balance_before);
+        if amount_out < min_out {
+            return Err(DSCEngineError::SlippageExceeded(SlippageExceeded {
+                _0: amount_out,
+            }));
+        }
+        Ok(amount_out)
+    }
+
+    // 转入用户的代币，兑换为抵押品并记入用户仓位，返回记入的抵押品数量
+    pub(crate) fn _zap_deposit(
+        &mut self,
+        token_in: Address,
+        amount_in: U256,
+        collateral: Address,
+        min_out: U256,
+    ) -> Result<U256, DSCEngineError> {
+        let sender = msg_sender();
+        if IERC20::new(token_in)
+            .transfer_from(Call::new(), sender, contract::address(), amount_in)
+            .is_err()
+        {
+            return Err(DSCEngineError::TransferFailed(TransferFailed {}));
+        }
+        let amount_out = self._swap_via_router(token_in, amount_in, collateral, min_out)?;
+        self._credit_collateral(sender, collateral, amount_out)?;
+        Ok(amount_out)
+    }
+
+    // 卖出用户的抵押品换成 DSC 并偿还债务，返回偿还的债务数量（调用前需先计提）
+    pub(crate) fn _repay_with_collateral(
+        &mut self,
+        token: Address,
+        amount_collateral: U256,
+        min_debt_repaid: U256,
+    ) -> Result<U256, DSCEngineError> {
+        self._require_local_collateral(token)?;
+        let user = msg_sender();
+        let engine = contract::address();
+        // 赎回手续费从卖出数量中扣除，剩余部分留在引擎中用于兑换
+        let fee = self._fee_for(
+            user,
+            amount_collateral,
+            self.collateral_redemption_fee.get(),
+        );
+        self._redeem_collateral_with_fee(token, amount_collateral, user, engine)?;
+        let dsc = self.dsc.get();
+        let dsc_received =
+            self._swap_via_router(token, amount_collateral - fee, dsc, min_debt_repaid)?;
+
+        // 偿还不超过（分桶）债务的部分，其余 DSC 转给用户
+        let bucket = self._debt_bucket(user, token);
+        let debt = match bucket {
+            Some(bucket) => self._bucket_debt(user, bucket),
+            None => self._user_debt(user),
+        };
+        let repaid = dsc_received.min(debt);
+        if repaid > U256::ZERO {
+            let principal_paid = self._reduce_debt(repaid, user, bucket)?;
+            self._record_repay(user, principal_paid);
+            self._burn_repaid_dsc(repaid, principal_paid, user, engine)?;
+        }
+        let excess = dsc_received - repaid;
+        if excess > U256::ZERO
+            && IDecentralizedStableCoin::new(dsc)
+                .transfer(Call::new(), user, excess)
+                .is_err()
+        {
+            return Err(DSCEngineError::TransferFailed(TransferFailed {}));
+        }
+        self._revert_if_health_factor_is_broken(user)?;
+        evm::log(RepaidWithCollateral {
+            user,
+            token,
+            collateralSold: amount_collateral - fee,
+            debtRepaid: repaid,
+        });
+        Ok(repaid)
+    }
+}